@@ -0,0 +1,202 @@
+use std::fmt::Debug;
+use std::str::FromStr;
+
+use anyhow::Result;
+use aws_config::meta::region::RegionProviderChain;
+use aws_sdk_dynamodb::client::Client;
+use aws_sdk_dynamodb::config::{Credentials, Region};
+use config::{Config, Environment};
+use infrastructure::telemetry::{Telemetry, TelemetrySettings};
+use serde::Deserialize;
+use sqlx::MySqlPool;
+use tracing::Instrument;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
+
+use command_domain::group_chat::{GroupChatId, MessageId};
+use command_interface_adaptor_impl::gateways::group_chat_repository::{
+  with_group_chat_serializers, GroupChatRepositoryImpl,
+};
+use command_interface_adaptor_impl::gateways::multi_event_store::MultiEventStoreForDynamoDB;
+use command_interface_adaptor_impl::gateways::payload_cipher::PayloadCipherSettings;
+use command_processor::group_chat_command::GroupChatCommand;
+use command_processor::group_chat_command_processor::{GroupChatCommandProcessor, GroupChatCommandProcessorConfig};
+use query_interface_adaptor::gateways::{ExpiredMessageDao, ExpiredMessageDaoImpl};
+
+#[derive(Deserialize, Debug)]
+struct AppSettings {
+  persistence: PersistenceSettings,
+  database: DatabaseSettings,
+  aws: AwsSettings,
+  #[serde(default)]
+  retention: RetentionSettings,
+  #[serde(default)]
+  payload_cipher: PayloadCipherSettings,
+  #[serde(default)]
+  telemetry: TelemetrySettings,
+}
+
+#[derive(Deserialize, Debug)]
+struct PersistenceSettings {
+  journal_table_name: String,
+  journal_aid_index_name: String,
+  snapshot_table_name: String,
+  snapshot_aid_index_name: String,
+  shard_count: u64,
+  snapshot_interval: usize,
+}
+
+#[derive(Deserialize, Debug)]
+struct DatabaseSettings {
+  url: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default)]
+struct RetentionSettings {
+  default_retention_days: Option<u32>,
+  batch_size: u32,
+}
+
+impl Default for RetentionSettings {
+  fn default() -> Self {
+    Self {
+      default_retention_days: None,
+      batch_size: 1000,
+    }
+  }
+}
+
+impl RetentionSettings {
+  fn to_processor_config(&self) -> GroupChatCommandProcessorConfig {
+    GroupChatCommandProcessorConfig {
+      default_message_retention: self
+        .default_retention_days
+        .map(|days| chrono::Duration::days(days as i64)),
+      ..GroupChatCommandProcessorConfig::default()
+    }
+  }
+}
+
+#[derive(Deserialize, Debug)]
+struct AwsSettings {
+  region_name: String,
+  endpoint_url: Option<String>,
+  access_key_id: Option<String>,
+  secret_access_key: Option<String>,
+}
+
+// 保持期間を過ぎたメッセージを削除するジョブ
+#[tokio::main]
+async fn main() -> Result<()> {
+  let app_settings = load_app_config().unwrap();
+  let telemetry = Telemetry::new("message-retention-job", &app_settings.telemetry)?;
+  tracing_subscriber::registry()
+    .with(
+      tracing_subscriber::fmt::layer()
+        .with_target(false)
+        .with_ansi(false)
+        .without_time()
+        .with_filter(LevelFilter::INFO),
+    )
+    .with(telemetry.layer())
+    .init();
+
+  let pool = MySqlPool::connect(&app_settings.database.url).await?;
+  let aws_client = create_aws_client(&app_settings.aws).await;
+  let payload_cipher = app_settings.payload_cipher.create_payload_cipher()?;
+  let egg = with_group_chat_serializers(
+    MultiEventStoreForDynamoDB::new(
+      aws_client,
+      app_settings.persistence.journal_table_name.clone(),
+      app_settings.persistence.journal_aid_index_name.clone(),
+      app_settings.persistence.snapshot_table_name.clone(),
+      app_settings.persistence.snapshot_aid_index_name.clone(),
+      app_settings.persistence.shard_count,
+    ),
+    payload_cipher,
+  );
+  let repository = GroupChatRepositoryImpl::new(egg, app_settings.persistence.snapshot_interval);
+  let mut processor =
+    GroupChatCommandProcessor::new_with_config(repository, app_settings.retention.to_processor_config());
+
+  let expired_message_dao = ExpiredMessageDaoImpl::new(pool);
+  let expired_messages = expired_message_dao
+    .get_expired_messages(
+      app_settings.retention.default_retention_days,
+      app_settings.retention.batch_size,
+    )
+    .await?;
+  tracing::info!("expired messages = {}", expired_messages.len());
+
+  // NOTE: リードモデルへの反映は非同期なので、前回の実行で削除したメッセージが残っている場合がある。
+  // その場合は集約側でメッセージが見つからずエラーとなるが、ログに残して次のメッセージの処理を続ける。
+  let mut count = 0;
+  for expired_message in expired_messages {
+    let group_chat_id = GroupChatId::from_str(&expired_message.group_chat_id)?;
+    let message_id = MessageId::from_str(&expired_message.message_id)?;
+    // NOTE: メッセージごとにスパンを開始し、GroupChatMessageExpiredイベントにトレースコンテキストを保存する
+    let span = tracing::info_span!(
+      "expire_message",
+      group_chat_id = %expired_message.group_chat_id,
+      message_id = %expired_message.message_id
+    );
+    match processor
+      .handle(GroupChatCommand::ExpireMessage {
+        group_chat_id,
+        message_id,
+      })
+      .instrument(span)
+      .await
+    {
+      Ok(_) => count += 1,
+      Err(error) => tracing::warn!(
+        "Failed to expire message: group_chat_id = {}, message_id = {}, error = {}",
+        expired_message.group_chat_id,
+        expired_message.message_id,
+        error
+      ),
+    }
+  }
+  tracing::info!("expired {} messages", count);
+  Ok(())
+}
+
+fn load_app_config() -> Result<AppSettings> {
+  let source = Environment::with_prefix("APP").try_parsing(true).separator("__");
+  let config = Config::builder()
+    .add_source(config::File::with_name("config/message-retention-job").required(false))
+    .add_source(source)
+    .build()?;
+  tracing::info!("config = {:#?}", config);
+  let app_config = config.try_deserialize()?;
+  Ok(app_config)
+}
+
+async fn create_aws_client(aws_settings: &AwsSettings) -> Client {
+  let region_name = aws_settings.region_name.clone();
+  let region = Region::new(region_name);
+  let region_provider_chain = RegionProviderChain::default_provider().or_else(region);
+
+  let mut config_loader = aws_config::from_env().region(region_provider_chain);
+  if let Some(endpoint_url) = aws_settings.endpoint_url.clone() {
+    tracing::info!("endpoint_url = {}", endpoint_url);
+    config_loader = config_loader.endpoint_url(endpoint_url);
+  }
+
+  if let (Some(access_key_id), Some(secret_access_key)) = (
+    aws_settings.access_key_id.clone(),
+    aws_settings.secret_access_key.clone(),
+  ) {
+    config_loader = config_loader.credentials_provider(Credentials::new(
+      access_key_id,
+      secret_access_key,
+      None,
+      None,
+      "default",
+    ));
+  }
+
+  let config = config_loader.load().await;
+  Client::new(&config)
+}
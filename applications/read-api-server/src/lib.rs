@@ -1,4 +1,9 @@
 use config::{ConfigError, Environment};
+use infrastructure::graphql::GraphQLSettings;
+use infrastructure::grpc::GrpcSettings;
+use infrastructure::health::HealthSettings;
+use infrastructure::shutdown::ShutdownSettings;
+use infrastructure::telemetry::TelemetrySettings;
 use serde::Deserialize;
 
 #[derive(Deserialize, Debug)]
@@ -6,6 +11,10 @@ pub struct ApiSettings {
   pub host: String,
   pub port: u16,
   pub allow_origins: Vec<String>,
+  #[serde(default)]
+  pub graphql: GraphQLSettings,
+  #[serde(default)]
+  pub health: HealthSettings,
 }
 
 #[derive(Deserialize, Debug)]
@@ -23,6 +32,11 @@ pub struct AppSettings {
   pub api: ApiSettings,
   pub database: DatabaseSettings,
   // pub redis: RedisSettings,
+  pub grpc: Option<GrpcSettings>,
+  #[serde(default)]
+  pub telemetry: TelemetrySettings,
+  #[serde(default)]
+  pub shutdown: ShutdownSettings,
 }
 
 pub fn load_app_config() -> Result<AppSettings, ConfigError> {
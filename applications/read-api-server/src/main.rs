@@ -1,34 +1,76 @@
 use std::net::{IpAddr, SocketAddr};
 use std::str::FromStr;
+use std::sync::Arc;
 
 use anyhow::Result;
 use axum::headers::HeaderValue;
 use hyper::header::CONTENT_TYPE;
+use infrastructure::health::{MySqlHealthCheck, ReadinessChecker};
+use infrastructure::shutdown::ShutdownSignal;
+use infrastructure::telemetry::Telemetry;
 use sqlx::MySqlPool;
 use tower_http::cors::{AllowMethods, CorsLayer};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
 
 use query_interface_adaptor::controllers::create_router;
+use query_interface_adaptor::grpc::create_grpc_service;
 use read_api_server::{load_app_config, AppSettings};
 
 #[tokio::main]
 async fn main() -> Result<()> {
-  tracing_subscriber::fmt()
-    .with_max_level(tracing::Level::DEBUG)
-    .with_target(false)
-    .with_ansi(false)
-    .without_time()
+  let app_settings = load_app_config().unwrap();
+  let telemetry = Telemetry::new("read-api-server", &app_settings.telemetry)?;
+  tracing_subscriber::registry()
+    .with(
+      tracing_subscriber::fmt::layer()
+        .with_target(false)
+        .with_ansi(false)
+        .without_time()
+        .with_filter(LevelFilter::DEBUG),
+    )
+    .with(telemetry.layer())
     .init();
 
-  let app_settings = load_app_config().unwrap();
   let pool = MySqlPool::connect(&app_settings.database.url).await?;
 
-  let router = create_router(pool).layer(create_cors_layer(&app_settings));
+  let readiness_checker =
+    Arc::new(ReadinessChecker::new(&app_settings.api.health).with_check(Arc::new(MySqlHealthCheck::new(pool.clone()))));
+
+  let router =
+    create_router(pool.clone(), readiness_checker, &app_settings.api.graphql)?.layer(create_cors_layer(&app_settings));
 
+  let shutdown_signal = ShutdownSignal::listen();
   let socket_addr = SocketAddr::new(IpAddr::from_str(&app_settings.api.host).unwrap(), app_settings.api.port);
   tracing::info!("Server listening on http://{}", socket_addr);
-  axum::Server::bind(&socket_addr)
+  let server = axum::Server::bind(&socket_addr)
     .serve(router.into_make_service())
-    .await?;
+    .with_graceful_shutdown(shutdown_signal.clone().triggered());
+
+  let servers = async {
+    match &app_settings.grpc {
+      Some(grpc_settings) => {
+        let grpc_socket_addr = SocketAddr::new(IpAddr::from_str(&grpc_settings.host).unwrap(), grpc_settings.port);
+        tracing::info!("gRPC server listening on http://{}", grpc_socket_addr);
+        let grpc_server = tonic::transport::Server::builder()
+          .add_service(create_grpc_service(pool.clone(), shutdown_signal.clone()))
+          .serve_with_shutdown(grpc_socket_addr, shutdown_signal.clone().triggered());
+        tokio::try_join!(async { server.await.map_err(anyhow::Error::from) }, async {
+          grpc_server.await.map_err(anyhow::Error::from)
+        },)?;
+      }
+      None => server.await?,
+    }
+    Ok::<(), anyhow::Error>(())
+  };
+  // NOTE: gRPCのStreamMessagesはシグナルを受けると終了する。それ以外の完了しないリクエストは猶予時間が経過したら切断する
+  tokio::select! {
+    result = servers => result?,
+    _ = shutdown_signal.clone().deadline(app_settings.shutdown.grace_period()) => {}
+  }
+
+  pool.close().await;
+  tracing::info!("Server stopped");
   Ok(())
 }
 
@@ -1,5 +1,8 @@
 use std::collections::HashMap;
+use std::fs;
 use std::mem::size_of;
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
 
 use anyhow::Result;
 use aws_config::meta::region::RegionProviderChain;
@@ -9,40 +12,65 @@ use aws_sdk_dynamodb::Client as DynamoDBClient;
 use aws_sdk_dynamodbstreams::config::{Credentials, Region};
 use aws_sdk_dynamodbstreams::types::ShardIteratorType;
 use aws_sdk_dynamodbstreams::Client as DynamoDBStreamsClient;
+use axum::http::header::CONTENT_TYPE;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
 use chrono::Utc;
+use command_domain::group_chat::GroupChatEvent;
 use command_domain::id_generate;
 use command_interface_adaptor_impl::gateways::group_chat_read_model_dao_impl::GroupChatReadModelUpdateDaoImpl;
+use event_store_adapter_rs::serializer::EventSerializer;
 use http::{HeaderMap, HeaderValue};
+use infrastructure::metrics::{gather_metrics, METRICS_CONTENT_TYPE};
+use infrastructure::shutdown::ShutdownSignal;
+use infrastructure::telemetry::Telemetry;
 use lambda_runtime::{Context, LambdaEvent};
 
 use serde_dynamo::Item;
 use sqlx::{MySql, MySqlPool, Pool};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
 
-use read_model_updater::{load_app_config, AwsSettings};
+use read_model_updater::{create_event_serializer, load_app_config, ApiSettings, AwsSettings, StreamSettings};
 
 // ローカル版のRead Model Updater
 #[tokio::main]
 async fn main() -> Result<()> {
-  tracing_subscriber::fmt()
-    .with_max_level(tracing::Level::INFO)
-    .with_target(false)
-    .with_ansi(false)
-    .without_time()
+  let app_settings = load_app_config().unwrap();
+  let telemetry = Telemetry::new("read-model-updater", &app_settings.telemetry)?;
+  tracing_subscriber::registry()
+    .with(
+      tracing_subscriber::fmt::layer()
+        .with_target(false)
+        .with_ansi(false)
+        .without_time()
+        .with_filter(LevelFilter::INFO),
+    )
+    .with(telemetry.layer())
     .init();
 
-  let app_settings = load_app_config().unwrap();
+  if let Some(api_settings) = &app_settings.api {
+    spawn_metrics_server(api_settings);
+  }
 
   let pool = MySqlPool::connect(&app_settings.database.url).await?;
   let dynamodb_client = create_aws_client(&app_settings.aws).await;
   let dynamodb_streams_client = create_aws_dynamodb_streams_client(&app_settings.aws).await;
+  let event_serializer = create_event_serializer(&app_settings)?;
   if let Some(stream_settings) = &app_settings.stream {
-    loop {
+    let shutdown_signal = ShutdownSignal::listen();
+    let mut checkpoint = Checkpoint::load(stream_settings.checkpoint_file.clone())?;
+    // NOTE: シグナルを受けたら、処理中のバッチをリードモデルに反映してから停止する
+    while !shutdown_signal.is_triggered() {
       match stream_events_driver(
         &dynamodb_client,
         &dynamodb_streams_client,
         pool.clone(),
-        &stream_settings.journal_table_name,
-        stream_settings.max_item_count,
+        event_serializer.as_ref(),
+        stream_settings,
+        &mut checkpoint,
+        &shutdown_signal,
       )
       .await
       {
@@ -57,11 +85,73 @@ async fn main() -> Result<()> {
         }
       }
     }
+    checkpoint.save()?;
+    pool.close().await;
+    tracing::info!("read-model-updater stopped");
+    Ok(())
   } else {
     Err(anyhow::anyhow!("stream_settings is not found"))
   }
 }
 
+/// Prometheusのスクレイプ用に`/metrics`を公開するHTTPサーバを起動する。
+///
+/// NOTE: Lambda版はリクエストを受けられないので、ローカル版のみ起動する。
+fn spawn_metrics_server(api_settings: &ApiSettings) {
+  let socket_addr = SocketAddr::new(IpAddr::from_str(&api_settings.host).unwrap(), api_settings.port);
+  tracing::info!("Metrics server listening on http://{}/metrics", socket_addr);
+  let route = Router::new().route("/metrics", get(metrics));
+  tokio::spawn(async move {
+    if let Err(error) = axum::Server::bind(&socket_addr).serve(route.into_make_service()).await {
+      tracing::error!("metrics server error: {}", error);
+    }
+  });
+}
+
+async fn metrics() -> impl IntoResponse {
+  ([(CONTENT_TYPE, METRICS_CONTENT_TYPE)], gather_metrics())
+}
+
+/// シャードごとに、リードモデルに反映した最後のレコードのシーケンス番号を保存するチェックポイント。
+#[derive(Debug)]
+struct Checkpoint {
+  file: Option<String>,
+  sequence_numbers: HashMap<String, String>,
+}
+
+impl Checkpoint {
+  /// チェックポイントのファイルを読み込む。ファイルがない場合は空のチェックポイントを返す。
+  fn load(file: Option<String>) -> Result<Self> {
+    let sequence_numbers = match &file {
+      Some(file) if fs::metadata(file).is_ok() => {
+        let sequence_numbers: HashMap<String, String> = serde_json::from_str(&fs::read_to_string(file)?)?;
+        tracing::info!("checkpoint is loaded from {}: {:?}", file, sequence_numbers);
+        sequence_numbers
+      }
+      _ => HashMap::new(),
+    };
+    Ok(Self { file, sequence_numbers })
+  }
+
+  fn sequence_number(&self, shard_id: &str) -> Option<&str> {
+    self.sequence_numbers.get(shard_id).map(|s| s.as_str())
+  }
+
+  fn update(&mut self, shard_id: &str, sequence_number: &str) {
+    self
+      .sequence_numbers
+      .insert(shard_id.to_string(), sequence_number.to_string());
+  }
+
+  fn save(&self) -> Result<()> {
+    if let Some(file) = &self.file {
+      fs::write(file, serde_json::to_string_pretty(&self.sequence_numbers)?)?;
+      tracing::info!("checkpoint is saved to {}: {:?}", file, self.sequence_numbers);
+    }
+    Ok(())
+  }
+}
+
 fn convert_to(src: aws_sdk_dynamodbstreams::types::AttributeValue) -> serde_dynamo::AttributeValue {
   match src {
     aws_sdk_dynamodbstreams::types::AttributeValue::B(b) => serde_dynamo::AttributeValue::B(b.into_inner()),
@@ -88,9 +178,13 @@ async fn stream_events_driver(
   dynamodb_client: &DynamoDBClient,
   dynamodb_streams_client: &DynamoDBStreamsClient,
   pool: Pool<MySql>,
-  journal_table_name: &str,
-  max_item_count: usize,
+  event_serializer: &dyn EventSerializer<GroupChatEvent>,
+  stream_settings: &StreamSettings,
+  checkpoint: &mut Checkpoint,
+  shutdown_signal: &ShutdownSignal,
 ) -> Result<()> {
+  let journal_table_name = &stream_settings.journal_table_name;
+  let max_item_count = stream_settings.max_item_count;
   let describe_table_out = dynamodb_client
     .describe_table()
     .table_name(journal_table_name)
@@ -113,17 +207,25 @@ async fn stream_events_driver(
     let shards = describe_stream_output.stream_description().unwrap().shards().to_vec();
 
     for shard in shards {
+      if shutdown_signal.is_triggered() {
+        return Ok(());
+      }
       tracing::info!("shard = {:?}", shard);
-      let get_shard_iterator_output = dynamodb_streams_client
+      let shard_id = shard.shard_id().unwrap();
+      let mut get_shard_iterator_builder = dynamodb_streams_client
         .get_shard_iterator()
         .stream_arn(stream_arn)
-        .shard_id(shard.shard_id().unwrap())
-        .shard_iterator_type(ShardIteratorType::Latest)
-        .send()
-        .await?;
+        .shard_id(shard_id);
+      get_shard_iterator_builder = match checkpoint.sequence_number(shard_id) {
+        Some(sequence_number) => get_shard_iterator_builder
+          .shard_iterator_type(ShardIteratorType::AfterSequenceNumber)
+          .sequence_number(sequence_number),
+        None => get_shard_iterator_builder.shard_iterator_type(ShardIteratorType::Latest),
+      };
+      let get_shard_iterator_output = get_shard_iterator_builder.send().await?;
       let mut shard_iterator_opt = get_shard_iterator_output.shard_iterator().map(|s| s.to_owned());
       let mut processed_record_count = 0usize;
-      while shard_iterator_opt.is_some() && processed_record_count < max_item_count {
+      while shard_iterator_opt.is_some() && processed_record_count < max_item_count && !shutdown_signal.is_triggered() {
         tracing::info!("shard_iterator = {:?}", shard_iterator_opt.clone().unwrap());
         let get_records_output = dynamodb_streams_client
           .get_records()
@@ -195,7 +297,10 @@ async fn stream_events_driver(
           let context = Context::try_from(headers).unwrap();
           let lambda_event = LambdaEvent::new(event, context);
 
-          rmu::update_read_model(&dao, lambda_event).await?;
+          rmu::update_read_model_with_serializer(&dao, event_serializer, lambda_event).await?;
+          if let Some(sequence_number) = stream_record.sequence_number() {
+            checkpoint.update(shard_id, sequence_number);
+          }
         }
         processed_record_count += records.len();
         shard_iterator_opt = get_records_output.next_shard_iterator().map(|s| s.to_owned())
@@ -208,7 +313,7 @@ async fn stream_events_driver(
       .last_evaluated_shard_id()
       .map(|s| s.to_owned());
 
-    if next_last_evaluated_shard_id.is_none() {
+    if next_last_evaluated_shard_id.is_none() || shutdown_signal.is_triggered() {
       break;
     }
 
@@ -229,22 +334,19 @@ async fn create_aws_client(aws_settings: &AwsSettings) -> DynamoDBClient {
     config_loader = config_loader.endpoint_url(endpoint_url);
   }
 
-  match (
+  if let (Some(access_key_id), Some(secret_access_key)) = (
     aws_settings.access_key_id.clone(),
     aws_settings.secret_access_key.clone(),
   ) {
-    (Some(access_key_id), Some(secret_access_key)) => {
-      tracing::info!("access_key_id = {}", access_key_id);
-      tracing::info!("secret_access_key = {}", secret_access_key);
-      config_loader = config_loader.credentials_provider(Credentials::new(
-        access_key_id,
-        secret_access_key,
-        None,
-        None,
-        "default",
-      ));
-    }
-    _ => {}
+    tracing::info!("access_key_id = {}", access_key_id);
+    tracing::info!("secret_access_key = {}", secret_access_key);
+    config_loader = config_loader.credentials_provider(Credentials::new(
+      access_key_id,
+      secret_access_key,
+      None,
+      None,
+      "default",
+    ));
   }
 
   let config = config_loader.load().await;
@@ -260,20 +362,17 @@ async fn create_aws_dynamodb_streams_client(aws_settings: &AwsSettings) -> Dynam
   if let Some(endpoint_url) = aws_settings.endpoint_url.clone() {
     config_loader = config_loader.endpoint_url(endpoint_url);
   }
-  match (
+  if let (Some(access_key_id), Some(secret_access_key)) = (
     aws_settings.access_key_id.clone(),
     aws_settings.secret_access_key.clone(),
   ) {
-    (Some(access_key_id), Some(secret_access_key)) => {
-      config_loader = config_loader.credentials_provider(Credentials::new(
-        access_key_id,
-        secret_access_key,
-        None,
-        None,
-        "default",
-      ));
-    }
-    _ => {}
+    config_loader = config_loader.credentials_provider(Credentials::new(
+      access_key_id,
+      secret_access_key,
+      None,
+      None,
+      "default",
+    ));
   }
   let config = config_loader.load().await;
 
@@ -1,11 +1,34 @@
+use std::sync::Arc;
+
 use config::{ConfigError, Environment};
+use event_store_adapter_rs::serializer::EventSerializer;
 use serde::Deserialize;
 
+use command_domain::group_chat::GroupChatEvent;
+use command_interface_adaptor_if::PayloadCipherError;
+use command_interface_adaptor_impl::gateways::payload_cipher::PayloadCipherSettings;
+use command_interface_adaptor_impl::gateways::payload_cipher_event_serializer::PayloadCipherEventSerializer;
+use infrastructure::telemetry::TelemetrySettings;
+
 #[derive(Deserialize, Debug)]
 pub struct AppSettings {
+  /// `/metrics`を公開するHTTPサーバの設定(ローカル版のみ利用する)
+  pub api: Option<ApiSettings>,
   pub aws: AwsSettings,
   pub stream: Option<StreamSettings>,
   pub database: DatabaseSettings,
+  /// メッセージの本文の暗号化の設定(write-api-serverと同じ設定にする)
+  #[serde(default)]
+  pub payload_cipher: PayloadCipherSettings,
+  /// トレースの設定
+  #[serde(default)]
+  pub telemetry: TelemetrySettings,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ApiSettings {
+  pub host: String,
+  pub port: u16,
 }
 
 #[derive(Deserialize, Debug)]
@@ -37,6 +60,10 @@ pub struct StreamSettings {
   pub journal_table_name: String,
   /// ストリームから読み込む最大件数
   pub max_item_count: usize,
+  /// シャードごとに反映済みのシーケンス番号を保存するファイル(ローカル版のみ利用する)
+  ///
+  /// 停止時に保存し、起動時に続きから読み込む。省略した場合は最新のレコードから読み込む。
+  pub checkpoint_file: Option<String>,
 }
 
 pub fn load_app_config() -> Result<AppSettings, ConfigError> {
@@ -47,3 +74,14 @@ pub fn load_app_config() -> Result<AppSettings, ConfigError> {
   let app_config = config.try_deserialize()?;
   Ok(app_config)
 }
+
+/// イベントのペイロードをデシリアライズする[EventSerializer]を生成する。
+///
+/// # 戻り値
+/// - 設定された[command_interface_adaptor_if::PayloadCipher]でメッセージの本文を復号する[EventSerializer]を返す。
+pub fn create_event_serializer(
+  app_settings: &AppSettings,
+) -> Result<Arc<dyn EventSerializer<GroupChatEvent>>, PayloadCipherError> {
+  let payload_cipher = app_settings.payload_cipher.create_payload_cipher()?;
+  Ok(Arc::new(PayloadCipherEventSerializer::new(payload_cipher)))
+}
@@ -4,27 +4,36 @@ use anyhow::Result;
 use std::time::Duration;
 
 use command_interface_adaptor_impl::gateways::group_chat_read_model_dao_impl::GroupChatReadModelUpdateDaoImpl;
+use infrastructure::telemetry::Telemetry;
 use lambda_runtime::{service_fn, Error};
-use read_model_updater::load_app_config;
+use read_model_updater::{create_event_serializer, load_app_config};
 use sqlx::mysql::{MySqlConnectOptions, MySqlPoolOptions, MySqlSslMode};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
 
-use rmu::update_read_model;
+use rmu::update_read_model_with_serializer;
 
 #[tokio::main]
 async fn main() -> Result<(), Error> {
-  tracing_subscriber::fmt()
-    .json()
-    .with_max_level(tracing::Level::DEBUG)
-    .with_current_span(false)
-    .with_ansi(false)
-    .without_time()
-    .with_target(false)
+  let app_settings = load_app_config().unwrap();
+  let telemetry = Telemetry::new("read-model-updater", &app_settings.telemetry)?;
+  tracing_subscriber::registry()
+    .with(
+      tracing_subscriber::fmt::layer()
+        .json()
+        .with_current_span(false)
+        .with_ansi(false)
+        .without_time()
+        .with_target(false)
+        .with_filter(LevelFilter::DEBUG),
+    )
+    .with(telemetry.layer())
     .init();
   env_logger::init();
 
   tracing::info!("main: start");
-  let app_settings = load_app_config().unwrap();
   tracing::info!("main: load_app_config");
+  let event_serializer = create_event_serializer(&app_settings)?;
   let database_url = app_settings.database.url;
   tracing::info!("main: database url: {:?}", database_url);
   let op: MySqlConnectOptions = database_url.parse()?;
@@ -42,8 +51,10 @@ async fn main() -> Result<(), Error> {
   tracing::info!("main: start");
   lambda_runtime::run(service_fn(|event| async {
     tracing::info!("function: start");
-    let result = update_read_model(&dao, event).await;
+    let result = update_read_model_with_serializer(&dao, event_serializer.as_ref(), event).await;
     tracing::info!("function: finished: {:?}", result);
+    // NOTE: Lambdaは呼び出しの間に停止されることがあるので、呼び出しごとにスパンを送信する
+    telemetry.force_flush();
     result
   }))
   .await?;
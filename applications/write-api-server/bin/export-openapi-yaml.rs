@@ -0,0 +1,9 @@
+use anyhow::Result;
+use utoipa::OpenApi;
+
+use command_interface_adaptor_impl::rest::ApiDoc;
+
+fn main() -> Result<()> {
+  println!("{}", ApiDoc::openapi().to_yaml()?);
+  Ok(())
+}
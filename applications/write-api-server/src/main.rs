@@ -1,26 +1,62 @@
 use std::fmt::Debug;
 use std::net::{IpAddr, SocketAddr};
 use std::str::FromStr;
+use std::sync::Arc;
 
 use anyhow::Result;
 use aws_config::meta::region::RegionProviderChain;
 use aws_sdk_dynamodb::client::Client;
 use aws_sdk_dynamodb::config::{Credentials, Region};
-use axum::http::HeaderValue;
+use axum::http::{HeaderName, HeaderValue};
 use config::{Config, Environment};
-use event_store_adapter_rs::EventStoreForDynamoDB;
 use hyper::header::CONTENT_TYPE;
+use infrastructure::graphql::GraphQLSettings;
+use infrastructure::grpc::GrpcSettings;
+use infrastructure::health::{HealthSettings, MySqlHealthCheck, ReadinessChecker};
+use infrastructure::shutdown::{ShutdownSettings, ShutdownSignal};
+use infrastructure::telemetry::{Telemetry, TelemetrySettings};
 use serde::Deserialize;
+use sqlx::MySqlPool;
 use tower_http::cors::{AllowMethods, CorsLayer};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
 
-use command_interface_adaptor_impl::controllers::create_router;
-use command_interface_adaptor_impl::gateways::group_chat_repository::GroupChatRepositoryImpl;
+use command_domain::group_chat::LastAdministratorPolicy;
+use command_domain::user_account::UserAccountId;
+use command_interface_adaptor_impl::controllers::create_router_with_context;
+use command_interface_adaptor_impl::gateways::group_chat_repository::{
+  with_group_chat_serializers, GroupChatRepositoryImpl,
+};
+use command_interface_adaptor_impl::gateways::health_checks::DynamoDbTableHealthCheck;
+use command_interface_adaptor_impl::gateways::idempotency_store::IdempotencySettings;
+use command_interface_adaptor_impl::gateways::multi_event_store::MultiEventStoreForDynamoDB;
+use command_interface_adaptor_impl::gateways::payload_cipher::PayloadCipherSettings;
+use command_interface_adaptor_impl::gateways::user_account_group_chat_dao_impl::UserAccountGroupChatDaoImpl;
+use command_interface_adaptor_impl::graphql::ServiceContext;
+use command_interface_adaptor_impl::grpc::GroupChatCommandGrpcService;
+use command_interface_adaptor_impl::rest::IDEMPOTENCY_KEY_HEADER;
+use command_processor::group_chat_command_middleware::{
+  AuthorizationMiddleware, LoggingMiddleware, MetricsMiddleware, ValidationMiddleware,
+};
+use command_processor::group_chat_command_processor::{GroupChatCommandProcessor, GroupChatCommandProcessorConfig};
 
 #[derive(Deserialize, Debug)]
 struct AppSettings {
   api: ApiSettings,
   persistence: PersistenceSettings,
+  database: DatabaseSettings,
   aws: AwsSettings,
+  #[serde(default)]
+  command: CommandSettings,
+  #[serde(default)]
+  payload_cipher: PayloadCipherSettings,
+  #[serde(default)]
+  idempotency: IdempotencySettings,
+  grpc: Option<GrpcSettings>,
+  #[serde(default)]
+  telemetry: TelemetrySettings,
+  #[serde(default)]
+  shutdown: ShutdownSettings,
 }
 
 #[derive(Deserialize, Debug)]
@@ -28,6 +64,10 @@ struct ApiSettings {
   pub host: String,
   pub port: u16,
   pub allow_origins: Vec<String>,
+  #[serde(default)]
+  pub graphql: GraphQLSettings,
+  #[serde(default)]
+  pub health: HealthSettings,
 }
 
 #[derive(Deserialize, Debug)]
@@ -40,6 +80,58 @@ struct PersistenceSettings {
   snapshot_interval: usize,
 }
 
+#[derive(Deserialize, Debug)]
+struct DatabaseSettings {
+  url: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default)]
+struct CommandSettings {
+  message_restore_window_secs: i64,
+  message_edit_window_secs: i64,
+  invitation_ttl_secs: i64,
+  last_administrator_policy: LastAdministratorPolicy,
+  max_batch_size: usize,
+  /// 他のユーザアカウントを消去できるユーザアカウントのID(運用者など)
+  privileged_user_account_ids: Vec<String>,
+}
+
+impl Default for CommandSettings {
+  fn default() -> Self {
+    Self {
+      message_restore_window_secs: 86400,
+      message_edit_window_secs: 86400,
+      invitation_ttl_secs: 604800,
+      last_administrator_policy: LastAdministratorPolicy::default(),
+      max_batch_size: 50,
+      privileged_user_account_ids: Vec::new(),
+    }
+  }
+}
+
+impl CommandSettings {
+  fn to_processor_config(&self) -> GroupChatCommandProcessorConfig {
+    GroupChatCommandProcessorConfig {
+      message_restore_window: chrono::Duration::seconds(self.message_restore_window_secs),
+      message_edit_window: chrono::Duration::seconds(self.message_edit_window_secs),
+      invitation_ttl: chrono::Duration::seconds(self.invitation_ttl_secs),
+      last_administrator_policy: self.last_administrator_policy,
+      max_batch_size: self.max_batch_size,
+      ..GroupChatCommandProcessorConfig::default()
+    }
+  }
+
+  fn to_authorization_middleware(&self) -> Result<AuthorizationMiddleware> {
+    let privileged_user_account_ids = self
+      .privileged_user_account_ids
+      .iter()
+      .map(|id| UserAccountId::from_str(id).map_err(|error| anyhow::anyhow!("{}: {:?}", id, error)))
+      .collect::<Result<Vec<_>>>()?;
+    Ok(AuthorizationMiddleware::new(privileged_user_account_ids))
+  }
+}
+
 #[derive(Deserialize, Debug)]
 struct AwsSettings {
   region_name: String,
@@ -50,32 +142,100 @@ struct AwsSettings {
 
 #[tokio::main]
 async fn main() -> Result<()> {
-  tracing_subscriber::fmt()
-    .with_max_level(tracing::Level::DEBUG)
-    .with_target(false)
-    .with_ansi(false)
-    .without_time()
+  let app_settings = load_app_config().unwrap();
+  let telemetry = Telemetry::new("write-api-server", &app_settings.telemetry)?;
+  tracing_subscriber::registry()
+    .with(
+      tracing_subscriber::fmt::layer()
+        .with_target(false)
+        .with_ansi(false)
+        .without_time()
+        .with_filter(LevelFilter::DEBUG),
+    )
+    .with(telemetry.layer())
     .init();
+  tracing::info!("app_settings = {:#?}", app_settings);
 
-  let app_settings = load_app_config().unwrap();
+  let pool = MySqlPool::connect(&app_settings.database.url).await?;
   let aws_client = create_aws_client(&app_settings.aws).await;
-  let egg = EventStoreForDynamoDB::new(
-    aws_client,
-    app_settings.persistence.journal_table_name.clone(),
-    app_settings.persistence.journal_aid_index_name.clone(),
-    app_settings.persistence.snapshot_table_name.clone(),
-    app_settings.persistence.snapshot_aid_index_name.clone(),
-    app_settings.persistence.shard_count,
+  let payload_cipher = app_settings.payload_cipher.create_payload_cipher()?;
+  let idempotency_store = app_settings.idempotency.create_idempotency_store(aws_client.clone())?;
+  let readiness_checker = Arc::new(
+    ReadinessChecker::new(&app_settings.api.health)
+      .with_check(Arc::new(DynamoDbTableHealthCheck::new(
+        aws_client.clone(),
+        "journal",
+        &app_settings.persistence.journal_table_name,
+      )))
+      .with_check(Arc::new(DynamoDbTableHealthCheck::new(
+        aws_client.clone(),
+        "snapshot",
+        &app_settings.persistence.snapshot_table_name,
+      )))
+      .with_check(Arc::new(MySqlHealthCheck::new(pool.clone()))),
+  );
+  let egg = with_group_chat_serializers(
+    MultiEventStoreForDynamoDB::new(
+      aws_client,
+      app_settings.persistence.journal_table_name.clone(),
+      app_settings.persistence.journal_aid_index_name.clone(),
+      app_settings.persistence.snapshot_table_name.clone(),
+      app_settings.persistence.snapshot_aid_index_name.clone(),
+      app_settings.persistence.shard_count,
+    ),
+    payload_cipher.clone(),
   );
   let repository = GroupChatRepositoryImpl::new(egg, app_settings.persistence.snapshot_interval);
+  let processor = GroupChatCommandProcessor::new_with_config(repository, app_settings.command.to_processor_config())
+    .with_payload_cipher(payload_cipher)
+    .with_middleware(Arc::new(LoggingMiddleware))
+    .with_middleware(Arc::new(MetricsMiddleware))
+    .with_middleware(Arc::new(ValidationMiddleware))
+    .with_middleware(Arc::new(app_settings.command.to_authorization_middleware()?));
+  let user_account_group_chat_dao = Arc::new(UserAccountGroupChatDaoImpl::new(pool.clone()));
 
-  let route = create_router(repository).layer(create_cors_layer(&app_settings));
+  let ctx = ServiceContext::new(processor, user_account_group_chat_dao).with_idempotency_store(idempotency_store);
+  // NOTE: gRPCのサービスはGraphQL・REST APIと同じGroupChatCommandProcessorとIdempotencyStoreを共有する
+  let grpc_service = GroupChatCommandGrpcService::new(
+    ctx.get_group_chat_command_processor(),
+    ctx.get_user_account_group_chat_dao(),
+    ctx.get_idempotency_store(),
+  );
 
+  let route = create_router_with_context(ctx, readiness_checker, &app_settings.api.graphql)?
+    .layer(create_cors_layer(&app_settings));
+
+  let shutdown_signal = ShutdownSignal::listen();
   let socket_addr = SocketAddr::new(IpAddr::from_str(&app_settings.api.host).unwrap(), app_settings.api.port);
   tracing::info!("Server listening on http://{}", socket_addr);
-  axum::Server::bind(&socket_addr)
+  // NOTE: シグナルを受けたら新しい接続を受け付けず、処理中のリクエスト(ミューテーション)の完了を待つ
+  let server = axum::Server::bind(&socket_addr)
     .serve(route.into_make_service())
-    .await?;
+    .with_graceful_shutdown(shutdown_signal.clone().triggered());
+
+  let servers = async {
+    match &app_settings.grpc {
+      Some(grpc_settings) => {
+        let grpc_socket_addr = SocketAddr::new(IpAddr::from_str(&grpc_settings.host).unwrap(), grpc_settings.port);
+        tracing::info!("gRPC server listening on http://{}", grpc_socket_addr);
+        let grpc_server = tonic::transport::Server::builder()
+          .add_service(grpc_service.into_server())
+          .serve_with_shutdown(grpc_socket_addr, shutdown_signal.clone().triggered());
+        tokio::try_join!(async { server.await.map_err(anyhow::Error::from) }, async {
+          grpc_server.await.map_err(anyhow::Error::from)
+        },)?;
+      }
+      None => server.await?,
+    }
+    Ok::<(), anyhow::Error>(())
+  };
+  tokio::select! {
+    result = servers => result?,
+    _ = shutdown_signal.clone().deadline(app_settings.shutdown.grace_period()) => {}
+  }
+
+  pool.close().await;
+  tracing::info!("Server stopped");
   Ok(())
 }
 
@@ -89,7 +249,7 @@ fn create_cors_layer(app_settings: &AppSettings) -> CorsLayer {
 
   CorsLayer::new()
     .allow_origin(origins)
-    .allow_headers(vec![CONTENT_TYPE])
+    .allow_headers(vec![CONTENT_TYPE, HeaderName::from_static(IDEMPOTENCY_KEY_HEADER)])
     .allow_methods(AllowMethods::any())
 }
 
@@ -103,7 +263,6 @@ fn load_app_config() -> Result<AppSettings> {
     .add_source(config::File::with_name("config/write-api-server").required(false))
     .add_source(source)
     .build()?;
-  tracing::info!("config = {:#?}", config);
   let app_config = config.try_deserialize()?;
   Ok(app_config)
 }
@@ -120,22 +279,19 @@ async fn create_aws_client(aws_settings: &AwsSettings) -> Client {
     config_loader = config_loader.endpoint_url(endpoint_url);
   }
 
-  match (
+  if let (Some(access_key_id), Some(secret_access_key)) = (
     aws_settings.access_key_id.clone(),
     aws_settings.secret_access_key.clone(),
   ) {
-    (Some(access_key_id), Some(secret_access_key)) => {
-      tracing::info!("access_key_id = {}", access_key_id);
-      tracing::info!("secret_access_key = {}", secret_access_key);
-      config_loader = config_loader.credentials_provider(Credentials::new(
-        access_key_id,
-        secret_access_key,
-        None,
-        None,
-        "default",
-      ));
-    }
-    _ => {}
+    tracing::info!("access_key_id = {}", access_key_id);
+    tracing::info!("secret_access_key = {}", secret_access_key);
+    config_loader = config_loader.credentials_provider(Credentials::new(
+      access_key_id,
+      secret_access_key,
+      None,
+      None,
+      "default",
+    ));
   }
 
   let config = config_loader.load().await;
@@ -1,35 +1,50 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use event_store_adapter_rs::types::Aggregate;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use ulid_generator_rs::ULIDError;
 
+pub use crate::group_chat::chat_kind::ChatKind;
 use crate::group_chat::events::GroupChatEventMessageEditedBody;
 pub use crate::group_chat::events::{
-  GroupChatEvent, GroupChatEventCreatedBody, GroupChatEventDeletedBody, GroupChatEventMemberAddedBody,
-  GroupChatEventMemberRemovedBody, GroupChatEventMessageDeletedBody, GroupChatEventMessagePostedBody,
-  GroupChatEventRenamedBody,
+  GroupChatEvent, GroupChatEventCreatedBody, GroupChatEventDeletedBody, GroupChatEventInvitationAcceptedBody,
+  GroupChatEventInvitationDeclinedBody, GroupChatEventInvitationRevokedBody, GroupChatEventMemberAddedBody,
+  GroupChatEventMemberInvitedBody, GroupChatEventMemberLeftBody, GroupChatEventMemberRemovedBody,
+  GroupChatEventMessageDeletedBody, GroupChatEventMessageExpiredBody, GroupChatEventMessageModeratedBody,
+  GroupChatEventMessagePostedBody, GroupChatEventMessageRestoredBody, GroupChatEventRenamedBody,
+  GroupChatEventSettingsUpdatedBody, GroupChatEventUserAccountErasedBody,
 };
 pub use crate::group_chat::group_chat_id::GroupChatId;
-pub use crate::group_chat::group_chat_name::GroupChatName;
+pub use crate::group_chat::group_chat_name::{GroupChatName, GroupChatNameError};
+pub use crate::group_chat::group_chat_settings::{GroupChatSettings, GroupChatSettingsError, Permission};
+pub use crate::group_chat::invitation::Invitation;
+pub use crate::group_chat::invitations::Invitations;
+pub use crate::group_chat::last_administrator_policy::LastAdministratorPolicy;
 pub use crate::group_chat::member::Member;
 pub use crate::group_chat::member_id::MemberId;
 pub use crate::group_chat::member_role::MemberRole;
 pub use crate::group_chat::members::Members;
-pub use crate::group_chat::message::Message;
+pub use crate::group_chat::message::{Message, MessageError};
+pub use crate::group_chat::message_body::MessageBody;
 pub use crate::group_chat::message_id::MessageId;
 pub use crate::group_chat::messages::Messages;
 use crate::group_chat_error::GroupChatError;
 use crate::user_account::UserAccountId;
 
+mod chat_kind;
 mod events;
 mod group_chat_id;
 mod group_chat_name;
+mod group_chat_settings;
+mod invitation;
+mod invitations;
+mod last_administrator_policy;
 mod member;
 mod member_id;
 mod member_role;
 mod members;
 mod message;
+mod message_body;
 mod message_id;
 mod messages;
 
@@ -39,8 +54,15 @@ pub enum ParseError {
   InvalidULID(#[from] ULIDError),
   #[error("invalid Role: {0}")]
   InvalidRole(String),
+  #[error("invalid ChatKind: {0}")]
+  InvalidChatKind(String),
+  #[error("invalid Permission: {0}")]
+  InvalidPermission(String),
 }
 
+/// 1対1のチャットの名前
+const DIRECT_CHAT_NAME: &str = "direct";
+
 /// [Message]をやりとりする場であるグループチャットを表すモデル。
 ///
 /// NOTE: Serialize, Deserializeをドメインモデルに適用することはレイヤーの責務違反になるので
@@ -52,9 +74,15 @@ pub enum ParseError {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GroupChat {
   id: GroupChatId,
+  #[serde(default)]
+  kind: ChatKind,
   deleted: bool,
   name: GroupChatName,
   members: Members,
+  #[serde(default)]
+  invitations: Invitations,
+  #[serde(default)]
+  settings: GroupChatSettings,
   messages: Messages,
   seq_nr_counter: usize,
   version: usize,
@@ -135,9 +163,12 @@ impl GroupChat {
   ) -> (Self, GroupChatEvent) {
     let mut my_self = Self {
       id: id.clone(),
+      kind: ChatKind::Group,
       deleted,
       name: name.clone(),
       members: members.clone(),
+      invitations: Invitations::new(),
+      settings: GroupChatSettings::default(),
       messages: Messages::new([]),
       seq_nr_counter,
       version,
@@ -153,6 +184,46 @@ impl GroupChat {
     (my_self, event)
   }
 
+  /// 1対1のチャットを生成する
+  ///
+  /// NOTE: IDは2人のユーザアカウントIDから決定的に生成されるため、同じ2人の組み合わせに対するチャットは常に1つとなる。
+  ///
+  /// # 引数
+  /// - `user_account_id1`: 1人目のユーザアカウントID
+  /// - `user_account_id2`: 2人目のユーザアカウントID
+  ///
+  /// # 戻り値
+  /// - 2人のユーザアカウントIDが同じ場合はエラーを返す。
+  /// - 成功した場合は、[GroupChat]とGroupChatCreatedイベントを返す。
+  pub fn new_direct(
+    user_account_id1: UserAccountId,
+    user_account_id2: UserAccountId,
+  ) -> Result<(Self, GroupChatEvent), GroupChatError> {
+    if user_account_id1 == user_account_id2 {
+      return Err(GroupChatError::DirectChatWithSelfError(user_account_id1));
+    }
+    let id = GroupChatId::direct(&user_account_id1, &user_account_id2);
+    let name = GroupChatName::new(DIRECT_CHAT_NAME).unwrap();
+    let members = Members::new_direct(user_account_id1, user_account_id2);
+    let my_self = Self {
+      id: id.clone(),
+      kind: ChatKind::Direct,
+      deleted: false,
+      name: name.clone(),
+      members: members.clone(),
+      invitations: Invitations::new(),
+      settings: GroupChatSettings::default(),
+      messages: Messages::new([]),
+      seq_nr_counter: 1,
+      version: 1,
+      last_updated_at: Utc::now(),
+    };
+    let event = GroupChatEvent::GroupChatCreated(
+      GroupChatEventCreatedBody::new(id, my_self.seq_nr_counter, name, members).with_kind(ChatKind::Direct),
+    );
+    Ok((my_self, event))
+  }
+
   /// 既存のインスタンスにイベントを適用する。
   ///
   /// # 引数
@@ -165,34 +236,102 @@ impl GroupChat {
       GroupChatEvent::GroupChatRenamed(body) => {
         self.rename(body.name.clone(), body.executor_id.clone()).unwrap();
       }
-      GroupChatEvent::GroupChatMemberAdded(body) => {
+      GroupChatEvent::GroupChatSettingsUpdated(body) => {
         self
-          .add_member(
-            body.member.breach_encapsulation_of_id().clone(),
-            body.member.breach_encapsulation_of_user_account_id().clone(),
-            body.member.breach_encapsulation_of_role().clone(),
-            body.executor_id.clone(),
-          )
+          .update_settings(body.settings.clone(), body.executor_id.clone())
           .unwrap();
       }
+      GroupChatEvent::GroupChatMemberAdded(body) => {
+        // NOTE: 権限やメンバー数の上限は後から追加・変更されることがあるので、再生時にはチェックせずに状態だけを反映する
+        self.members.add_member(body.member.clone());
+        self.seq_nr_counter += 1;
+      }
       GroupChatEvent::GroupChatMemberRemoved(body) => {
         self
           .remove_member(body.user_account_id.clone(), body.executor_id.clone())
           .unwrap();
       }
-      GroupChatEvent::GroupChatMessagePosted(body) => {
+      GroupChatEvent::GroupChatMemberLeft(body) => {
+        // NOTE: 最後の管理者の退出はGroupChatDeletedイベントになるか拒否されるので、再生時の方針は結果に影響しない
         self
-          .post_message(body.message.clone(), body.executor_id.clone())
+          .leave(body.user_account_id.clone(), LastAdministratorPolicy::RequireTransfer)
           .unwrap();
       }
+      GroupChatEvent::GroupChatMessagePosted(body) => {
+        // NOTE: 投稿日時を持たない過去のイベントは、イベントの発生日時を投稿日時とみなす
+        let message = match body.message.breach_encapsulation_of_posted_at() {
+          Some(_) => body.message.clone(),
+          None => body.message.clone().with_posted_at(body.occurred_at),
+        };
+        // NOTE: 投稿権限やメンションのチェックはコマンド実行時に済んでいるので、再生時には行わない
+        self.messages.add(message).unwrap();
+        self.seq_nr_counter += 1;
+      }
       GroupChatEvent::GroupChatMessageEdited(body) => {
+        // NOTE: 編集可能期間のチェックはコマンド実行時に済んでいるので、再生時には行わない
         self
-          .edit_message(body.message.clone(), body.executor_id.clone())
+          .edit_message(body.message.clone(), body.executor_id.clone(), Duration::max_value())
           .unwrap();
       }
       GroupChatEvent::GroupChatMessageDeleted(body) => {
         self
-          .delete_message(body.message_id.clone(), body.executor_id.clone())
+          .delete_message_at(body.message_id.clone(), body.executor_id.clone(), body.occurred_at)
+          .unwrap();
+      }
+      GroupChatEvent::GroupChatMessageRestored(body) => {
+        // NOTE: 復元可能期間のチェックはコマンド実行時に済んでいるので、再生時には行わない
+        self
+          .restore_message(body.message_id.clone(), body.executor_id.clone(), Duration::max_value())
+          .unwrap();
+      }
+      GroupChatEvent::GroupChatMessageModerated(body) => {
+        self
+          .moderate_message_at(
+            body.message_id.clone(),
+            body.reason.clone(),
+            body.executor_id.clone(),
+            body.occurred_at,
+          )
+          .unwrap();
+      }
+      GroupChatEvent::GroupChatMessageExpired(body) => {
+        // NOTE: 保持期間のチェックはコマンド実行時に済んでいるので、再生時には行わない
+        self
+          .expire_message_at(
+            body.message_id.clone(),
+            Some(Duration::zero()),
+            DateTime::<Utc>::MAX_UTC,
+          )
+          .unwrap();
+      }
+      GroupChatEvent::GroupChatUserAccountErased(body) => {
+        self
+          .erase_user_account(body.user_account_id.clone(), body.executor_id.clone())
+          .unwrap();
+      }
+      GroupChatEvent::GroupChatMemberInvited(body) => {
+        // NOTE: 権限やメンバー数の上限はコマンド実行時にチェック済みなので、再生時には行わない
+        self.invitations.add(body.invitation.clone());
+        self.seq_nr_counter += 1;
+      }
+      GroupChatEvent::GroupChatInvitationAccepted(body) => {
+        // NOTE: 招待の有効期限やメンバー数の上限のチェックはコマンド実行時に済んでいるので、再生時には行わない
+        self.invitations.remove(&body.executor_id);
+        self.members.add_member(body.member.clone());
+        self.seq_nr_counter += 1;
+      }
+      GroupChatEvent::GroupChatInvitationDeclined(body) => {
+        self
+          .decline_invitation_at(body.executor_id.clone(), DateTime::<Utc>::MIN_UTC)
+          .unwrap();
+      }
+      GroupChatEvent::GroupChatInvitationRevoked(body) => {
+        self
+          .revoke_invitation_at(
+            body.user_account_id.clone(),
+            body.executor_id.clone(),
+            DateTime::<Utc>::MIN_UTC,
+          )
           .unwrap();
       }
       _ => {}
@@ -218,6 +357,11 @@ impl GroupChat {
     })
   }
 
+  /// [ChatKind]の参照を返す。
+  pub fn kind(&self) -> &ChatKind {
+    &self.kind
+  }
+
   /// [GroupChatName]の参照を返す。
   pub fn name(&self) -> &GroupChatName {
     &self.name
@@ -228,6 +372,16 @@ impl GroupChat {
     &self.members
   }
 
+  /// 保留中の招待([Invitations])の参照を返す
+  pub fn invitations(&self) -> &Invitations {
+    &self.invitations
+  }
+
+  /// [GroupChatSettings]の参照を返す
+  pub fn settings(&self) -> &GroupChatSettings {
+    &self.settings
+  }
+
   /// [Messages]の参照を返す
   pub fn messages(&self) -> &Messages {
     &self.messages
@@ -248,6 +402,7 @@ impl GroupChat {
     if self.deleted {
       return Err(GroupChatError::AlreadyDeletedError(self.id.clone()));
     }
+    self.ensure_group("rename")?;
     if !self.members.is_member(&executor_id) {
       return Err(GroupChatError::NotMemberError("executor_id".to_string(), executor_id));
     }
@@ -270,6 +425,46 @@ impl GroupChat {
     )))
   }
 
+  /// グループチャットの設定を更新する
+  ///
+  /// # 引数
+  /// - settings: 新しい[GroupChatSettings]
+  /// - executor_id: 実行者のユーザアカウントID
+  ///
+  /// # 戻り値
+  /// - グループチャットが削除されている場合はエラーを返す。
+  /// - 1対1のチャットの場合はエラーを返す。
+  /// - 実行者が管理者でない場合はエラーを返す。
+  /// - メンバー数の上限が現在のメンバー数を下回る場合はエラーを返す。
+  /// - 成功した場合は、GroupChatSettingsUpdatedイベントを返す。
+  pub fn update_settings(
+    &mut self,
+    settings: GroupChatSettings,
+    executor_id: UserAccountId,
+  ) -> Result<GroupChatEvent, GroupChatError> {
+    if self.deleted {
+      return Err(GroupChatError::AlreadyDeletedError(self.id.clone()));
+    }
+    self.ensure_group("update_settings")?;
+    if !self.members.is_administrator(&executor_id) {
+      return Err(GroupChatError::NotAdministratorError(
+        "executor_id".to_string(),
+        executor_id,
+      ));
+    }
+    if settings.max_members() < self.members.count() {
+      return Err(GroupChatError::MaxMembersBelowCurrentError(
+        settings.max_members(),
+        self.members.count(),
+      ));
+    }
+    self.settings = settings.clone();
+    self.seq_nr_counter += 1;
+    Ok(GroupChatEvent::GroupChatSettingsUpdated(
+      GroupChatEventSettingsUpdatedBody::new(self.id.clone(), self.seq_nr_counter, settings, executor_id),
+    ))
+  }
+
   /// グループチャットにメンバーを追加する
   ///
   /// # 引数
@@ -280,9 +475,13 @@ impl GroupChat {
   ///
   /// # 戻り値
   /// - グループチャットが削除されている場合はエラーを返す。
-  /// - 実行者が管理者でない場合はエラーを返す。
+  /// - 1対1のチャットの場合はエラーを返す。
+  /// - 実行者にメンバーを追加する権限がない場合はエラーを返す。
   /// - ユーザアカウントIDが既にメンバーに設定されている場合はエラーを返す。
+  /// - メンバー数が上限に達している場合はエラーを返す。
   /// - 成功した場合は、GroupChatMemberAddedイベントを返す。
+  ///
+  /// NOTE: イベントには参加通知のシステムメッセージが含まれる。通知は読み込みモデルにのみ反映され、集約の[Messages]には含まれない。
   pub fn add_member(
     &mut self,
     member_id: MemberId,
@@ -293,23 +492,24 @@ impl GroupChat {
     if self.deleted {
       return Err(GroupChatError::AlreadyDeletedError(self.id.clone()));
     }
-    if !self.members.is_administrator(&executor_id) {
-      return Err(GroupChatError::NotAdministratorError(
-        "executor_id".to_string(),
-        executor_id,
-      ));
-    }
+    self.ensure_group("add_member")?;
+    self.ensure_can_add_member(&executor_id, &role)?;
     if self.members.is_member(&user_account_id) {
       return Err(GroupChatError::AlreadyMemberError(
         "user_account_id".to_string(),
         user_account_id,
       ));
     }
+    self.ensure_member_capacity()?;
+    let notice = Self::system_notice(
+      format!("{} joined the group chat", user_account_id),
+      user_account_id.clone(),
+    );
     let member = Member::new(member_id, user_account_id, role);
     self.members.add_member(member.clone());
     self.seq_nr_counter += 1;
     Ok(GroupChatEvent::GroupChatMemberAdded(
-      GroupChatEventMemberAddedBody::new(self.id.clone(), self.seq_nr_counter, member, executor_id),
+      GroupChatEventMemberAddedBody::new(self.id.clone(), self.seq_nr_counter, member, executor_id).with_notice(notice),
     ))
   }
 
@@ -321,9 +521,12 @@ impl GroupChat {
   ///
   /// # 戻り値
   /// - グループチャットが削除されている場合はエラーを返す。
+  /// - 1対1のチャットの場合はエラーを返す。
   /// - 実行者が管理者でない場合はエラーを返す。
   /// - ユーザアカウントIDがメンバーに設定されていない場合はエラーを返す。
   /// - 成功した場合は、GroupChatMemberRemovedイベントを返す。
+  ///
+  /// NOTE: イベントには退出通知のシステムメッセージが含まれる。
   pub fn remove_member(
     &mut self,
     user_account_id: UserAccountId,
@@ -332,6 +535,7 @@ impl GroupChat {
     if self.deleted {
       return Err(GroupChatError::AlreadyDeletedError(self.id.clone()));
     }
+    self.ensure_group("remove_member")?;
     if !self.members.is_administrator(&executor_id) {
       return Err(GroupChatError::NotAdministratorError(
         "executor_id".to_string(),
@@ -344,138 +548,220 @@ impl GroupChat {
         user_account_id,
       ));
     }
+    let notice = Self::system_notice(
+      format!("{} was removed from the group chat", user_account_id),
+      user_account_id.clone(),
+    );
     self.members.remove_member_by_user_account_id(&user_account_id);
     self.seq_nr_counter += 1;
     Ok(GroupChatEvent::GroupChatMemberRemoved(
-      GroupChatEventMemberRemovedBody::new(self.id.clone(), self.seq_nr_counter, user_account_id, executor_id),
+      GroupChatEventMemberRemovedBody::new(self.id.clone(), self.seq_nr_counter, user_account_id, executor_id)
+        .with_notice(notice),
     ))
   }
 
-  /// グループチャットにメッセージを投稿する
+  /// グループチャットから実行者自身が退出する
   ///
   /// # 引数
-  /// - message: メッセージ
-  /// - executor_id: 実行者のユーザアカウントID
+  /// - executor_id: 退出するユーザアカウントID
+  /// - policy: 実行者が最後の管理者だった場合の扱い
   ///
   /// # 戻り値
   /// - グループチャットが削除されている場合はエラーを返す。
-  /// - 実行者がメッセージの送信者でない場合はエラーを返す。
+  /// - 1対1のチャットの場合はエラーを返す。
   /// - 実行者がメンバーでない場合はエラーを返す。
-  /// - メッセージIDが既に存在する場合はエラーを返す。
-  /// - 成功した場合は、GroupChatMessagePostedイベントを返す。
-  pub fn post_message(
+  /// - 実行者が最後の管理者で`policy`が[LastAdministratorPolicy::RequireTransfer]の場合はエラーを返す。
+  /// - 実行者が最後の管理者で`policy`が[LastAdministratorPolicy::DeleteGroupChat]の場合は、GroupChatDeletedイベントを返す。
+  /// - 成功した場合は、GroupChatMemberLeftイベントを返す。
+  pub fn leave(
     &mut self,
-    message: Message,
     executor_id: UserAccountId,
+    policy: LastAdministratorPolicy,
   ) -> Result<GroupChatEvent, GroupChatError> {
     if self.deleted {
       return Err(GroupChatError::AlreadyDeletedError(self.id.clone()));
     }
+    self.ensure_group("leave")?;
     if !self.members.is_member(&executor_id) {
       return Err(GroupChatError::NotMemberError("executor_id".to_string(), executor_id));
     }
-    if executor_id != message.breach_encapsulation_of_sender_id().clone() {
-      return Err(GroupChatError::MismatchedUserAccountError(
-        "executor_id".to_string(),
-        "sender_id".to_string(),
-      ));
+    if self.members.is_last_administrator(&executor_id) {
+      return match policy {
+        LastAdministratorPolicy::RequireTransfer => Err(GroupChatError::LastAdministratorError(executor_id)),
+        LastAdministratorPolicy::DeleteGroupChat => self.delete(executor_id),
+      };
     }
-    self.messages.add(message.clone())?;
+    let notice = Self::system_notice(format!("{} left the group chat", executor_id), executor_id.clone());
+    self.members.remove_member_by_user_account_id(&executor_id);
     self.seq_nr_counter += 1;
-    Ok(GroupChatEvent::GroupChatMessagePosted(
-      GroupChatEventMessagePostedBody::new(self.id.clone(), self.seq_nr_counter, message, executor_id),
+    Ok(GroupChatEvent::GroupChatMemberLeft(
+      GroupChatEventMemberLeftBody::new(self.id.clone(), self.seq_nr_counter, executor_id).with_notice(notice),
     ))
   }
 
-  /// グループチャットのメッセージを編集する
+  /// ユーザアカウントをグループチャットに招待する
   ///
   /// # 引数
-  /// - message: メッセージ
+  /// - user_account_id: 招待するユーザアカウントID
+  /// - role: 参加後のメンバーの役割
   /// - executor_id: 実行者のユーザアカウントID
+  /// - ttl: 招待の有効期間
   ///
   /// # 戻り値
   /// - グループチャットが削除されている場合はエラーを返す。
-  /// - 実行者がメッセージの送信者でない場合はエラーを返す。
-  /// - 実行者がメンバーでない場合はエラーを返す。
-  /// - メッセージIDが既に存在する場合はエラーを返す。
-  /// - 成功した場合は、GroupChatMessagePostedイベントを返す。
-  pub fn edit_message(
+  /// - 1対1のチャットの場合はエラーを返す。
+  /// - 実行者にメンバーを追加する権限がない場合はエラーを返す。
+  /// - ユーザアカウントIDが既にメンバーに設定されている場合はエラーを返す。
+  /// - メンバー数が上限に達している場合はエラーを返す。
+  /// - ユーザアカウントIDへの有効な招待が既に存在する場合はエラーを返す。
+  /// - 成功した場合は、GroupChatMemberInvitedイベントを返す。
+  pub fn invite_member(
     &mut self,
-    message: Message,
+    user_account_id: UserAccountId,
+    role: MemberRole,
+    executor_id: UserAccountId,
+    ttl: Duration,
+  ) -> Result<GroupChatEvent, GroupChatError> {
+    let invited_at = Utc::now();
+    let expires_at = invited_at.checked_add_signed(ttl).unwrap_or(DateTime::<Utc>::MAX_UTC);
+    let invitation = Invitation::new(user_account_id, role, executor_id.clone(), invited_at, expires_at);
+    self.add_invitation(invitation, executor_id)
+  }
+
+  fn add_invitation(
+    &mut self,
+    invitation: Invitation,
     executor_id: UserAccountId,
   ) -> Result<GroupChatEvent, GroupChatError> {
     if self.deleted {
       return Err(GroupChatError::AlreadyDeletedError(self.id.clone()));
     }
-    if !self.members.is_member(&executor_id) {
-      return Err(GroupChatError::NotMemberError("executor_id".to_string(), executor_id));
+    self.ensure_group("invite_member")?;
+    self.ensure_can_add_member(&executor_id, invitation.breach_encapsulation_of_role())?;
+    let user_account_id = invitation.breach_encapsulation_of_user_account_id();
+    if self.members.is_member(user_account_id) {
+      return Err(GroupChatError::AlreadyMemberError(
+        "user_account_id".to_string(),
+        user_account_id.clone(),
+      ));
     }
-    if executor_id != message.breach_encapsulation_of_sender_id().clone() {
-      return Err(GroupChatError::MismatchedUserAccountError(
-        "executor_id".to_string(),
-        "sender_id".to_string(),
+    self.ensure_member_capacity()?;
+    if self
+      .invitations
+      .find_pending(user_account_id, invitation.breach_encapsulation_of_invited_at())
+      .is_some()
+    {
+      return Err(GroupChatError::AlreadyInvitedError(
+        "user_account_id".to_string(),
+        user_account_id.clone(),
       ));
     }
-    self.messages.edit(message.clone())?;
+    self.invitations.add(invitation.clone());
     self.seq_nr_counter += 1;
-    Ok(GroupChatEvent::GroupChatMessageEdited(
-      GroupChatEventMessageEditedBody::new(self.id.clone(), self.seq_nr_counter, message, executor_id),
+    Ok(GroupChatEvent::GroupChatMemberInvited(
+      GroupChatEventMemberInvitedBody::new(self.id.clone(), self.seq_nr_counter, invitation, executor_id),
     ))
   }
 
-  /// メッセージを削除する
+  /// 招待を承諾してメンバーになる
   ///
   /// # 引数
-  /// - message_id: メッセージID
-  /// - executor_id: 実行者のユーザアカウントID
+  /// - member_id: メンバーID
+  /// - executor_id: 実行者(招待されたユーザアカウント)のユーザアカウントID
   ///
   /// # 戻り値
   /// - グループチャットが削除されている場合はエラーを返す。
-  /// - 実行者がメッセージの送信者でない場合はエラーを返す。
-  /// - 実行者がメンバーでない場合はエラーを返す。
-  /// - メッセージIDが存在しない場合はエラーを返す。
-  /// - 成功した場合は、GroupChatMessageDeletedイベントを返す。
-  pub fn delete_message(
+  /// - 実行者への有効な招待が存在しない場合はエラーを返す。
+  /// - 成功した場合は、GroupChatInvitationAcceptedイベントを返す。
+  pub fn accept_invitation(
     &mut self,
-    message_id: MessageId,
+    member_id: MemberId,
+    executor_id: UserAccountId,
+  ) -> Result<GroupChatEvent, GroupChatError> {
+    self.accept_invitation_at(member_id, executor_id, Utc::now())
+  }
+
+  fn accept_invitation_at(
+    &mut self,
+    member_id: MemberId,
     executor_id: UserAccountId,
+    now: DateTime<Utc>,
   ) -> Result<GroupChatEvent, GroupChatError> {
     if self.deleted {
       return Err(GroupChatError::AlreadyDeletedError(self.id.clone()));
     }
-    if !self.members.is_member(&executor_id) {
-      return Err(GroupChatError::NotMemberError("executor_id".to_string(), executor_id));
+    let role = match self.invitations.find_pending(&executor_id, &now) {
+      Some(invitation) => invitation.breach_encapsulation_of_role().clone(),
+      None => return Err(GroupChatError::NotFoundInvitationError(executor_id)),
+    };
+    self.ensure_member_capacity()?;
+    self.invitations.remove(&executor_id);
+    let notice = Self::system_notice(format!("{} joined the group chat", executor_id), executor_id.clone());
+    let member = Member::new(member_id, executor_id.clone(), role);
+    self.members.add_member(member.clone());
+    self.seq_nr_counter += 1;
+    Ok(GroupChatEvent::GroupChatInvitationAccepted(
+      GroupChatEventInvitationAcceptedBody::new(self.id.clone(), self.seq_nr_counter, member, executor_id)
+        .with_notice(notice),
+    ))
+  }
+
+  /// 招待を辞退する
+  ///
+  /// # 引数
+  /// - executor_id: 実行者(招待されたユーザアカウント)のユーザアカウントID
+  ///
+  /// # 戻り値
+  /// - グループチャットが削除されている場合はエラーを返す。
+  /// - 実行者への有効な招待が存在しない場合はエラーを返す。
+  /// - 成功した場合は、GroupChatInvitationDeclinedイベントを返す。
+  pub fn decline_invitation(&mut self, executor_id: UserAccountId) -> Result<GroupChatEvent, GroupChatError> {
+    self.decline_invitation_at(executor_id, Utc::now())
+  }
+
+  fn decline_invitation_at(
+    &mut self,
+    executor_id: UserAccountId,
+    now: DateTime<Utc>,
+  ) -> Result<GroupChatEvent, GroupChatError> {
+    if self.deleted {
+      return Err(GroupChatError::AlreadyDeletedError(self.id.clone()));
     }
-    let result = self.messages.find_by_id(&message_id);
-    match result {
-      None => Err(GroupChatError::NotFoundMessageError(message_id)),
-      Some(message) => {
-        let member = self
-          .members
-          .find_by_user_account_id(message.breach_encapsulation_of_sender_id())
-          .unwrap();
-        if *member.breach_encapsulation_of_user_account_id() != executor_id {
-          return Err(GroupChatError::NotSenderError("executor_id".to_string(), executor_id));
-        }
-        self.messages.remove(&message_id, &executor_id).unwrap();
-        self.seq_nr_counter += 1;
-        Ok(GroupChatEvent::GroupChatMessageDeleted(
-          GroupChatEventMessageDeletedBody::new(self.id.clone(), self.seq_nr_counter, message_id, executor_id),
-        ))
-      }
+    if self.invitations.find_pending(&executor_id, &now).is_none() {
+      return Err(GroupChatError::NotFoundInvitationError(executor_id));
     }
+    self.invitations.remove(&executor_id);
+    self.seq_nr_counter += 1;
+    Ok(GroupChatEvent::GroupChatInvitationDeclined(
+      GroupChatEventInvitationDeclinedBody::new(self.id.clone(), self.seq_nr_counter, executor_id.clone(), executor_id),
+    ))
   }
 
-  /// グループチャットを削除する
+  /// 招待を取り消す
   ///
   /// # 引数
+  /// - user_account_id: 招待されたユーザアカウントID
   /// - executor_id: 実行者のユーザアカウントID
   ///
   /// # 戻り値
   /// - グループチャットが削除されている場合はエラーを返す。
   /// - 実行者が管理者でない場合はエラーを返す。
-  /// - 成功した場合は、GroupChatDeletedイベントを返す。
-  pub fn delete(&mut self, executor_id: UserAccountId) -> Result<GroupChatEvent, GroupChatError> {
+  /// - ユーザアカウントIDへの有効な招待が存在しない場合はエラーを返す。
+  /// - 成功した場合は、GroupChatInvitationRevokedイベントを返す。
+  pub fn revoke_invitation(
+    &mut self,
+    user_account_id: UserAccountId,
+    executor_id: UserAccountId,
+  ) -> Result<GroupChatEvent, GroupChatError> {
+    self.revoke_invitation_at(user_account_id, executor_id, Utc::now())
+  }
+
+  fn revoke_invitation_at(
+    &mut self,
+    user_account_id: UserAccountId,
+    executor_id: UserAccountId,
+    now: DateTime<Utc>,
+  ) -> Result<GroupChatEvent, GroupChatError> {
     if self.deleted {
       return Err(GroupChatError::AlreadyDeletedError(self.id.clone()));
     }
@@ -485,63 +771,680 @@ impl GroupChat {
         executor_id,
       ));
     }
-    self.deleted = true;
+    if self.invitations.find_pending(&user_account_id, &now).is_none() {
+      return Err(GroupChatError::NotFoundInvitationError(user_account_id));
+    }
+    self.invitations.remove(&user_account_id);
     self.seq_nr_counter += 1;
-    Ok(GroupChatEvent::GroupChatDeleted(GroupChatEventDeletedBody::new(
-      self.id.clone(),
-      self.seq_nr_counter,
-      executor_id,
-    )))
+    Ok(GroupChatEvent::GroupChatInvitationRevoked(
+      GroupChatEventInvitationRevokedBody::new(self.id.clone(), self.seq_nr_counter, user_account_id, executor_id),
+    ))
   }
-}
-
-#[cfg(test)]
-mod tests {
-  use super::*;
 
-  #[test]
-  fn test_delete_group_chat() {
-    let group_chat_name = GroupChatName::new("test").unwrap();
-    let admin_user_account_id = UserAccountId::new();
-    let members = Members::new(admin_user_account_id.clone());
-    let (mut group_chat, _) = GroupChat::new(group_chat_name.clone(), members);
-    let user_account_id = UserAccountId::new();
-    let member_id = MemberId::new();
+  /// グループチャット(1対1のチャットではない)であることを検証する。
+  /// 実行者が[GroupChatSettings]に従ってメンバーを追加できることを検証する。
+  ///
+  /// NOTE: 管理者のロールでメンバーを追加できるのは管理者のみ。
+  fn ensure_can_add_member(&self, executor_id: &UserAccountId, role: &MemberRole) -> Result<(), GroupChatError> {
+    if self.members.is_administrator(executor_id) {
+      return Ok(());
+    }
+    if self.settings.add_member_permission() == Permission::AdminsOnly || *role == MemberRole::Admin {
+      return Err(GroupChatError::NotAdministratorError(
+        "executor_id".to_string(),
+        executor_id.clone(),
+      ));
+    }
+    if !self.members.is_member(executor_id) {
+      return Err(GroupChatError::NotMemberError(
+        "executor_id".to_string(),
+        executor_id.clone(),
+      ));
+    }
+    Ok(())
+  }
 
-    let _ = group_chat
-      .add_member(
-        member_id,
-        user_account_id.clone(),
-        MemberRole::Member,
-        admin_user_account_id.clone(),
-      )
-      .unwrap();
-    assert!(group_chat.members().is_member(&user_account_id));
+  /// メンバー数が[GroupChatSettings]の上限に達していないことを検証する。
+  fn ensure_member_capacity(&self) -> Result<(), GroupChatError> {
+    let max_members = self.settings.max_members();
+    if self.members.count() >= max_members {
+      return Err(GroupChatError::MemberLimitExceededError(self.id.clone(), max_members));
+    }
+    Ok(())
+  }
 
-    let result = group_chat.delete(user_account_id.clone());
-    assert!(result.is_err());
+  fn ensure_group(&self, operation: &str) -> Result<(), GroupChatError> {
+    match self.kind {
+      ChatKind::Group => Ok(()),
+      ChatKind::Direct => Err(GroupChatError::NotSupportedInDirectChatError(
+        operation.to_string(),
+        self.id.clone(),
+      )),
+    }
+  }
 
-    let result = group_chat.delete(admin_user_account_id.clone());
-    assert!(result.is_ok());
+  /// 集約が生成するシステムメッセージを作成する。
+  ///
+  /// # 引数
+  /// - text: 通知の本文
+  /// - user_account_id: 通知の対象となるユーザアカウントID
+  fn system_notice(text: String, user_account_id: UserAccountId) -> Message {
+    Message::new_with_body(MessageId::new(), MessageBody::System { text }, user_account_id).with_posted_at(Utc::now())
   }
 
-  #[test]
-  fn test_rename() {
-    let group_chat_name = GroupChatName::new("test").unwrap();
+  /// グループチャットにメッセージを投稿する
+  ///
+  /// # 引数
+  /// - message: メッセージ
+  /// - executor_id: 実行者のユーザアカウントID
+  ///
+  /// # 戻り値
+  /// - グループチャットが削除されている場合はエラーを返す。
+  /// - 実行者がメッセージの送信者でない場合はエラーを返す。
+  /// - 実行者がメンバーでない場合はエラーを返す。
+  /// - アナウンスモードで実行者が管理者でない場合はエラーを返す。
+  /// - メンションされているユーザアカウントがメンバーでない場合はエラーを返す。
+  /// - システムメッセージの場合はエラーを返す。
+  /// - メッセージIDが既に存在する場合はエラーを返す。
+  /// - 成功した場合は、GroupChatMessagePostedイベントを返す。
+  pub fn post_message(
+    &mut self,
+    message: Message,
+    executor_id: UserAccountId,
+  ) -> Result<GroupChatEvent, GroupChatError> {
+    self.add_message(message.with_posted_at(Utc::now()), executor_id)
+  }
+
+  fn add_message(&mut self, message: Message, executor_id: UserAccountId) -> Result<GroupChatEvent, GroupChatError> {
+    if self.deleted {
+      return Err(GroupChatError::AlreadyDeletedError(self.id.clone()));
+    }
+    if !self.members.is_member(&executor_id) {
+      return Err(GroupChatError::NotMemberError("executor_id".to_string(), executor_id));
+    }
+    if self.settings.post_permission() == Permission::AdminsOnly && !self.members.is_administrator(&executor_id) {
+      return Err(GroupChatError::PostNotAllowedError(
+        "executor_id".to_string(),
+        executor_id,
+      ));
+    }
+    if executor_id != message.breach_encapsulation_of_sender_id().clone() {
+      return Err(GroupChatError::MismatchedUserAccountError(
+        "executor_id".to_string(),
+        "sender_id".to_string(),
+      ));
+    }
+    if message.is_system() {
+      return Err(GroupChatError::SystemMessageError(
+        message.breach_encapsulation_of_id().clone(),
+      ));
+    }
+    self.validate_mentions(&message)?;
+    self.messages.add(message.clone())?;
+    self.seq_nr_counter += 1;
+    Ok(GroupChatEvent::GroupChatMessagePosted(
+      GroupChatEventMessagePostedBody::new(self.id.clone(), self.seq_nr_counter, message, executor_id),
+    ))
+  }
+
+  /// メッセージでメンションされているユーザアカウントがすべてメンバーであることを検証する。
+  fn validate_mentions(&self, message: &Message) -> Result<(), GroupChatError> {
+    match message
+      .breach_encapsulation_of_mentions()
+      .iter()
+      .find(|user_account_id| !self.members.is_member(user_account_id))
+    {
+      Some(user_account_id) => Err(GroupChatError::NotMemberError(
+        "mentions".to_string(),
+        user_account_id.clone(),
+      )),
+      None => Ok(()),
+    }
+  }
+
+  /// グループチャットのメッセージを編集する
+  ///
+  /// # 引数
+  /// - message: メッセージ
+  /// - executor_id: 実行者のユーザアカウントID
+  /// - edit_window: 投稿から編集可能な期間
+  ///
+  /// # 戻り値
+  /// - グループチャットが削除されている場合はエラーを返す。
+  /// - 実行者がメッセージの送信者でない場合はエラーを返す。
+  /// - 実行者がメンバーでない場合はエラーを返す。
+  /// - メッセージIDが既に存在する場合はエラーを返す。
+  /// - メンションされているユーザアカウントがメンバーでない場合はエラーを返す。
+  /// - システムメッセージに置き換えようとした場合はエラーを返す。
+  /// - 投稿から編集可能な期間を過ぎている場合はエラーを返す。
+  /// - 成功した場合は、GroupChatMessagePostedイベントを返す。
+  pub fn edit_message(
+    &mut self,
+    message: Message,
+    executor_id: UserAccountId,
+    edit_window: Duration,
+  ) -> Result<GroupChatEvent, GroupChatError> {
+    if self.deleted {
+      return Err(GroupChatError::AlreadyDeletedError(self.id.clone()));
+    }
+    if !self.members.is_member(&executor_id) {
+      return Err(GroupChatError::NotMemberError("executor_id".to_string(), executor_id));
+    }
+    if executor_id != message.breach_encapsulation_of_sender_id().clone() {
+      return Err(GroupChatError::MismatchedUserAccountError(
+        "executor_id".to_string(),
+        "sender_id".to_string(),
+      ));
+    }
+    if message.is_system() {
+      return Err(GroupChatError::SystemMessageError(
+        message.breach_encapsulation_of_id().clone(),
+      ));
+    }
+    self.validate_mentions(&message)?;
+    let expired = self
+      .messages
+      .find_by_id(message.breach_encapsulation_of_id())
+      .and_then(|m| m.breach_encapsulation_of_posted_at())
+      .and_then(|posted_at| posted_at.checked_add_signed(edit_window))
+      .map(|deadline| deadline < Utc::now())
+      .unwrap_or(false);
+    if expired {
+      return Err(GroupChatError::EditWindowExpiredError(
+        message.breach_encapsulation_of_id().clone(),
+      ));
+    }
+    self.messages.edit(message.clone())?;
+    self.seq_nr_counter += 1;
+    Ok(GroupChatEvent::GroupChatMessageEdited(
+      GroupChatEventMessageEditedBody::new(self.id.clone(), self.seq_nr_counter, message, executor_id),
+    ))
+  }
+
+  /// メッセージを削除する
+  ///
+  /// # 引数
+  /// - message_id: メッセージID
+  /// - executor_id: 実行者のユーザアカウントID
+  ///
+  /// # 戻り値
+  /// - グループチャットが削除されている場合はエラーを返す。
+  /// - 実行者がメッセージの送信者でない場合はエラーを返す。
+  /// - 実行者がメンバーでない場合はエラーを返す。
+  /// - メッセージIDが存在しない場合はエラーを返す。
+  /// - 成功した場合は、GroupChatMessageDeletedイベントを返す。
+  ///
+  /// NOTE: 削除されたメッセージは墓標として集約内に残り、[GroupChat::restore_message]で復元できる。
+  pub fn delete_message(
+    &mut self,
+    message_id: MessageId,
+    executor_id: UserAccountId,
+  ) -> Result<GroupChatEvent, GroupChatError> {
+    self.delete_message_at(message_id, executor_id, Utc::now())
+  }
+
+  fn delete_message_at(
+    &mut self,
+    message_id: MessageId,
+    executor_id: UserAccountId,
+    deleted_at: DateTime<Utc>,
+  ) -> Result<GroupChatEvent, GroupChatError> {
+    if self.deleted {
+      return Err(GroupChatError::AlreadyDeletedError(self.id.clone()));
+    }
+    if !self.members.is_member(&executor_id) {
+      return Err(GroupChatError::NotMemberError("executor_id".to_string(), executor_id));
+    }
+    let result = self.messages.find_by_id(&message_id);
+    match result {
+      None => Err(GroupChatError::NotFoundMessageError(message_id)),
+      Some(message) => {
+        // NOTE: 送信者が退出・削除された後もメッセージは残るので、メンバーではなく送信者のIDと比較する
+        if *message.breach_encapsulation_of_sender_id() != executor_id {
+          return Err(GroupChatError::NotSenderError("executor_id".to_string(), executor_id));
+        }
+        self.messages.remove(&message_id, &executor_id, deleted_at)?;
+        self.seq_nr_counter += 1;
+        Ok(GroupChatEvent::GroupChatMessageDeleted(
+          GroupChatEventMessageDeletedBody::new(self.id.clone(), self.seq_nr_counter, message_id, executor_id),
+        ))
+      }
+    }
+  }
+
+  /// 管理者としてメッセージを削除する(モデレーション)
+  ///
+  /// # 引数
+  /// - message_id: メッセージID
+  /// - reason: 削除理由
+  /// - executor_id: 実行者のユーザアカウントID
+  ///
+  /// # 戻り値
+  /// - グループチャットが削除されている場合はエラーを返す。
+  /// - 実行者がメンバーでない場合はエラーを返す。
+  /// - 実行者が管理者でない場合はエラーを返す。
+  /// - 削除理由が空の場合はエラーを返す。
+  /// - メッセージIDが存在しない場合はエラーを返す。
+  /// - 成功した場合は、GroupChatMessageModeratedイベントを返す。
+  pub fn moderate_message(
+    &mut self,
+    message_id: MessageId,
+    reason: String,
+    executor_id: UserAccountId,
+  ) -> Result<GroupChatEvent, GroupChatError> {
+    self.moderate_message_at(message_id, reason, executor_id, Utc::now())
+  }
+
+  fn moderate_message_at(
+    &mut self,
+    message_id: MessageId,
+    reason: String,
+    executor_id: UserAccountId,
+    deleted_at: DateTime<Utc>,
+  ) -> Result<GroupChatEvent, GroupChatError> {
+    if self.deleted {
+      return Err(GroupChatError::AlreadyDeletedError(self.id.clone()));
+    }
+    if !self.members.is_member(&executor_id) {
+      return Err(GroupChatError::NotMemberError("executor_id".to_string(), executor_id));
+    }
+    if !self.members.is_administrator(&executor_id) {
+      return Err(GroupChatError::NotAdministratorError(
+        "executor_id".to_string(),
+        executor_id,
+      ));
+    }
+    if reason.trim().is_empty() {
+      return Err(GroupChatError::EmptyModerationReasonError(message_id));
+    }
+    self.messages.moderate(&message_id, &executor_id, deleted_at)?;
+    self.seq_nr_counter += 1;
+    Ok(GroupChatEvent::GroupChatMessageModerated(
+      GroupChatEventMessageModeratedBody::new(self.id.clone(), self.seq_nr_counter, message_id, reason, executor_id),
+    ))
+  }
+
+  /// 削除されたメッセージを復元する
+  ///
+  /// # 引数
+  /// - message_id: メッセージID
+  /// - executor_id: 実行者のユーザアカウントID
+  /// - restore_window: 削除から復元可能な期間
+  ///
+  /// # 戻り値
+  /// - グループチャットが削除されている場合はエラーを返す。
+  /// - 実行者がメンバーでない場合はエラーを返す。
+  /// - 実行者が管理者でない場合はエラーを返す。
+  /// - メッセージIDが存在しない場合はエラーを返す。
+  /// - メッセージが削除されていない場合はエラーを返す。
+  /// - 削除から復元可能な期間を過ぎている場合はエラーを返す。
+  /// - 成功した場合は、GroupChatMessageRestoredイベントを返す。
+  pub fn restore_message(
+    &mut self,
+    message_id: MessageId,
+    executor_id: UserAccountId,
+    restore_window: Duration,
+  ) -> Result<GroupChatEvent, GroupChatError> {
+    if self.deleted {
+      return Err(GroupChatError::AlreadyDeletedError(self.id.clone()));
+    }
+    if !self.members.is_member(&executor_id) {
+      return Err(GroupChatError::NotMemberError("executor_id".to_string(), executor_id));
+    }
+    if !self.members.is_administrator(&executor_id) {
+      return Err(GroupChatError::NotAdministratorError(
+        "executor_id".to_string(),
+        executor_id,
+      ));
+    }
+    let deleted_at = match self.messages.find_deleted_by_id(&message_id) {
+      Some(message) => *message.breach_encapsulation_of_deleted_at().unwrap(),
+      None if self.messages.contains(&message_id) => {
+        return Err(GroupChatError::NotDeletedMessageError(message_id));
+      }
+      None => return Err(GroupChatError::NotFoundMessageError(message_id)),
+    };
+    let expired = deleted_at
+      .checked_add_signed(restore_window)
+      .map(|deadline| deadline < Utc::now())
+      .unwrap_or(false);
+    if expired {
+      return Err(GroupChatError::RestoreWindowExpiredError(message_id));
+    }
+    self.messages.restore(&message_id)?;
+    self.seq_nr_counter += 1;
+    Ok(GroupChatEvent::GroupChatMessageRestored(
+      GroupChatEventMessageRestoredBody::new(self.id.clone(), self.seq_nr_counter, message_id, executor_id),
+    ))
+  }
+
+  /// 保持期間を過ぎたメッセージを完全に削除する
+  ///
+  /// 保持期間は[GroupChatSettings]の保持日数を優先し、未設定の場合は`default_retention`を用いる。
+  ///
+  /// # 引数
+  /// - message_id: メッセージID
+  /// - default_retention: グループチャットに保持日数が設定されていない場合の保持期間
+  ///
+  /// # 戻り値
+  /// - グループチャットが削除されている場合はエラーを返す。
+  /// - 保持期間が設定されていない場合はエラーを返す。
+  /// - メッセージIDが存在しない場合はエラーを返す。
+  /// - メッセージが保持期間を過ぎていない場合はエラーを返す。
+  /// - 成功した場合は、GroupChatMessageExpiredイベントを返す。
+  ///
+  /// NOTE: 削除済みのメッセージも対象となり、墓標も含めて集約から取り除かれる。
+  pub fn expire_message(
+    &mut self,
+    message_id: MessageId,
+    default_retention: Option<Duration>,
+  ) -> Result<GroupChatEvent, GroupChatError> {
+    let retention = self
+      .settings
+      .message_retention_days()
+      .map(|days| Duration::days(days as i64))
+      .or(default_retention);
+    self.expire_message_at(message_id, retention, Utc::now())
+  }
+
+  fn expire_message_at(
+    &mut self,
+    message_id: MessageId,
+    retention: Option<Duration>,
+    now: DateTime<Utc>,
+  ) -> Result<GroupChatEvent, GroupChatError> {
+    if self.deleted {
+      return Err(GroupChatError::AlreadyDeletedError(self.id.clone()));
+    }
+    let retention = retention.ok_or_else(|| GroupChatError::RetentionNotConfiguredError(self.id.clone()))?;
+    let message = self
+      .messages
+      .find_by_id(&message_id)
+      .or_else(|| self.messages.find_deleted_by_id(&message_id))
+      .ok_or_else(|| GroupChatError::NotFoundMessageError(message_id.clone()))?;
+    // NOTE: 投稿日時を持たない過去のスナップショットのメッセージは、メッセージIDの生成日時を投稿日時とみなす
+    let posted_at = message
+      .breach_encapsulation_of_posted_at()
+      .cloned()
+      .unwrap_or_else(|| message_id.generated_at());
+    let expired = posted_at
+      .checked_add_signed(retention)
+      .map(|expires_at| expires_at <= now)
+      .unwrap_or(false);
+    if !expired {
+      return Err(GroupChatError::MessageNotExpiredError(message_id));
+    }
+    self.messages.purge(&message_id)?;
+    self.seq_nr_counter += 1;
+    Ok(GroupChatEvent::GroupChatMessageExpired(
+      GroupChatEventMessageExpiredBody::new(self.id.clone(), self.seq_nr_counter, message_id),
+    ))
+  }
+
+  /// ユーザアカウントに関するデータをグループチャットから消去する(個人データの消去要求)
+  ///
+  /// メンバーから外し、保留中の招待を取り消し、送信したメッセージの本文を消去する。
+  ///
+  /// # 引数
+  /// - user_account_id: 消去するユーザアカウントID
+  /// - executor_id: 実行者のユーザアカウントID(監査用)
+  ///
+  /// # 戻り値
+  /// - ユーザアカウントがメンバーでも招待中でもなく、本文が残っているメッセージもない場合はエラーを返す。
+  /// - 成功した場合は、GroupChatUserAccountErasedイベントを返す。
+  ///
+  /// NOTE: 消去要求には必ず応じる必要があるため、削除済みのグループチャットや1対1のチャットも対象とし、
+  /// 最後の管理者であってもメンバーから外す。
+  pub fn erase_user_account(
+    &mut self,
+    user_account_id: UserAccountId,
+    executor_id: UserAccountId,
+  ) -> Result<GroupChatEvent, GroupChatError> {
+    let is_member = self.members.is_member(&user_account_id);
+    let invited = self.invitations.remove(&user_account_id).is_some();
+    if !is_member && !invited && !self.messages.contains_unredacted_by_sender(&user_account_id) {
+      return Err(GroupChatError::NoUserAccountDataError(self.id.clone(), user_account_id));
+    }
+    if is_member {
+      self.members.remove_member_by_user_account_id(&user_account_id);
+    }
+    let message_ids = self.messages.redact_by_sender(&user_account_id);
+    self.seq_nr_counter += 1;
+    Ok(GroupChatEvent::GroupChatUserAccountErased(
+      GroupChatEventUserAccountErasedBody::new(
+        self.id.clone(),
+        self.seq_nr_counter,
+        user_account_id,
+        message_ids,
+        executor_id,
+      ),
+    ))
+  }
+
+  /// グループチャットを削除する
+  ///
+  /// # 引数
+  /// - executor_id: 実行者のユーザアカウントID
+  ///
+  /// # 戻り値
+  /// - グループチャットが削除されている場合はエラーを返す。
+  /// - 実行者が管理者でない場合はエラーを返す。
+  /// - 成功した場合は、GroupChatDeletedイベントを返す。
+  pub fn delete(&mut self, executor_id: UserAccountId) -> Result<GroupChatEvent, GroupChatError> {
+    if self.deleted {
+      return Err(GroupChatError::AlreadyDeletedError(self.id.clone()));
+    }
+    if !self.members.is_administrator(&executor_id) {
+      return Err(GroupChatError::NotAdministratorError(
+        "executor_id".to_string(),
+        executor_id,
+      ));
+    }
+    self.deleted = true;
+    self.seq_nr_counter += 1;
+    Ok(GroupChatEvent::GroupChatDeleted(GroupChatEventDeletedBody::new(
+      self.id.clone(),
+      self.seq_nr_counter,
+      executor_id,
+    )))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_delete_group_chat() {
+    let group_chat_name = GroupChatName::new("test").unwrap();
+    let admin_user_account_id = UserAccountId::new();
+    let members = Members::new(admin_user_account_id.clone());
+    let (mut group_chat, _) = GroupChat::new(group_chat_name.clone(), members);
+    let user_account_id = UserAccountId::new();
+    let member_id = MemberId::new();
+
+    let _ = group_chat
+      .add_member(
+        member_id,
+        user_account_id.clone(),
+        MemberRole::Member,
+        admin_user_account_id.clone(),
+      )
+      .unwrap();
+    assert!(group_chat.members().is_member(&user_account_id));
+
+    let result = group_chat.delete(user_account_id.clone());
+    assert!(result.is_err());
+
+    let result = group_chat.delete(admin_user_account_id.clone());
+    assert!(result.is_ok());
+  }
+
+  #[test]
+  fn test_rename() {
+    let group_chat_name = GroupChatName::new("test").unwrap();
+    let admin_user_account_id = UserAccountId::new();
+    let members = Members::new(admin_user_account_id.clone());
+    let (mut group_chat, _) = GroupChat::new(group_chat_name.clone(), members);
+    assert_eq!(group_chat.name, group_chat_name);
+
+    let _ = group_chat
+      .rename(GroupChatName::new("test2").unwrap(), admin_user_account_id.clone())
+      .unwrap();
+
+    assert!(group_chat.name().to_string() == "test2");
+  }
+
+  #[test]
+  fn test_add_member() {
+    let group_chat_name = GroupChatName::new("test").unwrap();
+    let admin_user_account_id = UserAccountId::new();
+    let members = Members::new(admin_user_account_id.clone());
+    let (mut group_chat, _) = GroupChat::new(group_chat_name.clone(), members);
+    assert_eq!(group_chat.name, group_chat_name);
+
+    let user_account_id = UserAccountId::new();
+    let member_id = MemberId::new();
+
+    let event = group_chat
+      .add_member(
+        member_id,
+        user_account_id.clone(),
+        MemberRole::Member,
+        admin_user_account_id.clone(),
+      )
+      .unwrap();
+
+    assert!(group_chat.members().is_member(&user_account_id));
+    match event {
+      GroupChatEvent::GroupChatMemberAdded(body) => {
+        let notice = body.notice.unwrap();
+        assert!(notice.is_system());
+        assert_eq!(notice.breach_encapsulation_of_sender_id(), &user_account_id);
+      }
+      _ => panic!("unexpected event"),
+    }
+    assert!(group_chat.messages().is_empty());
+  }
+
+  #[test]
+  fn test_post_system_message() {
+    let group_chat_name = GroupChatName::new("test").unwrap();
+    let admin_user_account_id = UserAccountId::new();
+    let members = Members::new(admin_user_account_id.clone());
+    let (mut group_chat, _) = GroupChat::new(group_chat_name, members);
+
+    let message = Message::new_with_body(
+      MessageId::new(),
+      MessageBody::System {
+        text: "joined".to_string(),
+      },
+      admin_user_account_id.clone(),
+    );
+    let result = group_chat.post_message(message, admin_user_account_id);
+    assert!(matches!(result, Err(GroupChatError::SystemMessageError(_))));
+  }
+
+  #[test]
+  fn test_remove_member() {
+    let group_chat_name = GroupChatName::new("test").unwrap();
+    let admin_user_account_id = UserAccountId::new();
+    let members = Members::new(admin_user_account_id.clone());
+    let (mut group_chat, _) = GroupChat::new(group_chat_name.clone(), members);
+    assert_eq!(group_chat.name, group_chat_name);
+
+    let user_account_id = UserAccountId::new();
+    let member_id = MemberId::new();
+
+    let _ = group_chat
+      .add_member(
+        member_id,
+        user_account_id.clone(),
+        MemberRole::Member,
+        admin_user_account_id.clone(),
+      )
+      .unwrap();
+
+    assert!(group_chat.members().is_member(&user_account_id));
+
+    let _ = group_chat
+      .remove_member(user_account_id.clone(), admin_user_account_id.clone())
+      .unwrap();
+
+    assert!(!group_chat.members().is_member(&user_account_id));
+  }
+
+  #[test]
+  fn test_replay_members_over_the_limit() {
+    let admin_user_account_id = UserAccountId::new();
+    let (group_chat, _) = GroupChat::new(
+      GroupChatName::new("test").unwrap(),
+      Members::new(admin_user_account_id.clone()),
+    );
+    let max_members = GroupChatSettings::default().max_members();
+
+    // 上限が導入される前に、上限を超えるメンバーが追加されたグループチャット
+    let events = (0..max_members)
+      .map(|i| {
+        GroupChatEvent::GroupChatMemberAdded(GroupChatEventMemberAddedBody::new(
+          group_chat.id.clone(),
+          group_chat.seq_nr_counter + i + 1,
+          Member::new(MemberId::new(), UserAccountId::new(), MemberRole::Member),
+          admin_user_account_id.clone(),
+        ))
+      })
+      .collect::<Vec<_>>();
+    let replayed = GroupChat::replay(events, group_chat.clone());
+    assert_eq!(replayed.members().count(), max_members + 1);
+    assert_eq!(replayed.seq_nr_counter, group_chat.seq_nr_counter + max_members);
+
+    // 新しいメンバーの追加は上限のチェックで拒否される
+    let mut replayed = replayed;
+    let result = replayed.add_member(
+      MemberId::new(),
+      UserAccountId::new(),
+      MemberRole::Member,
+      admin_user_account_id,
+    );
+    assert!(matches!(result, Err(GroupChatError::MemberLimitExceededError(_, _))));
+  }
+
+  #[test]
+  fn test_leave() {
+    let group_chat_name = GroupChatName::new("test").unwrap();
     let admin_user_account_id = UserAccountId::new();
     let members = Members::new(admin_user_account_id.clone());
-    let (mut group_chat, _) = GroupChat::new(group_chat_name.clone(), members);
-    assert_eq!(group_chat.name, group_chat_name);
+    let (mut group_chat, _) = GroupChat::new(group_chat_name, members);
 
+    let user_account_id = UserAccountId::new();
     let _ = group_chat
-      .rename(GroupChatName::new("test2").unwrap(), admin_user_account_id.clone())
+      .add_member(
+        MemberId::new(),
+        user_account_id.clone(),
+        MemberRole::Member,
+        admin_user_account_id.clone(),
+      )
       .unwrap();
 
-    assert!(group_chat.name().to_string() == "test2");
+    let event = group_chat
+      .leave(user_account_id.clone(), LastAdministratorPolicy::RequireTransfer)
+      .unwrap();
+    assert!(matches!(event, GroupChatEvent::GroupChatMemberLeft(_)));
+    assert!(!group_chat.members().is_member(&user_account_id));
+
+    let result = group_chat.leave(admin_user_account_id.clone(), LastAdministratorPolicy::RequireTransfer);
+    assert!(matches!(result, Err(GroupChatError::LastAdministratorError(_))));
+    assert!(group_chat.members().is_administrator(&admin_user_account_id));
+
+    let event = group_chat
+      .leave(admin_user_account_id, LastAdministratorPolicy::DeleteGroupChat)
+      .unwrap();
+    assert!(matches!(event, GroupChatEvent::GroupChatDeleted(_)));
+    assert!(group_chat.deleted);
   }
 
   #[test]
-  fn test_add_member() {
+  fn test_post_message() {
     let group_chat_name = GroupChatName::new("test").unwrap();
     let admin_user_account_id = UserAccountId::new();
     let members = Members::new(admin_user_account_id.clone());
@@ -550,7 +1453,6 @@ mod tests {
 
     let user_account_id = UserAccountId::new();
     let member_id = MemberId::new();
-
     let _ = group_chat
       .add_member(
         member_id,
@@ -560,64 +1462,204 @@ mod tests {
       )
       .unwrap();
 
-    assert!(group_chat.members().is_member(&user_account_id));
+    let message_id = MessageId::new();
+    let message = Message::new(message_id, "test".to_string(), user_account_id.clone());
+    let _ = group_chat
+      .post_message(message.clone(), user_account_id.clone())
+      .unwrap();
+
+    assert!(group_chat.messages().contains(message.breach_encapsulation_of_id()));
   }
 
   #[test]
-  fn test_remove_member() {
+  fn test_update_settings() {
     let group_chat_name = GroupChatName::new("test").unwrap();
     let admin_user_account_id = UserAccountId::new();
     let members = Members::new(admin_user_account_id.clone());
-    let (mut group_chat, _) = GroupChat::new(group_chat_name.clone(), members);
-    assert_eq!(group_chat.name, group_chat_name);
+    let (mut group_chat, _) = GroupChat::new(group_chat_name, members);
 
     let user_account_id = UserAccountId::new();
-    let member_id = MemberId::new();
-
     let _ = group_chat
       .add_member(
-        member_id,
+        MemberId::new(),
         user_account_id.clone(),
         MemberRole::Member,
         admin_user_account_id.clone(),
       )
       .unwrap();
 
-    assert!(group_chat.members().is_member(&user_account_id));
+    let settings = GroupChatSettings::new(3, Permission::AdminsOnly, Permission::Everyone, Some(30)).unwrap();
+    let result = group_chat.update_settings(settings.clone(), user_account_id.clone());
+    assert!(matches!(result, Err(GroupChatError::NotAdministratorError(_, _))));
+    let event = group_chat
+      .update_settings(settings.clone(), admin_user_account_id.clone())
+      .unwrap();
+    assert!(matches!(event, GroupChatEvent::GroupChatSettingsUpdated(_)));
+    assert_eq!(*group_chat.settings(), settings);
+
+    // アナウンスモードでは管理者以外は投稿できない
+    let message = Message::new(MessageId::new(), "test".to_string(), user_account_id.clone());
+    let result = group_chat.post_message(message, user_account_id.clone());
+    assert!(matches!(result, Err(GroupChatError::PostNotAllowedError(_, _))));
 
+    // 管理者以外もメンバーを追加できるが、上限を超えることはできない
     let _ = group_chat
-      .remove_member(user_account_id.clone(), admin_user_account_id.clone())
+      .add_member(
+        MemberId::new(),
+        UserAccountId::new(),
+        MemberRole::Member,
+        user_account_id.clone(),
+      )
       .unwrap();
+    let result = group_chat.add_member(
+      MemberId::new(),
+      UserAccountId::new(),
+      MemberRole::Member,
+      admin_user_account_id.clone(),
+    );
+    assert!(matches!(result, Err(GroupChatError::MemberLimitExceededError(_, 3))));
 
-    assert!(!group_chat.members().is_member(&user_account_id));
+    let settings = GroupChatSettings::new(2, Permission::Everyone, Permission::AdminsOnly, None).unwrap();
+    let result = group_chat.update_settings(settings, admin_user_account_id);
+    assert!(matches!(result, Err(GroupChatError::MaxMembersBelowCurrentError(2, 3))));
   }
 
   #[test]
-  fn test_post_message() {
+  fn test_expire_message() {
     let group_chat_name = GroupChatName::new("test").unwrap();
     let admin_user_account_id = UserAccountId::new();
     let members = Members::new(admin_user_account_id.clone());
-    let (mut group_chat, _) = GroupChat::new(group_chat_name.clone(), members);
-    assert_eq!(group_chat.name, group_chat_name);
+    let (mut group_chat, _) = GroupChat::new(group_chat_name, members);
+
+    let message_id = MessageId::new();
+    let message = Message::new(message_id.clone(), "test".to_string(), admin_user_account_id.clone());
+    let _ = group_chat.post_message(message, admin_user_account_id.clone()).unwrap();
+
+    let result = group_chat.expire_message(message_id.clone(), None);
+    assert!(matches!(result, Err(GroupChatError::RetentionNotConfiguredError(_))));
+    let result = group_chat.expire_message(message_id.clone(), Some(Duration::days(30)));
+    assert!(matches!(result, Err(GroupChatError::MessageNotExpiredError(_))));
+
+    let now = Utc::now() + Duration::days(31);
+    let event = group_chat
+      .expire_message_at(message_id.clone(), Some(Duration::days(30)), now)
+      .unwrap();
+    assert!(matches!(event, GroupChatEvent::GroupChatMessageExpired(_)));
+    assert!(!group_chat.messages().contains(&message_id));
+    assert!(group_chat.messages().find_deleted_by_id(&message_id).is_none());
+  }
+
+  #[test]
+  fn test_expire_message_without_posted_at() {
+    let group_chat_name = GroupChatName::new("test").unwrap();
+    let admin_user_account_id = UserAccountId::new();
+    let members = Members::new(admin_user_account_id.clone());
+    let (mut group_chat, _) = GroupChat::new(group_chat_name, members);
+
+    // 投稿日時が導入される前のスナップショットから復元されたメッセージ
+    let message_id = MessageId::new();
+    let message = Message::new(message_id.clone(), "test".to_string(), admin_user_account_id);
+    assert!(message.breach_encapsulation_of_posted_at().is_none());
+    group_chat.messages.add(message).unwrap();
+
+    let result = group_chat.expire_message_at(message_id.clone(), Some(Duration::days(30)), Utc::now());
+    assert!(matches!(result, Err(GroupChatError::MessageNotExpiredError(_))));
+
+    let now = Utc::now() + Duration::days(31);
+    let event = group_chat
+      .expire_message_at(message_id.clone(), Some(Duration::days(30)), now)
+      .unwrap();
+    assert!(matches!(event, GroupChatEvent::GroupChatMessageExpired(_)));
+    assert!(!group_chat.messages().contains(&message_id));
+  }
+
+  #[test]
+  fn test_erase_user_account() {
+    let group_chat_name = GroupChatName::new("test").unwrap();
+    let admin_user_account_id = UserAccountId::new();
+    let members = Members::new(admin_user_account_id.clone());
+    let (mut group_chat, _) = GroupChat::new(group_chat_name, members);
 
     let user_account_id = UserAccountId::new();
-    let member_id = MemberId::new();
     let _ = group_chat
       .add_member(
-        member_id,
+        MemberId::new(),
         user_account_id.clone(),
         MemberRole::Member,
         admin_user_account_id.clone(),
       )
       .unwrap();
-
     let message_id = MessageId::new();
-    let message = Message::new(message_id, "test".to_string(), user_account_id.clone());
+    let message = Message::new(message_id.clone(), "secret".to_string(), user_account_id.clone());
+    let _ = group_chat.post_message(message, user_account_id.clone()).unwrap();
+    let deleted_message_id = MessageId::new();
+    let message = Message::new(
+      deleted_message_id.clone(),
+      "secret".to_string(),
+      user_account_id.clone(),
+    );
+    let _ = group_chat.post_message(message, user_account_id.clone()).unwrap();
     let _ = group_chat
-      .post_message(message.clone(), user_account_id.clone())
+      .delete_message(deleted_message_id.clone(), user_account_id.clone())
       .unwrap();
 
-    assert!(group_chat.messages().contains(message.breach_encapsulation_of_id()));
+    let event = group_chat
+      .erase_user_account(user_account_id.clone(), admin_user_account_id.clone())
+      .unwrap();
+    match event {
+      GroupChatEvent::GroupChatUserAccountErased(body) => {
+        assert_eq!(body.message_ids, vec![message_id.clone(), deleted_message_id.clone()]);
+      }
+      _ => panic!("unexpected event"),
+    }
+    assert!(!group_chat.members().is_member(&user_account_id));
+    let message = group_chat.messages().find_by_id(&message_id).unwrap();
+    assert!(message.is_redacted());
+    assert_eq!(message.breach_encapsulation_of_text(), "");
+    let message = group_chat.messages().find_deleted_by_id(&deleted_message_id).unwrap();
+    assert!(message.is_redacted());
+
+    let result = group_chat.erase_user_account(user_account_id, admin_user_account_id);
+    assert!(matches!(result, Err(GroupChatError::NoUserAccountDataError(_, _))));
+  }
+
+  #[test]
+  fn test_post_message_with_mentions() {
+    let group_chat_name = GroupChatName::new("test").unwrap();
+    let admin_user_account_id = UserAccountId::new();
+    let members = Members::new(admin_user_account_id.clone());
+    let (mut group_chat, _) = GroupChat::new(group_chat_name.clone(), members);
+
+    let non_member_id = UserAccountId::new();
+    let message = Message::new(
+      MessageId::new(),
+      format!("hello @{}", non_member_id),
+      admin_user_account_id.clone(),
+    );
+    let result = group_chat.post_message(message, admin_user_account_id.clone());
+    assert!(matches!(result, Err(GroupChatError::NotMemberError(_, _))));
+
+    let user_account_id = UserAccountId::new();
+    let _ = group_chat
+      .add_member(
+        MemberId::new(),
+        user_account_id.clone(),
+        MemberRole::Member,
+        admin_user_account_id.clone(),
+      )
+      .unwrap();
+    let message = Message::new(
+      MessageId::new(),
+      format!("hello @{}", user_account_id),
+      admin_user_account_id.clone(),
+    );
+    let event = group_chat.post_message(message, admin_user_account_id.clone()).unwrap();
+    match event {
+      GroupChatEvent::GroupChatMessagePosted(body) => {
+        assert_eq!(body.message.breach_encapsulation_of_mentions(), &[user_account_id]);
+      }
+      _ => panic!("unexpected event"),
+    }
   }
 
   #[test]
@@ -653,7 +1695,7 @@ mod tests {
 
     let message = message.with_text("test2".to_string());
     group_chat
-      .edit_message(message.clone(), user_account_id.clone())
+      .edit_message(message.clone(), user_account_id.clone(), Duration::hours(24))
       .unwrap();
     assert!(group_chat.messages().contains(message.breach_encapsulation_of_id()));
     let m = group_chat
@@ -663,6 +1705,27 @@ mod tests {
     assert_eq!(m.breach_encapsulation_of_text(), "test2");
   }
 
+  #[test]
+  fn test_edit_message_after_window() {
+    let group_chat_name = GroupChatName::new("test").unwrap();
+    let admin_user_account_id = UserAccountId::new();
+    let members = Members::new(admin_user_account_id.clone());
+    let (mut group_chat, _) = GroupChat::new(group_chat_name.clone(), members);
+
+    let message_id = MessageId::new();
+    let message = Message::new(message_id.clone(), "test1".to_string(), admin_user_account_id.clone())
+      .with_posted_at(Utc::now() - Duration::hours(25));
+    let _ = group_chat
+      .add_message(message.clone(), admin_user_account_id.clone())
+      .unwrap();
+
+    let message = message.with_text("test2".to_string());
+    let result = group_chat.edit_message(message, admin_user_account_id.clone(), Duration::hours(24));
+    assert!(matches!(result, Err(GroupChatError::EditWindowExpiredError(_))));
+    let m = group_chat.messages().find_by_id(&message_id).unwrap();
+    assert_eq!(m.breach_encapsulation_of_text(), "test1");
+  }
+
   #[test]
   fn test_delete_message() {
     let group_chat_name = GroupChatName::new("test").unwrap();
@@ -694,6 +1757,243 @@ mod tests {
       .unwrap();
 
     assert!(!group_chat.messages().contains(message.breach_encapsulation_of_id()));
+    let m = group_chat
+      .messages()
+      .find_deleted_by_id(message.breach_encapsulation_of_id())
+      .unwrap();
+    assert_eq!(m.breach_encapsulation_of_deleted_by(), Some(&user_account_id));
+  }
+
+  #[test]
+  fn test_delete_message_after_sender_removed() {
+    let group_chat_name = GroupChatName::new("test").unwrap();
+    let admin_user_account_id = UserAccountId::new();
+    let members = Members::new(admin_user_account_id.clone());
+    let (mut group_chat, _) = GroupChat::new(group_chat_name, members);
+
+    let user_account_id = UserAccountId::new();
+    let _ = group_chat
+      .add_member(
+        MemberId::new(),
+        user_account_id.clone(),
+        MemberRole::Member,
+        admin_user_account_id.clone(),
+      )
+      .unwrap();
+    let message_id = MessageId::new();
+    let message = Message::new(message_id.clone(), "test".to_string(), user_account_id.clone());
+    let _ = group_chat.post_message(message, user_account_id.clone()).unwrap();
+    let _ = group_chat
+      .remove_member(user_account_id, admin_user_account_id.clone())
+      .unwrap();
+
+    let result = group_chat.delete_message(message_id.clone(), admin_user_account_id);
+    assert!(matches!(result, Err(GroupChatError::NotSenderError(_, _))));
+    assert!(group_chat.messages().contains(&message_id));
+  }
+
+  #[test]
+  fn test_restore_message() {
+    let group_chat_name = GroupChatName::new("test").unwrap();
+    let admin_user_account_id = UserAccountId::new();
+    let members = Members::new(admin_user_account_id.clone());
+    let (mut group_chat, _) = GroupChat::new(group_chat_name.clone(), members);
+
+    let message_id = MessageId::new();
+    let message = Message::new(message_id.clone(), "test".to_string(), admin_user_account_id.clone());
+    let _ = group_chat
+      .post_message(message.clone(), admin_user_account_id.clone())
+      .unwrap();
+
+    let result = group_chat.restore_message(message_id.clone(), admin_user_account_id.clone(), Duration::hours(24));
+    assert!(matches!(result, Err(GroupChatError::NotDeletedMessageError(_))));
+
+    let _ = group_chat
+      .delete_message(message_id.clone(), admin_user_account_id.clone())
+      .unwrap();
+    assert!(!group_chat.messages().contains(&message_id));
+
+    let _ = group_chat
+      .restore_message(message_id.clone(), admin_user_account_id.clone(), Duration::hours(24))
+      .unwrap();
+    assert!(group_chat.messages().contains(&message_id));
+  }
+
+  #[test]
+  fn test_restore_message_after_window() {
+    let group_chat_name = GroupChatName::new("test").unwrap();
+    let admin_user_account_id = UserAccountId::new();
+    let members = Members::new(admin_user_account_id.clone());
+    let (mut group_chat, _) = GroupChat::new(group_chat_name.clone(), members);
+
+    let message_id = MessageId::new();
+    let message = Message::new(message_id.clone(), "test".to_string(), admin_user_account_id.clone());
+    let _ = group_chat
+      .post_message(message.clone(), admin_user_account_id.clone())
+      .unwrap();
+    let _ = group_chat
+      .delete_message_at(
+        message_id.clone(),
+        admin_user_account_id.clone(),
+        Utc::now() - Duration::hours(25),
+      )
+      .unwrap();
+
+    let result = group_chat.restore_message(message_id.clone(), admin_user_account_id.clone(), Duration::hours(24));
+    assert!(matches!(result, Err(GroupChatError::RestoreWindowExpiredError(_))));
+  }
+
+  #[test]
+  fn test_moderate_message() {
+    let group_chat_name = GroupChatName::new("test").unwrap();
+    let admin_user_account_id = UserAccountId::new();
+    let members = Members::new(admin_user_account_id.clone());
+    let (mut group_chat, _) = GroupChat::new(group_chat_name.clone(), members);
+
+    let user_account_id = UserAccountId::new();
+    let _ = group_chat
+      .add_member(
+        MemberId::new(),
+        user_account_id.clone(),
+        MemberRole::Member,
+        admin_user_account_id.clone(),
+      )
+      .unwrap();
+
+    let message_id = MessageId::new();
+    let message = Message::new(message_id.clone(), "test".to_string(), user_account_id.clone());
+    let _ = group_chat
+      .post_message(message.clone(), user_account_id.clone())
+      .unwrap();
+
+    let result = group_chat.delete_message(message_id.clone(), admin_user_account_id.clone());
+    assert!(matches!(result, Err(GroupChatError::NotSenderError(_, _))));
+
+    let result = group_chat.moderate_message(message_id.clone(), "spam".to_string(), user_account_id.clone());
+    assert!(matches!(result, Err(GroupChatError::NotAdministratorError(_, _))));
+
+    let result = group_chat.moderate_message(message_id.clone(), "".to_string(), admin_user_account_id.clone());
+    assert!(matches!(result, Err(GroupChatError::EmptyModerationReasonError(_))));
+
+    let _ = group_chat
+      .moderate_message(message_id.clone(), "spam".to_string(), admin_user_account_id.clone())
+      .unwrap();
+    assert!(!group_chat.messages().contains(&message_id));
+    let m = group_chat.messages().find_deleted_by_id(&message_id).unwrap();
+    assert_eq!(m.breach_encapsulation_of_deleted_by(), Some(&admin_user_account_id));
+  }
+
+  #[test]
+  fn test_new_direct() {
+    let user_account_id1 = UserAccountId::new();
+    let user_account_id2 = UserAccountId::new();
+    let (mut group_chat, _) = GroupChat::new_direct(user_account_id1.clone(), user_account_id2.clone()).unwrap();
+    assert_eq!(*group_chat.kind(), ChatKind::Direct);
+    assert_eq!(
+      *group_chat.id(),
+      GroupChatId::direct(&user_account_id2, &user_account_id1)
+    );
+    assert!(group_chat.members().is_member(&user_account_id1));
+    assert!(group_chat.members().is_member(&user_account_id2));
+    assert!(group_chat.members().find_administrator().is_none());
+
+    let result = group_chat.rename(GroupChatName::new("test").unwrap(), user_account_id1.clone());
+    assert!(matches!(
+      result,
+      Err(GroupChatError::NotSupportedInDirectChatError(_, _))
+    ));
+    let result = group_chat.add_member(
+      MemberId::new(),
+      UserAccountId::new(),
+      MemberRole::Member,
+      user_account_id1.clone(),
+    );
+    assert!(matches!(
+      result,
+      Err(GroupChatError::NotSupportedInDirectChatError(_, _))
+    ));
+
+    let message = Message::new(MessageId::new(), "hi".to_string(), user_account_id1.clone());
+    assert!(group_chat.post_message(message, user_account_id1.clone()).is_ok());
+
+    let result = GroupChat::new_direct(user_account_id1.clone(), user_account_id1);
+    assert!(matches!(result, Err(GroupChatError::DirectChatWithSelfError(_))));
+  }
+
+  #[test]
+  fn test_invitation() {
+    let admin_user_account_id = UserAccountId::new();
+    let members = Members::new(admin_user_account_id.clone());
+    let (mut group_chat, _) = GroupChat::new(GroupChatName::new("test").unwrap(), members);
+    let user_account_id = UserAccountId::new();
+
+    let result = group_chat.invite_member(
+      user_account_id.clone(),
+      MemberRole::Member,
+      user_account_id.clone(),
+      Duration::days(7),
+    );
+    assert!(matches!(result, Err(GroupChatError::NotAdministratorError(_, _))));
+
+    group_chat
+      .invite_member(
+        user_account_id.clone(),
+        MemberRole::Member,
+        admin_user_account_id.clone(),
+        Duration::days(7),
+      )
+      .unwrap();
+    assert!(!group_chat.members().is_member(&user_account_id));
+    let result = group_chat.invite_member(
+      user_account_id.clone(),
+      MemberRole::Member,
+      admin_user_account_id.clone(),
+      Duration::days(7),
+    );
+    assert!(matches!(result, Err(GroupChatError::AlreadyInvitedError(_, _))));
+
+    let result = group_chat.accept_invitation(MemberId::new(), UserAccountId::new());
+    assert!(matches!(result, Err(GroupChatError::NotFoundInvitationError(_))));
+    group_chat
+      .accept_invitation(MemberId::new(), user_account_id.clone())
+      .unwrap();
+    assert!(group_chat.members().is_member(&user_account_id));
+    assert!(group_chat.invitations().to_pending_vec(&Utc::now()).is_empty());
+
+    let other_user_account_id = UserAccountId::new();
+    group_chat
+      .invite_member(
+        other_user_account_id.clone(),
+        MemberRole::Member,
+        admin_user_account_id.clone(),
+        Duration::days(7),
+      )
+      .unwrap();
+    group_chat
+      .revoke_invitation(other_user_account_id.clone(), admin_user_account_id.clone())
+      .unwrap();
+    let result = group_chat.decline_invitation(other_user_account_id);
+    assert!(matches!(result, Err(GroupChatError::NotFoundInvitationError(_))));
+  }
+
+  #[test]
+  fn test_accept_expired_invitation() {
+    let admin_user_account_id = UserAccountId::new();
+    let members = Members::new(admin_user_account_id.clone());
+    let (mut group_chat, _) = GroupChat::new(GroupChatName::new("test").unwrap(), members);
+    let user_account_id = UserAccountId::new();
+    group_chat
+      .invite_member(
+        user_account_id.clone(),
+        MemberRole::Member,
+        admin_user_account_id.clone(),
+        Duration::zero(),
+      )
+      .unwrap();
+
+    let result = group_chat.accept_invitation(MemberId::new(), user_account_id.clone());
+    assert!(matches!(result, Err(GroupChatError::NotFoundInvitationError(_))));
+    assert!(!group_chat.members().is_member(&user_account_id));
   }
 
   #[test]
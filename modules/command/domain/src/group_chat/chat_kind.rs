@@ -0,0 +1,35 @@
+use crate::group_chat::ParseError;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// [GroupChat]の種類
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum ChatKind {
+  /// 名前と管理者を持つグループチャット
+  #[default]
+  Group,
+  /// 2人のメンバーだけで構成される1対1のチャット
+  Direct,
+}
+
+impl Display for ChatKind {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Group => write!(f, "Group"),
+      Self::Direct => write!(f, "Direct"),
+    }
+  }
+}
+
+impl FromStr for ChatKind {
+  type Err = ParseError;
+
+  fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    match s.to_lowercase().as_str() {
+      "group" => Ok(Self::Group),
+      "direct" => Ok(Self::Direct),
+      _ => Err(ParseError::InvalidChatKind(s.to_string())),
+    }
+  }
+}
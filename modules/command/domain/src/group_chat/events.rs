@@ -3,8 +3,9 @@ use event_store_adapter_rs::types::Event;
 use serde::{Deserialize, Serialize};
 use ulid_generator_rs::{ULIDGenerator, ULID};
 
+use crate::group_chat::invitation::Invitation;
 use crate::group_chat::member::Member;
-use crate::group_chat::{GroupChatId, GroupChatName, Members, Message, MessageId};
+use crate::group_chat::{ChatKind, GroupChatId, GroupChatName, GroupChatSettings, Members, Message, MessageId};
 use crate::id_generate;
 use crate::user_account::UserAccountId;
 
@@ -20,16 +21,36 @@ pub enum GroupChatEvent {
   GroupChatDeleted(GroupChatEventDeletedBody),
   /// グループチャットがリネームされた
   GroupChatRenamed(GroupChatEventRenamedBody),
+  /// グループチャットの設定が更新された
+  GroupChatSettingsUpdated(GroupChatEventSettingsUpdatedBody),
   /// グループチャットにメンバーが追加された
   GroupChatMemberAdded(GroupChatEventMemberAddedBody),
   /// グループチャットのメンバーが削除された
   GroupChatMemberRemoved(GroupChatEventMemberRemovedBody),
+  /// グループチャットからメンバーが自ら退出した
+  GroupChatMemberLeft(GroupChatEventMemberLeftBody),
   /// グループチャットにメッセージが投稿された
   GroupChatMessagePosted(GroupChatEventMessagePostedBody),
   /// グループチャットのメッセージが編集された
   GroupChatMessageEdited(GroupChatEventMessageEditedBody),
   /// グループチャットのメッセージが削除された
   GroupChatMessageDeleted(GroupChatEventMessageDeletedBody),
+  /// グループチャットの削除されたメッセージが復元された
+  GroupChatMessageRestored(GroupChatEventMessageRestoredBody),
+  /// グループチャットのメッセージが管理者によって削除された
+  GroupChatMessageModerated(GroupChatEventMessageModeratedBody),
+  /// グループチャットのメッセージが保持期間を過ぎて完全に削除された
+  GroupChatMessageExpired(GroupChatEventMessageExpiredBody),
+  /// グループチャットからユーザアカウントに関するデータが消去された
+  GroupChatUserAccountErased(GroupChatEventUserAccountErasedBody),
+  /// グループチャットにユーザアカウントが招待された
+  GroupChatMemberInvited(GroupChatEventMemberInvitedBody),
+  /// グループチャットへの招待が承諾された
+  GroupChatInvitationAccepted(GroupChatEventInvitationAcceptedBody),
+  /// グループチャットへの招待が辞退された
+  GroupChatInvitationDeclined(GroupChatEventInvitationDeclinedBody),
+  /// グループチャットへの招待が取り消された
+  GroupChatInvitationRevoked(GroupChatEventInvitationRevokedBody),
 }
 
 impl Event for GroupChatEvent {
@@ -41,11 +62,21 @@ impl Event for GroupChatEvent {
       GroupChatEvent::GroupChatCreated(event) => &event.id,
       GroupChatEvent::GroupChatDeleted(event) => &event.id,
       GroupChatEvent::GroupChatRenamed(event) => &event.id,
+      GroupChatEvent::GroupChatSettingsUpdated(event) => &event.id,
       GroupChatEvent::GroupChatMemberAdded(event) => &event.id,
       GroupChatEvent::GroupChatMemberRemoved(event) => &event.id,
+      GroupChatEvent::GroupChatMemberLeft(event) => &event.id,
       GroupChatEvent::GroupChatMessagePosted(event) => &event.id,
       GroupChatEvent::GroupChatMessageEdited(event) => &event.id,
       GroupChatEvent::GroupChatMessageDeleted(event) => &event.id,
+      GroupChatEvent::GroupChatMessageRestored(event) => &event.id,
+      GroupChatEvent::GroupChatMessageModerated(event) => &event.id,
+      GroupChatEvent::GroupChatMessageExpired(event) => &event.id,
+      GroupChatEvent::GroupChatUserAccountErased(event) => &event.id,
+      GroupChatEvent::GroupChatMemberInvited(event) => &event.id,
+      GroupChatEvent::GroupChatInvitationAccepted(event) => &event.id,
+      GroupChatEvent::GroupChatInvitationDeclined(event) => &event.id,
+      GroupChatEvent::GroupChatInvitationRevoked(event) => &event.id,
     }
   }
 
@@ -54,11 +85,21 @@ impl Event for GroupChatEvent {
       GroupChatEvent::GroupChatCreated(event) => event.seq_nr,
       GroupChatEvent::GroupChatDeleted(event) => event.seq_nr,
       GroupChatEvent::GroupChatRenamed(event) => event.seq_nr,
+      GroupChatEvent::GroupChatSettingsUpdated(event) => event.seq_nr,
       GroupChatEvent::GroupChatMemberAdded(event) => event.seq_nr,
       GroupChatEvent::GroupChatMemberRemoved(event) => event.seq_nr,
+      GroupChatEvent::GroupChatMemberLeft(event) => event.seq_nr,
       GroupChatEvent::GroupChatMessagePosted(event) => event.seq_nr,
       GroupChatEvent::GroupChatMessageEdited(event) => event.seq_nr,
       GroupChatEvent::GroupChatMessageDeleted(event) => event.seq_nr,
+      GroupChatEvent::GroupChatMessageRestored(event) => event.seq_nr,
+      GroupChatEvent::GroupChatMessageModerated(event) => event.seq_nr,
+      GroupChatEvent::GroupChatMessageExpired(event) => event.seq_nr,
+      GroupChatEvent::GroupChatUserAccountErased(event) => event.seq_nr,
+      GroupChatEvent::GroupChatMemberInvited(event) => event.seq_nr,
+      GroupChatEvent::GroupChatInvitationAccepted(event) => event.seq_nr,
+      GroupChatEvent::GroupChatInvitationDeclined(event) => event.seq_nr,
+      GroupChatEvent::GroupChatInvitationRevoked(event) => event.seq_nr,
     }
   }
 
@@ -67,11 +108,21 @@ impl Event for GroupChatEvent {
       GroupChatEvent::GroupChatCreated(event) => &event.aggregate_id,
       GroupChatEvent::GroupChatDeleted(event) => &event.aggregate_id,
       GroupChatEvent::GroupChatRenamed(event) => &event.aggregate_id,
+      GroupChatEvent::GroupChatSettingsUpdated(event) => &event.aggregate_id,
       GroupChatEvent::GroupChatMemberAdded(event) => &event.aggregate_id,
       GroupChatEvent::GroupChatMemberRemoved(event) => &event.aggregate_id,
+      GroupChatEvent::GroupChatMemberLeft(event) => &event.aggregate_id,
       GroupChatEvent::GroupChatMessagePosted(event) => &event.aggregate_id,
       GroupChatEvent::GroupChatMessageEdited(event) => &event.aggregate_id,
       GroupChatEvent::GroupChatMessageDeleted(event) => &event.aggregate_id,
+      GroupChatEvent::GroupChatMessageRestored(event) => &event.aggregate_id,
+      GroupChatEvent::GroupChatMessageModerated(event) => &event.aggregate_id,
+      GroupChatEvent::GroupChatMessageExpired(event) => &event.aggregate_id,
+      GroupChatEvent::GroupChatUserAccountErased(event) => &event.aggregate_id,
+      GroupChatEvent::GroupChatMemberInvited(event) => &event.aggregate_id,
+      GroupChatEvent::GroupChatInvitationAccepted(event) => &event.aggregate_id,
+      GroupChatEvent::GroupChatInvitationDeclined(event) => &event.aggregate_id,
+      GroupChatEvent::GroupChatInvitationRevoked(event) => &event.aggregate_id,
     }
   }
 
@@ -80,19 +131,26 @@ impl Event for GroupChatEvent {
       GroupChatEvent::GroupChatCreated(event) => &event.occurred_at,
       GroupChatEvent::GroupChatDeleted(event) => &event.occurred_at,
       GroupChatEvent::GroupChatRenamed(event) => &event.occurred_at,
+      GroupChatEvent::GroupChatSettingsUpdated(event) => &event.occurred_at,
       GroupChatEvent::GroupChatMemberAdded(event) => &event.occurred_at,
       GroupChatEvent::GroupChatMemberRemoved(event) => &event.occurred_at,
+      GroupChatEvent::GroupChatMemberLeft(event) => &event.occurred_at,
       GroupChatEvent::GroupChatMessagePosted(event) => &event.occurred_at,
       GroupChatEvent::GroupChatMessageEdited(event) => &event.occurred_at,
       GroupChatEvent::GroupChatMessageDeleted(event) => &event.occurred_at,
+      GroupChatEvent::GroupChatMessageRestored(event) => &event.occurred_at,
+      GroupChatEvent::GroupChatMessageModerated(event) => &event.occurred_at,
+      GroupChatEvent::GroupChatMessageExpired(event) => &event.occurred_at,
+      GroupChatEvent::GroupChatUserAccountErased(event) => &event.occurred_at,
+      GroupChatEvent::GroupChatMemberInvited(event) => &event.occurred_at,
+      GroupChatEvent::GroupChatInvitationAccepted(event) => &event.occurred_at,
+      GroupChatEvent::GroupChatInvitationDeclined(event) => &event.occurred_at,
+      GroupChatEvent::GroupChatInvitationRevoked(event) => &event.occurred_at,
     }
   }
 
   fn is_created(&self) -> bool {
-    match self {
-      GroupChatEvent::GroupChatCreated(_) => true,
-      _ => false,
-    }
+    matches!(self, GroupChatEvent::GroupChatCreated(_))
   }
 }
 
@@ -101,6 +159,8 @@ pub struct GroupChatEventCreatedBody {
   pub id: GroupChatEventId,
   pub aggregate_id: GroupChatId,
   pub seq_nr: usize,
+  #[serde(default)]
+  pub kind: ChatKind,
   pub name: GroupChatName,
   pub members: Members,
   pub occurred_at: DateTime<Utc>,
@@ -114,11 +174,18 @@ impl GroupChatEventCreatedBody {
       id,
       aggregate_id,
       seq_nr,
+      kind: ChatKind::Group,
       name,
       members,
       occurred_at,
     }
   }
+
+  /// チャットの種類を設定したイベントを返す。
+  pub fn with_kind(mut self, kind: ChatKind) -> Self {
+    self.kind = kind;
+    self
+  }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -225,7 +292,7 @@ pub struct GroupChatEventMessageDeletedBody {
   pub aggregate_id: GroupChatId,
   pub(crate) seq_nr: usize,
   pub message_id: MessageId,
-  pub(crate) executor_id: UserAccountId,
+  pub executor_id: UserAccountId,
   pub occurred_at: DateTime<Utc>,
 }
 
@@ -245,6 +312,152 @@ impl GroupChatEventMessageDeletedBody {
   }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupChatEventMessageRestoredBody {
+  pub(crate) id: GroupChatEventId,
+  pub aggregate_id: GroupChatId,
+  pub(crate) seq_nr: usize,
+  pub message_id: MessageId,
+  pub executor_id: UserAccountId,
+  pub occurred_at: DateTime<Utc>,
+}
+
+impl GroupChatEventMessageRestoredBody {
+  pub fn new(aggregate_id: GroupChatId, seq_nr: usize, message_id: MessageId, executor_id: UserAccountId) -> Self {
+    let id = id_generate();
+    let occurred_at = Utc::now();
+    Self {
+      id,
+      aggregate_id,
+      seq_nr,
+      message_id,
+      executor_id,
+      occurred_at,
+    }
+  }
+}
+
+/// NOTE: 保持期間の経過はシステムが判断するため、実行者を持たない。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupChatEventMessageExpiredBody {
+  pub(crate) id: GroupChatEventId,
+  pub aggregate_id: GroupChatId,
+  pub(crate) seq_nr: usize,
+  pub message_id: MessageId,
+  pub occurred_at: DateTime<Utc>,
+}
+
+impl GroupChatEventMessageExpiredBody {
+  pub fn new(aggregate_id: GroupChatId, seq_nr: usize, message_id: MessageId) -> Self {
+    let id = id_generate();
+    let occurred_at = Utc::now();
+    Self {
+      id,
+      aggregate_id,
+      seq_nr,
+      message_id,
+      occurred_at,
+    }
+  }
+}
+
+/// NOTE: 監査のため、本文を消去したメッセージのIDを記録する。本文や通知メッセージは含めない。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupChatEventUserAccountErasedBody {
+  pub(crate) id: GroupChatEventId,
+  pub aggregate_id: GroupChatId,
+  pub(crate) seq_nr: usize,
+  pub user_account_id: UserAccountId,
+  pub message_ids: Vec<MessageId>,
+  pub executor_id: UserAccountId,
+  pub occurred_at: DateTime<Utc>,
+}
+
+impl GroupChatEventUserAccountErasedBody {
+  pub fn new(
+    aggregate_id: GroupChatId,
+    seq_nr: usize,
+    user_account_id: UserAccountId,
+    message_ids: Vec<MessageId>,
+    executor_id: UserAccountId,
+  ) -> Self {
+    let id = id_generate();
+    let occurred_at = Utc::now();
+    Self {
+      id,
+      aggregate_id,
+      seq_nr,
+      user_account_id,
+      message_ids,
+      executor_id,
+      occurred_at,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupChatEventMessageModeratedBody {
+  pub(crate) id: GroupChatEventId,
+  pub aggregate_id: GroupChatId,
+  pub(crate) seq_nr: usize,
+  pub message_id: MessageId,
+  pub reason: String,
+  pub executor_id: UserAccountId,
+  pub occurred_at: DateTime<Utc>,
+}
+
+impl GroupChatEventMessageModeratedBody {
+  pub fn new(
+    aggregate_id: GroupChatId,
+    seq_nr: usize,
+    message_id: MessageId,
+    reason: String,
+    executor_id: UserAccountId,
+  ) -> Self {
+    let id = id_generate();
+    let occurred_at = Utc::now();
+    Self {
+      id,
+      aggregate_id,
+      seq_nr,
+      message_id,
+      reason,
+      executor_id,
+      occurred_at,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupChatEventSettingsUpdatedBody {
+  pub(crate) id: GroupChatEventId,
+  pub aggregate_id: GroupChatId,
+  pub(crate) seq_nr: usize,
+  pub settings: GroupChatSettings,
+  pub(crate) executor_id: UserAccountId,
+  pub occurred_at: DateTime<Utc>,
+}
+
+impl GroupChatEventSettingsUpdatedBody {
+  pub fn new(
+    aggregate_id: GroupChatId,
+    seq_nr: usize,
+    settings: GroupChatSettings,
+    executor_id: UserAccountId,
+  ) -> Self {
+    let id = id_generate();
+    let occurred_at = Utc::now();
+    Self {
+      id,
+      aggregate_id,
+      seq_nr,
+      settings,
+      executor_id,
+      occurred_at,
+    }
+  }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GroupChatEventMemberAddedBody {
   pub id: GroupChatEventId,
@@ -253,6 +466,9 @@ pub struct GroupChatEventMemberAddedBody {
   pub member: Member,
   pub executor_id: UserAccountId,
   pub occurred_at: DateTime<Utc>,
+  /// 集約が生成した参加通知のシステムメッセージ
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub notice: Option<Message>,
 }
 
 impl GroupChatEventMemberAddedBody {
@@ -266,8 +482,15 @@ impl GroupChatEventMemberAddedBody {
       member,
       executor_id,
       occurred_at,
+      notice: None,
     }
   }
+
+  /// 参加通知のシステムメッセージを設定したイベントを返す。
+  pub fn with_notice(mut self, notice: Message) -> Self {
+    self.notice = Some(notice);
+    self
+  }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -277,10 +500,173 @@ pub struct GroupChatEventMemberRemovedBody {
   pub(crate) seq_nr: usize,
   pub user_account_id: UserAccountId,
   pub(crate) executor_id: UserAccountId,
-  pub(crate) occurred_at: DateTime<Utc>,
+  pub occurred_at: DateTime<Utc>,
+  /// 集約が生成した退出通知のシステムメッセージ
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub notice: Option<Message>,
 }
 
 impl GroupChatEventMemberRemovedBody {
+  pub fn new(
+    aggregate_id: GroupChatId,
+    seq_nr: usize,
+    user_account_id: UserAccountId,
+    executor_id: UserAccountId,
+  ) -> Self {
+    let id = id_generate();
+    let occurred_at = Utc::now();
+    Self {
+      id,
+      aggregate_id,
+      seq_nr,
+      user_account_id,
+      executor_id,
+      occurred_at,
+      notice: None,
+    }
+  }
+
+  /// 退出通知のシステムメッセージを設定したイベントを返す。
+  pub fn with_notice(mut self, notice: Message) -> Self {
+    self.notice = Some(notice);
+    self
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupChatEventMemberLeftBody {
+  pub(crate) id: GroupChatEventId,
+  pub aggregate_id: GroupChatId,
+  pub(crate) seq_nr: usize,
+  pub user_account_id: UserAccountId,
+  pub occurred_at: DateTime<Utc>,
+  /// 集約が生成した退出通知のシステムメッセージ
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub notice: Option<Message>,
+}
+
+impl GroupChatEventMemberLeftBody {
+  pub fn new(aggregate_id: GroupChatId, seq_nr: usize, user_account_id: UserAccountId) -> Self {
+    let id = id_generate();
+    let occurred_at = Utc::now();
+    Self {
+      id,
+      aggregate_id,
+      seq_nr,
+      user_account_id,
+      occurred_at,
+      notice: None,
+    }
+  }
+
+  /// 退出通知のシステムメッセージを設定したイベントを返す。
+  pub fn with_notice(mut self, notice: Message) -> Self {
+    self.notice = Some(notice);
+    self
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupChatEventMemberInvitedBody {
+  pub(crate) id: GroupChatEventId,
+  pub aggregate_id: GroupChatId,
+  pub(crate) seq_nr: usize,
+  pub invitation: Invitation,
+  pub executor_id: UserAccountId,
+  pub occurred_at: DateTime<Utc>,
+}
+
+impl GroupChatEventMemberInvitedBody {
+  pub fn new(aggregate_id: GroupChatId, seq_nr: usize, invitation: Invitation, executor_id: UserAccountId) -> Self {
+    let id = id_generate();
+    let occurred_at = Utc::now();
+    Self {
+      id,
+      aggregate_id,
+      seq_nr,
+      invitation,
+      executor_id,
+      occurred_at,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupChatEventInvitationAcceptedBody {
+  pub(crate) id: GroupChatEventId,
+  pub aggregate_id: GroupChatId,
+  pub(crate) seq_nr: usize,
+  pub member: Member,
+  pub executor_id: UserAccountId,
+  pub occurred_at: DateTime<Utc>,
+  /// 集約が生成した参加通知のシステムメッセージ
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub notice: Option<Message>,
+}
+
+impl GroupChatEventInvitationAcceptedBody {
+  pub fn new(aggregate_id: GroupChatId, seq_nr: usize, member: Member, executor_id: UserAccountId) -> Self {
+    let id = id_generate();
+    let occurred_at = Utc::now();
+    Self {
+      id,
+      aggregate_id,
+      seq_nr,
+      member,
+      executor_id,
+      occurred_at,
+      notice: None,
+    }
+  }
+
+  /// 参加通知のシステムメッセージを設定したイベントを返す。
+  pub fn with_notice(mut self, notice: Message) -> Self {
+    self.notice = Some(notice);
+    self
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupChatEventInvitationDeclinedBody {
+  pub(crate) id: GroupChatEventId,
+  pub aggregate_id: GroupChatId,
+  pub(crate) seq_nr: usize,
+  pub user_account_id: UserAccountId,
+  pub executor_id: UserAccountId,
+  pub occurred_at: DateTime<Utc>,
+}
+
+impl GroupChatEventInvitationDeclinedBody {
+  pub fn new(
+    aggregate_id: GroupChatId,
+    seq_nr: usize,
+    user_account_id: UserAccountId,
+    executor_id: UserAccountId,
+  ) -> Self {
+    let id = id_generate();
+    let occurred_at = Utc::now();
+    Self {
+      id,
+      aggregate_id,
+      seq_nr,
+      user_account_id,
+      executor_id,
+      occurred_at,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupChatEventInvitationRevokedBody {
+  pub(crate) id: GroupChatEventId,
+  pub aggregate_id: GroupChatId,
+  pub(crate) seq_nr: usize,
+  pub user_account_id: UserAccountId,
+  pub executor_id: UserAccountId,
+  pub occurred_at: DateTime<Utc>,
+}
+
+impl GroupChatEventInvitationRevokedBody {
   pub fn new(
     aggregate_id: GroupChatId,
     seq_nr: usize,
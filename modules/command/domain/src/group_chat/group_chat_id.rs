@@ -1,7 +1,9 @@
 use crate::group_chat::ParseError;
 use crate::id_generate;
+use crate::user_account::UserAccountId;
 use event_store_adapter_rs::types::AggregateId;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 use ulid_generator_rs::ULID;
@@ -35,6 +37,36 @@ impl GroupChatId {
     let value = id_generate();
     Self { value }
   }
+
+  /// 1対1のチャットのIDを生成する。
+  ///
+  /// 同じ2人の組み合わせからは、順序に関わらず常に同じIDを生成する。
+  ///
+  /// # 引数
+  /// - `user_account_id1` - 1人目のユーザアカウントID
+  /// - `user_account_id2` - 2人目のユーザアカウントID
+  pub fn direct(user_account_id1: &UserAccountId, user_account_id2: &UserAccountId) -> Self {
+    let (a, b) = {
+      let (a, b) = (user_account_id1.to_string(), user_account_id2.to_string());
+      if a <= b {
+        (a, b)
+      } else {
+        (b, a)
+      }
+    };
+    let digest = Sha256::digest(format!("direct:{}:{}", a, b).as_bytes());
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    Self {
+      value: ULID::from(u128::from_be_bytes(bytes)),
+    }
+  }
+}
+
+impl Default for GroupChatId {
+  fn default() -> Self {
+    Self::new()
+  }
 }
 
 impl Display for GroupChatId {
@@ -64,3 +96,19 @@ impl FromStr for GroupChatId {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_direct_is_order_independent() {
+    let user_account_id1 = UserAccountId::new();
+    let user_account_id2 = UserAccountId::new();
+    let id1 = GroupChatId::direct(&user_account_id1, &user_account_id2);
+    let id2 = GroupChatId::direct(&user_account_id2, &user_account_id1);
+    assert_eq!(id1, id2);
+    assert_ne!(id1, GroupChatId::direct(&user_account_id1, &UserAccountId::new()));
+    assert_eq!(GroupChatId::from_str(&id1.to_string()).unwrap(), id1);
+  }
+}
@@ -0,0 +1,138 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::group_chat::ParseError;
+
+/// メンバー数の上限の既定値
+const DEFAULT_MAX_MEMBERS: usize = 1000;
+/// 設定できるメンバー数の上限の最小値
+const MIN_MAX_MEMBERS: usize = 2;
+/// 設定できるメンバー数の上限の最大値
+const MAX_MAX_MEMBERS: usize = 10000;
+
+/// 操作を許可するメンバーの範囲
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Permission {
+  /// すべてのメンバー
+  Everyone,
+  /// 管理者のみ
+  AdminsOnly,
+}
+
+impl Display for Permission {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Everyone => write!(f, "everyone"),
+      Self::AdminsOnly => write!(f, "admins_only"),
+    }
+  }
+}
+
+impl FromStr for Permission {
+  type Err = ParseError;
+
+  fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    match s.to_lowercase().as_str() {
+      "everyone" => Ok(Self::Everyone),
+      "admins_only" => Ok(Self::AdminsOnly),
+      _ => Err(ParseError::InvalidPermission(s.to_string())),
+    }
+  }
+}
+
+#[derive(Error, Debug, Clone)]
+pub enum GroupChatSettingsError {
+  #[error("the max members must be between {MIN_MAX_MEMBERS} and {MAX_MAX_MEMBERS}: {0}")]
+  InvalidMaxMembers(usize),
+  #[error("the message retention days must be greater than 0")]
+  InvalidMessageRetentionDays,
+}
+
+/// グループチャットごとの設定を表す値オブジェクト。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupChatSettings {
+  max_members: usize,
+  post_permission: Permission,
+  add_member_permission: Permission,
+  message_retention_days: Option<u32>,
+}
+
+impl Default for GroupChatSettings {
+  fn default() -> Self {
+    Self {
+      max_members: DEFAULT_MAX_MEMBERS,
+      post_permission: Permission::Everyone,
+      add_member_permission: Permission::AdminsOnly,
+      message_retention_days: None,
+    }
+  }
+}
+
+impl GroupChatSettings {
+  /// コンストラクタ。
+  ///
+  /// # 引数
+  /// - `max_members` - メンバー数の上限
+  /// - `post_permission` - メッセージを投稿できるメンバー。[Permission::AdminsOnly]の場合はアナウンスモードになる
+  /// - `add_member_permission` - メンバーを追加・招待できるメンバー
+  /// - `message_retention_days` - メッセージの保持日数。[None]の場合は無期限
+  ///
+  /// # 戻り値
+  /// - 検証に失敗した場合は[GroupChatSettingsError]を返す。
+  pub fn new(
+    max_members: usize,
+    post_permission: Permission,
+    add_member_permission: Permission,
+    message_retention_days: Option<u32>,
+  ) -> Result<Self, GroupChatSettingsError> {
+    if !(MIN_MAX_MEMBERS..=MAX_MAX_MEMBERS).contains(&max_members) {
+      return Err(GroupChatSettingsError::InvalidMaxMembers(max_members));
+    }
+    if message_retention_days == Some(0) {
+      return Err(GroupChatSettingsError::InvalidMessageRetentionDays);
+    }
+    Ok(Self {
+      max_members,
+      post_permission,
+      add_member_permission,
+      message_retention_days,
+    })
+  }
+
+  pub fn max_members(&self) -> usize {
+    self.max_members
+  }
+
+  pub fn post_permission(&self) -> Permission {
+    self.post_permission
+  }
+
+  pub fn add_member_permission(&self) -> Permission {
+    self.add_member_permission
+  }
+
+  pub fn message_retention_days(&self) -> Option<u32> {
+    self.message_retention_days
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_new() {
+    assert!(GroupChatSettings::new(2, Permission::AdminsOnly, Permission::Everyone, Some(30)).is_ok());
+    assert!(matches!(
+      GroupChatSettings::new(1, Permission::Everyone, Permission::AdminsOnly, None),
+      Err(GroupChatSettingsError::InvalidMaxMembers(1))
+    ));
+    assert!(matches!(
+      GroupChatSettings::new(100, Permission::Everyone, Permission::AdminsOnly, Some(0)),
+      Err(GroupChatSettingsError::InvalidMessageRetentionDays)
+    ));
+  }
+}
@@ -0,0 +1,69 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::group_chat::member_role::MemberRole;
+use crate::user_account::UserAccountId;
+
+/// グループチャットへの招待。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Invitation {
+  user_account_id: UserAccountId,
+  role: MemberRole,
+  invited_by: UserAccountId,
+  invited_at: DateTime<Utc>,
+  expires_at: DateTime<Utc>,
+}
+
+impl Invitation {
+  pub fn breach_encapsulation_of_user_account_id(&self) -> &UserAccountId {
+    &self.user_account_id
+  }
+
+  pub fn breach_encapsulation_of_role(&self) -> &MemberRole {
+    &self.role
+  }
+
+  pub fn breach_encapsulation_of_invited_by(&self) -> &UserAccountId {
+    &self.invited_by
+  }
+
+  pub fn breach_encapsulation_of_invited_at(&self) -> &DateTime<Utc> {
+    &self.invited_at
+  }
+
+  pub fn breach_encapsulation_of_expires_at(&self) -> &DateTime<Utc> {
+    &self.expires_at
+  }
+
+  /// 指定した日時の時点で期限切れかどうかを返す。
+  pub fn is_expired_at(&self, now: &DateTime<Utc>) -> bool {
+    self.expires_at <= *now
+  }
+
+  /// コンストラクタ。
+  ///
+  /// # 引数
+  /// - `user_account_id` - 招待されたユーザアカウントID
+  /// - `role` - 参加後の[MemberRole]
+  /// - `invited_by` - 招待したユーザアカウントID
+  /// - `invited_at` - 招待日時
+  /// - `expires_at` - 有効期限
+  ///
+  /// # 戻り値
+  /// - [Invitation]
+  pub fn new(
+    user_account_id: UserAccountId,
+    role: MemberRole,
+    invited_by: UserAccountId,
+    invited_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+  ) -> Self {
+    Self {
+      user_account_id,
+      role,
+      invited_by,
+      invited_at,
+      expires_at,
+    }
+  }
+}
@@ -0,0 +1,54 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::group_chat::invitation::Invitation;
+use crate::user_account::UserAccountId;
+
+/// 保留中の招待の集合。
+///
+/// NOTE: 招待はユーザアカウントごとに1件のみ保持する。期限切れの招待は参照系メソッドの対象外となる。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Invitations(BTreeMap<String, Invitation>);
+
+impl Invitations {
+  /// コンストラクタ
+  pub fn new() -> Self {
+    Self(BTreeMap::new())
+  }
+
+  /// 指定した日時の時点で有効な招待を取得する。
+  ///
+  /// # 引数
+  /// - `user_account_id` - 招待されたユーザアカウントID
+  /// - `now` - 基準日時
+  pub fn find_pending(&self, user_account_id: &UserAccountId, now: &DateTime<Utc>) -> Option<&Invitation> {
+    self
+      .0
+      .get(&user_account_id.to_string())
+      .filter(|invitation| !invitation.is_expired_at(now))
+  }
+
+  /// 指定した日時の時点で有効な招待の一覧を返す。
+  pub fn to_pending_vec(&self, now: &DateTime<Utc>) -> Vec<&Invitation> {
+    self
+      .0
+      .values()
+      .filter(|invitation| !invitation.is_expired_at(now))
+      .collect()
+  }
+
+  /// 招待を追加する。同じユーザアカウントへの招待が既に存在する場合は置き換える。
+  pub fn add(&mut self, invitation: Invitation) {
+    self.0.insert(
+      invitation.breach_encapsulation_of_user_account_id().to_string(),
+      invitation,
+    );
+  }
+
+  /// 指定したユーザアカウントへの招待を削除する。
+  pub fn remove(&mut self, user_account_id: &UserAccountId) -> Option<Invitation> {
+    self.0.remove(&user_account_id.to_string())
+  }
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// 最後の管理者がグループチャットから退出しようとした場合の扱い
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LastAdministratorPolicy {
+  /// 他のメンバーに管理者を移譲するまで退出を認めない
+  #[default]
+  RequireTransfer,
+  /// 退出の代わりにグループチャットを削除する
+  DeleteGroupChat,
+}
@@ -29,6 +29,32 @@ impl Members {
     my_self
   }
 
+  /// 1対1のチャット用のコンストラクタ。管理者は存在しない。
+  ///
+  /// # 引数
+  /// - `user_account_id1` - 1人目のユーザアカウントID
+  /// - `user_account_id2` - 2人目のユーザアカウントID
+  ///
+  /// # 戻り値
+  /// - [Members]
+  pub fn new_direct(user_account_id1: UserAccountId, user_account_id2: UserAccountId) -> Self {
+    let mut my_self = Self {
+      members_ids_by_user_account_id: BTreeMap::new(),
+      members: BTreeMap::new(),
+    };
+    my_self.add_member(Member::new(MemberId::new(), user_account_id1, MemberRole::Member));
+    my_self.add_member(Member::new(MemberId::new(), user_account_id2, MemberRole::Member));
+    my_self
+  }
+
+  /// 最初の管理者を取得する。管理者が存在しない場合は[None]を返す。
+  pub fn find_administrator(&self) -> Option<&Member> {
+    self
+      .members
+      .values()
+      .find(|member| *member.breach_encapsulation_of_role() == MemberRole::Admin)
+  }
+
   /// 最初の管理者を取得する。
   pub fn administrator_id(&self) -> &Member {
     self
@@ -44,6 +70,17 @@ impl Members {
     self.is_role(user_account_id, &[MemberRole::Admin])
   }
 
+  /// 唯一の管理者かどうかを判定する。
+  pub fn is_last_administrator(&self, user_account_id: &UserAccountId) -> bool {
+    self.is_administrator(user_account_id)
+      && self
+        .members
+        .values()
+        .filter(|member| *member.breach_encapsulation_of_role() == MemberRole::Admin)
+        .count()
+        == 1
+  }
+
   /// メンバーかどうかを判定する。
   pub fn is_member(&self, user_account_id: &UserAccountId) -> bool {
     self.is_role(user_account_id, &[MemberRole::Member, MemberRole::Admin])
@@ -100,6 +137,11 @@ impl Members {
     }
   }
 
+  /// メンバー数を取得する。
+  pub fn count(&self) -> usize {
+    self.members.len()
+  }
+
   /// メンバーの一覧を取得する。
   pub fn to_vec(&self) -> Vec<&Member> {
     self.members.values().collect()
@@ -1,14 +1,33 @@
+use crate::group_chat::message_body::MessageBody;
 use crate::group_chat::MessageId;
 use crate::user_account::UserAccountId;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 use thiserror::Error;
 
+const MENTION_PREFIX: &str = "@UserAccount-";
+const ULID_LENGTH: usize = 26;
+
 /// メッセージを表すローカルエンティティ。
+///
+/// NOTE: 削除されたメッセージは集約から取り除かず、`deleted_at`/`deleted_by`を持つ墓標(tombstone)として残す。
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "MessageData")]
 pub struct Message {
   id: MessageId,
-  text: String,
+  body: MessageBody,
   sender_id: UserAccountId,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  mentions: Vec<UserAccountId>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  posted_at: Option<DateTime<Utc>>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  deleted_at: Option<DateTime<Utc>>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  deleted_by: Option<UserAccountId>,
+  #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+  redacted: bool,
 }
 
 impl Message {
@@ -16,38 +35,280 @@ impl Message {
     &self.id
   }
 
+  /// 本文を表す文字列を返す。
+  ///
+  /// 添付ファイル・リンクプレビューの場合はURLを返す。
   pub fn breach_encapsulation_of_text(&self) -> &str {
-    &self.text
+    self.body.as_text()
+  }
+
+  pub fn breach_encapsulation_of_body(&self) -> &MessageBody {
+    &self.body
+  }
+
+  pub fn with_text(self, text: String) -> Self {
+    self.with_body(MessageBody::Text { text })
   }
 
-  pub fn with_text(mut self, text: String) -> Self {
-    self.text = text;
+  /// 本文を置き換えたメッセージを返す。
+  pub fn with_body(mut self, body: MessageBody) -> Self {
+    self.mentions = parse_body_mentions(&body);
+    self.body = body;
     self
   }
 
+  /// システムメッセージかどうかを返す。
+  pub fn is_system(&self) -> bool {
+    self.body.is_system()
+  }
+
   pub fn breach_encapsulation_of_sender_id(&self) -> &UserAccountId {
     &self.sender_id
   }
 
+  /// 本文中でメンションされている[UserAccountId]を返す。
+  pub fn breach_encapsulation_of_mentions(&self) -> &[UserAccountId] {
+    &self.mentions
+  }
+
+  pub fn breach_encapsulation_of_posted_at(&self) -> Option<&DateTime<Utc>> {
+    self.posted_at.as_ref()
+  }
+
+  /// 投稿日時を設定したメッセージを返す。
+  pub fn with_posted_at(mut self, posted_at: DateTime<Utc>) -> Self {
+    self.posted_at = Some(posted_at);
+    self
+  }
+
+  pub fn breach_encapsulation_of_deleted_at(&self) -> Option<&DateTime<Utc>> {
+    self.deleted_at.as_ref()
+  }
+
+  pub fn breach_encapsulation_of_deleted_by(&self) -> Option<&UserAccountId> {
+    self.deleted_by.as_ref()
+  }
+
+  /// 削除済み(墓標)かどうかを返す。
+  pub fn is_deleted(&self) -> bool {
+    self.deleted_at.is_some()
+  }
+
+  /// 削除済みとしてマークしたメッセージを返す。
+  ///
+  /// # 引数
+  /// - `deleted_by` - 削除したユーザアカウントID
+  /// - `deleted_at` - 削除日時
+  pub fn with_deleted(mut self, deleted_by: UserAccountId, deleted_at: DateTime<Utc>) -> Self {
+    self.deleted_by = Some(deleted_by);
+    self.deleted_at = Some(deleted_at);
+    self
+  }
+
+  /// 本文が消去されているかどうかを返す。
+  pub fn is_redacted(&self) -> bool {
+    self.redacted
+  }
+
+  /// 本文とメンションを消去したメッセージを返す(ユーザアカウントの消去)。
+  ///
+  /// NOTE: 前後のメッセージとの関係を保つため、メッセージ自体は残す。システムメッセージはシステムメッセージのまま消去する。
+  pub fn with_redacted(mut self) -> Self {
+    self.body = if self.body.is_system() {
+      MessageBody::System { text: String::new() }
+    } else {
+      MessageBody::Text { text: String::new() }
+    };
+    self.mentions = Vec::new();
+    self.redacted = true;
+    self
+  }
+
+  /// 削除済みのマークを外したメッセージを返す。
+  pub fn with_restored(mut self) -> Self {
+    self.deleted_by = None;
+    self.deleted_at = None;
+    self
+  }
+
   pub fn new(id: MessageId, text: String, sender_id: UserAccountId) -> Self {
-    Self { id, text, sender_id }
+    Self::new_with_body(id, MessageBody::Text { text }, sender_id)
+  }
+
+  /// 本文を指定してメッセージを生成する。
+  ///
+  /// # 引数
+  /// - `id` - メッセージID
+  /// - `body` - 本文
+  /// - `sender_id` - 送信者のユーザアカウントID
+  pub fn new_with_body(id: MessageId, body: MessageBody, sender_id: UserAccountId) -> Self {
+    let mentions = parse_body_mentions(&body);
+    Self {
+      id,
+      body,
+      sender_id,
+      mentions,
+      posted_at: None,
+      deleted_at: None,
+      deleted_by: None,
+      redacted: false,
+    }
   }
 
   pub fn validate(text: &str, message_id: MessageId, sender_id: UserAccountId) -> Result<Self, MessageError> {
-    if text.is_empty() {
-      return Err(MessageError::Empty);
+    Self::validate_body(MessageBody::Text { text: text.to_string() }, message_id, sender_id)
+  }
+
+  /// ユーザが投稿する本文を検証してメッセージを生成する。
+  ///
+  /// # 引数
+  /// - `body` - 本文
+  /// - `message_id` - メッセージID
+  /// - `sender_id` - 送信者のユーザアカウントID
+  ///
+  /// # 戻り値
+  /// - 検証に失敗した場合は[MessageError]を返す。システムメッセージはユーザから投稿できない。
+  pub fn validate_body(
+    body: MessageBody,
+    message_id: MessageId,
+    sender_id: UserAccountId,
+  ) -> Result<Self, MessageError> {
+    if body.is_system() {
+      return Err(MessageError::SystemNotAllowed);
     }
-    if text.len() > 1000 {
-      return Err(MessageError::TooLong);
+    body.validate()?;
+    Ok(Message::new_with_body(message_id, body, sender_id))
+  }
+}
+
+/// [Message]のデシリアライズ用の表現。
+///
+/// NOTE: [MessageBody]導入前のイベントは`text`のみを持つため、その場合はテキスト本文として扱う。
+#[derive(Deserialize)]
+struct MessageData {
+  id: MessageId,
+  #[serde(default)]
+  body: Option<MessageBody>,
+  #[serde(default)]
+  text: Option<String>,
+  sender_id: UserAccountId,
+  #[serde(default)]
+  mentions: Vec<UserAccountId>,
+  #[serde(default)]
+  posted_at: Option<DateTime<Utc>>,
+  #[serde(default)]
+  deleted_at: Option<DateTime<Utc>>,
+  #[serde(default)]
+  deleted_by: Option<UserAccountId>,
+  #[serde(default)]
+  redacted: bool,
+}
+
+impl From<MessageData> for Message {
+  fn from(data: MessageData) -> Self {
+    let body = data.body.unwrap_or_else(|| MessageBody::Text {
+      text: data.text.unwrap_or_default(),
+    });
+    Self {
+      id: data.id,
+      body,
+      sender_id: data.sender_id,
+      mentions: data.mentions,
+      posted_at: data.posted_at,
+      deleted_at: data.deleted_at,
+      deleted_by: data.deleted_by,
+      redacted: data.redacted,
     }
-    Ok(Message::new(message_id, text.to_string(), sender_id))
   }
 }
 
+/// テキスト本文の場合のみメンションを抽出する。
+fn parse_body_mentions(body: &MessageBody) -> Vec<UserAccountId> {
+  match body {
+    MessageBody::Text { text } => parse_mentions(text),
+    _ => Vec::new(),
+  }
+}
+
+/// 本文から`@UserAccount-<ULID>`形式のメンションを抽出する。
+///
+/// # 引数
+/// - `text` - メッセージ本文
+///
+/// # 戻り値
+/// - メンションされている[UserAccountId]の一覧(重複は除き、出現順)
+fn parse_mentions(text: &str) -> Vec<UserAccountId> {
+  let mut mentions: Vec<UserAccountId> = Vec::new();
+  for (index, _) in text.match_indices(MENTION_PREFIX) {
+    let start = index + MENTION_PREFIX.len();
+    let candidate = match text.get(start..start + ULID_LENGTH) {
+      Some(candidate) if candidate.chars().all(|c| c.is_ascii_alphanumeric()) => candidate,
+      _ => continue,
+    };
+    if let Ok(user_account_id) = UserAccountId::from_str(candidate) {
+      if !mentions.contains(&user_account_id) {
+        mentions.push(user_account_id);
+      }
+    }
+  }
+  mentions
+}
+
 #[derive(Debug, Clone, Error)]
 pub enum MessageError {
   #[error("the message is empty")]
   Empty,
   #[error("the message is too long")]
   TooLong,
+  #[error("the url is invalid: {0}")]
+  InvalidUrl(String),
+  #[error("the mime type is invalid: {0}")]
+  InvalidMime(String),
+  #[error("the attachment size is invalid: {0}")]
+  InvalidSize(u64),
+  #[error("the system message cannot be posted by users")]
+  SystemNotAllowed,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_mentions() {
+    let user_account_id1 = UserAccountId::new();
+    let user_account_id2 = UserAccountId::new();
+    let text = format!(
+      "hi @{} and @{}, @{} again. @UserAccount-invalid",
+      user_account_id1, user_account_id2, user_account_id1
+    );
+    let message = Message::new(MessageId::new(), text, UserAccountId::new());
+    assert_eq!(
+      message.breach_encapsulation_of_mentions(),
+      &[user_account_id1, user_account_id2]
+    );
+  }
+
+  #[test]
+  fn test_deserialize_legacy_message() {
+    let message = Message::new(MessageId::new(), "hello".to_string(), UserAccountId::new());
+    let mut json = serde_json::to_value(&message).unwrap();
+    let object = json.as_object_mut().unwrap();
+    object.remove("body");
+    object.insert("text".to_string(), serde_json::Value::String("hello".to_string()));
+    let actual: Message = serde_json::from_value(json).unwrap();
+    assert_eq!(actual, message);
+  }
+
+  #[test]
+  fn test_validate_body_rejects_system() {
+    let result = Message::validate_body(
+      MessageBody::System {
+        text: "joined".to_string(),
+      },
+      MessageId::new(),
+      UserAccountId::new(),
+    );
+    assert!(matches!(result, Err(MessageError::SystemNotAllowed)));
+  }
 }
@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+
+use crate::group_chat::message::MessageError;
+
+/// テキスト本文の最大文字数
+const MAX_TEXT_LENGTH: usize = 1000;
+/// URLの最大文字数
+const MAX_URL_LENGTH: usize = 2048;
+/// MIMEタイプの最大文字数
+const MAX_MIME_LENGTH: usize = 255;
+/// 添付ファイルの最大サイズ(バイト)
+const MAX_ATTACHMENT_SIZE: u64 = 100 * 1024 * 1024;
+/// リンクプレビューのタイトルの最大文字数
+const MAX_LINK_TITLE_LENGTH: usize = 200;
+/// リンクプレビューの説明の最大文字数
+const MAX_LINK_DESCRIPTION_LENGTH: usize = 1000;
+
+/// [crate::group_chat::Message]の本文を表す値オブジェクト。
+///
+/// NOTE: 文字数はバイト数ではなく文字(char)単位で数える。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum MessageBody {
+  /// テキスト
+  Text { text: String },
+  /// 添付ファイル
+  Attachment { url: String, mime: String, size: u64 },
+  /// リンクプレビュー
+  LinkPreview {
+    url: String,
+    title: Option<String>,
+    description: Option<String>,
+  },
+  /// システムメッセージ(参加・退出の通知など)。集約自身が生成する。
+  System { text: String },
+}
+
+impl MessageBody {
+  /// 本文の種類を表す文字列を返す。
+  pub fn content_type(&self) -> &'static str {
+    match self {
+      MessageBody::Text { .. } => "text",
+      MessageBody::Attachment { .. } => "attachment",
+      MessageBody::LinkPreview { .. } => "link_preview",
+      MessageBody::System { .. } => "system",
+    }
+  }
+
+  /// 本文を表す文字列を返す。
+  ///
+  /// # 戻り値
+  /// - テキスト・システムメッセージの場合は本文、添付ファイル・リンクプレビューの場合はURLを返す。
+  pub fn as_text(&self) -> &str {
+    match self {
+      MessageBody::Text { text } | MessageBody::System { text } => text,
+      MessageBody::Attachment { url, .. } | MessageBody::LinkPreview { url, .. } => url,
+    }
+  }
+
+  /// システムメッセージかどうかを返す。
+  pub fn is_system(&self) -> bool {
+    matches!(self, MessageBody::System { .. })
+  }
+
+  /// 本文を検証する。
+  ///
+  /// # 戻り値
+  /// - 検証に失敗した場合は[MessageError]を返す。
+  pub fn validate(&self) -> Result<(), MessageError> {
+    match self {
+      MessageBody::Text { text } | MessageBody::System { text } => validate_text(text),
+      MessageBody::Attachment { url, mime, size } => {
+        validate_url(url)?;
+        if mime.chars().count() > MAX_MIME_LENGTH || !is_valid_mime(mime) {
+          return Err(MessageError::InvalidMime(mime.clone()));
+        }
+        if *size == 0 || *size > MAX_ATTACHMENT_SIZE {
+          return Err(MessageError::InvalidSize(*size));
+        }
+        Ok(())
+      }
+      MessageBody::LinkPreview {
+        url,
+        title,
+        description,
+      } => {
+        validate_url(url)?;
+        if title
+          .as_ref()
+          .map(|t| t.chars().count() > MAX_LINK_TITLE_LENGTH)
+          .unwrap_or(false)
+        {
+          return Err(MessageError::TooLong);
+        }
+        if description
+          .as_ref()
+          .map(|d| d.chars().count() > MAX_LINK_DESCRIPTION_LENGTH)
+          .unwrap_or(false)
+        {
+          return Err(MessageError::TooLong);
+        }
+        Ok(())
+      }
+    }
+  }
+}
+
+fn validate_text(text: &str) -> Result<(), MessageError> {
+  if text.is_empty() {
+    return Err(MessageError::Empty);
+  }
+  if text.chars().count() > MAX_TEXT_LENGTH {
+    return Err(MessageError::TooLong);
+  }
+  Ok(())
+}
+
+fn validate_url(url: &str) -> Result<(), MessageError> {
+  let rest = url.strip_prefix("https://").or_else(|| url.strip_prefix("http://"));
+  match rest {
+    Some(rest) if !rest.is_empty() && url.chars().count() <= MAX_URL_LENGTH && !url.contains(char::is_whitespace) => {
+      Ok(())
+    }
+    _ => Err(MessageError::InvalidUrl(url.to_string())),
+  }
+}
+
+fn is_valid_mime(mime: &str) -> bool {
+  match mime.split_once('/') {
+    Some((t, s)) => !t.is_empty() && !s.is_empty() && !mime.contains(char::is_whitespace),
+    None => false,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_validate_text_counts_chars() {
+    let text = "あ".repeat(1000);
+    assert!(MessageBody::Text { text }.validate().is_ok());
+    let text = "あ".repeat(1001);
+    assert!(matches!(
+      MessageBody::Text { text }.validate(),
+      Err(MessageError::TooLong)
+    ));
+  }
+
+  #[test]
+  fn test_validate_attachment() {
+    let body = MessageBody::Attachment {
+      url: "https://example.com/a.png".to_string(),
+      mime: "image/png".to_string(),
+      size: 1024,
+    };
+    assert!(body.validate().is_ok());
+    let body = MessageBody::Attachment {
+      url: "ftp://example.com/a.png".to_string(),
+      mime: "image/png".to_string(),
+      size: 1024,
+    };
+    assert!(matches!(body.validate(), Err(MessageError::InvalidUrl(_))));
+    let body = MessageBody::Attachment {
+      url: "https://example.com/a.png".to_string(),
+      mime: "png".to_string(),
+      size: 1024,
+    };
+    assert!(matches!(body.validate(), Err(MessageError::InvalidMime(_))));
+    let body = MessageBody::Attachment {
+      url: "https://example.com/a.png".to_string(),
+      mime: "image/png".to_string(),
+      size: 0,
+    };
+    assert!(matches!(body.validate(), Err(MessageError::InvalidSize(_))));
+  }
+}
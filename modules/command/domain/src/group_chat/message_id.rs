@@ -3,6 +3,7 @@ use std::str::FromStr;
 
 use crate::group_chat::ParseError;
 use crate::id_generate;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use ulid_generator_rs::ULID;
 
@@ -18,6 +19,17 @@ impl MessageId {
     let value = id_generate();
     Self { value }
   }
+
+  /// ULIDに含まれる生成日時を返す。
+  pub fn generated_at(&self) -> DateTime<Utc> {
+    self.value.to_date_time().with_timezone(&Utc)
+  }
+}
+
+impl Default for MessageId {
+  fn default() -> Self {
+    Self::new()
+  }
 }
 
 impl Display for MessageId {
@@ -1,11 +1,15 @@
 use crate::group_chat::message::Message;
 use crate::group_chat::message_id::MessageId;
 use crate::group_chat_error::GroupChatError;
-use crate::group_chat_error::GroupChatError::{NotFoundMessageError, NotSenderError};
+use crate::group_chat_error::GroupChatError::{NotDeletedMessageError, NotFoundMessageError, NotSenderError};
 use crate::user_account::UserAccountId;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 /// [GroupChat]内でやりとりする[Message]の集合。
+///
+/// NOTE: 削除された[Message]は墓標として保持する。`len`, `iter`, `contains`などの参照系メソッドは
+/// 削除されていない[Message]のみを対象とする。
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Messages(Vec<Message>);
 
@@ -17,7 +21,12 @@ impl Messages {
 
   /// [Message]の件数を返す。
   pub fn len(&self) -> usize {
-    self.0.len()
+    self.iter().count()
+  }
+
+  /// [Message]が空かどうかを返す。
+  pub fn is_empty(&self) -> bool {
+    self.iter().next().is_none()
   }
 
   /// 指定したインデックスの[Message]への参照を返す。
@@ -28,12 +37,17 @@ impl Messages {
   /// # 戻り値
   /// - 指定したインデックスの[Message]への参照を返す。
   pub fn get_at(&self, index: usize) -> Option<&Message> {
-    self.0.get(index)
+    self.iter().nth(index)
   }
 
   /// [Message]のイテレータを返す。
   pub fn iter(&self) -> impl Iterator<Item = &Message> {
-    self.0.iter()
+    self.0.iter().filter(|message| !message.is_deleted())
+  }
+
+  /// 削除済みの[Message]のイテレータを返す。
+  pub fn iter_deleted(&self) -> impl Iterator<Item = &Message> {
+    self.0.iter().filter(|message| message.is_deleted())
   }
 
   /// 指定した[MessageId]を持つ[Message]が含まれているかどうかを返す。
@@ -45,7 +59,6 @@ impl Messages {
   /// - 指定した[MessageId]を持つ[Message]が含まれている場合は`true`を返す。
   pub fn contains(&self, message_id: &MessageId) -> bool {
     self
-      .0
       .iter()
       .any(|message| *message.breach_encapsulation_of_id() == *message_id)
   }
@@ -59,17 +72,49 @@ impl Messages {
   /// - 指定した[MessageId]を持つ[Message]が含まれている場合は[Message]への参照を返す。
   pub fn find_by_id(&self, message_id: &MessageId) -> Option<&Message> {
     self
-      .0
       .iter()
       .find(|message| *message.breach_encapsulation_of_id() == *message_id)
   }
 
+  /// 指定した[MessageId]を持つ削除済みの[Message]を返す。
+  ///
+  /// # 引数
+  /// - `message_id` - 検索する[Message]のID
+  ///
+  /// # 戻り値
+  /// - 指定した[MessageId]を持つ削除済みの[Message]が含まれている場合は[Message]への参照を返す。
+  pub fn find_deleted_by_id(&self, message_id: &MessageId) -> Option<&Message> {
+    self
+      .iter_deleted()
+      .find(|message| *message.breach_encapsulation_of_id() == *message_id)
+  }
+
+  /// 指定した送信者の本文が消去されていない[Message]が、削除済みかどうかに関わらず含まれているかどうかを返す。
+  ///
+  /// # 引数
+  /// - `sender_id` - 送信者のユーザアカウントID
+  pub fn contains_unredacted_by_sender(&self, sender_id: &UserAccountId) -> bool {
+    self
+      .0
+      .iter()
+      .any(|message| message.breach_encapsulation_of_sender_id() == sender_id && !message.is_redacted())
+  }
+
+  fn position(&self, message_id: &MessageId) -> Option<usize> {
+    self
+      .0
+      .iter()
+      .position(|m| *m.breach_encapsulation_of_id() == *message_id)
+  }
+
   /// [Message]を追加する。
   ///
   /// # 引数
   /// - `message` - 追加する[Message]
+  ///
+  /// NOTE: 削除済みの[Message]と同じIDを持つ[Message]も追加できない。
   pub fn add(&mut self, message: Message) -> Result<(), GroupChatError> {
-    if self.contains(message.breach_encapsulation_of_id()) {
+    if self.position(message.breach_encapsulation_of_id()).is_some() {
       return Err(GroupChatError::AlreadyExistsMessageError(
         message.breach_encapsulation_of_id().clone(),
       ));
@@ -80,50 +125,133 @@ impl Messages {
 
   /// [Message]を編集する。
   ///
+  /// NOTE: 本文([crate::group_chat::MessageBody])のみを置き換え、投稿日時などは維持する。
+  ///
   /// # 引数
   /// - `message` - 編集する[Message]
   pub fn edit(&mut self, message: Message) -> Result<(), GroupChatError> {
-    let index = self
-      .0
-      .iter()
-      .position(|m| *m.breach_encapsulation_of_id() == *message.breach_encapsulation_of_id());
+    let index = self.position(message.breach_encapsulation_of_id());
     match index {
-      Some(i) => {
+      Some(i) if !self.0[i].is_deleted() => {
         if self.0[i].breach_encapsulation_of_sender_id() != message.breach_encapsulation_of_sender_id() {
           return Err(NotSenderError(
             "message.sender_id".to_string(),
             message.breach_encapsulation_of_sender_id().clone(),
           ));
         }
-        self.0[i] = message;
+        self.0[i] = self.0[i]
+          .clone()
+          .with_body(message.breach_encapsulation_of_body().clone());
         Ok(())
       }
-      None => Err(NotFoundMessageError(message.breach_encapsulation_of_id().clone()).into()),
+      _ => Err(NotFoundMessageError(message.breach_encapsulation_of_id().clone())),
     }
   }
 
-  /// 指定した[MessageId]を持つ[Message]を削除する。
+  /// 指定した[MessageId]を持つ[Message]を削除済みとしてマークする。
   ///
   /// # 引数
   /// - `message_id` - 削除する[Message]のID
+  /// - `sender_id` - 削除する[Message]の送信者のユーザアカウントID
+  /// - `deleted_at` - 削除日時
   ///
   /// # 戻り値
-  /// - 削除に失敗した場合は`Err(anyhow!("Message not found"))`を返す。
+  /// - 削除に失敗した場合は`Err(NotFoundMessageError)`または`Err(NotSenderError)`を返す。
   /// - 削除に成功した場合は`Ok(())`を返す。
-  pub fn remove(&mut self, message_id: &MessageId, sender_id: &UserAccountId) -> Result<(), GroupChatError> {
-    let index = self
-      .0
-      .iter()
-      .position(|m| *m.breach_encapsulation_of_id() == *message_id);
-    match index {
-      Some(i) => {
+  pub fn remove(
+    &mut self,
+    message_id: &MessageId,
+    sender_id: &UserAccountId,
+    deleted_at: DateTime<Utc>,
+  ) -> Result<(), GroupChatError> {
+    match self.position(message_id) {
+      Some(i) if !self.0[i].is_deleted() => {
         if self.0[i].breach_encapsulation_of_sender_id() != sender_id {
           return Err(NotSenderError("message.sender_id".to_string(), sender_id.clone()));
         }
-        self.0.remove(i);
+        self.0[i] = self.0[i].clone().with_deleted(sender_id.clone(), deleted_at);
+        Ok(())
+      }
+      _ => Err(NotFoundMessageError(message_id.clone())),
+    }
+  }
+
+  /// 指定した[MessageId]を持つ[Message]を送信者に関わらず削除済みとしてマークする(モデレーション)。
+  ///
+  /// # 引数
+  /// - `message_id` - 削除する[Message]のID
+  /// - `moderator_id` - 削除した管理者のユーザアカウントID
+  /// - `deleted_at` - 削除日時
+  ///
+  /// # 戻り値
+  /// - [Message]が存在しない場合は`Err(NotFoundMessageError)`を返す。
+  /// - 削除に成功した場合は`Ok(())`を返す。
+  pub fn moderate(
+    &mut self,
+    message_id: &MessageId,
+    moderator_id: &UserAccountId,
+    deleted_at: DateTime<Utc>,
+  ) -> Result<(), GroupChatError> {
+    match self.position(message_id) {
+      Some(i) if !self.0[i].is_deleted() => {
+        self.0[i] = self.0[i].clone().with_deleted(moderator_id.clone(), deleted_at);
+        Ok(())
+      }
+      _ => Err(NotFoundMessageError(message_id.clone())),
+    }
+  }
+
+  /// 指定した[MessageId]を持つ[Message]を削除済みかどうかに関わらず集合から取り除く(保持期間の経過による完全削除)。
+  ///
+  /// # 引数
+  /// - `message_id` - 取り除く[Message]のID
+  ///
+  /// # 戻り値
+  /// - [Message]が存在しない場合は`Err(NotFoundMessageError)`を返す。
+  /// - 成功した場合は取り除いた[Message]を返す。
+  pub fn purge(&mut self, message_id: &MessageId) -> Result<Message, GroupChatError> {
+    match self.position(message_id) {
+      Some(i) => Ok(self.0.remove(i)),
+      None => Err(NotFoundMessageError(message_id.clone())),
+    }
+  }
+
+  /// 指定した送信者の[Message]の本文を、削除済みかどうかに関わらず消去する。
+  ///
+  /// # 引数
+  /// - `sender_id` - 送信者のユーザアカウントID
+  ///
+  /// # 戻り値
+  /// - 新たに本文を消去した[Message]のIDを返す。
+  pub fn redact_by_sender(&mut self, sender_id: &UserAccountId) -> Vec<MessageId> {
+    self
+      .0
+      .iter_mut()
+      .filter(|message| message.breach_encapsulation_of_sender_id() == sender_id && !message.is_redacted())
+      .map(|message| {
+        *message = message.clone().with_redacted();
+        message.breach_encapsulation_of_id().clone()
+      })
+      .collect()
+  }
+
+  /// 削除済みの[Message]を復元する。
+  ///
+  /// # 引数
+  /// - `message_id` - 復元する[Message]のID
+  ///
+  /// # 戻り値
+  /// - [Message]が存在しない場合は`Err(NotFoundMessageError)`を返す。
+  /// - [Message]が削除されていない場合は`Err(NotDeletedMessageError)`を返す。
+  /// - 復元に成功した場合は`Ok(())`を返す。
+  pub fn restore(&mut self, message_id: &MessageId) -> Result<(), GroupChatError> {
+    match self.position(message_id) {
+      Some(i) if self.0[i].is_deleted() => {
+        self.0[i] = self.0[i].clone().with_restored();
         Ok(())
       }
-      None => Err(NotFoundMessageError(message_id.clone()).into()),
+      Some(_) => Err(NotDeletedMessageError(message_id.clone())),
+      None => Err(NotFoundMessageError(message_id.clone())),
     }
   }
 }
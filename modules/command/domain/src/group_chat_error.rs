@@ -20,6 +20,38 @@ pub enum GroupChatError {
   NotFoundMessageError(MessageId),
   #[error("This {0} is not the sender of the message: {1:?}")]
   NotSenderError(String, UserAccountId),
+  #[error("The message is not deleted: {0:?}")]
+  NotDeletedMessageError(MessageId),
+  #[error("The restore window of the message has expired: {0:?}")]
+  RestoreWindowExpiredError(MessageId),
+  #[error("The edit window of the message has expired: {0:?}")]
+  EditWindowExpiredError(MessageId),
+  #[error("The moderation reason is empty: {0:?}")]
+  EmptyModerationReasonError(MessageId),
+  #[error("The system message cannot be posted or edited by users: {0:?}")]
+  SystemMessageError(MessageId),
+  #[error("The {0} is not supported in a direct chat: {1:?}")]
+  NotSupportedInDirectChatError(String, GroupChatId),
+  #[error("A direct chat requires two different user accounts: {0:?}")]
+  DirectChatWithSelfError(UserAccountId),
+  #[error("The {0} is already invited to the group chat: {1:?}")]
+  AlreadyInvitedError(String, UserAccountId),
+  #[error("The invitation is not found: {0:?}")]
+  NotFoundInvitationError(UserAccountId),
+  #[error("The last administrator cannot leave the group chat without transferring ownership: {0:?}")]
+  LastAdministratorError(UserAccountId),
+  #[error("The number of members has reached the limit of the group chat: {0:?}, {1}")]
+  MemberLimitExceededError(GroupChatId, usize),
+  #[error("The {0} is not allowed to post in announcement mode: {1:?}")]
+  PostNotAllowedError(String, UserAccountId),
+  #[error("The max members is less than the current number of members: {0} < {1}")]
+  MaxMembersBelowCurrentError(usize, usize),
+  #[error("The message retention period is not configured: {0:?}")]
+  RetentionNotConfiguredError(GroupChatId),
+  #[error("The message has not exceeded the retention period: {0:?}")]
+  MessageNotExpiredError(MessageId),
+  #[error("The user account has no data in the group chat: {0:?}, {1:?}")]
+  NoUserAccountDataError(GroupChatId, UserAccountId),
   #[error("The group chat name is already exists: {0:?}, {1:?}")]
   AlreadyExistsNameError(GroupChatId, GroupChatName),
 }
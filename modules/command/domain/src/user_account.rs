@@ -57,6 +57,12 @@ impl FromStr for UserAccountId {
   }
 }
 
+impl Default for UserAccountId {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
 impl Display for UserAccountId {
   fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
     write!(f, "{}-{}", self.type_name(), self.value)
@@ -10,7 +10,7 @@ use command_domain::user_account::UserAccountId;
 #[derive(Debug, Error)]
 pub enum GroupChatRepositoryError {
   #[error("Failed to store the group chat: {0:?}")]
-  StoreError(GroupChat, EventStoreWriteError),
+  StoreError(Box<GroupChat>, EventStoreWriteError),
   #[error("Failed to find the group chat by id: {0:?}")]
   FindByIdError(GroupChatId, EventStoreReadError),
 }
@@ -29,6 +29,23 @@ pub trait GroupChatRepository: Debug + Clone + Sync + Send + 'static {
   /// - 成功した場合はOk, 失敗した場合はErrを返す。
   async fn store(&mut self, event: &GroupChatEvent, snapshot: &GroupChat) -> Result<(), GroupChatRepositoryError>;
 
+  /// 同じグループチャットの複数のイベント及びスナップショットを、1つのトランザクションで保存する。
+  ///
+  /// NOTE: すべてのイベントが保存されるか、どのイベントも保存されないかのどちらかになります。
+  /// 楽観的ロックの確認は、最初のイベントを適用する前のバージョンに対して一度だけ行います。
+  ///
+  /// # 引数
+  /// - `events` - グループチャットのイベント(発生順)
+  /// - `snapshot` - すべてのイベントを適用した後のグループチャットのスナップショット
+  ///
+  /// # 戻り値
+  /// - 成功した場合はOk, 失敗した場合はErrを返す。
+  async fn store_all(
+    &mut self,
+    events: &[GroupChatEvent],
+    snapshot: &GroupChat,
+  ) -> Result<(), GroupChatRepositoryError>;
+
   /// 指定したグループチャットIDに該当するグループチャットを取得する。
   ///
   /// # 引数
@@ -57,6 +74,20 @@ pub enum GroupChatReadModelUpdateDaoError {
   UpdateMessageError,
   #[error("Failed to delete message")]
   DeleteMessageError,
+  #[error("Failed to purge message")]
+  PurgeMessageError,
+  #[error("Failed to restore message")]
+  RestoreMessageError,
+  #[error("Failed to moderate message")]
+  ModerateMessageError,
+  #[error("Failed to insert invitation")]
+  InsertInvitationError,
+  #[error("Failed to delete invitation")]
+  DeleteInvitationError,
+  #[error("Failed to upsert group chat settings")]
+  UpsertGroupChatSettingsError,
+  #[error("Failed to erase user account")]
+  EraseUserAccountError,
 }
 
 /// グループチャットリードモデル更新用のデータアクセスオブジェクト。
@@ -68,6 +99,7 @@ pub trait GroupChatReadModelUpdateDao {
   async fn insert_group_chat(
     &self,
     aggregate_id: GroupChatId,
+    kind: ChatKind,
     name: GroupChatName,
     administrator_id: UserAccountId,
     created_at: DateTime<Utc>,
@@ -100,6 +132,26 @@ pub trait GroupChatReadModelUpdateDao {
     aggregate_id: GroupChatId,
     account_id: UserAccountId,
   ) -> Result<(), GroupChatReadModelUpdateDaoError>;
+  /// グループチャット設定リードモデルを作成または更新します。
+  async fn upsert_group_chat_settings(
+    &self,
+    aggregate_id: GroupChatId,
+    settings: GroupChatSettings,
+    updated_at: DateTime<Utc>,
+  ) -> Result<(), GroupChatReadModelUpdateDaoError>;
+  /// 招待リードモデルを追加します。同じユーザアカウントへの招待が既に存在する場合は置き換えます。
+  async fn insert_invitation(
+    &self,
+    aggregate_id: GroupChatId,
+    invitation: Invitation,
+    created_at: DateTime<Utc>,
+  ) -> Result<(), GroupChatReadModelUpdateDaoError>;
+  /// 招待リードモデルを削除します。
+  async fn delete_invitation(
+    &self,
+    aggregate_id: GroupChatId,
+    account_id: UserAccountId,
+  ) -> Result<(), GroupChatReadModelUpdateDaoError>;
   /// メッセージリードモデル追加します。
   async fn insert_message(
     &self,
@@ -108,6 +160,7 @@ pub trait GroupChatReadModelUpdateDao {
     created_at: DateTime<Utc>,
   ) -> Result<(), GroupChatReadModelUpdateDaoError>;
 
+  /// メッセージリードモデルを更新します。更新前の本文は履歴として残します。
   async fn update_message(
     &self,
     aggregate_id: GroupChatId,
@@ -116,9 +169,230 @@ pub trait GroupChatReadModelUpdateDao {
   ) -> Result<(), GroupChatReadModelUpdateDaoError>;
 
   /// メッセージリードモデルを削除します。
+  ///
+  /// NOTE: 削除したユーザと削除日時を記録した論理削除です。
   async fn delete_message(
+    &self,
+    message_id: MessageId,
+    deleted_by: UserAccountId,
+    deleted_at: DateTime<Utc>,
+  ) -> Result<(), GroupChatReadModelUpdateDaoError>;
+
+  /// メッセージリードモデルを管理者による削除済みとしてマークします。
+  async fn moderate_message(
+    &self,
+    message_id: MessageId,
+    moderated_by: UserAccountId,
+    reason: String,
+    moderated_at: DateTime<Utc>,
+  ) -> Result<(), GroupChatReadModelUpdateDaoError>;
+
+  /// 保持期間を過ぎたメッセージリードモデルを、編集履歴・メンションを含めて物理削除します。
+  async fn purge_message(&self, message_id: MessageId) -> Result<(), GroupChatReadModelUpdateDaoError>;
+
+  /// 削除されたメッセージリードモデルを復元します。
+  async fn restore_message(
     &self,
     message_id: MessageId,
     updated_at: DateTime<Utc>,
   ) -> Result<(), GroupChatReadModelUpdateDaoError>;
+
+  /// グループチャット内のユーザアカウントに関するリードモデルを消去します。
+  ///
+  /// NOTE: メンバー・招待・メンションを削除し、送信したメッセージの本文と編集履歴を消去します。
+  async fn erase_user_account(
+    &self,
+    aggregate_id: GroupChatId,
+    account_id: UserAccountId,
+    updated_at: DateTime<Utc>,
+  ) -> Result<(), GroupChatReadModelUpdateDaoError>;
+}
+
+#[derive(Debug, Clone, Error)]
+pub enum UserAccountGroupChatDaoError {
+  #[error("Failed to get group chat ids: {0}")]
+  OtherError(String),
+}
+
+/// ユーザアカウントが関与しているグループチャットを検索するためのデータアクセスオブジェクト。
+///
+/// NOTE: 個人データの消去対象を特定するために、コマンド側からリードモデルを参照します。
+/// リードモデルへの反映は非同期なので、直前の変更は含まれない場合があります。
+#[async_trait::async_trait]
+pub trait UserAccountGroupChatDao: Send + Sync {
+  /// ユーザアカウントがメンバー・招待中・メッセージの送信者のいずれかであるグループチャットのIDを取得します。
+  async fn get_group_chat_ids(
+    &self,
+    user_account_id: &UserAccountId,
+  ) -> Result<Vec<GroupChatId>, UserAccountGroupChatDaoError>;
+}
+
+#[derive(Debug, Clone, Error)]
+pub enum UserAccountKeyStoreError {
+  #[error("Failed to access the key store: {0}")]
+  IOError(String),
+  #[error("The key is invalid: {0:?}, {1}")]
+  InvalidKeyError(UserAccountId, String),
+  #[error("The key is not found, but the keys of the user account have not been shredded: {0:?}, {1}")]
+  KeyNotFoundError(UserAccountId, String),
+  #[error("The keys of the user account have been shredded: {0:?}")]
+  ShreddedError(UserAccountId),
+}
+
+/// ユーザアカウントの暗号鍵。
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserAccountKey {
+  /// 鍵ID。暗号文と一緒に保存され、復号時に鍵を特定するために利用する
+  pub key_id: String,
+  pub key: Vec<u8>,
+}
+
+/// ユーザアカウントごとの暗号鍵を管理するキーストア。
+///
+/// NOTE: メッセージの本文をユーザアカウントごとの鍵で暗号化してジャーナルに保存し、
+/// 鍵を削除することでジャーナル上の本文を読めなくする(クリプトシュレッディング)ために利用します。
+/// イベントのシリアライズから呼び出されるため、同期的なインターフェイスになっています。
+/// 鍵をローテーションしても古い鍵は残るので、過去のイベントを書き換える必要はありません。
+pub trait UserAccountKeyStore: Debug + Send + Sync + 'static {
+  /// 暗号化に利用する最新の鍵を取得します。存在しない場合は生成します。
+  ///
+  /// # 戻り値
+  /// - 鍵が削除済みの場合は、鍵を生成し直さずに[UserAccountKeyStoreError::ShreddedError]を返します。
+  fn get_or_create_current_key(
+    &self,
+    user_account_id: &UserAccountId,
+  ) -> Result<UserAccountKey, UserAccountKeyStoreError>;
+
+  /// 鍵IDを指定して鍵を取得します。存在しない場合は`None`を返します。
+  fn find_key(
+    &self,
+    user_account_id: &UserAccountId,
+    key_id: &str,
+  ) -> Result<Option<Vec<u8>>, UserAccountKeyStoreError>;
+
+  /// 新しい鍵を生成し、以降の暗号化に利用する鍵とします。
+  ///
+  /// # 戻り値
+  /// - 鍵が削除済みの場合は[UserAccountKeyStoreError::ShreddedError]を返します。
+  fn rotate_key(&self, user_account_id: &UserAccountId) -> Result<UserAccountKey, UserAccountKeyStoreError>;
+
+  /// ユーザアカウントのすべての鍵を削除します。鍵が存在しない場合も成功とします。
+  ///
+  /// NOTE: 鍵を削除する前に、削除したことを記録します(墓標)。
+  /// 鍵が見つからない理由が削除によるものか、鍵を共有していないなどの設定の誤りによるものかを区別するためです。
+  fn delete_keys(&self, user_account_id: &UserAccountId) -> Result<(), UserAccountKeyStoreError>;
+
+  /// [UserAccountKeyStore::delete_keys]で鍵が削除されている場合は`true`を返します。
+  fn is_shredded(&self, user_account_id: &UserAccountId) -> Result<bool, UserAccountKeyStoreError>;
+}
+
+/// 暗号化されたペイロード。
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncryptedPayload {
+  /// 暗号化に利用した鍵の鍵ID
+  pub key_id: String,
+  pub nonce: Vec<u8>,
+  pub ciphertext: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Error)]
+pub enum PayloadCipherError {
+  #[error("UserAccountKeyStoreError: {0}")]
+  KeyStoreError(#[from] UserAccountKeyStoreError),
+  #[error("Failed to encrypt or decrypt the payload: {0}")]
+  CipherError(String),
+}
+
+/// イベントのペイロードを、所有者のユーザアカウントごとに暗号化する。
+///
+/// NOTE: [UserAccountKeyStore]と同様に、イベントのシリアライズから呼び出されるため同期的なインターフェイスになっています。
+pub trait PayloadCipher: Debug + Send + Sync + 'static {
+  /// ペイロードを暗号化します。
+  ///
+  /// # 引数
+  /// - `owner_id` - ペイロードの所有者(メッセージの送信者)
+  /// - `plaintext` - 平文
+  /// - `aad` - 追加の認証データ
+  ///
+  /// # 戻り値
+  /// - 暗号化しない場合は`None`を返します。
+  fn encrypt(
+    &self,
+    owner_id: &UserAccountId,
+    plaintext: &[u8],
+    aad: &[u8],
+  ) -> Result<Option<EncryptedPayload>, PayloadCipherError>;
+
+  /// ペイロードを復号します。
+  ///
+  /// # 戻り値
+  /// - 鍵が削除済みの場合は`None`を返します。
+  /// - 鍵が見つからないが削除が記録されていない場合は[UserAccountKeyStoreError::KeyNotFoundError]を返します。
+  fn decrypt(
+    &self,
+    owner_id: &UserAccountId,
+    payload: &EncryptedPayload,
+    aad: &[u8],
+  ) -> Result<Option<Vec<u8>>, PayloadCipherError>;
+
+  /// 所有者のすべての鍵を削除して、暗号化済みのペイロードを復号できなくします(クリプトシュレッディング)。
+  fn shred(&self, owner_id: &UserAccountId) -> Result<(), PayloadCipherError>;
+}
+
+#[derive(Debug, Clone, Error)]
+pub enum IdempotencyStoreError {
+  #[error("Failed to access the idempotency store: {0}")]
+  IOError(String),
+}
+
+/// 冪等キーに保存された記録。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdempotencyRecord {
+  /// 操作を実行中
+  InProgress { fingerprint: String },
+  /// 操作が成功した
+  Completed { fingerprint: String, result: String },
+}
+
+impl IdempotencyRecord {
+  /// 冪等キーを獲得した操作のフィンガープリント(操作名と入力のハッシュ)を返します。
+  pub fn fingerprint(&self) -> &str {
+    match self {
+      IdempotencyRecord::InProgress { fingerprint } | IdempotencyRecord::Completed { fingerprint, .. } => fingerprint,
+    }
+  }
+}
+
+/// 冪等キーと、そのキーで実行した操作の結果を保存するストア。
+///
+/// NOTE: クライアントがタイムアウトなどで同じ操作を再送した場合に、操作を再実行せずに最初の結果を返すために利用します。
+/// 結果は一定期間(TTL)が過ぎると破棄されるので、その後の再送は新しい操作として扱われます。
+/// 実行中の記録は、プロセスが停止しても残り続けないように、結果より短い期間で破棄されます。
+#[async_trait::async_trait]
+pub trait IdempotencyStore: Debug + Send + Sync + 'static {
+  /// 冪等キーを獲得し、操作を実行中であることを記録します。
+  ///
+  /// NOTE: 同じキーの操作が同時に実行されないように、記録が存在しない(または期限切れの)場合のみ原子的に記録します。
+  ///
+  /// # 引数
+  /// - `key` - 冪等キー
+  /// - `fingerprint` - 操作名と入力のハッシュ
+  ///
+  /// # 戻り値
+  /// - 獲得できた場合は`None`、既に記録がある場合はその記録を返します。
+  async fn claim(&self, key: &str, fingerprint: &str) -> Result<Option<IdempotencyRecord>, IdempotencyStoreError>;
+
+  /// 獲得した冪等キーに、操作の結果を保存します。
+  ///
+  /// # 引数
+  /// - `key` - 冪等キー
+  /// - `fingerprint` - 操作名と入力のハッシュ
+  /// - `result` - 操作の結果(JSON)
+  async fn complete(&self, key: &str, fingerprint: &str, result: &str) -> Result<(), IdempotencyStoreError>;
+
+  /// 操作が失敗した場合に、獲得した冪等キーを解放します。解放したキーで再送すると操作を再実行します。
+  ///
+  /// # 引数
+  /// - `key` - 冪等キー
+  async fn release(&self, key: &str) -> Result<(), IdempotencyStoreError>;
 }
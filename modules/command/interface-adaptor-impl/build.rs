@@ -0,0 +1,12 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+  // NOTE: protocをインストールしていない環境でもビルドできるように、同梱のprotocを使う
+  std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+  // NOTE: 冪等キーのフィンガープリントと結果の保存のために、メッセージをserdeでシリアライズできるようにする
+  tonic_build::configure()
+    .type_attribute(".", "#[derive(serde::Serialize, serde::Deserialize)]")
+    .compile(
+      &["../../../proto/group_chat/command/v1/group_chat_command.proto"],
+      &["../../../proto"],
+    )?;
+  Ok(())
+}
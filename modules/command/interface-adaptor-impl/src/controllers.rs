@@ -1,19 +1,35 @@
 use async_graphql::http::GraphiQLSource;
 use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use axum::http::header::CONTENT_TYPE;
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
-use axum::routing::get;
-use axum::{response, Extension, Router};
+use axum::routing::{delete, get, patch, post};
+use axum::{response, Extension, Json, Router};
+use command_interface_adaptor_if::UserAccountGroupChatDao;
+use command_processor::group_chat_command_processor::GroupChatCommandProcessor;
+use infrastructure::graphql::{GraphQLSettings, GraphQLSettingsError};
+use infrastructure::health::ReadinessChecker;
+use infrastructure::metrics::{gather_metrics, METRICS_CONTENT_TYPE};
+use std::sync::Arc;
 
 use crate::gateways::group_chat_repository::GroupChatRepositoryImpl;
 
-use crate::graphql::{create_schema, ApiSchema, ES};
+use crate::graphql::{create_schema_with_context, ApiSchema, ServiceContext, ES};
+use crate::rest;
 
 pub enum EndpointPaths {
   Root,
   HealthAlive,
   HealthReady,
+  Metrics,
   GraphQL,
+  GroupChats,
+  GroupChat,
+  GroupChatMembers,
+  GroupChatMember,
+  GroupChatMessages,
+  GroupChatMessage,
+  OpenApi,
 }
 
 impl EndpointPaths {
@@ -22,7 +38,15 @@ impl EndpointPaths {
       EndpointPaths::Root => "/",
       EndpointPaths::HealthAlive => "/health/alive",
       EndpointPaths::HealthReady => "/health/ready",
+      EndpointPaths::Metrics => "/metrics",
       EndpointPaths::GraphQL => "/query",
+      EndpointPaths::GroupChats => "/group-chats",
+      EndpointPaths::GroupChat => "/group-chats/:group_chat_id",
+      EndpointPaths::GroupChatMembers => "/group-chats/:group_chat_id/members",
+      EndpointPaths::GroupChatMember => "/group-chats/:group_chat_id/members/:user_account_id",
+      EndpointPaths::GroupChatMessages => "/group-chats/:group_chat_id/messages",
+      EndpointPaths::GroupChatMessage => "/group-chats/:group_chat_id/messages/:message_id",
+      EndpointPaths::OpenApi => "/openapi.json",
     }
   }
 }
@@ -35,8 +59,22 @@ pub async fn alive() -> impl IntoResponse {
   (StatusCode::OK, "OK")
 }
 
-pub async fn ready() -> impl IntoResponse {
-  (StatusCode::OK, "OK")
+/// 依存先(ジャーナル・スナップショットのテーブルとMySQL)に疎通できる場合のみ`200 OK`を返すエンドポイント。
+///
+/// 疎通できない場合は`503 Service Unavailable`を返す。いずれも依存先ごとの状態とレイテンシをJSONで返す。
+pub async fn ready(Extension(readiness_checker): Extension<Arc<ReadinessChecker>>) -> impl IntoResponse {
+  let report = readiness_checker.check().await;
+  let status_code = if report.is_ready() {
+    StatusCode::OK
+  } else {
+    StatusCode::SERVICE_UNAVAILABLE
+  };
+  (status_code, Json(report))
+}
+
+/// Prometheusのメトリクスを返すエンドポイント。
+pub async fn metrics() -> impl IntoResponse {
+  ([(CONTENT_TYPE, METRICS_CONTENT_TYPE)], gather_metrics())
 }
 
 /// GraphQLのリクエストを受け付けるエンドポイント。
@@ -53,12 +91,51 @@ async fn graphql() -> impl IntoResponse {
   )
 }
 
-pub fn create_router(repository: GroupChatRepositoryImpl<ES>) -> Router {
-  let schema = create_schema(repository);
-  Router::new()
-    .route(EndpointPaths::Root.as_str(), get(hello_write_api))
-    .route(EndpointPaths::HealthAlive.as_str(), get(alive))
-    .route(EndpointPaths::HealthReady.as_str(), get(ready))
-    .route(EndpointPaths::GraphQL.as_str(), get(graphql).post(graphql_handler))
-    .layer(Extension(schema))
+pub fn create_router(
+  processor: GroupChatCommandProcessor<GroupChatRepositoryImpl<ES>>,
+  user_account_group_chat_dao: Arc<dyn UserAccountGroupChatDao>,
+  readiness_checker: Arc<ReadinessChecker>,
+  graphql_settings: &GraphQLSettings,
+) -> Result<Router, GraphQLSettingsError> {
+  let ctx = ServiceContext::new(processor, user_account_group_chat_dao);
+  create_router_with_context(ctx, readiness_checker, graphql_settings)
+}
+
+/// [ServiceContext]を共有する[Router]を生成する関数。
+///
+/// # 引数
+/// - `ctx` - [ServiceContext]。gRPCのサービスなどと共有する場合は、先に必要なものを取り出しておく
+/// - `readiness_checker` - `/health/ready`で依存先を確認する[ReadinessChecker]
+/// - `graphql_settings` - クエリの深さ・複雑さの制限と永続化クエリの設定
+pub fn create_router_with_context(
+  ctx: ServiceContext<GroupChatRepositoryImpl<ES>>,
+  readiness_checker: Arc<ReadinessChecker>,
+  graphql_settings: &GraphQLSettings,
+) -> Result<Router, GraphQLSettingsError> {
+  // NOTE: REST APIはGraphQLと同じGroupChatCommandProcessorを共有する
+  let processor = ctx.get_group_chat_command_processor();
+  let idempotency_store = ctx.get_idempotency_store();
+  let schema = create_schema_with_context(ctx, graphql_settings)?;
+  Ok(
+    Router::new()
+      .route(EndpointPaths::Root.as_str(), get(hello_write_api))
+      .route(EndpointPaths::HealthAlive.as_str(), get(alive))
+      .route(EndpointPaths::HealthReady.as_str(), get(ready))
+      .route(EndpointPaths::Metrics.as_str(), get(metrics))
+      .route(EndpointPaths::GraphQL.as_str(), get(graphql).post(graphql_handler))
+      .route(EndpointPaths::GroupChats.as_str(), post(rest::create_group_chat))
+      .route(
+        EndpointPaths::GroupChat.as_str(),
+        patch(rest::rename_group_chat).delete(rest::delete_group_chat),
+      )
+      .route(EndpointPaths::GroupChatMembers.as_str(), post(rest::add_member))
+      .route(EndpointPaths::GroupChatMember.as_str(), delete(rest::remove_member))
+      .route(EndpointPaths::GroupChatMessages.as_str(), post(rest::post_message))
+      .route(EndpointPaths::GroupChatMessage.as_str(), delete(rest::delete_message))
+      .route(EndpointPaths::OpenApi.as_str(), get(rest::openapi))
+      .layer(Extension(schema))
+      .layer(Extension(processor))
+      .layer(Extension(idempotency_store))
+      .layer(Extension(readiness_checker)),
+  )
 }
@@ -4,6 +4,15 @@ use std::hash::{Hash, Hasher};
 
 pub mod group_chat_read_model_dao_impl;
 pub mod group_chat_repository;
+pub mod health_checks;
+pub mod idempotency_store;
+pub mod multi_event_store;
+pub mod payload_cipher;
+pub mod payload_cipher_event_serializer;
+pub mod payload_cipher_snapshot_serializer;
+pub mod trace_context_event_serializer;
+pub mod user_account_group_chat_dao_impl;
+pub mod user_account_key_store;
 
 use event_store_adapter_rs::types::{Aggregate, AggregateId, Event};
 use serde::{de, Serialize};
@@ -34,8 +43,8 @@ impl KeyResolver for DefaultPartitionKeyResolver {
 pub trait EventPersistenceGateway: Debug + Clone + Sync + Send + 'static {
   async fn get_snapshot_by_id<E, T, AID: AggregateId>(&self, aid: &AID) -> Result<(T, usize, usize), ()>
   where
-    E: ?Sized + Serialize + Event + for<'de> de::Deserialize<'de>,
-    T: ?Sized + Serialize + Aggregate + for<'de> de::Deserialize<'de>;
+    E: Serialize + Event + for<'de> de::Deserialize<'de>,
+    T: Serialize + Aggregate + for<'de> de::Deserialize<'de>;
 
   async fn get_events_by_id_and_seq_nr<T, AID: AggregateId>(&self, aid: &AID, seq_nr: usize) -> Result<Vec<T>, ()>
   where
@@ -48,6 +57,6 @@ pub trait EventPersistenceGateway: Debug + Clone + Sync + Send + 'static {
     aggregate: Option<&A>,
   ) -> Result<(), ()>
   where
-    A: ?Sized + Serialize + Aggregate,
-    E: ?Sized + Serialize + Event;
+    A: Serialize + Aggregate,
+    E: Serialize + Event;
 }
@@ -4,7 +4,9 @@ use chrono::{DateTime, Utc};
 use sqlx::MySqlPool;
 
 use command_domain::group_chat::MemberId;
-use command_domain::group_chat::{GroupChatId, GroupChatName, MemberRole, Message, MessageId};
+use command_domain::group_chat::{
+  ChatKind, GroupChatId, GroupChatName, GroupChatSettings, Invitation, MemberRole, Message, MessageId,
+};
 use command_domain::user_account::UserAccountId;
 use command_interface_adaptor_if::{GroupChatReadModelUpdateDao, GroupChatReadModelUpdateDaoError};
 
@@ -21,9 +23,11 @@ impl GroupChatReadModelUpdateDaoImpl {
 
 #[async_trait::async_trait]
 impl GroupChatReadModelUpdateDao for GroupChatReadModelUpdateDaoImpl {
+  #[tracing::instrument(skip_all)]
   async fn insert_group_chat(
     &self,
     aggregate_id: GroupChatId,
+    kind: ChatKind,
     name: GroupChatName,
     administrator_id: UserAccountId,
     created_at: DateTime<Utc>,
@@ -36,9 +40,10 @@ impl GroupChatReadModelUpdateDao for GroupChatReadModelUpdateDaoImpl {
     // のようなクエリを実行して更新件数が0件だった場合は、発生したイベントもしくはリードモデルの状態に不整合が発生した判断できる。お
     // 不整合が発生した場合はシステムは続行できないので、データが破壊される前にプログラムを即時終了し、障害扱いとする
     let result = sqlx::query!(
-      "INSERT INTO group_chats (id, disabled, name, owner_id, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?)",
+      "INSERT INTO group_chats (id, disabled, kind, name, owner_id, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
       aggregate_id.to_string(),
       false,
+      kind.to_string().to_lowercase(),
       name.to_string(),
       administrator_id.to_string(),
       created_at.clone(),
@@ -56,6 +61,7 @@ impl GroupChatReadModelUpdateDao for GroupChatReadModelUpdateDaoImpl {
     }
   }
 
+  #[tracing::instrument(skip_all)]
   async fn delete_group_chat(
     &self,
     aggregate_id: GroupChatId,
@@ -81,6 +87,7 @@ impl GroupChatReadModelUpdateDao for GroupChatReadModelUpdateDaoImpl {
     }
   }
 
+  #[tracing::instrument(skip_all)]
   async fn rename_group_chat(
     &self,
     aggregate_id: GroupChatId,
@@ -105,6 +112,7 @@ impl GroupChatReadModelUpdateDao for GroupChatReadModelUpdateDaoImpl {
     }
   }
 
+  #[tracing::instrument(skip_all)]
   async fn insert_member(
     &self,
     aggregate_id: GroupChatId,
@@ -134,6 +142,7 @@ impl GroupChatReadModelUpdateDao for GroupChatReadModelUpdateDaoImpl {
     }
   }
 
+  #[tracing::instrument(skip_all)]
   async fn delete_member(
     &self,
     aggregate_id: GroupChatId,
@@ -142,7 +151,7 @@ impl GroupChatReadModelUpdateDao for GroupChatReadModelUpdateDaoImpl {
     // NOTE: 現状は物理削除になっている。論理削除変えたい場合はstatusフラグを導入しUPDATEに変更する。
     // もう一つの方法は履歴テーブルを作り、そちらに移動させる方法もある。
     let result = sqlx::query!(
-      "DELETE FROM members WHERE id = ? AND group_chat_id = ?",
+      "DELETE FROM members WHERE user_account_id = ? AND group_chat_id = ?",
       account_id.to_string(),
       aggregate_id.to_string()
     )
@@ -158,25 +167,131 @@ impl GroupChatReadModelUpdateDao for GroupChatReadModelUpdateDaoImpl {
     }
   }
 
-  async fn insert_message(
+  #[tracing::instrument(skip_all)]
+  async fn upsert_group_chat_settings(
     &self,
     aggregate_id: GroupChatId,
-    message: Message,
+    settings: GroupChatSettings,
+    updated_at: DateTime<Utc>,
+  ) -> Result<(), GroupChatReadModelUpdateDaoError> {
+    let result = sqlx::query!(
+      "INSERT INTO group_chat_settings (group_chat_id, max_members, post_permission, add_member_permission, message_retention_days, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?)
+       ON DUPLICATE KEY UPDATE max_members = VALUES(max_members), post_permission = VALUES(post_permission), add_member_permission = VALUES(add_member_permission), message_retention_days = VALUES(message_retention_days), updated_at = VALUES(updated_at)",
+      aggregate_id.to_string(),
+      settings.max_members() as u32,
+      settings.post_permission().to_string(),
+      settings.add_member_permission().to_string(),
+      settings.message_retention_days(),
+      updated_at.clone(),
+      updated_at.clone()
+    )
+    .execute(&self.pool)
+    .await;
+
+    match result {
+      Ok(_) => Ok(()),
+      Err(e) => {
+        log::error!("Failed to upsert group chat settings: {:?}", e);
+        Err(GroupChatReadModelUpdateDaoError::UpsertGroupChatSettingsError)
+      }
+    }
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn insert_invitation(
+    &self,
+    aggregate_id: GroupChatId,
+    invitation: Invitation,
     created_at: DateTime<Utc>,
   ) -> Result<(), GroupChatReadModelUpdateDaoError> {
+    // NOTE: 期限切れの招待を再招待した場合は、既存の行を置き換える
     let result = sqlx::query!(
-      "INSERT INTO messages (id, disabled, group_chat_id, user_account_id, text, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
-      message.breach_encapsulation_of_id().to_string(),
-      false,
+      "INSERT INTO invitations (group_chat_id, user_account_id, role, invited_by, expires_at, created_at) VALUES (?, ?, ?, ?, ?, ?)
+       ON DUPLICATE KEY UPDATE role = VALUES(role), invited_by = VALUES(invited_by), expires_at = VALUES(expires_at), created_at = VALUES(created_at)",
       aggregate_id.to_string(),
-      message.breach_encapsulation_of_sender_id().to_string(),
-      message.breach_encapsulation_of_text(),
-      created_at.clone(),
+      invitation.breach_encapsulation_of_user_account_id().to_string(),
+      invitation.breach_encapsulation_of_role().to_string().to_lowercase(),
+      invitation.breach_encapsulation_of_invited_by().to_string(),
+      invitation.breach_encapsulation_of_expires_at().clone(),
       created_at.clone()
     )
     .execute(&self.pool)
     .await;
 
+    match result {
+      Ok(_) => Ok(()),
+      Err(e) => {
+        log::error!("Failed to insert invitation: {:?}", e);
+        Err(GroupChatReadModelUpdateDaoError::InsertInvitationError)
+      }
+    }
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn delete_invitation(
+    &self,
+    aggregate_id: GroupChatId,
+    account_id: UserAccountId,
+  ) -> Result<(), GroupChatReadModelUpdateDaoError> {
+    let result = sqlx::query!(
+      "DELETE FROM invitations WHERE group_chat_id = ? AND user_account_id = ?",
+      aggregate_id.to_string(),
+      account_id.to_string()
+    )
+    .execute(&self.pool)
+    .await;
+
+    match result {
+      Ok(_) => Ok(()),
+      Err(e) => {
+        log::error!("Failed to delete invitation: {:?}", e);
+        Err(GroupChatReadModelUpdateDaoError::DeleteInvitationError)
+      }
+    }
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn insert_message(
+    &self,
+    aggregate_id: GroupChatId,
+    message: Message,
+    created_at: DateTime<Utc>,
+  ) -> Result<(), GroupChatReadModelUpdateDaoError> {
+    let content = serde_json::to_string(message.breach_encapsulation_of_body()).map_err(|e| {
+      log::error!("Failed to serialize message body: {:?}", e);
+      GroupChatReadModelUpdateDaoError::InsertMessageError
+    })?;
+    let result: Result<(), sqlx::Error> = async {
+      let mut tx = self.pool.begin().await?;
+      sqlx::query!(
+        "INSERT INTO messages (id, disabled, group_chat_id, user_account_id, text, content_type, content, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        message.breach_encapsulation_of_id().to_string(),
+        false,
+        aggregate_id.to_string(),
+        message.breach_encapsulation_of_sender_id().to_string(),
+        message.breach_encapsulation_of_text(),
+        message.breach_encapsulation_of_body().content_type(),
+        content,
+        created_at.clone(),
+        created_at.clone()
+      )
+      .execute(&mut *tx)
+      .await?;
+      for user_account_id in message.breach_encapsulation_of_mentions() {
+        sqlx::query!(
+          "INSERT INTO message_mentions (message_id, group_chat_id, user_account_id, created_at) VALUES (?, ?, ?, ?)",
+          message.breach_encapsulation_of_id().to_string(),
+          aggregate_id.to_string(),
+          user_account_id.to_string(),
+          created_at.clone()
+        )
+        .execute(&mut *tx)
+        .await?;
+      }
+      tx.commit().await
+    }
+    .await;
+
     match result {
       Ok(_) => Ok(()),
       Err(e) => {
@@ -186,19 +301,58 @@ impl GroupChatReadModelUpdateDao for GroupChatReadModelUpdateDaoImpl {
     }
   }
 
+  #[tracing::instrument(skip_all)]
   async fn update_message(
     &self,
     aggregate_id: GroupChatId,
     message: Message,
     updated_at: DateTime<Utc>,
   ) -> Result<(), GroupChatReadModelUpdateDaoError> {
-    let result = sqlx::query!(
-      "UPDATE messages SET text = ?, updated_at = ? WHERE id = ?",
-      message.breach_encapsulation_of_text(),
-      updated_at.clone(),
-      message.breach_encapsulation_of_id().to_string()
-    )
-    .execute(&self.pool)
+    // NOTE: 編集前の本文をmessage_revisionsに退避してから本文を更新する。
+    // message_revisions#created_atには、退避した本文が書かれた日時(=直前のmessages#updated_at)を記録する。
+    // メンションは編集後の本文のもので置き換える。
+    let content = serde_json::to_string(message.breach_encapsulation_of_body()).map_err(|e| {
+      log::error!("Failed to serialize message body: {:?}", e);
+      GroupChatReadModelUpdateDaoError::UpdateMessageError
+    })?;
+    let result: Result<(), sqlx::Error> = async {
+      let mut tx = self.pool.begin().await?;
+      sqlx::query!(
+        "INSERT INTO message_revisions (message_id, text, created_at) SELECT id, text, updated_at FROM messages WHERE id = ?",
+        message.breach_encapsulation_of_id().to_string()
+      )
+      .execute(&mut *tx)
+      .await?;
+      sqlx::query!(
+        "UPDATE messages SET text = ?, content_type = ?, content = ?, edited_at = ?, updated_at = ? WHERE id = ?",
+        message.breach_encapsulation_of_text(),
+        message.breach_encapsulation_of_body().content_type(),
+        content,
+        updated_at.clone(),
+        updated_at.clone(),
+        message.breach_encapsulation_of_id().to_string()
+      )
+      .execute(&mut *tx)
+      .await?;
+      sqlx::query!(
+        "DELETE FROM message_mentions WHERE message_id = ?",
+        message.breach_encapsulation_of_id().to_string()
+      )
+      .execute(&mut *tx)
+      .await?;
+      for user_account_id in message.breach_encapsulation_of_mentions() {
+        sqlx::query!(
+          "INSERT INTO message_mentions (message_id, group_chat_id, user_account_id, created_at) VALUES (?, ?, ?, ?)",
+          message.breach_encapsulation_of_id().to_string(),
+          aggregate_id.to_string(),
+          user_account_id.to_string(),
+          updated_at.clone()
+        )
+        .execute(&mut *tx)
+        .await?;
+      }
+      tx.commit().await
+    }
     .await;
     match result {
       Ok(_) => Ok(()),
@@ -209,17 +363,20 @@ impl GroupChatReadModelUpdateDao for GroupChatReadModelUpdateDaoImpl {
     }
   }
 
+  #[tracing::instrument(skip_all)]
   async fn delete_message(
     &self,
     message_id: MessageId,
-    updated_at: DateTime<Utc>,
+    deleted_by: UserAccountId,
+    deleted_at: DateTime<Utc>,
   ) -> Result<(), GroupChatReadModelUpdateDaoError> {
-    // NOTE: 現状は物理削除になっている。論理削除変えたい場合はstatusフラグを導入しUPDATEに変更する。
-    // もう一つの方法は履歴テーブルを作り、そちらに移動させる方法もある。
+    // NOTE: 論理削除。削除したユーザと削除日時を残し、管理者が参照・復元できるようにしている。
     let result = sqlx::query!(
-      "UPDATE messages SET disabled = ?, updated_at = ? WHERE id = ?",
+      "UPDATE messages SET disabled = ?, deleted_at = ?, deleted_by = ?, updated_at = ? WHERE id = ?",
       true,
-      updated_at.clone(),
+      deleted_at.clone(),
+      deleted_by.to_string(),
+      deleted_at.clone(),
       message_id.to_string()
     )
     .execute(&self.pool)
@@ -232,6 +389,154 @@ impl GroupChatReadModelUpdateDao for GroupChatReadModelUpdateDaoImpl {
       }
     }
   }
+
+  #[tracing::instrument(skip_all)]
+  async fn purge_message(&self, message_id: MessageId) -> Result<(), GroupChatReadModelUpdateDaoError> {
+    let result: Result<(), sqlx::Error> = async {
+      let mut tx = self.pool.begin().await?;
+      sqlx::query!(
+        "DELETE FROM message_mentions WHERE message_id = ?",
+        message_id.to_string()
+      )
+      .execute(&mut *tx)
+      .await?;
+      sqlx::query!(
+        "DELETE FROM message_revisions WHERE message_id = ?",
+        message_id.to_string()
+      )
+      .execute(&mut *tx)
+      .await?;
+      sqlx::query!("DELETE FROM messages WHERE id = ?", message_id.to_string())
+        .execute(&mut *tx)
+        .await?;
+      tx.commit().await
+    }
+    .await;
+    match result {
+      Ok(_) => Ok(()),
+      Err(e) => {
+        log::error!("Failed to purge message: {:?}", e);
+        Err(GroupChatReadModelUpdateDaoError::PurgeMessageError)
+      }
+    }
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn moderate_message(
+    &self,
+    message_id: MessageId,
+    moderated_by: UserAccountId,
+    reason: String,
+    moderated_at: DateTime<Utc>,
+  ) -> Result<(), GroupChatReadModelUpdateDaoError> {
+    // NOTE: 管理者による削除は行を非表示にせず、クエリ側で本文を"removed by moderator"に置き換えて表示する。
+    let result = sqlx::query!(
+      "UPDATE messages SET moderated_at = ?, moderated_by = ?, moderation_reason = ?, updated_at = ? WHERE id = ?",
+      moderated_at.clone(),
+      moderated_by.to_string(),
+      reason,
+      moderated_at.clone(),
+      message_id.to_string()
+    )
+    .execute(&self.pool)
+    .await;
+    match result {
+      Ok(_) => Ok(()),
+      Err(e) => {
+        log::error!("Failed to moderate message: {:?}", e);
+        Err(GroupChatReadModelUpdateDaoError::ModerateMessageError)
+      }
+    }
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn restore_message(
+    &self,
+    message_id: MessageId,
+    updated_at: DateTime<Utc>,
+  ) -> Result<(), GroupChatReadModelUpdateDaoError> {
+    let result = sqlx::query!(
+      "UPDATE messages SET disabled = ?, deleted_at = NULL, deleted_by = NULL, moderated_at = NULL, moderated_by = NULL, moderation_reason = NULL, updated_at = ? WHERE id = ?",
+      false,
+      updated_at.clone(),
+      message_id.to_string()
+    )
+    .execute(&self.pool)
+    .await;
+    match result {
+      Ok(_) => Ok(()),
+      Err(e) => {
+        log::error!("Failed to restore message: {:?}", e);
+        Err(GroupChatReadModelUpdateDaoError::RestoreMessageError)
+      }
+    }
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn erase_user_account(
+    &self,
+    aggregate_id: GroupChatId,
+    account_id: UserAccountId,
+    updated_at: DateTime<Utc>,
+  ) -> Result<(), GroupChatReadModelUpdateDaoError> {
+    // NOTE: メッセージの行は前後関係を保つために残し、本文・本文のJSON表現・編集履歴を消去する。
+    // メンションは、消去するユーザアカウントが送信したメッセージのものと、ユーザアカウントへのものの両方を削除する。
+    let result: Result<(), sqlx::Error> = async {
+      let mut tx = self.pool.begin().await?;
+      sqlx::query!(
+        "DELETE FROM members WHERE group_chat_id = ? AND user_account_id = ?",
+        aggregate_id.to_string(),
+        account_id.to_string()
+      )
+      .execute(&mut *tx)
+      .await?;
+      sqlx::query!(
+        "DELETE FROM invitations WHERE group_chat_id = ? AND user_account_id = ?",
+        aggregate_id.to_string(),
+        account_id.to_string()
+      )
+      .execute(&mut *tx)
+      .await?;
+      sqlx::query!(
+        "DELETE FROM message_mentions WHERE group_chat_id = ? AND user_account_id = ?",
+        aggregate_id.to_string(),
+        account_id.to_string()
+      )
+      .execute(&mut *tx)
+      .await?;
+      sqlx::query!(
+        "DELETE FROM message_mentions WHERE message_id IN (SELECT id FROM messages WHERE group_chat_id = ? AND user_account_id = ?)",
+        aggregate_id.to_string(),
+        account_id.to_string()
+      )
+      .execute(&mut *tx)
+      .await?;
+      sqlx::query!(
+        "DELETE FROM message_revisions WHERE message_id IN (SELECT id FROM messages WHERE group_chat_id = ? AND user_account_id = ?)",
+        aggregate_id.to_string(),
+        account_id.to_string()
+      )
+      .execute(&mut *tx)
+      .await?;
+      sqlx::query!(
+        "UPDATE messages SET text = '', content = NULL, content_type = IF(content_type = 'system', 'system', 'text'), updated_at = ? WHERE group_chat_id = ? AND user_account_id = ?",
+        updated_at.clone(),
+        aggregate_id.to_string(),
+        account_id.to_string()
+      )
+      .execute(&mut *tx)
+      .await?;
+      tx.commit().await
+    }
+    .await;
+    match result {
+      Ok(_) => Ok(()),
+      Err(e) => {
+        log::error!("Failed to erase user account: {:?}", e);
+        Err(GroupChatReadModelUpdateDaoError::EraseUserAccountError)
+      }
+    }
+  }
 }
 
 #[derive(Debug)]
@@ -242,6 +547,7 @@ impl GroupChatReadModelUpdateDao for MockGroupChatReadModelUpdateDao {
   async fn insert_group_chat(
     &self,
     _: GroupChatId,
+    _: ChatKind,
     _: GroupChatName,
     _: UserAccountId,
     _: DateTime<Utc>,
@@ -277,6 +583,28 @@ impl GroupChatReadModelUpdateDao for MockGroupChatReadModelUpdateDao {
     Ok(())
   }
 
+  async fn upsert_group_chat_settings(
+    &self,
+    _: GroupChatId,
+    _: GroupChatSettings,
+    _: DateTime<Utc>,
+  ) -> Result<(), GroupChatReadModelUpdateDaoError> {
+    Ok(())
+  }
+
+  async fn insert_invitation(
+    &self,
+    _: GroupChatId,
+    _: Invitation,
+    _: DateTime<Utc>,
+  ) -> Result<(), GroupChatReadModelUpdateDaoError> {
+    Ok(())
+  }
+
+  async fn delete_invitation(&self, _: GroupChatId, _: UserAccountId) -> Result<(), GroupChatReadModelUpdateDaoError> {
+    Ok(())
+  }
+
   async fn insert_message(
     &self,
     _: GroupChatId,
@@ -295,7 +623,39 @@ impl GroupChatReadModelUpdateDao for MockGroupChatReadModelUpdateDao {
     Ok(())
   }
 
-  async fn delete_message(&self, _: MessageId, _: DateTime<Utc>) -> Result<(), GroupChatReadModelUpdateDaoError> {
+  async fn delete_message(
+    &self,
+    _: MessageId,
+    _: UserAccountId,
+    _: DateTime<Utc>,
+  ) -> Result<(), GroupChatReadModelUpdateDaoError> {
+    Ok(())
+  }
+
+  async fn purge_message(&self, _: MessageId) -> Result<(), GroupChatReadModelUpdateDaoError> {
+    Ok(())
+  }
+
+  async fn moderate_message(
+    &self,
+    _: MessageId,
+    _: UserAccountId,
+    _: String,
+    _: DateTime<Utc>,
+  ) -> Result<(), GroupChatReadModelUpdateDaoError> {
+    Ok(())
+  }
+
+  async fn restore_message(&self, _: MessageId, _: DateTime<Utc>) -> Result<(), GroupChatReadModelUpdateDaoError> {
+    Ok(())
+  }
+
+  async fn erase_user_account(
+    &self,
+    _: GroupChatId,
+    _: UserAccountId,
+    _: DateTime<Utc>,
+  ) -> Result<(), GroupChatReadModelUpdateDaoError> {
     Ok(())
   }
 }
@@ -1,9 +1,66 @@
-use event_store_adapter_rs::types::{Aggregate, Event, EventStore};
+use event_store_adapter_rs::types::{Aggregate, Event, EventStore, EventStoreWriteError};
+use once_cell::sync::Lazy;
+use prometheus::{register_histogram, register_int_counter, Histogram, IntCounter};
 use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
 
 use command_domain::group_chat::GroupChatEvent;
 use command_domain::group_chat::{GroupChat, GroupChatId};
-use command_interface_adaptor_if::{GroupChatRepository, GroupChatRepositoryError};
+use command_interface_adaptor_if::{GroupChatRepository, GroupChatRepositoryError, PayloadCipher};
+
+use crate::gateways::multi_event_store::{MultiEventStore, MultiEventStoreForDynamoDB};
+use crate::gateways::payload_cipher_event_serializer::PayloadCipherEventSerializer;
+use crate::gateways::payload_cipher_snapshot_serializer::PayloadCipherSnapshotSerializer;
+use crate::gateways::trace_context_event_serializer::TraceContextEventSerializer;
+
+/// 楽観的ロックの失敗(同時更新)の件数
+static OPTIMISTIC_LOCK_ERRORS_TOTAL: Lazy<IntCounter> = Lazy::new(|| {
+  register_int_counter!(
+    "group_chat_optimistic_lock_errors_total",
+    "Number of OptimisticLockError when storing group chat events"
+  )
+  .unwrap()
+});
+
+/// スナップショットの保存件数
+static SNAPSHOT_WRITES_TOTAL: Lazy<IntCounter> = Lazy::new(|| {
+  register_int_counter!(
+    "group_chat_snapshot_writes_total",
+    "Number of group chat snapshots written"
+  )
+  .unwrap()
+});
+
+/// `find_by_id`でスナップショットに適用したイベントの件数
+static REPLAYED_EVENTS: Lazy<Histogram> = Lazy::new(|| {
+  register_histogram!(
+    "group_chat_replayed_events",
+    "Number of events replayed on a snapshot per find_by_id",
+    vec![0.0, 1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0]
+  )
+  .unwrap()
+});
+
+/// グループチャットのイベントストアに、イベントを保存するアプリケーションで共通のシリアライザを設定する。
+///
+/// イベントは[PayloadCipherEventSerializer]で本文を暗号化し、[TraceContextEventSerializer]で保存した操作のトレースコンテキストを付与する。
+/// スナップショットは[PayloadCipherSnapshotSerializer]で本文を暗号化する。
+///
+/// NOTE: Write API Serverとメッセージ保持ジョブで構成が食い違わないように、シリアライザの組み立てはここに集約する。
+///
+/// # 引数
+/// - `event_store` - シリアライザを設定するイベントストア
+/// - `payload_cipher` - メッセージの本文を暗号化する[PayloadCipher]
+pub fn with_group_chat_serializers(
+  event_store: MultiEventStoreForDynamoDB<GroupChatId, GroupChat, GroupChatEvent>,
+  payload_cipher: Arc<dyn PayloadCipher>,
+) -> MultiEventStoreForDynamoDB<GroupChatId, GroupChat, GroupChatEvent> {
+  event_store
+    .with_event_serializer(Arc::new(TraceContextEventSerializer::new(Arc::new(
+      PayloadCipherEventSerializer::new(payload_cipher.clone()),
+    ))))
+    .with_snapshot_serializer(Arc::new(PayloadCipherSnapshotSerializer::new(payload_cipher)))
+}
 
 #[derive(Debug, Clone)]
 pub struct MockGroupChatRepository {
@@ -11,6 +68,12 @@ pub struct MockGroupChatRepository {
   snapshot: HashMap<GroupChatId, Option<GroupChat>>,
 }
 
+impl Default for MockGroupChatRepository {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
 impl MockGroupChatRepository {
   pub fn new() -> Self {
     Self {
@@ -26,7 +89,7 @@ impl GroupChatRepository for MockGroupChatRepository {
     self
       .events
       .entry(event.aggregate_id().clone())
-      .or_insert_with(VecDeque::new)
+      .or_default()
       .push_back(event.clone());
     *self
       .snapshot
@@ -35,15 +98,31 @@ impl GroupChatRepository for MockGroupChatRepository {
     Ok(())
   }
 
-  async fn find_by_id(&self, id: &GroupChatId) -> Result<Option<GroupChat>, GroupChatRepositoryError> {
-    let events = self.events.get(id).unwrap().clone();
-    let snapshot_opt = self.snapshot.get(id).unwrap().clone();
-    if let Some(snapshot) = snapshot_opt {
-      let result = GroupChat::replay(events.into(), snapshot);
-      Ok(Some(result))
-    } else {
-      Ok(None)
+  async fn store_all(
+    &mut self,
+    events: &[GroupChatEvent],
+    snapshot: &GroupChat,
+  ) -> Result<(), GroupChatRepositoryError> {
+    for event in events {
+      self.store(event, snapshot).await?;
     }
+    Ok(())
+  }
+
+  async fn find_by_id(&self, id: &GroupChatId) -> Result<Option<GroupChat>, GroupChatRepositoryError> {
+    let Some(Some(snapshot)) = self.snapshot.get(id).cloned() else {
+      return Ok(None);
+    };
+    // NOTE: スナップショットは常に最新の状態で保存されるので、それより後のイベントだけを適用する
+    let events = self
+      .events
+      .get(id)
+      .into_iter()
+      .flatten()
+      .filter(|event| event.seq_nr() > snapshot.seq_nr())
+      .cloned()
+      .collect();
+    Ok(Some(GroupChat::replay(events, snapshot)))
   }
 }
 
@@ -79,35 +158,67 @@ impl<ES: EventStore<AID = GroupChatId, AG = GroupChat, EV = GroupChatEvent>> Gro
   ///
   /// # 引数
   /// - `snapshot_interval` - スナップショットを永続化する間隔
-  /// - `created` - グループチャットが作成されたかどうか
+  /// - `created` - グループチャットが作成されたかどうか、またはユーザアカウントが消去されたかどうか
   /// - `group_chat` - グループチャット
   ///
   /// # 戻り値
   /// スナップショットを永続化する場合は `Some` 、そうでない場合は `None` 。
   fn resolve_snapshot(snapshot_interval: usize, created: bool, group_chat: &GroupChat) -> Option<&GroupChat> {
-    if created || group_chat.seq_nr() % snapshot_interval == 0 {
+    if created || group_chat.seq_nr().is_multiple_of(snapshot_interval) {
       Some(group_chat)
     } else {
       None
     }
   }
+
+  /// 保存の結果をメトリクスに記録する。
+  ///
+  /// # 引数
+  /// - `result` - 保存の結果
+  /// - `snapshot_written` - スナップショットを保存したかどうか
+  fn record_store_result(result: &Result<(), EventStoreWriteError>, snapshot_written: bool) {
+    match result {
+      Ok(_) if snapshot_written => SNAPSHOT_WRITES_TOTAL.inc(),
+      Ok(_) => {}
+      Err(EventStoreWriteError::OptimisticLockError(_)) => OPTIMISTIC_LOCK_ERRORS_TOTAL.inc(),
+      Err(_) => {}
+    }
+  }
 }
 
 #[async_trait::async_trait]
-impl<ES: EventStore<AID = GroupChatId, AG = GroupChat, EV = GroupChatEvent>> GroupChatRepository
+impl<ES: MultiEventStore<AID = GroupChatId, AG = GroupChat, EV = GroupChatEvent>> GroupChatRepository
   for GroupChatRepositoryImpl<ES>
 {
+  #[tracing::instrument(skip_all, fields(group_chat_id = %snapshot.id(), seq_nr = event.seq_nr()))]
   async fn store(&mut self, event: &GroupChatEvent, snapshot: &GroupChat) -> Result<(), GroupChatRepositoryError> {
-    let result = match Self::resolve_snapshot(self.snapshot_interval, event.is_created(), snapshot) {
+    // NOTE: ユーザアカウントの消去後は、消去前の本文を含むスナップショットが残らないように必ずスナップショットを更新する
+    let force_snapshot = event.is_created() || matches!(event, GroupChatEvent::GroupChatUserAccountErased(_));
+    let snapshot_opt = Self::resolve_snapshot(self.snapshot_interval, force_snapshot, snapshot);
+    let result = match snapshot_opt {
       Some(snapshot) => self.event_store.persist_event_and_snapshot(event, snapshot).await,
       None => self.event_store.persist_event(event, snapshot.version()).await,
     };
+    Self::record_store_result(&result, snapshot_opt.is_some());
     match result {
       Ok(_) => Ok(()),
-      Err(error) => Err(GroupChatRepositoryError::StoreError(snapshot.clone(), error)),
+      Err(error) => Err(GroupChatRepositoryError::StoreError(Box::new(snapshot.clone()), error)),
     }
   }
 
+  #[tracing::instrument(skip_all, fields(group_chat_id = %snapshot.id(), events = events.len()))]
+  async fn store_all(
+    &mut self,
+    events: &[GroupChatEvent],
+    snapshot: &GroupChat,
+  ) -> Result<(), GroupChatRepositoryError> {
+    // NOTE: 複数のイベントをまとめて保存する場合は、スナップショットの間隔に関わらず常にスナップショットを更新する
+    let result = self.event_store.persist_events_and_snapshot(events, snapshot).await;
+    Self::record_store_result(&result, true);
+    result.map_err(|error| GroupChatRepositoryError::StoreError(Box::new(snapshot.clone()), error))
+  }
+
+  #[tracing::instrument(skip_all, fields(group_chat_id = %id))]
   async fn find_by_id(&self, id: &GroupChatId) -> Result<Option<GroupChat>, GroupChatRepositoryError> {
     let snapshot_opt = self.event_store.get_latest_snapshot_by_id(id).await;
     match snapshot_opt {
@@ -119,6 +230,7 @@ impl<ES: EventStore<AID = GroupChatId, AG = GroupChat, EV = GroupChatEvent>> Gro
           .await;
         match events {
           Ok(events) => {
+            REPLAYED_EVENTS.observe(events.len() as f64);
             let result = GroupChat::replay(events, snapshot.clone());
             Ok(Some(result))
           }
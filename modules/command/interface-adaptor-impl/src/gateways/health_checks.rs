@@ -0,0 +1,51 @@
+use async_trait::async_trait;
+use aws_sdk_dynamodb::types::TableStatus;
+use aws_sdk_dynamodb::Client;
+use infrastructure::health::HealthCheck;
+
+/// DynamoDBのテーブルを`DescribeTable`で確認する[HealthCheck]。
+///
+/// NOTE: テーブルが`ACTIVE`でない場合(作成中・削除中など)も疎通できないとみなす。
+#[derive(Debug, Clone)]
+pub struct DynamoDbTableHealthCheck {
+  client: Client,
+  name: String,
+  table_name: String,
+}
+
+impl DynamoDbTableHealthCheck {
+  /// コンストラクタ。
+  ///
+  /// # 引数
+  /// - `client` - DynamoDBのクライアント
+  /// - `name` - レスポンスに表示する名前(`journal`など)
+  /// - `table_name` - 確認するテーブル名
+  pub fn new(client: Client, name: &str, table_name: &str) -> Self {
+    Self {
+      client,
+      name: name.to_string(),
+      table_name: table_name.to_string(),
+    }
+  }
+}
+
+#[async_trait]
+impl HealthCheck for DynamoDbTableHealthCheck {
+  fn name(&self) -> &str {
+    &self.name
+  }
+
+  async fn check(&self) -> Result<(), String> {
+    let output = self
+      .client
+      .describe_table()
+      .table_name(&self.table_name)
+      .send()
+      .await
+      .map_err(|error| format!("{}: {}", self.table_name, error))?;
+    match output.table().and_then(|table| table.table_status()) {
+      Some(TableStatus::Active) => Ok(()),
+      status => Err(format!("{} is not active: {:?}", self.table_name, status)),
+    }
+  }
+}
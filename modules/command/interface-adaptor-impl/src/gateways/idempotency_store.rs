@@ -0,0 +1,392 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use aws_sdk_dynamodb::operation::delete_item::DeleteItemError;
+use aws_sdk_dynamodb::operation::put_item::PutItemError;
+use aws_sdk_dynamodb::types::AttributeValue;
+use aws_sdk_dynamodb::Client;
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+
+use command_interface_adaptor_if::{IdempotencyRecord, IdempotencyStore, IdempotencyStoreError};
+
+/// 実行中の記録を表す`status`の値
+const STATUS_IN_PROGRESS: &str = "in_progress";
+/// 成功した記録を表す`status`の値
+const STATUS_COMPLETED: &str = "completed";
+/// 獲得を再試行する回数(記録の解放や期限切れと競合した場合)
+const MAX_CLAIM_ATTEMPTS: usize = 3;
+
+#[derive(Debug, Default)]
+struct InMemoryEntries {
+  records: HashMap<String, (IdempotencyRecord, DateTime<Utc>)>,
+  /// 期限の早い順に記録を取り出すためのキュー
+  expirations: BinaryHeap<Reverse<(DateTime<Utc>, String)>>,
+}
+
+impl InMemoryEntries {
+  /// 期限切れの記録を取り除く。
+  ///
+  /// NOTE: 期限の早い順に取り出すので、取り除く記録の件数に比例した時間で済む。
+  /// 記録を上書きした場合はキューに古い期限が残るので、記録の期限と一致する場合のみ取り除く。
+  fn purge_expired(&mut self, now: DateTime<Utc>) {
+    while let Some(Reverse((expires_at, _))) = self.expirations.peek() {
+      if *expires_at > now {
+        break;
+      }
+      let Reverse((expires_at, key)) = self.expirations.pop().unwrap();
+      if matches!(self.records.get(&key), Some((_, current)) if *current == expires_at) {
+        self.records.remove(&key);
+      }
+    }
+  }
+
+  fn insert(&mut self, key: &str, record: IdempotencyRecord, expires_at: DateTime<Utc>) {
+    self.records.insert(key.to_string(), (record, expires_at));
+    self.expirations.push(Reverse((expires_at, key.to_string())));
+  }
+}
+
+/// 冪等キーと結果を、プロセスのメモリに保存する[IdempotencyStore]。
+///
+/// NOTE: 単一プロセスでの運用やローカル開発向けの実装です。
+/// 再起動すると結果は失われ、複数のプロセスでは共有されません。
+#[derive(Debug)]
+pub struct InMemoryIdempotencyStore {
+  ttl: Duration,
+  in_progress_ttl: Duration,
+  entries: Mutex<InMemoryEntries>,
+}
+
+impl InMemoryIdempotencyStore {
+  /// コンストラクタ。
+  ///
+  /// # 引数
+  /// - `ttl` - 結果を保持する期間
+  /// - `in_progress_ttl` - 実行中の記録を保持する期間
+  pub fn new(ttl: Duration, in_progress_ttl: Duration) -> Self {
+    Self {
+      ttl,
+      in_progress_ttl,
+      entries: Mutex::new(InMemoryEntries::default()),
+    }
+  }
+}
+
+#[async_trait::async_trait]
+impl IdempotencyStore for InMemoryIdempotencyStore {
+  async fn claim(&self, key: &str, fingerprint: &str) -> Result<Option<IdempotencyRecord>, IdempotencyStoreError> {
+    let now = Utc::now();
+    let mut entries = self.entries.lock().unwrap();
+    entries.purge_expired(now);
+    if let Some((record, _)) = entries.records.get(key) {
+      return Ok(Some(record.clone()));
+    }
+    entries.insert(
+      key,
+      IdempotencyRecord::InProgress {
+        fingerprint: fingerprint.to_string(),
+      },
+      now + self.in_progress_ttl,
+    );
+    Ok(None)
+  }
+
+  async fn complete(&self, key: &str, fingerprint: &str, result: &str) -> Result<(), IdempotencyStoreError> {
+    let now = Utc::now();
+    let mut entries = self.entries.lock().unwrap();
+    entries.purge_expired(now);
+    entries.insert(
+      key,
+      IdempotencyRecord::Completed {
+        fingerprint: fingerprint.to_string(),
+        result: result.to_string(),
+      },
+      now + self.ttl,
+    );
+    Ok(())
+  }
+
+  async fn release(&self, key: &str) -> Result<(), IdempotencyStoreError> {
+    let mut entries = self.entries.lock().unwrap();
+    if matches!(
+      entries.records.get(key),
+      Some((IdempotencyRecord::InProgress { .. }, _))
+    ) {
+      entries.records.remove(key);
+    }
+    Ok(())
+  }
+}
+
+/// 冪等キーと結果を、DynamoDBのテーブルに保存する[IdempotencyStore]。
+///
+/// テーブルはパーティションキー`key`(文字列)を持ち、`expires_at`(UNIX時間の秒)をTTL属性とする。
+/// 項目には`status`(`in_progress`または`completed`)、`fingerprint`、及び成功した場合は`result`を保存する。
+/// ローカル環境ではlocalstackのテーブルを利用する(`tools/dynamodb-setup/create-tables.sh`を参照)。
+///
+/// NOTE: 複数のインスタンスで同じキーの操作が同時に実行されないように、条件付きの書き込みでキーを獲得する。
+/// DynamoDBのTTLによる削除は即時ではないので、獲得と読み込みの時にも期限を確認する。
+#[derive(Debug, Clone)]
+pub struct DynamoDbIdempotencyStore {
+  client: Client,
+  table_name: String,
+  ttl: Duration,
+  in_progress_ttl: Duration,
+}
+
+impl DynamoDbIdempotencyStore {
+  /// コンストラクタ。
+  ///
+  /// # 引数
+  /// - `client` - DynamoDBのクライアント
+  /// - `table_name` - テーブル名
+  /// - `ttl` - 結果を保持する期間
+  /// - `in_progress_ttl` - 実行中の記録を保持する期間
+  pub fn new(client: Client, table_name: String, ttl: Duration, in_progress_ttl: Duration) -> Self {
+    Self {
+      client,
+      table_name,
+      ttl,
+      in_progress_ttl,
+    }
+  }
+
+  async fn get(&self, key: &str) -> Result<Option<IdempotencyRecord>, IdempotencyStoreError> {
+    let output = self
+      .client
+      .get_item()
+      .table_name(&self.table_name)
+      .key("key", AttributeValue::S(key.to_string()))
+      .consistent_read(true)
+      .send()
+      .await
+      .map_err(|e| IdempotencyStoreError::IOError(e.to_string()))?;
+    let Some(item) = output.item else {
+      return Ok(None);
+    };
+    let expires_at = item
+      .get("expires_at")
+      .and_then(|value| value.as_n().ok())
+      .and_then(|value| value.parse::<i64>().ok())
+      .unwrap_or(0);
+    if expires_at <= Utc::now().timestamp() {
+      return Ok(None);
+    }
+    let string_of = |name: &str| {
+      item
+        .get(name)
+        .and_then(|value| value.as_s().ok())
+        .cloned()
+        .ok_or_else(|| IdempotencyStoreError::IOError(format!("{} is missing: {}", name, key)))
+    };
+    let fingerprint = string_of("fingerprint")?;
+    match string_of("status")?.as_str() {
+      STATUS_IN_PROGRESS => Ok(Some(IdempotencyRecord::InProgress { fingerprint })),
+      STATUS_COMPLETED => Ok(Some(IdempotencyRecord::Completed {
+        fingerprint,
+        result: string_of("result")?,
+      })),
+      status => Err(IdempotencyStoreError::IOError(format!(
+        "unknown status: {}, {}",
+        status, key
+      ))),
+    }
+  }
+}
+
+#[async_trait::async_trait]
+impl IdempotencyStore for DynamoDbIdempotencyStore {
+  async fn claim(&self, key: &str, fingerprint: &str) -> Result<Option<IdempotencyRecord>, IdempotencyStoreError> {
+    for _ in 0..MAX_CLAIM_ATTEMPTS {
+      let now = Utc::now();
+      let result = self
+        .client
+        .put_item()
+        .table_name(&self.table_name)
+        .item("key", AttributeValue::S(key.to_string()))
+        .item("status", AttributeValue::S(STATUS_IN_PROGRESS.to_string()))
+        .item("fingerprint", AttributeValue::S(fingerprint.to_string()))
+        .item(
+          "expires_at",
+          AttributeValue::N((now + self.in_progress_ttl).timestamp().to_string()),
+        )
+        .condition_expression("attribute_not_exists(#key) OR #expires_at <= :now")
+        .expression_attribute_names("#key", "key")
+        .expression_attribute_names("#expires_at", "expires_at")
+        .expression_attribute_values(":now", AttributeValue::N(now.timestamp().to_string()))
+        .send()
+        .await;
+      match result {
+        Ok(_) => return Ok(None),
+        Err(e) => match e.into_service_error() {
+          PutItemError::ConditionalCheckFailedException(_) => {}
+          error => return Err(IdempotencyStoreError::IOError(error.to_string())),
+        },
+      }
+      // NOTE: 読み込むまでの間に記録が解放された場合や期限切れになった場合は、獲得をやり直す
+      if let Some(record) = self.get(key).await? {
+        return Ok(Some(record));
+      }
+    }
+    Err(IdempotencyStoreError::IOError(format!(
+      "failed to claim the idempotency key: {}",
+      key
+    )))
+  }
+
+  async fn complete(&self, key: &str, fingerprint: &str, result: &str) -> Result<(), IdempotencyStoreError> {
+    let expires_at = (Utc::now() + self.ttl).timestamp();
+    self
+      .client
+      .put_item()
+      .table_name(&self.table_name)
+      .item("key", AttributeValue::S(key.to_string()))
+      .item("status", AttributeValue::S(STATUS_COMPLETED.to_string()))
+      .item("fingerprint", AttributeValue::S(fingerprint.to_string()))
+      .item("result", AttributeValue::S(result.to_string()))
+      .item("expires_at", AttributeValue::N(expires_at.to_string()))
+      .send()
+      .await
+      .map_err(|e| IdempotencyStoreError::IOError(e.to_string()))?;
+    Ok(())
+  }
+
+  async fn release(&self, key: &str) -> Result<(), IdempotencyStoreError> {
+    let result = self
+      .client
+      .delete_item()
+      .table_name(&self.table_name)
+      .key("key", AttributeValue::S(key.to_string()))
+      .condition_expression("#status = :in_progress")
+      .expression_attribute_names("#status", "status")
+      .expression_attribute_values(":in_progress", AttributeValue::S(STATUS_IN_PROGRESS.to_string()))
+      .send()
+      .await;
+    match result {
+      Ok(_) => Ok(()),
+      Err(e) => match e.into_service_error() {
+        // NOTE: 既に期限切れで別の操作が獲得している場合などは、何もしない
+        DeleteItemError::ConditionalCheckFailedException(_) => Ok(()),
+        error => Err(IdempotencyStoreError::IOError(error.to_string())),
+      },
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdempotencyStoreKind {
+  /// プロセスのメモリに保存する
+  #[default]
+  Memory,
+  /// DynamoDBのテーブルに保存する
+  Dynamodb,
+}
+
+/// [IdempotencyStore]の設定。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct IdempotencySettings {
+  pub kind: IdempotencyStoreKind,
+  /// 結果を保存するテーブル名(`dynamodb`の場合は必須)
+  pub table_name: Option<String>,
+  /// 結果を保持する秒数
+  pub ttl_secs: i64,
+  /// 実行中の記録を保持する秒数。操作のタイムアウトより長くすること
+  pub in_progress_ttl_secs: i64,
+}
+
+impl Default for IdempotencySettings {
+  fn default() -> Self {
+    Self {
+      kind: IdempotencyStoreKind::default(),
+      table_name: None,
+      ttl_secs: 86400,
+      in_progress_ttl_secs: 60,
+    }
+  }
+}
+
+impl IdempotencySettings {
+  /// 設定に応じた[IdempotencyStore]を生成する。
+  ///
+  /// # 引数
+  /// - `client` - DynamoDBのクライアント(`dynamodb`の場合に利用する)
+  pub fn create_idempotency_store(&self, client: Client) -> Result<Arc<dyn IdempotencyStore>, IdempotencyStoreError> {
+    let ttl = Duration::seconds(self.ttl_secs);
+    let in_progress_ttl = Duration::seconds(self.in_progress_ttl_secs);
+    match self.kind {
+      IdempotencyStoreKind::Memory => Ok(Arc::new(InMemoryIdempotencyStore::new(ttl, in_progress_ttl))),
+      IdempotencyStoreKind::Dynamodb => {
+        let table_name = self
+          .table_name
+          .clone()
+          .ok_or_else(|| IdempotencyStoreError::IOError("table_name is required".to_string()))?;
+        Ok(Arc::new(DynamoDbIdempotencyStore::new(
+          client,
+          table_name,
+          ttl,
+          in_progress_ttl,
+        )))
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn completed(fingerprint: &str, result: &str) -> IdempotencyRecord {
+    IdempotencyRecord::Completed {
+      fingerprint: fingerprint.to_string(),
+      result: result.to_string(),
+    }
+  }
+
+  #[tokio::test]
+  async fn test_in_memory_idempotency_store() {
+    let store = InMemoryIdempotencyStore::new(Duration::seconds(60), Duration::seconds(60));
+    assert_eq!(store.claim("a", "f1").await.unwrap(), None);
+    // 実行中は同じキーを獲得できない
+    assert_eq!(
+      store.claim("a", "f1").await.unwrap(),
+      Some(IdempotencyRecord::InProgress {
+        fingerprint: "f1".to_string()
+      })
+    );
+
+    store.complete("a", "f1", "{\"id\":1}").await.unwrap();
+    assert_eq!(
+      store.claim("a", "f1").await.unwrap(),
+      Some(completed("f1", "{\"id\":1}"))
+    );
+    // 成功した記録は解放しない
+    store.release("a").await.unwrap();
+    assert_eq!(
+      store.claim("a", "f2").await.unwrap(),
+      Some(completed("f1", "{\"id\":1}"))
+    );
+
+    // 失敗した場合は解放して再実行できる
+    assert_eq!(store.claim("b", "f1").await.unwrap(), None);
+    store.release("b").await.unwrap();
+    assert_eq!(store.claim("b", "f1").await.unwrap(), None);
+  }
+
+  #[tokio::test]
+  async fn test_in_memory_idempotency_store_expiration() {
+    let store = InMemoryIdempotencyStore::new(Duration::seconds(0), Duration::seconds(0));
+    assert_eq!(store.claim("a", "f1").await.unwrap(), None);
+    assert_eq!(store.claim("a", "f1").await.unwrap(), None);
+    store.complete("a", "f1", "{\"id\":1}").await.unwrap();
+    assert_eq!(store.claim("a", "f1").await.unwrap(), None);
+
+    // 期限切れの記録は、獲得の時に取り除かれる
+    let entries = store.entries.lock().unwrap();
+    assert_eq!(entries.records.len(), 1);
+    assert_eq!(entries.expirations.len(), 1);
+  }
+}
@@ -0,0 +1,273 @@
+use std::sync::Arc;
+
+use aws_sdk_dynamodb::error::BuildError;
+use aws_sdk_dynamodb::operation::transact_write_items::TransactWriteItemsError;
+use aws_sdk_dynamodb::primitives::Blob;
+use aws_sdk_dynamodb::types::{AttributeValue, Put, TransactWriteItem, Update};
+use aws_sdk_dynamodb::Client;
+use event_store_adapter_rs::key_resolver::{DefaultKeyResolver, KeyResolver};
+use event_store_adapter_rs::serializer::{EventSerializer, JsonSnapshotSerializer, SnapshotSerializer};
+use event_store_adapter_rs::types::{
+  Aggregate, AggregateId, Event, EventStore, EventStoreReadError, EventStoreWriteError,
+  TransactionCanceledExceptionWrapper,
+};
+use event_store_adapter_rs::EventStoreForDynamoDB;
+
+/// DynamoDBの1つのトランザクションに含められる項目数の上限
+pub const MAX_TRANSACT_ITEMS: usize = 100;
+
+/// 複数のイベントを1つのトランザクションで保存できる[EventStore]。
+#[async_trait::async_trait]
+pub trait MultiEventStore: EventStore {
+  /// 同じ集約の複数のイベント及びスナップショットを、1つのトランザクションで保存します。
+  ///
+  /// # 引数
+  /// - `events` - 保存するイベント(発生順)
+  /// - `aggregate` - すべてのイベントを適用した後の集約
+  ///
+  /// # 戻り値
+  /// - バージョンが一致しない場合は[EventStoreWriteError::OptimisticLockError]を返します。
+  async fn persist_events_and_snapshot(
+    &mut self,
+    events: &[Self::EV],
+    aggregate: &Self::AG,
+  ) -> Result<(), EventStoreWriteError>;
+}
+
+/// [EventStoreForDynamoDB]に、複数のイベントをまとめて保存する機能を追加したイベントストア。
+///
+/// 1件ずつの保存と読み込みは[EventStoreForDynamoDB]に委譲し、
+/// 複数のイベントの保存は同じテーブル・同じ項目の形式で`TransactWriteItems`を発行する。
+///
+/// NOTE: event-store-adapter-rsは1件ずつの保存しか提供していないため、項目の形式をこちらでも実装しています。
+/// スナップショットの世代管理(`keep_snapshot_count`)には対応していないため、設定も提供していません。
+/// 常に最新のスナップショットのみを保持します。
+#[derive(Debug, Clone)]
+pub struct MultiEventStoreForDynamoDB<AID: AggregateId, A: Aggregate, E: Event> {
+  event_store: EventStoreForDynamoDB<AID, A, E>,
+  client: Client,
+  journal_table_name: String,
+  snapshot_table_name: String,
+  shard_count: u64,
+  event_serializer: Option<Arc<dyn EventSerializer<E> + Send + Sync>>,
+  snapshot_serializer: Arc<dyn SnapshotSerializer<A> + Send + Sync>,
+}
+
+impl<AID: AggregateId, A: Aggregate<ID = AID>, E: Event<AggregateID = AID>> MultiEventStoreForDynamoDB<AID, A, E> {
+  /// コンストラクタ。引数は[EventStoreForDynamoDB::new]と同じ。
+  pub fn new(
+    client: Client,
+    journal_table_name: String,
+    journal_aid_index_name: String,
+    snapshot_table_name: String,
+    snapshot_aid_index_name: String,
+    shard_count: u64,
+  ) -> Self {
+    let event_store = EventStoreForDynamoDB::new(
+      client.clone(),
+      journal_table_name.clone(),
+      journal_aid_index_name,
+      snapshot_table_name.clone(),
+      snapshot_aid_index_name,
+      shard_count,
+    );
+    Self {
+      event_store,
+      client,
+      journal_table_name,
+      snapshot_table_name,
+      shard_count,
+      event_serializer: None,
+      snapshot_serializer: Arc::new(JsonSnapshotSerializer::default()),
+    }
+  }
+
+  /// イベントのシリアライザを設定する。1件ずつの保存と複数のイベントの保存で同じシリアライザを利用する。
+  pub fn with_event_serializer(mut self, event_serializer: Arc<dyn EventSerializer<E> + Send + Sync>) -> Self {
+    self.event_store = self.event_store.with_event_serializer(event_serializer.clone());
+    self.event_serializer = Some(event_serializer);
+    self
+  }
+
+  /// スナップショットのシリアライザを設定する。1件ずつの保存と複数のイベントの保存、及び読み込みで同じシリアライザを利用する。
+  pub fn with_snapshot_serializer(mut self, snapshot_serializer: Arc<dyn SnapshotSerializer<A> + Send + Sync>) -> Self {
+    self.event_store = self.event_store.with_snapshot_serializer(snapshot_serializer.clone());
+    self.snapshot_serializer = snapshot_serializer;
+    self
+  }
+
+  fn put_journal(&self, event: &E, payload: Vec<u8>) -> Result<Put, BuildError> {
+    let key_resolver = DefaultKeyResolver::<AID>::default();
+    Put::builder()
+      .table_name(self.journal_table_name.clone())
+      .item(
+        "pkey",
+        AttributeValue::S(key_resolver.resolve_partition_key(event.aggregate_id(), self.shard_count)),
+      )
+      .item(
+        "skey",
+        AttributeValue::S(key_resolver.resolve_sort_key(event.aggregate_id(), event.seq_nr())),
+      )
+      .item("aid", AttributeValue::S(event.aggregate_id().to_string()))
+      .item("seq_nr", AttributeValue::N(event.seq_nr().to_string()))
+      .item("payload", AttributeValue::B(Blob::new(payload)))
+      .item(
+        "occurred_at",
+        AttributeValue::N(event.occurred_at().timestamp_millis().to_string()),
+      )
+      .build()
+  }
+
+  /// 集約の作成時は、スナップショットを新しく保存する。
+  fn put_snapshot(&self, event: &E, aggregate: &A, payload: Vec<u8>) -> Result<Put, BuildError> {
+    let key_resolver = DefaultKeyResolver::<AID>::default();
+    Put::builder()
+      .table_name(self.snapshot_table_name.clone())
+      .item(
+        "pkey",
+        AttributeValue::S(key_resolver.resolve_partition_key(aggregate.id(), self.shard_count)),
+      )
+      .item(
+        "skey",
+        AttributeValue::S(key_resolver.resolve_sort_key(aggregate.id(), 0)),
+      )
+      .item("payload", AttributeValue::B(Blob::new(payload)))
+      .item("aid", AttributeValue::S(aggregate.id().to_string()))
+      .item("seq_nr", AttributeValue::N("0".to_string()))
+      .item("version", AttributeValue::N("1".to_string()))
+      .item("ttl", AttributeValue::N("0".to_string()))
+      .item(
+        "last_updated_at",
+        AttributeValue::N(event.occurred_at().timestamp_millis().to_string()),
+      )
+      .condition_expression("attribute_not_exists(pkey) AND attribute_not_exists(skey)")
+      .build()
+  }
+
+  /// 集約の更新時は、バージョンが一致する場合のみスナップショットを置き換える。
+  fn update_snapshot(&self, event: &E, aggregate: &A, payload: Vec<u8>) -> Result<Update, BuildError> {
+    let key_resolver = DefaultKeyResolver::<AID>::default();
+    let version = aggregate.version();
+    Update::builder()
+      .table_name(self.snapshot_table_name.clone())
+      .key(
+        "pkey",
+        AttributeValue::S(key_resolver.resolve_partition_key(aggregate.id(), self.shard_count)),
+      )
+      .key(
+        "skey",
+        AttributeValue::S(key_resolver.resolve_sort_key(aggregate.id(), 0)),
+      )
+      .update_expression(
+        "SET #payload=:payload, #seq_nr=:seq_nr, #version=:after_version, #last_updated_at=:last_updated_at",
+      )
+      .expression_attribute_names("#payload", "payload")
+      .expression_attribute_names("#seq_nr", "seq_nr")
+      .expression_attribute_names("#version", "version")
+      .expression_attribute_names("#last_updated_at", "last_updated_at")
+      .expression_attribute_values(":payload", AttributeValue::B(Blob::new(payload)))
+      .expression_attribute_values(":seq_nr", AttributeValue::N("0".to_string()))
+      .expression_attribute_values(":before_version", AttributeValue::N(version.to_string()))
+      .expression_attribute_values(":after_version", AttributeValue::N((version + 1).to_string()))
+      .expression_attribute_values(
+        ":last_updated_at",
+        AttributeValue::N(event.occurred_at().timestamp_millis().to_string()),
+      )
+      .condition_expression("#version=:before_version")
+      .build()
+  }
+}
+
+#[async_trait::async_trait]
+impl<AID: AggregateId, A: Aggregate<ID = AID>, E: Event<AggregateID = AID>> EventStore
+  for MultiEventStoreForDynamoDB<AID, A, E>
+{
+  type AG = A;
+  type AID = AID;
+  type EV = E;
+
+  async fn persist_event(&mut self, event: &Self::EV, version: usize) -> Result<(), EventStoreWriteError> {
+    self.event_store.persist_event(event, version).await
+  }
+
+  async fn persist_event_and_snapshot(
+    &mut self,
+    event: &Self::EV,
+    aggregate: &Self::AG,
+  ) -> Result<(), EventStoreWriteError> {
+    self.event_store.persist_event_and_snapshot(event, aggregate).await
+  }
+
+  async fn get_latest_snapshot_by_id(&self, aid: &Self::AID) -> Result<Option<Self::AG>, EventStoreReadError> {
+    self.event_store.get_latest_snapshot_by_id(aid).await
+  }
+
+  async fn get_events_by_id_since_seq_nr(
+    &self,
+    aid: &Self::AID,
+    seq_nr: usize,
+  ) -> Result<Vec<Self::EV>, EventStoreReadError> {
+    self.event_store.get_events_by_id_since_seq_nr(aid, seq_nr).await
+  }
+}
+
+#[async_trait::async_trait]
+impl<AID: AggregateId, A: Aggregate<ID = AID>, E: Event<AggregateID = AID>> MultiEventStore
+  for MultiEventStoreForDynamoDB<AID, A, E>
+{
+  async fn persist_events_and_snapshot(
+    &mut self,
+    events: &[Self::EV],
+    aggregate: &Self::AG,
+  ) -> Result<(), EventStoreWriteError> {
+    let (Some(first_event), Some(last_event)) = (events.first(), events.last()) else {
+      return Ok(());
+    };
+    // NOTE: スナップショットの分を含めて、1つのトランザクションに収まる件数に制限する
+    if events.len() + 1 > MAX_TRANSACT_ITEMS {
+      return Err(EventStoreWriteError::OtherError(format!(
+        "Too many events in a transaction: {} (max: {})",
+        events.len(),
+        MAX_TRANSACT_ITEMS - 1
+      )));
+    }
+
+    let build_error = |e: BuildError| EventStoreWriteError::IOError(Box::new(e));
+    let snapshot_payload = self.snapshot_serializer.serialize(aggregate)?;
+    let snapshot_item = if first_event.is_created() {
+      TransactWriteItem::builder()
+        .put(
+          self
+            .put_snapshot(last_event, aggregate, snapshot_payload)
+            .map_err(build_error)?,
+        )
+        .build()
+    } else {
+      TransactWriteItem::builder()
+        .update(
+          self
+            .update_snapshot(last_event, aggregate, snapshot_payload)
+            .map_err(build_error)?,
+        )
+        .build()
+    };
+    let mut builder = self.client.transact_write_items().transact_items(snapshot_item);
+    for event in events {
+      let payload = match &self.event_serializer {
+        Some(event_serializer) => event_serializer.serialize(event)?,
+        None => serde_json::to_vec(event).map_err(|e| EventStoreWriteError::SerializationError(e.into()))?,
+      };
+      let put = self.put_journal(event, payload).map_err(build_error)?;
+      builder = builder.transact_items(TransactWriteItem::builder().put(put).build());
+    }
+    match builder.send().await {
+      Ok(_) => Ok(()),
+      Err(e) => match e.into_service_error() {
+        TransactWriteItemsError::TransactionCanceledException(e) if !e.cancellation_reasons().is_empty() => Err(
+          EventStoreWriteError::OptimisticLockError(TransactionCanceledExceptionWrapper(Some(e))),
+        ),
+        error => Err(EventStoreWriteError::IOError(error.into())),
+      },
+    }
+  }
+}
@@ -0,0 +1,207 @@
+use std::sync::Arc;
+
+use openssl::symm::{decrypt_aead, encrypt_aead, Cipher};
+use serde::Deserialize;
+
+use command_domain::user_account::UserAccountId;
+use command_interface_adaptor_if::{
+  EncryptedPayload, PayloadCipher, PayloadCipherError, UserAccountKeyStore, UserAccountKeyStoreError,
+};
+
+use crate::gateways::user_account_key_store::FileUserAccountKeyStore;
+
+/// ナンスの長さ
+const NONCE_LENGTH: usize = 12;
+/// 認証タグの長さ
+const TAG_LENGTH: usize = 16;
+
+fn cipher_error(error: openssl::error::ErrorStack) -> PayloadCipherError {
+  PayloadCipherError::CipherError(error.to_string())
+}
+
+/// 所有者ごとの鍵を使い、AES-256-GCMでペイロードを暗号化する[PayloadCipher]。
+///
+/// 暗号文の末尾には認証タグを付与する。
+#[derive(Debug)]
+pub struct AesGcmPayloadCipher {
+  key_store: Arc<dyn UserAccountKeyStore>,
+}
+
+impl AesGcmPayloadCipher {
+  /// コンストラクタ。
+  ///
+  /// # 引数
+  /// - `key_store` - ユーザアカウントごとの鍵を管理する[UserAccountKeyStore]
+  pub fn new(key_store: Arc<dyn UserAccountKeyStore>) -> Self {
+    Self { key_store }
+  }
+}
+
+impl PayloadCipher for AesGcmPayloadCipher {
+  fn encrypt(
+    &self,
+    owner_id: &UserAccountId,
+    plaintext: &[u8],
+    aad: &[u8],
+  ) -> Result<Option<EncryptedPayload>, PayloadCipherError> {
+    let key = self.key_store.get_or_create_current_key(owner_id)?;
+    let mut nonce = vec![0u8; NONCE_LENGTH];
+    openssl::rand::rand_bytes(&mut nonce).map_err(cipher_error)?;
+    let mut tag = [0u8; TAG_LENGTH];
+    let mut ciphertext =
+      encrypt_aead(Cipher::aes_256_gcm(), &key.key, Some(&nonce), aad, plaintext, &mut tag).map_err(cipher_error)?;
+    ciphertext.extend_from_slice(&tag);
+    Ok(Some(EncryptedPayload {
+      key_id: key.key_id,
+      nonce,
+      ciphertext,
+    }))
+  }
+
+  fn decrypt(
+    &self,
+    owner_id: &UserAccountId,
+    payload: &EncryptedPayload,
+    aad: &[u8],
+  ) -> Result<Option<Vec<u8>>, PayloadCipherError> {
+    let key = match self.key_store.find_key(owner_id, &payload.key_id)? {
+      Some(key) => key,
+      // NOTE: 鍵がないだけでは削除済みとみなさない。別のホストの鍵を参照しているなどの場合に、
+      // 復号できるメッセージを消去済みとしてリードモデルに反映してしまうため
+      None if self.key_store.is_shredded(owner_id)? => return Ok(None),
+      None => return Err(UserAccountKeyStoreError::KeyNotFoundError(owner_id.clone(), payload.key_id.clone()).into()),
+    };
+    if payload.ciphertext.len() < TAG_LENGTH {
+      return Err(PayloadCipherError::CipherError(
+        "The ciphertext is too short".to_string(),
+      ));
+    }
+    let (ciphertext, tag) = payload.ciphertext.split_at(payload.ciphertext.len() - TAG_LENGTH);
+    let plaintext =
+      decrypt_aead(Cipher::aes_256_gcm(), &key, Some(&payload.nonce), aad, ciphertext, tag).map_err(cipher_error)?;
+    Ok(Some(plaintext))
+  }
+
+  fn shred(&self, owner_id: &UserAccountId) -> Result<(), PayloadCipherError> {
+    Ok(self.key_store.delete_keys(owner_id)?)
+  }
+}
+
+/// ペイロードを暗号化しない[PayloadCipher]。
+///
+/// NOTE: 暗号化済みのペイロードは復号できないため、一度暗号化を有効にした環境では利用できない。
+#[derive(Debug, Default)]
+pub struct NoOpPayloadCipher;
+
+impl PayloadCipher for NoOpPayloadCipher {
+  fn encrypt(
+    &self,
+    _owner_id: &UserAccountId,
+    _plaintext: &[u8],
+    _aad: &[u8],
+  ) -> Result<Option<EncryptedPayload>, PayloadCipherError> {
+    Ok(None)
+  }
+
+  fn decrypt(
+    &self,
+    _owner_id: &UserAccountId,
+    payload: &EncryptedPayload,
+    _aad: &[u8],
+  ) -> Result<Option<Vec<u8>>, PayloadCipherError> {
+    Err(PayloadCipherError::CipherError(format!(
+      "The payload is encrypted with the key {}, but no cipher is configured",
+      payload.key_id
+    )))
+  }
+
+  fn shred(&self, _owner_id: &UserAccountId) -> Result<(), PayloadCipherError> {
+    Ok(())
+  }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PayloadCipherKind {
+  /// 暗号化しない
+  #[default]
+  None,
+  /// ローカルの鍵ファイルを使ってAES-256-GCMで暗号化する
+  AesGcm,
+}
+
+/// [PayloadCipher]の設定。
+///
+/// NOTE: write-api-server・read-model-updater・message-retention-jobで同じ設定にすること
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PayloadCipherSettings {
+  #[serde(default)]
+  pub kind: PayloadCipherKind,
+  /// 鍵ファイルを保存するディレクトリ(`aes_gcm`の場合は必須)
+  pub key_directory: Option<String>,
+}
+
+impl PayloadCipherSettings {
+  /// 設定に応じた[PayloadCipher]を生成する。
+  pub fn create_payload_cipher(&self) -> Result<Arc<dyn PayloadCipher>, PayloadCipherError> {
+    match self.kind {
+      PayloadCipherKind::None => Ok(Arc::new(NoOpPayloadCipher)),
+      PayloadCipherKind::AesGcm => {
+        let key_directory = self
+          .key_directory
+          .as_ref()
+          .ok_or_else(|| PayloadCipherError::CipherError("key_directory is required".to_string()))?;
+        let key_store = FileUserAccountKeyStore::new(key_directory)?;
+        Ok(Arc::new(AesGcmPayloadCipher::new(Arc::new(key_store))))
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::gateways::user_account_key_store::MockUserAccountKeyStore;
+
+  use super::*;
+
+  #[test]
+  fn test_decrypt_after_key_rotation() {
+    let key_store = Arc::new(MockUserAccountKeyStore::default());
+    let cipher = AesGcmPayloadCipher::new(key_store.clone());
+    let owner_id = UserAccountId::new();
+
+    let before = cipher.encrypt(&owner_id, b"before", b"aad").unwrap().unwrap();
+    key_store.rotate_key(&owner_id).unwrap();
+    let after = cipher.encrypt(&owner_id, b"after", b"aad").unwrap().unwrap();
+
+    assert_ne!(before.key_id, after.key_id);
+    assert_eq!(
+      cipher.decrypt(&owner_id, &before, b"aad").unwrap(),
+      Some(b"before".to_vec())
+    );
+    assert_eq!(
+      cipher.decrypt(&owner_id, &after, b"aad").unwrap(),
+      Some(b"after".to_vec())
+    );
+    assert!(cipher.decrypt(&owner_id, &before, b"other").is_err());
+
+    cipher.shred(&owner_id).unwrap();
+    assert_eq!(cipher.decrypt(&owner_id, &before, b"aad").unwrap(), None);
+  }
+
+  #[test]
+  fn test_decrypt_with_missing_key() {
+    let cipher = AesGcmPayloadCipher::new(Arc::new(MockUserAccountKeyStore::default()));
+    let owner_id = UserAccountId::new();
+    let payload = cipher.encrypt(&owner_id, b"secret", b"aad").unwrap().unwrap();
+
+    // 鍵を共有していないキーストアでは、削除済みとみなさずにエラーにする
+    let other_cipher = AesGcmPayloadCipher::new(Arc::new(MockUserAccountKeyStore::default()));
+    assert!(matches!(
+      other_cipher.decrypt(&owner_id, &payload, b"aad"),
+      Err(PayloadCipherError::KeyStoreError(
+        UserAccountKeyStoreError::KeyNotFoundError(_, _)
+      ))
+    ));
+  }
+}
@@ -0,0 +1,244 @@
+use std::sync::Arc;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use event_store_adapter_rs::serializer::EventSerializer;
+use event_store_adapter_rs::types::{EventStoreReadError, EventStoreWriteError};
+use serde_json::{json, Map, Value};
+use thiserror::Error;
+
+use command_domain::group_chat::GroupChatEvent;
+use command_domain::user_account::UserAccountId;
+use command_interface_adaptor_if::{EncryptedPayload, PayloadCipher, PayloadCipherError};
+
+/// 暗号化された本文を表す`type`の値
+const ENCRYPTED_BODY_TYPE: &str = "Encrypted";
+
+#[derive(Debug, Error)]
+pub enum PayloadCipherEventSerializerError {
+  #[error("PayloadCipherError: {0}")]
+  CipherError(#[from] PayloadCipherError),
+  #[error("JsonError: {0}")]
+  JsonError(#[from] serde_json::Error),
+  #[error("The encrypted body is invalid: {0}")]
+  InvalidPayloadError(String),
+}
+
+/// メッセージの本文を[PayloadCipher]で送信者ごとに暗号化する[EventSerializer]。
+///
+/// イベントに含まれるメッセージ(`sender_id`と`body`を持つオブジェクト)の`body`を
+/// `{"type": "Encrypted", "key_id": ..., "nonce": ..., "ciphertext": ...}`に置き換えて保存する。
+/// 鍵IDを一緒に保存するので、鍵をローテーションしても過去のイベントを書き換える必要はない。
+/// 送信者の鍵が削除されている場合は、本文を消去したメッセージとして復元する(クリプトシュレッディング)。
+///
+/// NOTE: スナップショットは[PayloadCipherSnapshotSerializer]で同じように暗号化する。
+///
+/// [PayloadCipherSnapshotSerializer]: crate::gateways::payload_cipher_snapshot_serializer::PayloadCipherSnapshotSerializer
+#[derive(Debug)]
+pub struct PayloadCipherEventSerializer {
+  payload_cipher: Arc<dyn PayloadCipher>,
+}
+
+impl PayloadCipherEventSerializer {
+  /// コンストラクタ。
+  ///
+  /// # 引数
+  /// - `payload_cipher` - メッセージの本文を暗号化する[PayloadCipher]
+  pub fn new(payload_cipher: Arc<dyn PayloadCipher>) -> Self {
+    Self { payload_cipher }
+  }
+
+  /// 値に含まれるすべてのメッセージの本文を暗号化する。
+  pub(crate) fn encrypt_messages(&self, value: &mut Value) -> Result<(), PayloadCipherEventSerializerError> {
+    Self::for_each_message(value, &mut |message| self.encrypt_body(message))
+  }
+
+  /// 値に含まれるすべてのメッセージの本文を復号する。
+  pub(crate) fn decrypt_messages(&self, value: &mut Value) -> Result<(), PayloadCipherEventSerializerError> {
+    Self::for_each_message(value, &mut |message| self.decrypt_body(message))
+  }
+
+  fn encrypt_body(&self, message: &mut Map<String, Value>) -> Result<(), PayloadCipherEventSerializerError> {
+    // NOTE: 消去済みのメッセージは本文が空なので暗号化しない。
+    // 消去したユーザアカウントの鍵は削除済みなので、暗号化しようとすると鍵を生成し直してしまうため
+    if message.get("redacted") == Some(&Value::Bool(true)) {
+      return Ok(());
+    }
+    let sender_id = Self::sender_id(message)?;
+    let plaintext = serde_json::to_vec(&message["body"])?;
+    let aad = Self::aad(message)?;
+    if let Some(payload) = self.payload_cipher.encrypt(&sender_id, &plaintext, &aad)? {
+      message.insert(
+        "body".to_string(),
+        json!({
+          "type": ENCRYPTED_BODY_TYPE,
+          "key_id": payload.key_id,
+          "nonce": STANDARD.encode(payload.nonce),
+          "ciphertext": STANDARD.encode(payload.ciphertext),
+        }),
+      );
+    }
+    Ok(())
+  }
+
+  fn decrypt_body(&self, message: &mut Map<String, Value>) -> Result<(), PayloadCipherEventSerializerError> {
+    if message["body"]["type"] != ENCRYPTED_BODY_TYPE {
+      return Ok(());
+    }
+    let sender_id = Self::sender_id(message)?;
+    let payload = EncryptedPayload {
+      key_id: message["body"]["key_id"]
+        .as_str()
+        .ok_or_else(|| PayloadCipherEventSerializerError::InvalidPayloadError("key_id".to_string()))?
+        .to_string(),
+      nonce: Self::decode_field(&message["body"], "nonce")?,
+      ciphertext: Self::decode_field(&message["body"], "ciphertext")?,
+    };
+    let aad = Self::aad(message)?;
+    match self.payload_cipher.decrypt(&sender_id, &payload, &aad)? {
+      Some(plaintext) => {
+        message.insert("body".to_string(), serde_json::from_slice(&plaintext)?);
+      }
+      None => {
+        message.insert("body".to_string(), json!({ "type": "Text", "text": "" }));
+        message.insert("mentions".to_string(), json!([]));
+        message.insert("redacted".to_string(), json!(true));
+      }
+    }
+    Ok(())
+  }
+
+  fn sender_id(message: &Map<String, Value>) -> Result<UserAccountId, PayloadCipherEventSerializerError> {
+    let sender_id: UserAccountId = serde_json::from_value(message["sender_id"].clone())?;
+    Ok(sender_id)
+  }
+
+  /// 暗号文を別のメッセージに付け替えられないように、メッセージIDと送信者を追加の認証データとする。
+  fn aad(message: &Map<String, Value>) -> Result<Vec<u8>, PayloadCipherEventSerializerError> {
+    let id = message
+      .get("id")
+      .ok_or_else(|| PayloadCipherEventSerializerError::InvalidPayloadError("id".to_string()))?;
+    Ok(serde_json::to_vec(&json!([id, message["sender_id"]]))?)
+  }
+
+  fn decode_field(body: &Value, name: &str) -> Result<Vec<u8>, PayloadCipherEventSerializerError> {
+    body[name]
+      .as_str()
+      .and_then(|value| STANDARD.decode(value).ok())
+      .ok_or_else(|| PayloadCipherEventSerializerError::InvalidPayloadError(name.to_string()))
+  }
+
+  /// イベントに含まれるメッセージを走査する。
+  fn for_each_message<F>(value: &mut Value, f: &mut F) -> Result<(), PayloadCipherEventSerializerError>
+  where
+    F: FnMut(&mut Map<String, Value>) -> Result<(), PayloadCipherEventSerializerError>,
+  {
+    match value {
+      Value::Object(map) => {
+        if map.contains_key("sender_id") && map.contains_key("body") {
+          return f(map);
+        }
+        for value in map.values_mut() {
+          Self::for_each_message(value, f)?;
+        }
+        Ok(())
+      }
+      Value::Array(values) => {
+        for value in values {
+          Self::for_each_message(value, f)?;
+        }
+        Ok(())
+      }
+      _ => Ok(()),
+    }
+  }
+}
+
+impl EventSerializer<GroupChatEvent> for PayloadCipherEventSerializer {
+  fn serialize(&self, event: &GroupChatEvent) -> Result<Vec<u8>, EventStoreWriteError> {
+    let result: Result<Vec<u8>, PayloadCipherEventSerializerError> = (|| {
+      let mut value = serde_json::to_value(event)?;
+      self.encrypt_messages(&mut value)?;
+      Ok(serde_json::to_vec(&value)?)
+    })();
+    result.map_err(|e| EventStoreWriteError::SerializationError(e.into()))
+  }
+
+  fn deserialize(&self, data: &[u8]) -> Result<Box<GroupChatEvent>, EventStoreReadError> {
+    let result: Result<GroupChatEvent, PayloadCipherEventSerializerError> = (|| {
+      let mut value: Value = serde_json::from_slice(data)?;
+      self.decrypt_messages(&mut value)?;
+      Ok(serde_json::from_value(value)?)
+    })();
+    result
+      .map(Box::new)
+      .map_err(|e| EventStoreReadError::DeserializationError(e.into()))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use command_domain::group_chat::{GroupChat, GroupChatName, Members, Message, MessageId};
+
+  use crate::gateways::payload_cipher::{AesGcmPayloadCipher, NoOpPayloadCipher};
+  use crate::gateways::user_account_key_store::MockUserAccountKeyStore;
+
+  use super::*;
+
+  fn post_message(user_account_id: &UserAccountId, message_id: &MessageId) -> GroupChatEvent {
+    let (mut group_chat, _) = GroupChat::new(
+      GroupChatName::new("test").unwrap(),
+      Members::new(user_account_id.clone()),
+    );
+    let message = Message::new(message_id.clone(), "secret".to_string(), user_account_id.clone());
+    group_chat.post_message(message, user_account_id.clone()).unwrap()
+  }
+
+  #[test]
+  fn test_encrypt_and_shred_message_body() {
+    let cipher = Arc::new(AesGcmPayloadCipher::new(Arc::new(MockUserAccountKeyStore::default())));
+    let serializer = PayloadCipherEventSerializer::new(cipher.clone());
+
+    let user_account_id = UserAccountId::new();
+    let message_id = MessageId::new();
+    let event = post_message(&user_account_id, &message_id);
+
+    let data = serializer.serialize(&event).unwrap();
+    let json = String::from_utf8(data.clone()).unwrap();
+    assert!(!json.contains("secret"));
+    assert!(json.contains("key_id"));
+
+    let actual = serializer.deserialize(&data).unwrap();
+    match *actual {
+      GroupChatEvent::GroupChatMessagePosted(body) => {
+        assert_eq!(body.message.breach_encapsulation_of_text(), "secret");
+      }
+      _ => panic!("unexpected event"),
+    }
+
+    cipher.shred(&user_account_id).unwrap();
+    let actual = serializer.deserialize(&data).unwrap();
+    match *actual {
+      GroupChatEvent::GroupChatMessagePosted(body) => {
+        assert!(body.message.is_redacted());
+        assert_eq!(body.message.breach_encapsulation_of_text(), "");
+        assert_eq!(body.message.breach_encapsulation_of_id(), &message_id);
+      }
+      _ => panic!("unexpected event"),
+    }
+  }
+
+  #[test]
+  fn test_no_op_cipher_keeps_message_body() {
+    let serializer = PayloadCipherEventSerializer::new(Arc::new(NoOpPayloadCipher));
+
+    let user_account_id = UserAccountId::new();
+    let event = post_message(&user_account_id, &MessageId::new());
+
+    let data = serializer.serialize(&event).unwrap();
+    let expected = serde_json::to_value(&event).unwrap();
+    assert_eq!(serde_json::from_slice::<Value>(&data).unwrap(), expected);
+    let actual = serializer.deserialize(&data).unwrap();
+    assert_eq!(serde_json::to_value(&*actual).unwrap(), expected);
+  }
+}
@@ -0,0 +1,133 @@
+use std::sync::Arc;
+
+use event_store_adapter_rs::serializer::SnapshotSerializer;
+use event_store_adapter_rs::types::{EventStoreReadError, EventStoreWriteError};
+use serde_json::Value;
+
+use command_domain::group_chat::GroupChat;
+use command_interface_adaptor_if::PayloadCipher;
+
+use crate::gateways::payload_cipher_event_serializer::{
+  PayloadCipherEventSerializer, PayloadCipherEventSerializerError,
+};
+
+/// スナップショットに含まれるメッセージの本文を[PayloadCipher]で送信者ごとに暗号化する[SnapshotSerializer]。
+///
+/// 本文の形式と鍵の扱いは[PayloadCipherEventSerializer]と同じ。
+/// 送信者の鍵が削除されている場合は、本文を消去したメッセージとして復元する。
+#[derive(Debug)]
+pub struct PayloadCipherSnapshotSerializer {
+  event_serializer: PayloadCipherEventSerializer,
+}
+
+impl PayloadCipherSnapshotSerializer {
+  /// コンストラクタ。
+  ///
+  /// # 引数
+  /// - `payload_cipher` - メッセージの本文を暗号化する[PayloadCipher]
+  pub fn new(payload_cipher: Arc<dyn PayloadCipher>) -> Self {
+    Self {
+      event_serializer: PayloadCipherEventSerializer::new(payload_cipher),
+    }
+  }
+}
+
+impl SnapshotSerializer<GroupChat> for PayloadCipherSnapshotSerializer {
+  fn serialize(&self, aggregate: &GroupChat) -> Result<Vec<u8>, EventStoreWriteError> {
+    let result: Result<Vec<u8>, PayloadCipherEventSerializerError> = (|| {
+      let mut value = serde_json::to_value(aggregate)?;
+      self.event_serializer.encrypt_messages(&mut value)?;
+      Ok(serde_json::to_vec(&value)?)
+    })();
+    result.map_err(|e| EventStoreWriteError::SerializationError(e.into()))
+  }
+
+  fn deserialize(&self, data: &[u8]) -> Result<Box<GroupChat>, EventStoreReadError> {
+    let result: Result<GroupChat, PayloadCipherEventSerializerError> = (|| {
+      let mut value: Value = serde_json::from_slice(data)?;
+      self.event_serializer.decrypt_messages(&mut value)?;
+      Ok(serde_json::from_value(value)?)
+    })();
+    result
+      .map(Box::new)
+      .map_err(|e| EventStoreReadError::DeserializationError(e.into()))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use command_domain::group_chat::{GroupChatName, MemberId, MemberRole, Members, Message, MessageId};
+  use command_domain::user_account::UserAccountId;
+
+  use crate::gateways::payload_cipher::AesGcmPayloadCipher;
+  use crate::gateways::user_account_key_store::{FileUserAccountKeyStore, MockUserAccountKeyStore};
+
+  use super::*;
+
+  #[test]
+  fn test_encrypt_and_shred_message_body_in_snapshot() {
+    let cipher = Arc::new(AesGcmPayloadCipher::new(Arc::new(MockUserAccountKeyStore::default())));
+    let serializer = PayloadCipherSnapshotSerializer::new(cipher.clone());
+
+    let user_account_id = UserAccountId::new();
+    let (mut group_chat, _) = GroupChat::new(
+      GroupChatName::new("test").unwrap(),
+      Members::new(user_account_id.clone()),
+    );
+    let message = Message::new(MessageId::new(), "secret".to_string(), user_account_id.clone());
+    group_chat.post_message(message, user_account_id.clone()).unwrap();
+
+    let data = serializer.serialize(&group_chat).unwrap();
+    let value: Value = serde_json::from_slice(&data).unwrap();
+    let body = &value["messages"][0]["body"];
+    assert_eq!(body["type"], "Encrypted");
+    assert!(body["ciphertext"].is_string());
+    assert!(!String::from_utf8(data.clone()).unwrap().contains("secret"));
+
+    let actual = serializer.deserialize(&data).unwrap();
+    assert_eq!(*actual, group_chat);
+
+    cipher.shred(&user_account_id).unwrap();
+    let actual = serializer.deserialize(&data).unwrap();
+    let message = actual.messages().iter().next().unwrap();
+    assert!(message.is_redacted());
+    assert_eq!(message.breach_encapsulation_of_text(), "");
+  }
+
+  #[test]
+  fn test_serialize_snapshot_after_shred() {
+    let directory = std::env::temp_dir().join(format!("user-account-keys-{}", command_domain::id_generate()));
+    let key_store = Arc::new(FileUserAccountKeyStore::new(&directory).unwrap());
+    let cipher = Arc::new(AesGcmPayloadCipher::new(key_store));
+    let serializer = PayloadCipherSnapshotSerializer::new(cipher.clone());
+
+    let admin_id = UserAccountId::new();
+    let user_account_id = UserAccountId::new();
+    let (mut group_chat, _) = GroupChat::new(GroupChatName::new("test").unwrap(), Members::new(admin_id.clone()));
+    group_chat
+      .add_member(
+        MemberId::new(),
+        user_account_id.clone(),
+        MemberRole::Member,
+        admin_id.clone(),
+      )
+      .unwrap();
+    let message = Message::new(MessageId::new(), "secret".to_string(), user_account_id.clone());
+    group_chat.post_message(message, user_account_id.clone()).unwrap();
+    serializer.serialize(&group_chat).unwrap();
+    assert!(directory.join(user_account_id.to_string()).exists());
+
+    // 消去した後に保存するスナップショットで、削除した鍵を生成し直さない
+    group_chat
+      .erase_user_account(user_account_id.clone(), admin_id.clone())
+      .unwrap();
+    cipher.shred(&user_account_id).unwrap();
+    let data = serializer.serialize(&group_chat).unwrap();
+    assert!(!directory.join(user_account_id.to_string()).exists());
+
+    let actual = serializer.deserialize(&data).unwrap();
+    assert_eq!(*actual, group_chat);
+
+    std::fs::remove_dir_all(&directory).unwrap();
+  }
+}
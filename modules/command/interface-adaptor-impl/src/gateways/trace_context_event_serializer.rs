@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use event_store_adapter_rs::serializer::EventSerializer;
+use event_store_adapter_rs::types::{Event, EventStoreReadError, EventStoreWriteError};
+use infrastructure::telemetry::current_trace_context;
+use serde_json::Value;
+
+/// トレースコンテキストを保存するペイロードのキー
+pub const TRACE_CONTEXT_KEY: &str = "trace_context";
+
+/// イベントを保存した操作のトレースコンテキストを、ペイロードのメタデータとして保存する[EventSerializer]。
+///
+/// 委譲先のシリアライザが出力したJSONに`"trace_context": {"traceparent": ...}`を追加する。
+/// Read Model Updaterは[trace_context_of]で取り出して、リードモデルの更新を元のミューテーションに紐付ける。
+///
+/// NOTE: イベントのデシリアライズでは未知のキーは無視されるので、読み込み時は委譲先にそのまま渡す。
+/// トレースしていない場合はキーを追加しない。
+#[derive(Debug)]
+pub struct TraceContextEventSerializer<E: Event> {
+  event_serializer: Arc<dyn EventSerializer<E> + Send + Sync>,
+}
+
+impl<E: Event> TraceContextEventSerializer<E> {
+  /// コンストラクタ。
+  ///
+  /// # 引数
+  /// - `event_serializer` - イベント本体をシリアライズする[EventSerializer]
+  pub fn new(event_serializer: Arc<dyn EventSerializer<E> + Send + Sync>) -> Self {
+    Self { event_serializer }
+  }
+}
+
+impl<E: Event> EventSerializer<E> for TraceContextEventSerializer<E> {
+  fn serialize(&self, event: &E) -> Result<Vec<u8>, EventStoreWriteError> {
+    let data = self.event_serializer.serialize(event)?;
+    let trace_context = current_trace_context();
+    if trace_context.is_empty() {
+      return Ok(data);
+    }
+    let mut value: Value =
+      serde_json::from_slice(&data).map_err(|e| EventStoreWriteError::SerializationError(e.into()))?;
+    if let Value::Object(map) = &mut value {
+      map.insert(
+        TRACE_CONTEXT_KEY.to_string(),
+        serde_json::to_value(trace_context).map_err(|e| EventStoreWriteError::SerializationError(e.into()))?,
+      );
+    }
+    serde_json::to_vec(&value).map_err(|e| EventStoreWriteError::SerializationError(e.into()))
+  }
+
+  fn deserialize(&self, data: &[u8]) -> Result<Box<E>, EventStoreReadError> {
+    self.event_serializer.deserialize(data)
+  }
+}
+
+/// イベントのペイロードから、保存時のトレースコンテキストを取り出す。
+///
+/// # 戻り値
+/// - トレースコンテキストがない場合は`None`
+pub fn trace_context_of(payload: &[u8]) -> Option<HashMap<String, String>> {
+  let mut value: Value = serde_json::from_slice(payload).ok()?;
+  serde_json::from_value(value.get_mut(TRACE_CONTEXT_KEY)?.take()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+  use event_store_adapter_rs::serializer::JsonEventSerializer;
+
+  use command_domain::group_chat::{GroupChat, GroupChatEvent, GroupChatName, Members};
+  use command_domain::user_account::UserAccountId;
+
+  use super::*;
+
+  #[test]
+  fn test_trace_context_in_payload() {
+    let serializer = TraceContextEventSerializer::new(Arc::new(JsonEventSerializer::<GroupChatEvent>::default()));
+    let (_, event) = GroupChat::new(GroupChatName::new("test").unwrap(), Members::new(UserAccountId::new()));
+
+    let data = serializer.serialize(&event).unwrap();
+    assert_eq!(trace_context_of(&data), None);
+    assert!(matches!(
+      *serializer.deserialize(&data).unwrap(),
+      GroupChatEvent::GroupChatCreated(_)
+    ));
+
+    let mut value: Value = serde_json::from_slice(&data).unwrap();
+    value[TRACE_CONTEXT_KEY] =
+      serde_json::json!({ "traceparent": "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01" });
+    let data = serde_json::to_vec(&value).unwrap();
+    assert_eq!(
+      trace_context_of(&data).unwrap()["traceparent"],
+      "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01"
+    );
+    assert!(matches!(
+      *serializer.deserialize(&data).unwrap(),
+      GroupChatEvent::GroupChatCreated(_)
+    ));
+  }
+}
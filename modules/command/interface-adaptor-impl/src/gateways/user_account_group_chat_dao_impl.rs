@@ -0,0 +1,65 @@
+use std::str::FromStr;
+
+use sqlx::MySqlPool;
+
+use command_domain::group_chat::GroupChatId;
+use command_domain::user_account::UserAccountId;
+use command_interface_adaptor_if::{UserAccountGroupChatDao, UserAccountGroupChatDaoError};
+
+#[derive(Debug)]
+pub struct UserAccountGroupChatDaoImpl {
+  pool: MySqlPool,
+}
+
+impl UserAccountGroupChatDaoImpl {
+  pub fn new(pool: MySqlPool) -> Self {
+    Self { pool }
+  }
+}
+
+#[async_trait::async_trait]
+impl UserAccountGroupChatDao for UserAccountGroupChatDaoImpl {
+  async fn get_group_chat_ids(
+    &self,
+    user_account_id: &UserAccountId,
+  ) -> Result<Vec<GroupChatId>, UserAccountGroupChatDaoError> {
+    // NOTE: 退出済みのグループチャットにもメッセージが残っているため、メッセージの送信者も対象にする。
+    let rows = sqlx::query!(
+      r#"SELECT group_chat_id FROM members WHERE user_account_id = ?
+         UNION
+         SELECT group_chat_id FROM invitations WHERE user_account_id = ?
+         UNION
+         SELECT group_chat_id FROM messages WHERE user_account_id = ?"#,
+      user_account_id.to_string(),
+      user_account_id.to_string(),
+      user_account_id.to_string()
+    )
+    .fetch_all(&self.pool)
+    .await
+    .map_err(|e| UserAccountGroupChatDaoError::OtherError(e.to_string()))?;
+    rows
+      .into_iter()
+      .map(|row| {
+        GroupChatId::from_str(&row.group_chat_id).map_err(|e| UserAccountGroupChatDaoError::OtherError(e.to_string()))
+      })
+      .collect()
+  }
+}
+
+#[derive(Debug, Default)]
+pub struct MockUserAccountGroupChatDao {
+  group_chat_ids: Vec<GroupChatId>,
+}
+
+impl MockUserAccountGroupChatDao {
+  pub fn new(group_chat_ids: Vec<GroupChatId>) -> Self {
+    Self { group_chat_ids }
+  }
+}
+
+#[async_trait::async_trait]
+impl UserAccountGroupChatDao for MockUserAccountGroupChatDao {
+  async fn get_group_chat_ids(&self, _: &UserAccountId) -> Result<Vec<GroupChatId>, UserAccountGroupChatDaoError> {
+    Ok(self.group_chat_ids.clone())
+  }
+}
@@ -0,0 +1,275 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use chrono::Utc;
+use command_domain::id_generate;
+use command_domain::user_account::UserAccountId;
+use command_interface_adaptor_if::{UserAccountKey, UserAccountKeyStore, UserAccountKeyStoreError};
+
+/// 鍵の長さ(AES-256)
+pub const KEY_LENGTH: usize = 32;
+/// 鍵ファイルの拡張子
+const KEY_FILE_EXTENSION: &str = "key";
+/// 鍵を削除したことを記録するファイルの拡張子
+const SHREDDED_FILE_EXTENSION: &str = "shredded";
+
+fn generate_key() -> Result<UserAccountKey, UserAccountKeyStoreError> {
+  let mut key = vec![0u8; KEY_LENGTH];
+  openssl::rand::rand_bytes(&mut key).map_err(|e| UserAccountKeyStoreError::IOError(e.to_string()))?;
+  // NOTE: ULIDは生成順にソートできるので、鍵IDの最大値を最新の鍵とする
+  Ok(UserAccountKey {
+    key_id: id_generate().to_string(),
+    key,
+  })
+}
+
+fn is_valid_key_id(key_id: &str) -> bool {
+  !key_id.is_empty() && key_id.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// ユーザアカウントごとの鍵を、ローカルのディレクトリにファイルとして保存する[UserAccountKeyStore]。
+///
+/// 鍵は`<directory>/<ユーザアカウントID>/<鍵ID>.key`に保存する。
+/// 鍵を削除したユーザアカウントは`<directory>/<ユーザアカウントID>.shredded`に記録する。
+/// ローテーションは新しい鍵ファイルを追加するだけなので、古い鍵で暗号化されたイベントもそのまま復号できる。
+///
+/// NOTE: 単一ホストでの運用やローカル開発向けの実装です。
+/// 複数のプロセスやホストで鍵を共有する場合は、KMSなどを利用した実装に置き換えてください。
+#[derive(Debug)]
+pub struct FileUserAccountKeyStore {
+  directory: PathBuf,
+}
+
+impl FileUserAccountKeyStore {
+  /// コンストラクタ。ディレクトリが存在しない場合は作成する。
+  ///
+  /// # 引数
+  /// - `directory` - 鍵を保存するディレクトリ
+  pub fn new(directory: impl Into<PathBuf>) -> Result<Self, UserAccountKeyStoreError> {
+    let directory = directory.into();
+    fs::create_dir_all(&directory).map_err(|e| UserAccountKeyStoreError::IOError(e.to_string()))?;
+    Ok(Self { directory })
+  }
+
+  fn user_account_directory(&self, user_account_id: &UserAccountId) -> PathBuf {
+    self.directory.join(user_account_id.to_string())
+  }
+
+  fn shredded_path(&self, user_account_id: &UserAccountId) -> PathBuf {
+    self
+      .directory
+      .join(format!("{}.{}", user_account_id, SHREDDED_FILE_EXTENSION))
+  }
+
+  fn key_path(&self, user_account_id: &UserAccountId, key_id: &str) -> PathBuf {
+    self
+      .user_account_directory(user_account_id)
+      .join(format!("{}.{}", key_id, KEY_FILE_EXTENSION))
+  }
+
+  /// 最新の鍵IDを取得する。
+  fn find_current_key_id(&self, user_account_id: &UserAccountId) -> Result<Option<String>, UserAccountKeyStoreError> {
+    let entries = match fs::read_dir(self.user_account_directory(user_account_id)) {
+      Ok(entries) => entries,
+      Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+      Err(e) => return Err(UserAccountKeyStoreError::IOError(e.to_string())),
+    };
+    let mut current_key_id: Option<String> = None;
+    for entry in entries {
+      let path = entry
+        .map_err(|e| UserAccountKeyStoreError::IOError(e.to_string()))?
+        .path();
+      if path.extension().and_then(|e| e.to_str()) != Some(KEY_FILE_EXTENSION) {
+        continue;
+      }
+      if let Some(key_id) = path.file_stem().and_then(|s| s.to_str()) {
+        if current_key_id.as_deref().map(|c| key_id > c).unwrap_or(true) {
+          current_key_id = Some(key_id.to_string());
+        }
+      }
+    }
+    Ok(current_key_id)
+  }
+
+  /// 鍵を生成する。鍵が削除済みの場合は、削除した状態を取り消さないようにエラーを返す。
+  fn create_key(&self, user_account_id: &UserAccountId) -> Result<UserAccountKey, UserAccountKeyStoreError> {
+    if self.is_shredded(user_account_id)? {
+      return Err(UserAccountKeyStoreError::ShreddedError(user_account_id.clone()));
+    }
+    let key = generate_key()?;
+    fs::create_dir_all(self.user_account_directory(user_account_id))
+      .map_err(|e| UserAccountKeyStoreError::IOError(e.to_string()))?;
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::OpenOptionsExt;
+      options.mode(0o600);
+    }
+    let mut file = options
+      .open(self.key_path(user_account_id, &key.key_id))
+      .map_err(|e| UserAccountKeyStoreError::IOError(e.to_string()))?;
+    file
+      .write_all(&key.key)
+      .map_err(|e| UserAccountKeyStoreError::IOError(e.to_string()))?;
+    Ok(key)
+  }
+}
+
+impl UserAccountKeyStore for FileUserAccountKeyStore {
+  // NOTE: 複数のスレッドが同時に鍵を生成した場合は鍵が複数できるが、
+  // 鍵IDが暗号文と一緒に保存されるので、どの鍵で暗号化したイベントも復号できる
+  fn get_or_create_current_key(
+    &self,
+    user_account_id: &UserAccountId,
+  ) -> Result<UserAccountKey, UserAccountKeyStoreError> {
+    if let Some(key_id) = self.find_current_key_id(user_account_id)? {
+      if let Some(key) = self.find_key(user_account_id, &key_id)? {
+        return Ok(UserAccountKey { key_id, key });
+      }
+    }
+    self.create_key(user_account_id)
+  }
+
+  fn find_key(
+    &self,
+    user_account_id: &UserAccountId,
+    key_id: &str,
+  ) -> Result<Option<Vec<u8>>, UserAccountKeyStoreError> {
+    if !is_valid_key_id(key_id) {
+      return Err(UserAccountKeyStoreError::InvalidKeyError(
+        user_account_id.clone(),
+        key_id.to_string(),
+      ));
+    }
+    match fs::read(self.key_path(user_account_id, key_id)) {
+      Ok(key) if key.len() == KEY_LENGTH => Ok(Some(key)),
+      Ok(_) => Err(UserAccountKeyStoreError::InvalidKeyError(
+        user_account_id.clone(),
+        key_id.to_string(),
+      )),
+      Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+      Err(e) => Err(UserAccountKeyStoreError::IOError(e.to_string())),
+    }
+  }
+
+  fn rotate_key(&self, user_account_id: &UserAccountId) -> Result<UserAccountKey, UserAccountKeyStoreError> {
+    self.create_key(user_account_id)
+  }
+
+  fn delete_keys(&self, user_account_id: &UserAccountId) -> Result<(), UserAccountKeyStoreError> {
+    fs::write(self.shredded_path(user_account_id), Utc::now().to_rfc3339())
+      .map_err(|e| UserAccountKeyStoreError::IOError(e.to_string()))?;
+    match fs::remove_dir_all(self.user_account_directory(user_account_id)) {
+      Ok(_) => Ok(()),
+      Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+      Err(e) => Err(UserAccountKeyStoreError::IOError(e.to_string())),
+    }
+  }
+
+  fn is_shredded(&self, user_account_id: &UserAccountId) -> Result<bool, UserAccountKeyStoreError> {
+    match fs::metadata(self.shredded_path(user_account_id)) {
+      Ok(_) => Ok(true),
+      Err(e) if e.kind() == ErrorKind::NotFound => Ok(false),
+      Err(e) => Err(UserAccountKeyStoreError::IOError(e.to_string())),
+    }
+  }
+}
+
+#[derive(Debug, Default)]
+pub struct MockUserAccountKeyStore {
+  keys: Mutex<HashMap<UserAccountId, Vec<UserAccountKey>>>,
+  shredded: Mutex<HashSet<UserAccountId>>,
+}
+
+impl UserAccountKeyStore for MockUserAccountKeyStore {
+  fn get_or_create_current_key(
+    &self,
+    user_account_id: &UserAccountId,
+  ) -> Result<UserAccountKey, UserAccountKeyStoreError> {
+    if self.is_shredded(user_account_id)? {
+      return Err(UserAccountKeyStoreError::ShreddedError(user_account_id.clone()));
+    }
+    let mut keys = self.keys.lock().unwrap();
+    if let Some(key) = keys.get(user_account_id).and_then(|keys| keys.last()) {
+      return Ok(key.clone());
+    }
+    let key = generate_key()?;
+    keys.entry(user_account_id.clone()).or_default().push(key.clone());
+    Ok(key)
+  }
+
+  fn find_key(
+    &self,
+    user_account_id: &UserAccountId,
+    key_id: &str,
+  ) -> Result<Option<Vec<u8>>, UserAccountKeyStoreError> {
+    Ok(
+      self
+        .keys
+        .lock()
+        .unwrap()
+        .get(user_account_id)
+        .and_then(|keys| keys.iter().find(|key| key.key_id == key_id))
+        .map(|key| key.key.clone()),
+    )
+  }
+
+  fn rotate_key(&self, user_account_id: &UserAccountId) -> Result<UserAccountKey, UserAccountKeyStoreError> {
+    if self.is_shredded(user_account_id)? {
+      return Err(UserAccountKeyStoreError::ShreddedError(user_account_id.clone()));
+    }
+    let key = generate_key()?;
+    self
+      .keys
+      .lock()
+      .unwrap()
+      .entry(user_account_id.clone())
+      .or_default()
+      .push(key.clone());
+    Ok(key)
+  }
+
+  fn delete_keys(&self, user_account_id: &UserAccountId) -> Result<(), UserAccountKeyStoreError> {
+    self.shredded.lock().unwrap().insert(user_account_id.clone());
+    self.keys.lock().unwrap().remove(user_account_id);
+    Ok(())
+  }
+
+  fn is_shredded(&self, user_account_id: &UserAccountId) -> Result<bool, UserAccountKeyStoreError> {
+    Ok(self.shredded.lock().unwrap().contains(user_account_id))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_delete_keys_records_shredded() {
+    let directory = std::env::temp_dir().join(format!("user-account-keys-{}", id_generate()));
+    let key_store = FileUserAccountKeyStore::new(&directory).unwrap();
+    let user_account_id = UserAccountId::new();
+    let key = key_store.get_or_create_current_key(&user_account_id).unwrap();
+    assert!(!key_store.is_shredded(&user_account_id).unwrap());
+
+    key_store.delete_keys(&user_account_id).unwrap();
+    assert!(key_store.is_shredded(&user_account_id).unwrap());
+    assert!(key_store.find_key(&user_account_id, &key.key_id).unwrap().is_none());
+    // 削除した鍵を生成し直さない
+    assert!(matches!(
+      key_store.get_or_create_current_key(&user_account_id),
+      Err(UserAccountKeyStoreError::ShreddedError(_))
+    ));
+    assert!(matches!(
+      key_store.rotate_key(&user_account_id),
+      Err(UserAccountKeyStoreError::ShreddedError(_))
+    ));
+    assert!(!directory.join(user_account_id.to_string()).exists());
+
+    fs::remove_dir_all(&directory).unwrap();
+  }
+}
@@ -1,29 +1,163 @@
+use std::future::Future;
 use std::sync::Arc;
 
-use async_graphql::{EmptySubscription, Object, Schema, SchemaBuilder};
-use event_store_adapter_rs::EventStoreForDynamoDB;
+use async_graphql::extensions::Tracing;
+use async_graphql::{EmptySubscription, FieldResult, Object, Schema, SchemaBuilder};
+use infrastructure::error::{ApiError, ErrorCode};
+use infrastructure::graphql::{GraphQLSettings, GraphQLSettingsError};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use tokio::sync::Mutex;
 
 use command_domain::group_chat::{GroupChat, GroupChatEvent, GroupChatId};
-use command_interface_adaptor_if::GroupChatRepository;
+use command_interface_adaptor_if::{GroupChatRepository, IdempotencyRecord, IdempotencyStore, UserAccountGroupChatDao};
 use command_processor::group_chat_command_processor::GroupChatCommandProcessor;
 
 use crate::gateways::group_chat_repository::GroupChatRepositoryImpl;
+use crate::gateways::idempotency_store::InMemoryIdempotencyStore;
+use crate::gateways::multi_event_store::MultiEventStoreForDynamoDB;
+use crate::graphql::errors::internal_error;
 
+pub mod errors;
 pub mod inputs;
 pub mod outputs;
 pub mod resolvers;
 
 pub struct ServiceContext<TR: GroupChatRepository> {
   group_chat_command_processor: Arc<Mutex<GroupChatCommandProcessor<TR>>>,
+  user_account_group_chat_dao: Arc<dyn UserAccountGroupChatDao>,
+  idempotency_store: Arc<dyn IdempotencyStore>,
 }
 
 impl<TR: GroupChatRepository> ServiceContext<TR> {
-  pub fn new(group_chat_command_processor: GroupChatCommandProcessor<TR>) -> Self {
+  pub fn new(
+    group_chat_command_processor: GroupChatCommandProcessor<TR>,
+    user_account_group_chat_dao: Arc<dyn UserAccountGroupChatDao>,
+  ) -> Self {
     Self {
       group_chat_command_processor: Arc::new(Mutex::new(group_chat_command_processor)),
+      user_account_group_chat_dao,
+      idempotency_store: Arc::new(InMemoryIdempotencyStore::new(
+        chrono::Duration::days(1),
+        chrono::Duration::minutes(1),
+      )),
     }
   }
+
+  /// 冪等キーと結果を保存する[IdempotencyStore]を設定する。既定ではメモリに保存する。
+  pub fn with_idempotency_store(mut self, idempotency_store: Arc<dyn IdempotencyStore>) -> Self {
+    self.idempotency_store = idempotency_store;
+    self
+  }
+
+  /// GraphQL以外のエンドポイント(REST APIなど)と共有するための[GroupChatCommandProcessor]を返す。
+  pub fn get_group_chat_command_processor(&self) -> Arc<Mutex<GroupChatCommandProcessor<TR>>> {
+    self.group_chat_command_processor.clone()
+  }
+
+  /// GraphQL以外のエンドポイント(gRPCなど)と共有するための[UserAccountGroupChatDao]を返す。
+  pub fn get_user_account_group_chat_dao(&self) -> Arc<dyn UserAccountGroupChatDao> {
+    self.user_account_group_chat_dao.clone()
+  }
+
+  /// 冪等キーと結果を保存する[IdempotencyStore]を返す。REST APIやgRPCのサービスでも同じストアを利用する。
+  pub fn get_idempotency_store(&self) -> Arc<dyn IdempotencyStore> {
+    self.idempotency_store.clone()
+  }
+
+  /// 冪等キーを指定した操作を実行する。[execute_idempotently]を参照。
+  pub(crate) async fn execute_idempotently<T, F>(
+    &self,
+    idempotency_key: Option<IdempotencyKey>,
+    execution: F,
+  ) -> FieldResult<T>
+  where
+    T: Serialize + DeserializeOwned,
+    F: Future<Output = FieldResult<T>>,
+  {
+    execute_idempotently(self.idempotency_store.as_ref(), idempotency_key, execution).await
+  }
+}
+
+/// 冪等キーを指定した操作を実行する。GraphQL・REST API・gRPCで共通の処理。
+///
+/// 同じ冪等キーの操作が既に成功している場合は、操作を実行せずに最初の結果を返す。
+/// 失敗した場合はキーを解放するので、再送すると操作を再実行する。
+///
+/// NOTE: 操作の前に[IdempotencyStore::claim]でキーを獲得するので、複数のインスタンスで同じキーの操作が同時に実行されることはない。
+/// 実行中に再送された場合は[ErrorCode::IdempotencyKeyInProgress]を、
+/// 同じキーを異なる入力で再利用した場合は[ErrorCode::IdempotencyKeyReused]を返す。
+/// リクエストが中断されてキーを解放できなかった場合は、実行中の記録の期限が切れるまで再送できない。
+///
+/// # 引数
+/// - `idempotency_store` - 冪等キーと結果を保存する[IdempotencyStore]
+/// - `idempotency_key` - 操作名と実行者で修飾した冪等キー。`None`の場合は常に操作を実行する
+/// - `execution` - 操作
+pub(crate) async fn execute_idempotently<T, E, F>(
+  idempotency_store: &dyn IdempotencyStore,
+  idempotency_key: Option<IdempotencyKey>,
+  execution: F,
+) -> Result<T, E>
+where
+  T: Serialize + DeserializeOwned,
+  E: From<ApiError>,
+  F: Future<Output = Result<T, E>>,
+{
+  let Some(IdempotencyKey { key, fingerprint }) = idempotency_key else {
+    return execution.await;
+  };
+
+  match idempotency_store
+    .claim(&key, &fingerprint)
+    .await
+    .map_err(|error| E::from(internal_error(&error)))?
+  {
+    None => {}
+    Some(record) if record.fingerprint() != fingerprint => {
+      return Err(
+        ApiError::new(
+          ErrorCode::IdempotencyKeyReused,
+          "the idempotency key is already used for a different request",
+        )
+        .into(),
+      )
+    }
+    Some(IdempotencyRecord::InProgress { .. }) => {
+      return Err(
+        ApiError::new(
+          ErrorCode::IdempotencyKeyInProgress,
+          "the request with the same idempotency key is in progress",
+        )
+        .into(),
+      )
+    }
+    Some(IdempotencyRecord::Completed { result, .. }) => {
+      return serde_json::from_str(&result).map_err(|error| E::from(internal_error(&error)))
+    }
+  }
+
+  let output = match execution.await {
+    Ok(output) => output,
+    Err(error) => {
+      if let Err(release_error) = idempotency_store.release(&key).await {
+        log::warn!("failed to release the idempotency key: {}, {}", key, release_error);
+      }
+      return Err(error);
+    }
+  };
+  let result = serde_json::to_string(&output).map_err(|error| E::from(internal_error(&error)))?;
+  idempotency_store
+    .complete(&key, &fingerprint, &result)
+    .await
+    .map_err(|error| E::from(internal_error(&error)))?;
+  Ok(output)
+}
+
+/// 操作名と実行者で修飾した冪等キーと、操作の入力のフィンガープリント。
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct IdempotencyKey {
+  pub(crate) key: String,
+  pub(crate) fingerprint: String,
 }
 
 pub struct QueryRoot;
@@ -37,16 +171,462 @@ impl QueryRoot {
 
 pub struct MutationRoot;
 
-pub type ES = EventStoreForDynamoDB<GroupChatId, GroupChat, GroupChatEvent>;
+pub type ES = MultiEventStoreForDynamoDB<GroupChatId, GroupChat, GroupChatEvent>;
 
 pub type ApiSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
 
+/// スキーマのビルダーを生成する。
+///
+/// NOTE: [ErrorCode]はフィールドの型ではないが、エラーの`code`拡張の値としてSDLに出力するために登録する。
+/// リクエストとリゾルバごとのスパンは[Tracing]拡張で生成する。
 pub fn create_schema_builder() -> SchemaBuilder<QueryRoot, MutationRoot, EmptySubscription> {
   Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+    .register_output_type::<ErrorCode>()
+    .extension(Tracing)
 }
 
-pub fn create_schema(group_chat_repository: GroupChatRepositoryImpl<ES>) -> ApiSchema {
-  let processor = GroupChatCommandProcessor::new(group_chat_repository);
-  let ctx = ServiceContext::new(processor);
-  create_schema_builder().data(ctx).finish()
+pub fn create_schema(
+  processor: GroupChatCommandProcessor<GroupChatRepositoryImpl<ES>>,
+  user_account_group_chat_dao: Arc<dyn UserAccountGroupChatDao>,
+  graphql_settings: &GraphQLSettings,
+) -> Result<ApiSchema, GraphQLSettingsError> {
+  let ctx = ServiceContext::new(processor, user_account_group_chat_dao);
+  create_schema_with_context(ctx, graphql_settings)
+}
+
+/// [ServiceContext]を登録したスキーマを生成する。
+///
+/// # 引数
+/// - `ctx` - [ServiceContext]
+/// - `graphql_settings` - クエリの深さ・複雑さの制限と永続化クエリの設定
+pub fn create_schema_with_context(
+  ctx: ServiceContext<GroupChatRepositoryImpl<ES>>,
+  graphql_settings: &GraphQLSettings,
+) -> Result<ApiSchema, GraphQLSettingsError> {
+  Ok(graphql_settings.apply(create_schema_builder().data(ctx))?.finish())
+}
+
+#[cfg(test)]
+mod tests {
+  use command_domain::group_chat::{GroupChatId, GroupChatName, MemberRole, Message, MessageId};
+  use command_domain::group_chat_error::GroupChatError;
+  use command_domain::user_account::UserAccountId;
+  use command_processor::group_chat_command::GroupChatCommand;
+  use command_processor::group_chat_command_middleware::{
+    AuthorizationMiddleware, GroupChatCommandMiddleware, ValidationMiddleware,
+  };
+  use command_processor::group_chat_command_processor::CommandProcessError;
+
+  use crate::gateways::group_chat_repository::MockGroupChatRepository;
+  use crate::gateways::user_account_group_chat_dao_impl::MockUserAccountGroupChatDao;
+  use crate::graphql::inputs::{BatchCommandInput, BatchGroupChatNameInput, BatchMemberInput};
+  use crate::graphql::outputs::MessageOut;
+  use crate::graphql::resolvers::{error_handling, validate_batch_command, validate_idempotency_key};
+  use infrastructure::error::error_code_of;
+
+  use super::*;
+
+  #[tokio::test]
+  async fn test_post_message_with_idempotency_key() {
+    let ctx = ServiceContext::new(
+      GroupChatCommandProcessor::new(MockGroupChatRepository::new()),
+      Arc::new(MockUserAccountGroupChatDao::new(vec![])),
+    );
+    let executor_id = UserAccountId::new();
+    let group_chat_id = ctx
+      .group_chat_command_processor
+      .lock()
+      .await
+      .handle(GroupChatCommand::CreateGroupChat {
+        group_chat_id: GroupChatId::new(),
+        name: GroupChatName::new("test").unwrap(),
+        executor_id: executor_id.clone(),
+      })
+      .await
+      .unwrap();
+
+    let post_message = |message_id: MessageId, idempotency_key: Option<&str>, content: &str| {
+      let idempotency_key = validate_idempotency_key("post_message", &executor_id, idempotency_key, &content).unwrap();
+      let message = Message::new(message_id.clone(), content.to_string(), executor_id.clone());
+      let ctx = &ctx;
+      let group_chat_id = group_chat_id.clone();
+      let executor_id = executor_id.clone();
+      async move {
+        let mut processor = ctx.group_chat_command_processor.lock().await;
+        ctx
+          .execute_idempotently(idempotency_key, async {
+            processor
+              .handle(GroupChatCommand::PostMessage {
+                group_chat_id,
+                message,
+                executor_id,
+              })
+              .await
+              .map(|group_chat_id| MessageOut::new(group_chat_id.to_string(), message_id.to_string()))
+              .map_err(error_handling)
+          })
+          .await
+      }
+    };
+
+    // 同じ冪等キーで再送した場合は、新しいメッセージIDで投稿せずに最初の結果を返す
+    let first = post_message(MessageId::new(), Some("key-1"), "Hello").await.unwrap();
+    let retried = post_message(MessageId::new(), Some("key-1"), "Hello").await.unwrap();
+    assert_eq!(first, retried);
+
+    let other = post_message(MessageId::new(), Some("key-2"), "Hello").await.unwrap();
+    assert_ne!(first, other);
+
+    // 同じ冪等キーを異なる入力で再利用した場合は拒否する
+    let error = post_message(MessageId::new(), Some("key-1"), "Bye").await.unwrap_err();
+    assert_eq!(error_code_of(&error), Some(ErrorCode::IdempotencyKeyReused));
+
+    // 同じ冪等キーの操作を実行中の場合は拒否する
+    let in_progress = validate_idempotency_key("post_message", &executor_id, Some("key-3"), &"Hello")
+      .unwrap()
+      .unwrap();
+    ctx
+      .idempotency_store
+      .claim(&in_progress.key, &in_progress.fingerprint)
+      .await
+      .unwrap();
+    let error = post_message(MessageId::new(), Some("key-3"), "Hello")
+      .await
+      .unwrap_err();
+    assert_eq!(error_code_of(&error), Some(ErrorCode::IdempotencyKeyInProgress));
+
+    // 失敗した場合はキーを解放するので、再送すると操作を再実行する
+    let posted_message_id = MessageId::new();
+    post_message(posted_message_id.clone(), None, "Hello").await.unwrap();
+    for _ in 0..2 {
+      let error = post_message(posted_message_id.clone(), Some("key-4"), "Hello")
+        .await
+        .unwrap_err();
+      assert_eq!(error_code_of(&error), Some(ErrorCode::MessageAlreadyExists));
+    }
+
+    // 同じメッセージIDで再送した場合は、集約で重複として拒否される
+    let message_id = MessageId::new();
+    post_message(message_id.clone(), None, "Hello").await.unwrap();
+    let error = ctx
+      .group_chat_command_processor
+      .lock()
+      .await
+      .handle(GroupChatCommand::PostMessage {
+        group_chat_id: group_chat_id.clone(),
+        message: Message::new(message_id.clone(), "Hello".to_string(), executor_id.clone()),
+        executor_id: executor_id.clone(),
+      })
+      .await
+      .unwrap_err();
+    assert!(matches!(
+      error,
+      CommandProcessError::DomainLogicError(GroupChatError::AlreadyExistsMessageError(id)) if id == message_id
+    ));
+  }
+
+  #[test]
+  fn test_validate_idempotency_key() {
+    let executor_id = UserAccountId::new();
+    assert_eq!(
+      validate_idempotency_key("post_message", &executor_id, None, &"Hello").unwrap(),
+      None
+    );
+    let idempotency_key = validate_idempotency_key("post_message", &executor_id, Some("key"), &"Hello")
+      .unwrap()
+      .unwrap();
+    assert_eq!(idempotency_key.key, format!("post_message:{}:key", executor_id));
+    // フィンガープリントは操作名と入力で決まる
+    let same_input = validate_idempotency_key("post_message", &executor_id, Some("other"), &"Hello")
+      .unwrap()
+      .unwrap();
+    assert_eq!(idempotency_key.fingerprint, same_input.fingerprint);
+    let other_input = validate_idempotency_key("post_message", &executor_id, Some("key"), &"Bye")
+      .unwrap()
+      .unwrap();
+    assert_ne!(idempotency_key.fingerprint, other_input.fingerprint);
+    let other_operation = validate_idempotency_key("edit_message", &executor_id, Some("key"), &"Hello")
+      .unwrap()
+      .unwrap();
+    assert_ne!(idempotency_key.fingerprint, other_operation.fingerprint);
+    assert!(validate_idempotency_key("post_message", &executor_id, Some(" "), &"Hello").is_err());
+    assert!(validate_idempotency_key("post_message", &executor_id, Some(&"a".repeat(256)), &"Hello").is_err());
+  }
+
+  #[tokio::test]
+  async fn test_execute_batch() {
+    let mut processor = GroupChatCommandProcessor::new(MockGroupChatRepository::new());
+    let executor_id = UserAccountId::new();
+    let user_account_id = UserAccountId::new();
+    let add_member = || BatchMemberInput {
+      user_account_id: user_account_id.to_string(),
+      role: "member".to_string(),
+    };
+
+    // 作成からメンバーの追加までを1つのバッチで適用できる
+    let group_chat_id = GroupChatId::new();
+    let commands = vec![
+      BatchCommandInput::CreateGroupChat(BatchGroupChatNameInput {
+        name: "test".to_string(),
+      }),
+      BatchCommandInput::AddMember(add_member()),
+    ]
+    .into_iter()
+    .map(|command| validate_batch_command(command, &group_chat_id, &executor_id).unwrap())
+    .collect::<Vec<_>>();
+    let result = processor.execute_batch(group_chat_id.clone(), commands).await.unwrap();
+    assert_eq!(result, group_chat_id);
+
+    // いずれかのコマンドが失敗した場合は、どのコマンドも適用されない
+    let other_user_account_id = UserAccountId::new();
+    let error = processor
+      .execute_batch(
+        group_chat_id.clone(),
+        vec![
+          GroupChatCommand::AddMember {
+            group_chat_id: group_chat_id.clone(),
+            user_account_id: other_user_account_id.clone(),
+            role: MemberRole::Member,
+            executor_id: executor_id.clone(),
+          },
+          GroupChatCommand::AddMember {
+            group_chat_id: group_chat_id.clone(),
+            user_account_id: user_account_id.clone(),
+            role: MemberRole::Member,
+            executor_id: executor_id.clone(),
+          },
+        ],
+      )
+      .await
+      .unwrap_err();
+    assert!(matches!(error, CommandProcessError::DomainLogicError(_)));
+    processor
+      .handle(GroupChatCommand::AddMember {
+        group_chat_id: group_chat_id.clone(),
+        user_account_id: other_user_account_id,
+        role: MemberRole::Member,
+        executor_id: executor_id.clone(),
+      })
+      .await
+      .unwrap();
+
+    // 存在しないグループチャットには、作成から始まるバッチのみ適用できる
+    let missing_group_chat_id = GroupChatId::new();
+    let error = processor
+      .execute_batch(
+        missing_group_chat_id.clone(),
+        vec![validate_batch_command(
+          BatchCommandInput::AddMember(add_member()),
+          &missing_group_chat_id,
+          &executor_id,
+        )
+        .unwrap()],
+      )
+      .await
+      .unwrap_err();
+    assert!(matches!(error, CommandProcessError::NotFoundError));
+
+    let error = processor.execute_batch(group_chat_id, vec![]).await.unwrap_err();
+    assert!(matches!(error, CommandProcessError::InvalidBatchError(_)));
+  }
+
+  /// 特定のユーザアカウントのコマンドを拒否し、処理したコマンドの名前を記録するミドルウェア
+  struct RecordingMiddleware {
+    forbidden_user_account_id: UserAccountId,
+    handled: std::sync::Mutex<Vec<String>>,
+  }
+
+  #[async_trait::async_trait]
+  impl GroupChatCommandMiddleware for RecordingMiddleware {
+    async fn before(&self, command: &GroupChatCommand) -> Result<(), CommandProcessError> {
+      if command.executor_id() == Some(&self.forbidden_user_account_id) {
+        return Err(CommandProcessError::ForbiddenError(command.name().to_string()));
+      }
+      Ok(())
+    }
+
+    async fn after(
+      &self,
+      command: &GroupChatCommand,
+      result: &Result<GroupChatId, CommandProcessError>,
+      _elapsed: std::time::Duration,
+    ) {
+      self
+        .handled
+        .lock()
+        .unwrap()
+        .push(format!("{}:{}", command.name(), result.is_ok()));
+    }
+
+    async fn after_batch(
+      &self,
+      commands: &[GroupChatCommand],
+      result: &Result<GroupChatId, CommandProcessError>,
+      _elapsed: std::time::Duration,
+    ) {
+      let names = commands.iter().map(|command| command.name()).collect::<Vec<_>>();
+      self
+        .handled
+        .lock()
+        .unwrap()
+        .push(format!("batch({}):{}", names.join(","), result.is_ok()));
+    }
+  }
+
+  #[tokio::test]
+  async fn test_handle_with_middleware() {
+    let forbidden_user_account_id = UserAccountId::new();
+    let middleware = Arc::new(RecordingMiddleware {
+      forbidden_user_account_id: forbidden_user_account_id.clone(),
+      handled: std::sync::Mutex::new(Vec::new()),
+    });
+    let mut processor =
+      GroupChatCommandProcessor::new(MockGroupChatRepository::new()).with_middleware(middleware.clone());
+    let executor_id = UserAccountId::new();
+    let group_chat_id = GroupChatId::new();
+
+    let command = GroupChatCommand::CreateGroupChat {
+      group_chat_id: group_chat_id.clone(),
+      name: GroupChatName::new("test").unwrap(),
+      executor_id: executor_id.clone(),
+    };
+    // コマンドはJSONで出力し、そのまま再実行できる
+    let json = serde_json::to_string(&command).unwrap();
+    assert!(json.contains("\"type\":\"create_group_chat\""));
+    let command = serde_json::from_str::<GroupChatCommand>(&json).unwrap();
+    assert_eq!(processor.handle(command).await.unwrap(), group_chat_id);
+
+    let error = processor
+      .handle(GroupChatCommand::RenameGroupChat {
+        group_chat_id: group_chat_id.clone(),
+        name: GroupChatName::new("renamed").unwrap(),
+        executor_id: forbidden_user_account_id,
+      })
+      .await
+      .unwrap_err();
+    assert!(matches!(error, CommandProcessError::ForbiddenError(_)));
+
+    let error = processor
+      .handle(GroupChatCommand::LeaveGroupChat {
+        group_chat_id: GroupChatId::new(),
+        executor_id,
+      })
+      .await
+      .unwrap_err();
+    assert!(matches!(error, CommandProcessError::NotFoundError));
+
+    // 拒否したコマンドは処理していないので、afterは呼び出されない
+    assert_eq!(
+      *middleware.handled.lock().unwrap(),
+      vec![
+        "create_group_chat:true".to_string(),
+        "leave_group_chat:false".to_string()
+      ]
+    );
+  }
+  #[tokio::test]
+  async fn test_execute_batch_with_middleware() {
+    let middleware = Arc::new(RecordingMiddleware {
+      forbidden_user_account_id: UserAccountId::new(),
+      handled: std::sync::Mutex::new(Vec::new()),
+    });
+    let mut processor =
+      GroupChatCommandProcessor::new(MockGroupChatRepository::new()).with_middleware(middleware.clone());
+    let executor_id = UserAccountId::new();
+    let group_chat_id = GroupChatId::new();
+
+    processor
+      .execute_batch(
+        group_chat_id.clone(),
+        vec![
+          GroupChatCommand::CreateGroupChat {
+            group_chat_id: group_chat_id.clone(),
+            name: GroupChatName::new("test").unwrap(),
+            executor_id: executor_id.clone(),
+          },
+          GroupChatCommand::AddMember {
+            group_chat_id: group_chat_id.clone(),
+            user_account_id: UserAccountId::new(),
+            role: MemberRole::Member,
+            executor_id,
+          },
+        ],
+      )
+      .await
+      .unwrap();
+
+    // バッチの場合はコマンドごとのafterではなく、after_batchを一度だけ呼び出す
+    assert_eq!(
+      *middleware.handled.lock().unwrap(),
+      vec!["batch(create_group_chat,add_member):true".to_string()]
+    );
+  }
+
+  #[tokio::test]
+  async fn test_authorization_and_validation_middleware() {
+    let privileged_id = UserAccountId::new();
+    let mut processor = GroupChatCommandProcessor::new(MockGroupChatRepository::new())
+      .with_middleware(Arc::new(ValidationMiddleware))
+      .with_middleware(Arc::new(AuthorizationMiddleware::new(vec![privileged_id.clone()])));
+    let admin_id = UserAccountId::new();
+    let member_id = UserAccountId::new();
+    let group_chat_id = processor
+      .handle(GroupChatCommand::CreateGroupChat {
+        group_chat_id: GroupChatId::new(),
+        name: GroupChatName::new("test").unwrap(),
+        executor_id: admin_id.clone(),
+      })
+      .await
+      .unwrap();
+    processor
+      .handle(GroupChatCommand::AddMember {
+        group_chat_id: group_chat_id.clone(),
+        user_account_id: member_id.clone(),
+        role: MemberRole::Member,
+        executor_id: admin_id.clone(),
+      })
+      .await
+      .unwrap();
+    let message_id = MessageId::new();
+    processor
+      .handle(GroupChatCommand::PostMessage {
+        group_chat_id: group_chat_id.clone(),
+        message: Message::new(message_id.clone(), "Hello".to_string(), admin_id.clone()),
+        executor_id: admin_id.clone(),
+      })
+      .await
+      .unwrap();
+
+    // 実行者のないコマンドはAPI経由では受け付けない
+    let expire_message = GroupChatCommand::ExpireMessage {
+      group_chat_id: group_chat_id.clone(),
+      message_id,
+    };
+    let error = processor.handle(expire_message.clone()).await.unwrap_err();
+    assert!(matches!(error, CommandProcessError::InvalidCommandError(_)));
+    let error = AuthorizationMiddleware::default()
+      .before(&expire_message)
+      .await
+      .unwrap_err();
+    assert!(matches!(error, CommandProcessError::ForbiddenError(_)));
+
+    // 管理者であっても、特権がなければ他のユーザアカウントを消去できない
+    let error = processor
+      .erase_user_account(member_id.clone(), vec![group_chat_id.clone()], admin_id.clone())
+      .await
+      .unwrap_err();
+    assert!(matches!(error, CommandProcessError::ForbiddenError(_)));
+
+    // ユーザアカウント自身と、特権を持つユーザアカウントは消去できる
+    let erased = processor
+      .erase_user_account(member_id.clone(), vec![group_chat_id.clone()], member_id)
+      .await
+      .unwrap();
+    assert_eq!(erased, vec![group_chat_id.clone()]);
+    let erased = processor
+      .erase_user_account(admin_id, vec![group_chat_id.clone()], privileged_id)
+      .await
+      .unwrap();
+    assert_eq!(erased, vec![group_chat_id]);
+  }
 }
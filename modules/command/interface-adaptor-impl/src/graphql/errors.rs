@@ -0,0 +1,214 @@
+use event_store_adapter_rs::types::EventStoreWriteError;
+use infrastructure::error::{ApiError, ErrorCode};
+
+use command_domain::group_chat::{GroupChatNameError, GroupChatSettingsError, MessageError, ParseError};
+use command_domain::group_chat_error::GroupChatError;
+use command_interface_adaptor_if::GroupChatRepositoryError;
+use command_processor::group_chat_command_processor::CommandProcessError;
+
+/// [ErrorCode::Internal]のエラーを返す。
+///
+/// NOTE: 内部の詳細をクライアントに返さないように、原因はログにのみ出力する。
+pub(crate) fn internal_error(cause: &dyn std::fmt::Display) -> ApiError {
+  log::error!("internal error: {}", cause);
+  ApiError::new(ErrorCode::Internal, "internal server error")
+}
+
+/// [CommandProcessError]を[ApiError]に変換する。
+pub(crate) fn command_process_error(error: &CommandProcessError) -> ApiError {
+  match error {
+    CommandProcessError::DomainLogicError(cause) => group_chat_error(cause),
+    CommandProcessError::NotFoundError => ApiError::new(ErrorCode::GroupChatNotFound, error.to_string()),
+    CommandProcessError::RepositoryError(cause) => repository_error(cause),
+    CommandProcessError::PayloadCipherError(cause) => internal_error(cause),
+    CommandProcessError::InvalidBatchError(reason) => {
+      ApiError::new(ErrorCode::InvalidBatch, error.to_string()).with_field("reason", reason)
+    }
+    CommandProcessError::InvalidCommandError(_) => ApiError::new(ErrorCode::InvalidArgument, error.to_string()),
+    CommandProcessError::ForbiddenError(_) => ApiError::new(ErrorCode::Forbidden, error.to_string()),
+  }
+}
+
+fn repository_error(error: &GroupChatRepositoryError) -> ApiError {
+  match error {
+    GroupChatRepositoryError::StoreError(_, EventStoreWriteError::OptimisticLockError(_)) => ApiError::new(
+      ErrorCode::Conflict,
+      "the group chat was updated concurrently, please retry",
+    ),
+    GroupChatRepositoryError::StoreError(_, _) | GroupChatRepositoryError::FindByIdError(_, _) => internal_error(error),
+  }
+}
+
+/// [GroupChatError]を[ApiError]に変換する。
+pub(crate) fn group_chat_error(error: &GroupChatError) -> ApiError {
+  let message = error.to_string();
+  match error {
+    GroupChatError::AlreadyDeletedError(group_chat_id) => {
+      ApiError::new(ErrorCode::GroupChatDeleted, message).with_field("groupChatId", group_chat_id)
+    }
+    GroupChatError::NotAdministratorError(_, user_account_id) => {
+      ApiError::new(ErrorCode::NotAdministrator, message).with_field("userAccountId", user_account_id)
+    }
+    GroupChatError::NotMemberError(_, user_account_id) => {
+      ApiError::new(ErrorCode::NotMember, message).with_field("userAccountId", user_account_id)
+    }
+    GroupChatError::AlreadyMemberError(_, user_account_id) => {
+      ApiError::new(ErrorCode::AlreadyMember, message).with_field("userAccountId", user_account_id)
+    }
+    GroupChatError::MismatchedUserAccountError(_, _) => ApiError::new(ErrorCode::MismatchedUserAccount, message),
+    GroupChatError::AlreadyExistsMessageError(message_id) => {
+      ApiError::new(ErrorCode::MessageAlreadyExists, message).with_field("messageId", message_id)
+    }
+    GroupChatError::NotFoundMessageError(message_id) => {
+      ApiError::new(ErrorCode::MessageNotFound, message).with_field("messageId", message_id)
+    }
+    GroupChatError::NotSenderError(_, user_account_id) => {
+      ApiError::new(ErrorCode::NotSender, message).with_field("userAccountId", user_account_id)
+    }
+    GroupChatError::NotDeletedMessageError(message_id) => {
+      ApiError::new(ErrorCode::MessageNotDeleted, message).with_field("messageId", message_id)
+    }
+    GroupChatError::RestoreWindowExpiredError(message_id) => {
+      ApiError::new(ErrorCode::RestoreWindowExpired, message).with_field("messageId", message_id)
+    }
+    GroupChatError::EditWindowExpiredError(message_id) => {
+      ApiError::new(ErrorCode::EditWindowExpired, message).with_field("messageId", message_id)
+    }
+    GroupChatError::EmptyModerationReasonError(message_id) => {
+      ApiError::new(ErrorCode::ModerationReasonEmpty, message).with_field("messageId", message_id)
+    }
+    GroupChatError::SystemMessageError(message_id) => {
+      ApiError::new(ErrorCode::SystemMessageNotAllowed, message).with_field("messageId", message_id)
+    }
+    GroupChatError::NotSupportedInDirectChatError(operation, group_chat_id) => {
+      ApiError::new(ErrorCode::NotSupportedInDirectChat, message)
+        .with_field("operation", operation)
+        .with_field("groupChatId", group_chat_id)
+    }
+    GroupChatError::DirectChatWithSelfError(user_account_id) => {
+      ApiError::new(ErrorCode::DirectChatWithSelf, message).with_field("userAccountId", user_account_id)
+    }
+    GroupChatError::AlreadyInvitedError(_, user_account_id) => {
+      ApiError::new(ErrorCode::AlreadyInvited, message).with_field("userAccountId", user_account_id)
+    }
+    GroupChatError::NotFoundInvitationError(user_account_id) => {
+      ApiError::new(ErrorCode::InvitationNotFound, message).with_field("userAccountId", user_account_id)
+    }
+    GroupChatError::LastAdministratorError(user_account_id) => {
+      ApiError::new(ErrorCode::LastAdministrator, message).with_field("userAccountId", user_account_id)
+    }
+    GroupChatError::MemberLimitExceededError(group_chat_id, max_members) => {
+      ApiError::new(ErrorCode::MemberLimitExceeded, message)
+        .with_field("groupChatId", group_chat_id)
+        .with_field("maxMembers", max_members)
+    }
+    GroupChatError::PostNotAllowedError(_, user_account_id) => {
+      ApiError::new(ErrorCode::PostNotAllowed, message).with_field("userAccountId", user_account_id)
+    }
+    GroupChatError::MaxMembersBelowCurrentError(max_members, current_members) => {
+      ApiError::new(ErrorCode::MaxMembersBelowCurrent, message)
+        .with_field("maxMembers", max_members)
+        .with_field("currentMembers", current_members)
+    }
+    GroupChatError::RetentionNotConfiguredError(group_chat_id) => {
+      ApiError::new(ErrorCode::RetentionNotConfigured, message).with_field("groupChatId", group_chat_id)
+    }
+    GroupChatError::MessageNotExpiredError(message_id) => {
+      ApiError::new(ErrorCode::MessageNotExpired, message).with_field("messageId", message_id)
+    }
+    GroupChatError::NoUserAccountDataError(group_chat_id, user_account_id) => {
+      ApiError::new(ErrorCode::NoUserAccountData, message)
+        .with_field("groupChatId", group_chat_id)
+        .with_field("userAccountId", user_account_id)
+    }
+    GroupChatError::AlreadyExistsNameError(_, name) => {
+      ApiError::new(ErrorCode::NameAlreadyExists, message).with_field("name", name)
+    }
+  }
+}
+
+/// IDや列挙値の[ParseError]を[ApiError]に変換する。
+///
+/// # 引数
+/// - `argument` - 入力の名前(camelCase)
+/// - `value` - 入力値
+pub(crate) fn parse_error(error: &ParseError, argument: &'static str, value: &str) -> ApiError {
+  let code = match error {
+    ParseError::InvalidULID(_) => ErrorCode::InvalidId,
+    ParseError::InvalidRole(_) => ErrorCode::InvalidRole,
+    ParseError::InvalidChatKind(_) => ErrorCode::InvalidChatKind,
+    ParseError::InvalidPermission(_) => ErrorCode::InvalidPermission,
+  };
+  ApiError::new(code, error.to_string())
+    .with_field("argument", argument)
+    .with_field("value", value)
+}
+
+/// [GroupChatNameError]を[ApiError]に変換する。
+pub(crate) fn group_chat_name_error(error: &GroupChatNameError) -> ApiError {
+  let code = match error {
+    GroupChatNameError::Empty => ErrorCode::NameEmpty,
+    GroupChatNameError::TooLong => ErrorCode::NameTooLong,
+  };
+  ApiError::new(code, error.to_string())
+}
+
+/// [GroupChatSettingsError]を[ApiError]に変換する。
+pub(crate) fn group_chat_settings_error(error: &GroupChatSettingsError) -> ApiError {
+  match error {
+    GroupChatSettingsError::InvalidMaxMembers(max_members) => {
+      ApiError::new(ErrorCode::InvalidMaxMembers, error.to_string()).with_field("maxMembers", max_members)
+    }
+    GroupChatSettingsError::InvalidMessageRetentionDays => {
+      ApiError::new(ErrorCode::InvalidMessageRetentionDays, error.to_string())
+    }
+  }
+}
+
+/// [MessageError]を[ApiError]に変換する。
+pub(crate) fn message_error(error: &MessageError) -> ApiError {
+  let message = error.to_string();
+  match error {
+    MessageError::Empty => ApiError::new(ErrorCode::MessageEmpty, message),
+    MessageError::TooLong => ApiError::new(ErrorCode::MessageTooLong, message),
+    MessageError::InvalidUrl(url) => ApiError::new(ErrorCode::InvalidUrl, message).with_field("url", url),
+    MessageError::InvalidMime(mime) => ApiError::new(ErrorCode::InvalidMime, message).with_field("mime", mime),
+    MessageError::InvalidSize(size) => {
+      ApiError::new(ErrorCode::InvalidAttachmentSize, message).with_field("size", size)
+    }
+    MessageError::SystemNotAllowed => ApiError::new(ErrorCode::SystemMessageNotAllowed, message),
+  }
+}
+
+/// [ErrorCode::InvalidArgument]のエラーを返す。
+///
+/// # 引数
+/// - `argument` - 入力の名前(camelCase)
+/// - `message` - メッセージ
+pub(crate) fn invalid_argument(argument: &'static str, message: &str) -> ApiError {
+  ApiError::new(ErrorCode::InvalidArgument, message).with_field("argument", argument)
+}
+
+#[cfg(test)]
+mod tests {
+  use command_domain::user_account::UserAccountId;
+
+  use super::*;
+
+  #[test]
+  fn test_command_process_error() {
+    let user_account_id = UserAccountId::new();
+    let error = command_process_error(&CommandProcessError::DomainLogicError(GroupChatError::NotMemberError(
+      "executor_id".to_string(),
+      user_account_id.clone(),
+    )));
+    assert_eq!(error.code(), ErrorCode::NotMember);
+    assert_eq!(error.fields(), &[("userAccountId", user_account_id.to_string())]);
+
+    let error = command_process_error(&CommandProcessError::NotFoundError);
+    assert_eq!(error.code(), ErrorCode::GroupChatNotFound);
+
+    let error = group_chat_name_error(&GroupChatNameError::TooLong);
+    assert_eq!(error.code(), ErrorCode::NameTooLong);
+  }
+}
@@ -1,57 +1,273 @@
-use async_graphql::InputObject;
+use async_graphql::{InputObject, OneofObject};
+use serde::Serialize;
 
-#[derive(Debug, Clone, InputObject)]
+#[derive(Debug, Clone, Serialize, InputObject)]
 pub struct CreateGroupChatInput {
   pub name: String,
   pub executor_id: String,
+  /// 冪等キー。同じ実行者が同じキーで再送した場合は、操作を実行せずに最初の結果を返す
+  pub idempotency_key: Option<String>,
 }
 
-#[derive(Debug, Clone, InputObject)]
+#[derive(Debug, Clone, Serialize, InputObject)]
+pub struct OpenDirectChatInput {
+  pub other_user_account_id: String,
+  pub executor_id: String,
+  /// 冪等キー。同じ実行者が同じキーで再送した場合は、操作を実行せずに最初の結果を返す
+  pub idempotency_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, InputObject)]
 pub struct DeleteGroupChatInput {
   pub group_chat_id: String,
   pub executor_id: String,
+  /// 冪等キー。同じ実行者が同じキーで再送した場合は、操作を実行せずに最初の結果を返す
+  pub idempotency_key: Option<String>,
 }
 
-#[derive(Debug, Clone, InputObject)]
+#[derive(Debug, Clone, Serialize, InputObject)]
 pub struct RenameGroupChatInput {
   pub group_chat_id: String,
   pub name: String,
   pub executor_id: String,
+  /// 冪等キー。同じ実行者が同じキーで再送した場合は、操作を実行せずに最初の結果を返す
+  pub idempotency_key: Option<String>,
 }
 
-#[derive(Debug, Clone, InputObject)]
+/// グループチャットの設定。`post_permission`と`add_member_permission`には`everyone`または`admins_only`を指定する。
+///
+/// `post_permission`に`admins_only`を指定するとアナウンスモードになる。`message_retention_days`を省略した場合は無期限となる。
+#[derive(Debug, Clone, Serialize, InputObject)]
+pub struct UpdateSettingsInput {
+  pub group_chat_id: String,
+  pub max_members: u32,
+  pub post_permission: String,
+  pub add_member_permission: String,
+  pub message_retention_days: Option<u32>,
+  pub executor_id: String,
+  /// 冪等キー。同じ実行者が同じキーで再送した場合は、操作を実行せずに最初の結果を返す
+  pub idempotency_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, InputObject)]
 pub struct AddMemberInput {
   pub group_chat_id: String,
   pub user_account_id: String,
   pub role: String,
   pub executor_id: String,
+  /// 冪等キー。同じ実行者が同じキーで再送した場合は、操作を実行せずに最初の結果を返す
+  pub idempotency_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, InputObject)]
+pub struct InviteMemberInput {
+  pub group_chat_id: String,
+  pub user_account_id: String,
+  pub role: String,
+  pub executor_id: String,
+  /// 冪等キー。同じ実行者が同じキーで再送した場合は、操作を実行せずに最初の結果を返す
+  pub idempotency_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, InputObject)]
+pub struct AcceptInvitationInput {
+  pub group_chat_id: String,
+  pub executor_id: String,
+  /// 冪等キー。同じ実行者が同じキーで再送した場合は、操作を実行せずに最初の結果を返す
+  pub idempotency_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, InputObject)]
+pub struct DeclineInvitationInput {
+  pub group_chat_id: String,
+  pub executor_id: String,
+  /// 冪等キー。同じ実行者が同じキーで再送した場合は、操作を実行せずに最初の結果を返す
+  pub idempotency_key: Option<String>,
 }
 
-#[derive(Debug, Clone, InputObject)]
+#[derive(Debug, Clone, Serialize, InputObject)]
+pub struct RevokeInvitationInput {
+  pub group_chat_id: String,
+  pub user_account_id: String,
+  pub executor_id: String,
+  /// 冪等キー。同じ実行者が同じキーで再送した場合は、操作を実行せずに最初の結果を返す
+  pub idempotency_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, InputObject)]
 pub struct RemoveMemberInput {
   pub group_chat_id: String,
   pub user_account_id: String,
   pub executor_id: String,
+  /// 冪等キー。同じ実行者が同じキーで再送した場合は、操作を実行せずに最初の結果を返す
+  pub idempotency_key: Option<String>,
+}
+
+/// `executor_id`が自分自身をグループチャットから退出させる。
+#[derive(Debug, Clone, Serialize, InputObject)]
+pub struct LeaveGroupChatInput {
+  pub group_chat_id: String,
+  pub executor_id: String,
+  /// 冪等キー。同じ実行者が同じキーで再送した場合は、操作を実行せずに最初の結果を返す
+  pub idempotency_key: Option<String>,
+}
+
+/// `user_account_id`に関するデータをすべてのグループチャットから消去する。`executor_id`は監査のために記録される。
+#[derive(Debug, Clone, Serialize, InputObject)]
+pub struct EraseUserAccountInput {
+  pub user_account_id: String,
+  pub executor_id: String,
+  /// 冪等キー。同じ実行者が同じキーで再送した場合は、操作を実行せずに最初の結果を返す
+  pub idempotency_key: Option<String>,
 }
 
-#[derive(Debug, Clone, InputObject)]
+/// メッセージの本文。`content`(テキスト)と`body`のどちらか一方を指定する。
+#[derive(Debug, Clone, Serialize, InputObject)]
 pub struct PostMessageInput {
   pub group_chat_id: String,
-  pub content: String,
+  /// メッセージID。省略した場合は生成する。再送時に同じIDを指定すると、重複した投稿はエラーになる
+  pub message_id: Option<String>,
+  pub content: Option<String>,
+  pub body: Option<MessageBodyInput>,
   pub executor_id: String,
+  /// 冪等キー。同じ実行者が同じキーで再送した場合は、操作を実行せずに最初の結果を返す
+  pub idempotency_key: Option<String>,
 }
 
-#[derive(Debug, Clone, InputObject)]
+/// メッセージの本文。`content`(テキスト)と`body`のどちらか一方を指定する。
+#[derive(Debug, Clone, Serialize, InputObject)]
 pub struct EditMessageInput {
   pub group_chat_id: String,
   pub message_id: String,
-  pub content: String,
+  pub content: Option<String>,
+  pub body: Option<MessageBodyInput>,
   pub executor_id: String,
+  /// 冪等キー。同じ実行者が同じキーで再送した場合は、操作を実行せずに最初の結果を返す
+  pub idempotency_key: Option<String>,
+}
+
+/// ユーザが投稿できるメッセージ本文。システムメッセージは投稿できない。
+#[derive(Debug, Clone, Serialize, OneofObject)]
+pub enum MessageBodyInput {
+  Text(String),
+  Attachment(AttachmentInput),
+  LinkPreview(LinkPreviewInput),
 }
 
-#[derive(Debug, Clone, InputObject)]
+#[derive(Debug, Clone, Serialize, InputObject)]
+pub struct AttachmentInput {
+  pub url: String,
+  pub mime: String,
+  pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize, InputObject)]
+pub struct LinkPreviewInput {
+  pub url: String,
+  pub title: Option<String>,
+  pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, InputObject)]
 pub struct DeleteMessageInput {
   pub group_chat_id: String,
   pub message_id: String,
   pub executor_id: String,
+  /// 冪等キー。同じ実行者が同じキーで再送した場合は、操作を実行せずに最初の結果を返す
+  pub idempotency_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, InputObject)]
+pub struct ModerateMessageInput {
+  pub group_chat_id: String,
+  pub message_id: String,
+  pub reason: String,
+  pub executor_id: String,
+  /// 冪等キー。同じ実行者が同じキーで再送した場合は、操作を実行せずに最初の結果を返す
+  pub idempotency_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, InputObject)]
+pub struct RestoreMessageInput {
+  pub group_chat_id: String,
+  pub message_id: String,
+  pub executor_id: String,
+  /// 冪等キー。同じ実行者が同じキーで再送した場合は、操作を実行せずに最初の結果を返す
+  pub idempotency_key: Option<String>,
+}
+
+/// 1つのグループチャットに複数のコマンドをまとめて適用する。`executor_id`がすべてのコマンドを実行する。
+///
+/// `group_chat_id`を省略した場合は新しいグループチャットを作成するので、先頭のコマンドに`createGroupChat`を指定する。
+/// いずれかのコマンドが失敗した場合は、どのコマンドも適用されない。
+#[derive(Debug, Clone, Serialize, InputObject)]
+pub struct BatchInput {
+  pub group_chat_id: Option<String>,
+  pub commands: Vec<BatchCommandInput>,
+  pub executor_id: String,
+  /// 冪等キー。同じ実行者が同じキーで再送した場合は、操作を実行せずに最初の結果を返す
+  pub idempotency_key: Option<String>,
+}
+
+/// バッチで適用するコマンド。いずれか1つを指定する。
+#[derive(Debug, Clone, Serialize, OneofObject)]
+pub enum BatchCommandInput {
+  CreateGroupChat(BatchGroupChatNameInput),
+  RenameGroupChat(BatchGroupChatNameInput),
+  UpdateSettings(BatchUpdateSettingsInput),
+  AddMember(BatchMemberInput),
+  InviteMember(BatchMemberInput),
+  /// 招待を取り消すユーザアカウントID
+  RevokeInvitation(String),
+  /// 削除するメンバーのユーザアカウントID
+  RemoveMember(String),
+  PostMessage(BatchPostMessageInput),
+  EditMessage(BatchEditMessageInput),
+  /// 削除するメッセージID
+  DeleteMessage(String),
+  ModerateMessage(BatchModerateMessageInput),
+  /// 復元するメッセージID
+  RestoreMessage(String),
+}
+
+#[derive(Debug, Clone, Serialize, InputObject)]
+pub struct BatchGroupChatNameInput {
+  pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, InputObject)]
+pub struct BatchUpdateSettingsInput {
+  pub max_members: u32,
+  pub post_permission: String,
+  pub add_member_permission: String,
+  pub message_retention_days: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, InputObject)]
+pub struct BatchMemberInput {
+  pub user_account_id: String,
+  pub role: String,
+}
+
+/// メッセージの本文。`content`(テキスト)と`body`のどちらか一方を指定する。
+#[derive(Debug, Clone, Serialize, InputObject)]
+pub struct BatchPostMessageInput {
+  /// メッセージID。省略した場合は生成する
+  pub message_id: Option<String>,
+  pub content: Option<String>,
+  pub body: Option<MessageBodyInput>,
+}
+
+/// メッセージの本文。`content`(テキスト)と`body`のどちらか一方を指定する。
+#[derive(Debug, Clone, Serialize, InputObject)]
+pub struct BatchEditMessageInput {
+  pub message_id: String,
+  pub content: Option<String>,
+  pub body: Option<MessageBodyInput>,
+}
+
+#[derive(Debug, Clone, Serialize, InputObject)]
+pub struct BatchModerateMessageInput {
+  pub message_id: String,
+  pub reason: String,
 }
@@ -1,6 +1,7 @@
 use async_graphql::SimpleObject;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, SimpleObject)]
+#[derive(Debug, Clone, PartialEq, SimpleObject, Serialize, Deserialize)]
 pub struct GroupChatOut {
   group_chat_id: String,
 }
@@ -11,7 +12,7 @@ impl GroupChatOut {
   }
 }
 
-#[derive(Debug, Clone, SimpleObject)]
+#[derive(Debug, Clone, PartialEq, SimpleObject, Serialize, Deserialize)]
 pub struct MessageOut {
   group_chat_id: String,
   message_id: String,
@@ -25,3 +26,35 @@ impl MessageOut {
     }
   }
 }
+
+#[derive(Debug, Clone, PartialEq, SimpleObject, Serialize, Deserialize)]
+pub struct UserAccountErasureOut {
+  user_account_id: String,
+  /// データを消去したグループチャットのID
+  group_chat_ids: Vec<String>,
+}
+
+impl UserAccountErasureOut {
+  pub fn new(user_account_id: String, group_chat_ids: Vec<String>) -> Self {
+    Self {
+      user_account_id,
+      group_chat_ids,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, SimpleObject, Serialize, Deserialize)]
+pub struct BatchOut {
+  group_chat_id: String,
+  /// バッチで投稿したメッセージのID(投稿順)
+  message_ids: Vec<String>,
+}
+
+impl BatchOut {
+  pub fn new(group_chat_id: String, message_ids: Vec<String>) -> Self {
+    Self {
+      group_chat_id,
+      message_ids,
+    }
+  }
+}
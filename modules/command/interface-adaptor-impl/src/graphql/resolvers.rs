@@ -1,19 +1,29 @@
-use async_graphql::{Context, Error, ErrorExtensions, FieldResult, Object};
-use event_store_adapter_rs::types::EventStoreWriteError;
+use async_graphql::{Context, Error, FieldResult, Object};
+use infrastructure::error::{ApiError, ErrorCode};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
 use std::str::FromStr;
 
-use command_domain::group_chat::{GroupChatId, GroupChatName, MemberRole, Message, MessageId};
+use command_domain::group_chat::{
+  GroupChatId, GroupChatName, GroupChatSettings, MemberRole, Message, MessageBody, MessageId, Permission,
+};
 use command_domain::user_account::UserAccountId;
-use command_interface_adaptor_if::GroupChatRepositoryError;
+use command_processor::group_chat_command::GroupChatCommand;
 use command_processor::group_chat_command_processor::CommandProcessError;
 
 use crate::gateways::group_chat_repository::GroupChatRepositoryImpl;
+use crate::graphql::errors::{
+  command_process_error, group_chat_name_error, group_chat_settings_error, internal_error, invalid_argument,
+  message_error, parse_error,
+};
 use crate::graphql::inputs::{
-  AddMemberInput, CreateGroupChatInput, DeleteGroupChatInput, DeleteMessageInput, EditMessageInput, PostMessageInput,
-  RemoveMemberInput, RenameGroupChatInput,
+  AcceptInvitationInput, AddMemberInput, BatchCommandInput, BatchInput, CreateGroupChatInput, DeclineInvitationInput,
+  DeleteGroupChatInput, DeleteMessageInput, EditMessageInput, EraseUserAccountInput, InviteMemberInput,
+  LeaveGroupChatInput, MessageBodyInput, ModerateMessageInput, OpenDirectChatInput, PostMessageInput,
+  RemoveMemberInput, RenameGroupChatInput, RestoreMessageInput, RevokeInvitationInput, UpdateSettingsInput,
 };
-use crate::graphql::outputs::{GroupChatOut, MessageOut};
-use crate::graphql::{MutationRoot, ServiceContext, ES};
+use crate::graphql::outputs::{BatchOut, GroupChatOut, MessageOut, UserAccountErasureOut};
+use crate::graphql::{IdempotencyKey, MutationRoot, ServiceContext, ES};
 
 #[Object]
 impl MutationRoot {
@@ -26,13 +36,55 @@ impl MutationRoot {
 
     let group_chat_name = validate_group_chat_name(&input.name)?;
     let executor_id = validate_user_account_id(&input.executor_id)?;
+    let idempotency_key = validate_idempotency_key(
+      "create_group_chat",
+      &executor_id,
+      input.idempotency_key.as_deref(),
+      &input,
+    )?;
 
     let mut processor = service_ctx.group_chat_command_processor.lock().await;
-    processor
-      .create_group_chat(group_chat_name, executor_id)
+    service_ctx
+      .execute_idempotently(idempotency_key, async {
+        processor
+          .handle(GroupChatCommand::CreateGroupChat {
+            group_chat_id: GroupChatId::new(),
+            name: group_chat_name,
+            executor_id,
+          })
+          .await
+          .map(|group_chat_id| GroupChatOut::new(group_chat_id.to_string()))
+          .map_err(error_handling)
+      })
+      .await
+  }
+
+  /// 1対1のチャットを開く。同じ2人のチャットが既に存在する場合はそのIDを返す。
+  async fn open_direct_chat<'ctx>(&self, ctx: &Context<'ctx>, input: OpenDirectChatInput) -> FieldResult<GroupChatOut> {
+    let service_ctx = ctx.data::<ServiceContext<GroupChatRepositoryImpl<ES>>>().unwrap();
+
+    let other_user_account_id = validate_user_account_id(&input.other_user_account_id)?;
+    let executor_id = validate_user_account_id(&input.executor_id)?;
+    let idempotency_key = validate_idempotency_key(
+      "open_direct_chat",
+      &executor_id,
+      input.idempotency_key.as_deref(),
+      &input,
+    )?;
+
+    let mut processor = service_ctx.group_chat_command_processor.lock().await;
+    service_ctx
+      .execute_idempotently(idempotency_key, async {
+        processor
+          .handle(GroupChatCommand::OpenDirectChat {
+            other_user_account_id,
+            executor_id,
+          })
+          .await
+          .map(|group_chat_id| GroupChatOut::new(group_chat_id.to_string()))
+          .map_err(error_handling)
+      })
       .await
-      .map(|group_chat_id| GroupChatOut::new(group_chat_id.to_string()))
-      .map_err(error_handling)
   }
 
   async fn delete_group_chat<'ctx>(
@@ -44,13 +96,26 @@ impl MutationRoot {
 
     let group_chat_id = validate_group_chat_id(&input.group_chat_id)?;
     let executor_id = validate_user_account_id(&input.executor_id)?;
+    let idempotency_key = validate_idempotency_key(
+      "delete_group_chat",
+      &executor_id,
+      input.idempotency_key.as_deref(),
+      &input,
+    )?;
 
     let mut processor = service_ctx.group_chat_command_processor.lock().await;
-    processor
-      .delete_group_chat(group_chat_id, executor_id)
+    service_ctx
+      .execute_idempotently(idempotency_key, async {
+        processor
+          .handle(GroupChatCommand::DeleteGroupChat {
+            group_chat_id,
+            executor_id,
+          })
+          .await
+          .map(|group_chat_id| GroupChatOut::new(group_chat_id.to_string()))
+          .map_err(error_handling)
+      })
       .await
-      .map(|group_chat_id| GroupChatOut::new(group_chat_id.to_string()))
-      .map_err(error_handling)
   }
 
   async fn rename_group_chat<'ctx>(
@@ -63,13 +128,62 @@ impl MutationRoot {
     let group_chat_id = validate_group_chat_id(&input.group_chat_id)?;
     let group_chat_name = validate_group_chat_name(&input.name)?;
     let executor_id = validate_user_account_id(&input.executor_id)?;
+    let idempotency_key = validate_idempotency_key(
+      "rename_group_chat",
+      &executor_id,
+      input.idempotency_key.as_deref(),
+      &input,
+    )?;
+
+    let mut processor = service_ctx.group_chat_command_processor.lock().await;
+    service_ctx
+      .execute_idempotently(idempotency_key, async {
+        processor
+          .handle(GroupChatCommand::RenameGroupChat {
+            group_chat_id,
+            name: group_chat_name,
+            executor_id,
+          })
+          .await
+          .map(|group_chat_id| GroupChatOut::new(group_chat_id.to_string()))
+          .map_err(error_handling)
+      })
+      .await
+  }
+
+  /// グループチャットの設定(メンバー数の上限、投稿・メンバー追加の権限、メッセージの保持日数)を更新する。
+  async fn update_settings<'ctx>(&self, ctx: &Context<'ctx>, input: UpdateSettingsInput) -> FieldResult<GroupChatOut> {
+    let service_ctx = ctx.data::<ServiceContext<GroupChatRepositoryImpl<ES>>>().unwrap();
+
+    let group_chat_id = validate_group_chat_id(&input.group_chat_id)?;
+    let settings = validate_settings(
+      input.max_members,
+      &input.post_permission,
+      &input.add_member_permission,
+      input.message_retention_days,
+    )?;
+    let executor_id = validate_user_account_id(&input.executor_id)?;
+    let idempotency_key = validate_idempotency_key(
+      "update_settings",
+      &executor_id,
+      input.idempotency_key.as_deref(),
+      &input,
+    )?;
 
     let mut processor = service_ctx.group_chat_command_processor.lock().await;
-    processor
-      .rename_group_chat(group_chat_id, group_chat_name, executor_id)
+    service_ctx
+      .execute_idempotently(idempotency_key, async {
+        processor
+          .handle(GroupChatCommand::UpdateSettings {
+            group_chat_id,
+            settings,
+            executor_id,
+          })
+          .await
+          .map(|group_chat_id| GroupChatOut::new(group_chat_id.to_string()))
+          .map_err(error_handling)
+      })
       .await
-      .map(|group_chat_id| GroupChatOut::new(group_chat_id.to_string()))
-      .map_err(error_handling)
   }
 
   async fn add_member<'ctx>(&self, ctx: &Context<'ctx>, input: AddMemberInput) -> FieldResult<GroupChatOut> {
@@ -79,13 +193,146 @@ impl MutationRoot {
     let user_account_id = validate_user_account_id(&input.user_account_id)?;
     let role = validate_member_role(&input.role)?;
     let executor_id = validate_user_account_id(&input.executor_id)?;
+    let idempotency_key =
+      validate_idempotency_key("add_member", &executor_id, input.idempotency_key.as_deref(), &input)?;
+
+    let mut processor = service_ctx.group_chat_command_processor.lock().await;
+    service_ctx
+      .execute_idempotently(idempotency_key, async {
+        processor
+          .handle(GroupChatCommand::AddMember {
+            group_chat_id,
+            user_account_id,
+            role,
+            executor_id,
+          })
+          .await
+          .map(|group_chat_id| GroupChatOut::new(group_chat_id.to_string()))
+          .map_err(error_handling)
+      })
+      .await
+  }
+
+  async fn invite_member<'ctx>(&self, ctx: &Context<'ctx>, input: InviteMemberInput) -> FieldResult<GroupChatOut> {
+    let service_ctx = ctx.data::<ServiceContext<GroupChatRepositoryImpl<ES>>>().unwrap();
+
+    let group_chat_id = validate_group_chat_id(&input.group_chat_id)?;
+    let user_account_id = validate_user_account_id(&input.user_account_id)?;
+    let role = validate_member_role(&input.role)?;
+    let executor_id = validate_user_account_id(&input.executor_id)?;
+    let idempotency_key =
+      validate_idempotency_key("invite_member", &executor_id, input.idempotency_key.as_deref(), &input)?;
+
+    let mut processor = service_ctx.group_chat_command_processor.lock().await;
+    service_ctx
+      .execute_idempotently(idempotency_key, async {
+        processor
+          .handle(GroupChatCommand::InviteMember {
+            group_chat_id,
+            user_account_id,
+            role,
+            executor_id,
+          })
+          .await
+          .map(|group_chat_id| GroupChatOut::new(group_chat_id.to_string()))
+          .map_err(error_handling)
+      })
+      .await
+  }
+
+  async fn accept_invitation<'ctx>(
+    &self,
+    ctx: &Context<'ctx>,
+    input: AcceptInvitationInput,
+  ) -> FieldResult<GroupChatOut> {
+    let service_ctx = ctx.data::<ServiceContext<GroupChatRepositoryImpl<ES>>>().unwrap();
+
+    let group_chat_id = validate_group_chat_id(&input.group_chat_id)?;
+    let executor_id = validate_user_account_id(&input.executor_id)?;
+    let idempotency_key = validate_idempotency_key(
+      "accept_invitation",
+      &executor_id,
+      input.idempotency_key.as_deref(),
+      &input,
+    )?;
+
+    let mut processor = service_ctx.group_chat_command_processor.lock().await;
+    service_ctx
+      .execute_idempotently(idempotency_key, async {
+        processor
+          .handle(GroupChatCommand::AcceptInvitation {
+            group_chat_id,
+            executor_id,
+          })
+          .await
+          .map(|group_chat_id| GroupChatOut::new(group_chat_id.to_string()))
+          .map_err(error_handling)
+      })
+      .await
+  }
+
+  async fn decline_invitation<'ctx>(
+    &self,
+    ctx: &Context<'ctx>,
+    input: DeclineInvitationInput,
+  ) -> FieldResult<GroupChatOut> {
+    let service_ctx = ctx.data::<ServiceContext<GroupChatRepositoryImpl<ES>>>().unwrap();
+
+    let group_chat_id = validate_group_chat_id(&input.group_chat_id)?;
+    let executor_id = validate_user_account_id(&input.executor_id)?;
+    let idempotency_key = validate_idempotency_key(
+      "decline_invitation",
+      &executor_id,
+      input.idempotency_key.as_deref(),
+      &input,
+    )?;
+
+    let mut processor = service_ctx.group_chat_command_processor.lock().await;
+    service_ctx
+      .execute_idempotently(idempotency_key, async {
+        processor
+          .handle(GroupChatCommand::DeclineInvitation {
+            group_chat_id,
+            executor_id,
+          })
+          .await
+          .map(|group_chat_id| GroupChatOut::new(group_chat_id.to_string()))
+          .map_err(error_handling)
+      })
+      .await
+  }
+
+  async fn revoke_invitation<'ctx>(
+    &self,
+    ctx: &Context<'ctx>,
+    input: RevokeInvitationInput,
+  ) -> FieldResult<GroupChatOut> {
+    let service_ctx = ctx.data::<ServiceContext<GroupChatRepositoryImpl<ES>>>().unwrap();
+
+    let group_chat_id = validate_group_chat_id(&input.group_chat_id)?;
+    let user_account_id = validate_user_account_id(&input.user_account_id)?;
+    let executor_id = validate_user_account_id(&input.executor_id)?;
+    let idempotency_key = validate_idempotency_key(
+      "revoke_invitation",
+      &executor_id,
+      input.idempotency_key.as_deref(),
+      &input,
+    )?;
 
     let mut processor = service_ctx.group_chat_command_processor.lock().await;
-    processor
-      .add_member(group_chat_id, user_account_id, role, executor_id)
+    service_ctx
+      .execute_idempotently(idempotency_key, async {
+        processor
+          .handle(GroupChatCommand::RevokeInvitation {
+            group_chat_id,
+            user_account_id,
+            executor_id,
+          })
+          .await
+          .map(|group_chat_id| GroupChatOut::new(group_chat_id.to_string()))
+          .map_err(error_handling)
+      })
       .await
-      .map(|group_chat_id| GroupChatOut::new(group_chat_id.to_string()))
-      .map_err(error_handling)
   }
 
   async fn remove_member<'ctx>(&self, ctx: &Context<'ctx>, input: RemoveMemberInput) -> FieldResult<GroupChatOut> {
@@ -94,14 +341,51 @@ impl MutationRoot {
     let group_chat_id = validate_group_chat_id(&input.group_chat_id)?;
     let user_account_id = validate_user_account_id(&input.user_account_id)?;
     let executor_id = validate_user_account_id(&input.executor_id)?;
+    let idempotency_key =
+      validate_idempotency_key("remove_member", &executor_id, input.idempotency_key.as_deref(), &input)?;
 
     let mut processor = service_ctx.group_chat_command_processor.lock().await;
+    service_ctx
+      .execute_idempotently(idempotency_key, async {
+        processor
+          .handle(GroupChatCommand::RemoveMember {
+            group_chat_id,
+            user_account_id,
+            executor_id,
+          })
+          .await
+          .map(|group_chat_id| GroupChatOut::new(group_chat_id.to_string()))
+          .map_err(error_handling)
+      })
+      .await
+  }
 
-    processor
-      .remove_member(group_chat_id, user_account_id, executor_id)
+  /// グループチャットから退出する。最後の管理者の場合は設定に従って拒否されるかグループチャットが削除される。
+  async fn leave_group_chat<'ctx>(&self, ctx: &Context<'ctx>, input: LeaveGroupChatInput) -> FieldResult<GroupChatOut> {
+    let service_ctx = ctx.data::<ServiceContext<GroupChatRepositoryImpl<ES>>>().unwrap();
+
+    let group_chat_id = validate_group_chat_id(&input.group_chat_id)?;
+    let executor_id = validate_user_account_id(&input.executor_id)?;
+    let idempotency_key = validate_idempotency_key(
+      "leave_group_chat",
+      &executor_id,
+      input.idempotency_key.as_deref(),
+      &input,
+    )?;
+
+    let mut processor = service_ctx.group_chat_command_processor.lock().await;
+    service_ctx
+      .execute_idempotently(idempotency_key, async {
+        processor
+          .handle(GroupChatCommand::LeaveGroupChat {
+            group_chat_id,
+            executor_id,
+          })
+          .await
+          .map(|group_chat_id| GroupChatOut::new(group_chat_id.to_string()))
+          .map_err(error_handling)
+      })
       .await
-      .map(|group_chat_id| GroupChatOut::new(group_chat_id.to_string()))
-      .map_err(error_handling)
   }
 
   async fn post_message<'ctx>(&self, ctx: &Context<'ctx>, input: PostMessageInput) -> FieldResult<MessageOut> {
@@ -109,14 +393,28 @@ impl MutationRoot {
 
     let group_chat_id = validate_group_chat_id(&input.group_chat_id)?;
     let executor_id = validate_user_account_id(&input.executor_id)?;
-    let message = validate_message(&input.content, MessageId::new(), executor_id.clone())?;
+    let message_id = match input.message_id.as_deref() {
+      Some(message_id) => validate_message_id(message_id)?,
+      None => MessageId::new(),
+    };
+    let idempotency_key =
+      validate_idempotency_key("post_message", &executor_id, input.idempotency_key.as_deref(), &input)?;
+    let message = validate_message(input.content, input.body, message_id.clone(), executor_id.clone())?;
 
     let mut processor = service_ctx.group_chat_command_processor.lock().await;
-    processor
-      .post_message(group_chat_id, message, executor_id)
+    service_ctx
+      .execute_idempotently(idempotency_key, async {
+        processor
+          .handle(GroupChatCommand::PostMessage {
+            group_chat_id,
+            message,
+            executor_id,
+          })
+          .await
+          .map(|group_chat_id| MessageOut::new(group_chat_id.to_string(), message_id.to_string()))
+          .map_err(error_handling)
+      })
       .await
-      .map(|(group_chat_id, message_id)| MessageOut::new(group_chat_id.to_string(), message_id.to_string()))
-      .map_err(error_handling)
   }
 
   async fn edit_message<'ctx>(&self, ctx: &Context<'ctx>, input: EditMessageInput) -> FieldResult<GroupChatOut> {
@@ -125,14 +423,24 @@ impl MutationRoot {
     let group_chat_id = validate_group_chat_id(&input.group_chat_id)?;
     let executor_id = validate_user_account_id(&input.executor_id)?;
     let message_id = validate_message_id(&input.message_id)?;
-    let message = validate_message(&input.content, message_id, executor_id.clone())?;
+    let idempotency_key =
+      validate_idempotency_key("edit_message", &executor_id, input.idempotency_key.as_deref(), &input)?;
+    let message = validate_message(input.content, input.body, message_id, executor_id.clone())?;
 
     let mut processor = service_ctx.group_chat_command_processor.lock().await;
-    processor
-      .edit_message(group_chat_id, message, executor_id)
+    service_ctx
+      .execute_idempotently(idempotency_key, async {
+        processor
+          .handle(GroupChatCommand::EditMessage {
+            group_chat_id,
+            message,
+            executor_id,
+          })
+          .await
+          .map(|group_chat_id| GroupChatOut::new(group_chat_id.to_string()))
+          .map_err(error_handling)
+      })
       .await
-      .map(|group_chat_id| GroupChatOut::new(group_chat_id.to_string()))
-      .map_err(error_handling)
   }
 
   async fn delete_message<'ctx>(&self, ctx: &Context<'ctx>, input: DeleteMessageInput) -> FieldResult<GroupChatOut> {
@@ -141,63 +449,372 @@ impl MutationRoot {
     let group_chat_id = validate_group_chat_id(&input.group_chat_id)?;
     let message_id = validate_message_id(&input.message_id)?;
     let executor_id = validate_user_account_id(&input.executor_id)?;
+    let idempotency_key =
+      validate_idempotency_key("delete_message", &executor_id, input.idempotency_key.as_deref(), &input)?;
 
     let mut processor = service_ctx.group_chat_command_processor.lock().await;
-    processor
-      .delete_message(group_chat_id, message_id, executor_id)
+    service_ctx
+      .execute_idempotently(idempotency_key, async {
+        processor
+          .handle(GroupChatCommand::DeleteMessage {
+            group_chat_id,
+            message_id,
+            executor_id,
+          })
+          .await
+          .map(|group_chat_id| GroupChatOut::new(group_chat_id.to_string()))
+          .map_err(error_handling)
+      })
       .await
-      .map(|group_chat_id| GroupChatOut::new(group_chat_id.to_string()))
-      .map_err(error_handling)
   }
-}
 
-fn error_handling_repository_error(error: &CommandProcessError, cause: &GroupChatRepositoryError) -> Error {
-  match cause {
-    GroupChatRepositoryError::StoreError(_, EventStoreWriteError::OptimisticLockError(_)) => {
-      Error::new(error.to_string())
-        .extend_with(|_, e| e.set("code", "409"))
-        .extend_with(|_, e| e.set("cause", cause.to_string()))
-    }
-    GroupChatRepositoryError::StoreError(_, _) => Error::new(error.to_string())
-      .extend_with(|_, e| e.set("code", "500"))
-      .extend_with(|_, e| e.set("cause", cause.to_string())),
-    GroupChatRepositoryError::FindByIdError(_, _) => Error::new(error.to_string())
-      .extend_with(|_, e| e.set("code", "500"))
-      .extend_with(|_, e| e.set("cause", cause.to_string())),
+  async fn moderate_message<'ctx>(
+    &self,
+    ctx: &Context<'ctx>,
+    input: ModerateMessageInput,
+  ) -> FieldResult<GroupChatOut> {
+    let service_ctx = ctx.data::<ServiceContext<GroupChatRepositoryImpl<ES>>>().unwrap();
+
+    let group_chat_id = validate_group_chat_id(&input.group_chat_id)?;
+    let message_id = validate_message_id(&input.message_id)?;
+    let reason = validate_moderation_reason(&input.reason)?;
+    let executor_id = validate_user_account_id(&input.executor_id)?;
+    let idempotency_key = validate_idempotency_key(
+      "moderate_message",
+      &executor_id,
+      input.idempotency_key.as_deref(),
+      &input,
+    )?;
+
+    let mut processor = service_ctx.group_chat_command_processor.lock().await;
+    service_ctx
+      .execute_idempotently(idempotency_key, async {
+        processor
+          .handle(GroupChatCommand::ModerateMessage {
+            group_chat_id,
+            message_id,
+            reason,
+            executor_id,
+          })
+          .await
+          .map(|group_chat_id| GroupChatOut::new(group_chat_id.to_string()))
+          .map_err(error_handling)
+      })
+      .await
   }
-}
 
-fn error_handling(error: CommandProcessError) -> Error {
-  match error {
-    CommandProcessError::DomainLogicError(ref cause) => Error::new(error.to_string())
-      .extend_with(|_, e| e.set("code", "422"))
-      .extend_with(|_, e| e.set("cause", cause.to_string())),
-    CommandProcessError::NotFoundError => Error::new(error.to_string()).extend_with(|_, e| e.set("code", "404")),
-    CommandProcessError::RepositoryError(ref cause) => error_handling_repository_error(&error, cause),
+  async fn restore_message<'ctx>(&self, ctx: &Context<'ctx>, input: RestoreMessageInput) -> FieldResult<GroupChatOut> {
+    let service_ctx = ctx.data::<ServiceContext<GroupChatRepositoryImpl<ES>>>().unwrap();
+
+    let group_chat_id = validate_group_chat_id(&input.group_chat_id)?;
+    let message_id = validate_message_id(&input.message_id)?;
+    let executor_id = validate_user_account_id(&input.executor_id)?;
+    let idempotency_key = validate_idempotency_key(
+      "restore_message",
+      &executor_id,
+      input.idempotency_key.as_deref(),
+      &input,
+    )?;
+
+    let mut processor = service_ctx.group_chat_command_processor.lock().await;
+    service_ctx
+      .execute_idempotently(idempotency_key, async {
+        processor
+          .handle(GroupChatCommand::RestoreMessage {
+            group_chat_id,
+            message_id,
+            executor_id,
+          })
+          .await
+          .map(|group_chat_id| GroupChatOut::new(group_chat_id.to_string()))
+          .map_err(error_handling)
+      })
+      .await
+  }
+
+  /// 1つのグループチャットに複数のコマンドをまとめて適用する。
+  ///
+  /// グループチャットを一度だけ読み込み、すべてのコマンドで発生したイベントを1つのトランザクションで保存する。
+  /// いずれかのコマンドが失敗した場合は、どのコマンドも適用されない。
+  async fn batch<'ctx>(&self, ctx: &Context<'ctx>, input: BatchInput) -> FieldResult<BatchOut> {
+    let service_ctx = ctx.data::<ServiceContext<GroupChatRepositoryImpl<ES>>>().unwrap();
+
+    let group_chat_id = match input.group_chat_id.as_deref() {
+      Some(group_chat_id) => validate_group_chat_id(group_chat_id)?,
+      None => GroupChatId::new(),
+    };
+    let executor_id = validate_user_account_id(&input.executor_id)?;
+    let idempotency_key = validate_idempotency_key("batch", &executor_id, input.idempotency_key.as_deref(), &input)?;
+    let commands = input
+      .commands
+      .into_iter()
+      .map(|command| validate_batch_command(command, &group_chat_id, &executor_id))
+      .collect::<Result<Vec<_>, _>>()?;
+    let message_ids = commands
+      .iter()
+      .filter_map(|command| match command {
+        GroupChatCommand::PostMessage { message, .. } => Some(message.breach_encapsulation_of_id().to_string()),
+        _ => None,
+      })
+      .collect::<Vec<_>>();
+
+    let mut processor = service_ctx.group_chat_command_processor.lock().await;
+    service_ctx
+      .execute_idempotently(idempotency_key, async {
+        processor
+          .execute_batch(group_chat_id, commands)
+          .await
+          .map(|group_chat_id| BatchOut::new(group_chat_id.to_string(), message_ids))
+          .map_err(error_handling)
+      })
+      .await
+  }
+
+  /// ユーザアカウントに関するデータを、関与しているすべてのグループチャットから消去する。
+  ///
+  /// メンバーから外し、招待を取り消し、送信したメッセージの本文を消去する。
+  /// メッセージの本文を暗号化している場合は、ユーザアカウントの鍵も削除する(クリプトシュレッディング)。
+  async fn erase_user_account<'ctx>(
+    &self,
+    ctx: &Context<'ctx>,
+    input: EraseUserAccountInput,
+  ) -> FieldResult<UserAccountErasureOut> {
+    let service_ctx = ctx.data::<ServiceContext<GroupChatRepositoryImpl<ES>>>().unwrap();
+
+    let user_account_id = validate_user_account_id(&input.user_account_id)?;
+    let executor_id = validate_user_account_id(&input.executor_id)?;
+    let idempotency_key = validate_idempotency_key(
+      "erase_user_account",
+      &executor_id,
+      input.idempotency_key.as_deref(),
+      &input,
+    )?;
+
+    let group_chat_ids = service_ctx
+      .user_account_group_chat_dao
+      .get_group_chat_ids(&user_account_id)
+      .await
+      .map_err(|error| Error::from(internal_error(&error)))?;
+
+    let mut processor = service_ctx.group_chat_command_processor.lock().await;
+    service_ctx
+      .execute_idempotently(idempotency_key, async {
+        processor
+          .erase_user_account(user_account_id.clone(), group_chat_ids, executor_id)
+          .await
+          .map(|group_chat_ids| {
+            UserAccountErasureOut::new(
+              user_account_id.to_string(),
+              group_chat_ids.iter().map(|id| id.to_string()).collect(),
+            )
+          })
+          .map_err(error_handling)
+      })
+      .await
   }
 }
 
-fn validate_group_chat_id(value: &str) -> Result<GroupChatId, Error> {
-  GroupChatId::from_str(value).map_err(|error| Error::new(error.to_string()).extend_with(|_, e| e.set("code", "400")))
+/// [CommandProcessError]を、[ErrorCode]を`code`拡張に持つエラーに変換する。
+pub(crate) fn error_handling(error: CommandProcessError) -> Error {
+  command_process_error(&error).into()
 }
 
-fn validate_group_chat_name(value: &str) -> Result<GroupChatName, Error> {
-  GroupChatName::from_str(value).map_err(|error| Error::new(error.to_string()).extend_with(|_, e| e.set("code", "400")))
+pub(crate) fn validate_group_chat_id(value: &str) -> Result<GroupChatId, Error> {
+  GroupChatId::from_str(value).map_err(|error| parse_error(&error, "groupChatId", value).into())
 }
 
-fn validate_member_role(value: &str) -> Result<MemberRole, Error> {
-  MemberRole::from_str(value).map_err(|error| Error::new(error.to_string()).extend_with(|_, e| e.set("code", "400")))
+pub(crate) fn validate_group_chat_name(value: &str) -> Result<GroupChatName, Error> {
+  GroupChatName::from_str(value).map_err(|error| group_chat_name_error(&error).into())
 }
 
-fn validate_message_id(value: &str) -> Result<MessageId, Error> {
-  MessageId::from_str(value).map_err(|error| Error::new(error.to_string()).extend_with(|_, e| e.set("code", "400")))
+pub(crate) fn validate_member_role(value: &str) -> Result<MemberRole, Error> {
+  MemberRole::from_str(value).map_err(|error| parse_error(&error, "role", value).into())
 }
 
-fn validate_message(value: &str, message_id: MessageId, sender_id: UserAccountId) -> Result<Message, Error> {
-  Message::validate(&value, message_id, sender_id.clone())
-    .map_err(|error| Error::new(error.to_string()).extend_with(|_, e| e.set("code", "400")))
+pub(crate) fn validate_settings(
+  max_members: u32,
+  post_permission: &str,
+  add_member_permission: &str,
+  message_retention_days: Option<u32>,
+) -> Result<GroupChatSettings, Error> {
+  let post_permission = Permission::from_str(post_permission)
+    .map_err(|error| Error::from(parse_error(&error, "postPermission", post_permission)))?;
+  let add_member_permission = Permission::from_str(add_member_permission)
+    .map_err(|error| Error::from(parse_error(&error, "addMemberPermission", add_member_permission)))?;
+  GroupChatSettings::new(
+    max_members as usize,
+    post_permission,
+    add_member_permission,
+    message_retention_days,
+  )
+  .map_err(|error| group_chat_settings_error(&error).into())
+}
+
+pub(crate) fn validate_message_id(value: &str) -> Result<MessageId, Error> {
+  MessageId::from_str(value).map_err(|error| parse_error(&error, "messageId", value).into())
+}
+
+pub(crate) fn validate_message(
+  content: Option<String>,
+  body: Option<MessageBodyInput>,
+  message_id: MessageId,
+  sender_id: UserAccountId,
+) -> Result<Message, Error> {
+  let body = match (content, body) {
+    (Some(text), None) | (None, Some(MessageBodyInput::Text(text))) => MessageBody::Text { text },
+    (None, Some(MessageBodyInput::Attachment(input))) => MessageBody::Attachment {
+      url: input.url,
+      mime: input.mime,
+      size: input.size,
+    },
+    (None, Some(MessageBodyInput::LinkPreview(input))) => MessageBody::LinkPreview {
+      url: input.url,
+      title: input.title,
+      description: input.description,
+    },
+    _ => return Err(invalid_argument("body", "exactly one of content or body must be specified").into()),
+  };
+  Message::validate_body(body, message_id, sender_id).map_err(|error| message_error(&error).into())
+}
+
+pub(crate) fn validate_moderation_reason(value: &str) -> Result<String, Error> {
+  if value.trim().is_empty() {
+    return Err(ApiError::new(ErrorCode::ModerationReasonEmpty, "the moderation reason is empty").into());
+  }
+  if value.chars().count() > 255 {
+    return Err(invalid_argument("reason", "the moderation reason is too long").into());
+  }
+  Ok(value.to_string())
+}
+
+/// バッチのコマンドを検証し、`executor_id`が`group_chat_id`に対して実行する[GroupChatCommand]に変換する。
+pub(crate) fn validate_batch_command(
+  command: BatchCommandInput,
+  group_chat_id: &GroupChatId,
+  executor_id: &UserAccountId,
+) -> Result<GroupChatCommand, Error> {
+  let executor_id = executor_id.clone();
+  let command = match command {
+    BatchCommandInput::CreateGroupChat(input) => GroupChatCommand::CreateGroupChat {
+      group_chat_id: group_chat_id.clone(),
+      name: validate_group_chat_name(&input.name)?,
+      executor_id,
+    },
+    BatchCommandInput::RenameGroupChat(input) => GroupChatCommand::RenameGroupChat {
+      group_chat_id: group_chat_id.clone(),
+      name: validate_group_chat_name(&input.name)?,
+      executor_id,
+    },
+    BatchCommandInput::UpdateSettings(input) => GroupChatCommand::UpdateSettings {
+      group_chat_id: group_chat_id.clone(),
+      settings: validate_settings(
+        input.max_members,
+        &input.post_permission,
+        &input.add_member_permission,
+        input.message_retention_days,
+      )?,
+      executor_id,
+    },
+    BatchCommandInput::AddMember(input) => GroupChatCommand::AddMember {
+      group_chat_id: group_chat_id.clone(),
+      user_account_id: validate_user_account_id(&input.user_account_id)?,
+      role: validate_member_role(&input.role)?,
+      executor_id,
+    },
+    BatchCommandInput::InviteMember(input) => GroupChatCommand::InviteMember {
+      group_chat_id: group_chat_id.clone(),
+      user_account_id: validate_user_account_id(&input.user_account_id)?,
+      role: validate_member_role(&input.role)?,
+      executor_id,
+    },
+    BatchCommandInput::RevokeInvitation(user_account_id) => GroupChatCommand::RevokeInvitation {
+      group_chat_id: group_chat_id.clone(),
+      user_account_id: validate_user_account_id(&user_account_id)?,
+      executor_id,
+    },
+    BatchCommandInput::RemoveMember(user_account_id) => GroupChatCommand::RemoveMember {
+      group_chat_id: group_chat_id.clone(),
+      user_account_id: validate_user_account_id(&user_account_id)?,
+      executor_id,
+    },
+    BatchCommandInput::PostMessage(input) => {
+      let message_id = match input.message_id.as_deref() {
+        Some(message_id) => validate_message_id(message_id)?,
+        None => MessageId::new(),
+      };
+      GroupChatCommand::PostMessage {
+        group_chat_id: group_chat_id.clone(),
+        message: validate_message(input.content, input.body, message_id, executor_id.clone())?,
+        executor_id,
+      }
+    }
+    BatchCommandInput::EditMessage(input) => {
+      let message_id = validate_message_id(&input.message_id)?;
+      GroupChatCommand::EditMessage {
+        group_chat_id: group_chat_id.clone(),
+        message: validate_message(input.content, input.body, message_id, executor_id.clone())?,
+        executor_id,
+      }
+    }
+    BatchCommandInput::DeleteMessage(message_id) => GroupChatCommand::DeleteMessage {
+      group_chat_id: group_chat_id.clone(),
+      message_id: validate_message_id(&message_id)?,
+      executor_id,
+    },
+    BatchCommandInput::ModerateMessage(input) => GroupChatCommand::ModerateMessage {
+      group_chat_id: group_chat_id.clone(),
+      message_id: validate_message_id(&input.message_id)?,
+      reason: validate_moderation_reason(&input.reason)?,
+      executor_id,
+    },
+    BatchCommandInput::RestoreMessage(message_id) => GroupChatCommand::RestoreMessage {
+      group_chat_id: group_chat_id.clone(),
+      message_id: validate_message_id(&message_id)?,
+      executor_id,
+    },
+  };
+  Ok(command)
+}
+
+/// 冪等キーを検証し、操作名と実行者で修飾する。
+///
+/// NOTE: 異なる操作や実行者の間で冪等キーが衝突しても、別の操作の結果を返さないように修飾する。
+/// 同じキーを異なる入力で再利用した場合に検出できるように、操作名と入力のハッシュをフィンガープリントとする。
+///
+/// # 引数
+/// - `operation` - 操作名
+/// - `executor_id` - 実行者のユーザアカウントID
+/// - `value` - クライアントが指定した冪等キー
+/// - `input` - ミューテーションの入力
+///
+/// # 戻り値
+/// - 冪等キーを指定していない場合は`None`を返します。
+pub(crate) fn validate_idempotency_key<I: Serialize>(
+  operation: &str,
+  executor_id: &UserAccountId,
+  value: Option<&str>,
+  input: &I,
+) -> Result<Option<IdempotencyKey>, Error> {
+  let Some(value) = value else {
+    return Ok(None);
+  };
+  if value.trim().is_empty() {
+    return Err(invalid_argument("idempotencyKey", "the idempotency key is empty").into());
+  }
+  if value.chars().count() > 255 {
+    return Err(invalid_argument("idempotencyKey", "the idempotency key is too long").into());
+  }
+  let input = serde_json::to_vec(input).map_err(|error| Error::from(internal_error(&error)))?;
+  let mut hasher = Sha256::new();
+  hasher.update(operation.as_bytes());
+  hasher.update(b":");
+  hasher.update(&input);
+  Ok(Some(IdempotencyKey {
+    key: format!("{}:{}:{}", operation, executor_id, value),
+    fingerprint: format!("{:x}", hasher.finalize()),
+  }))
 }
 
-fn validate_user_account_id(value: &str) -> Result<UserAccountId, Error> {
-  UserAccountId::from_str(value).map_err(|error| Error::new(error.to_string()).extend_with(|_, e| e.set("code", "400")))
+pub(crate) fn validate_user_account_id(value: &str) -> Result<UserAccountId, Error> {
+  UserAccountId::from_str(value).map_err(|error| parse_error(&error, "userAccountId", value).into())
 }
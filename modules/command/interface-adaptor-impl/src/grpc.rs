@@ -0,0 +1,757 @@
+use std::sync::Arc;
+
+use infrastructure::grpc::status_from_graphql_error;
+use tonic::{Request, Response, Status};
+
+use command_domain::group_chat::{GroupChatId, MessageId};
+use command_interface_adaptor_if::{IdempotencyStore, UserAccountGroupChatDao};
+use command_processor::group_chat_command::GroupChatCommand;
+
+use crate::graphql::execute_idempotently;
+use crate::graphql::inputs::{AttachmentInput, LinkPreviewInput, MessageBodyInput};
+use crate::graphql::resolvers::{
+  error_handling, validate_group_chat_id, validate_group_chat_name, validate_idempotency_key, validate_member_role,
+  validate_message, validate_message_id, validate_moderation_reason, validate_settings, validate_user_account_id,
+};
+use crate::rest::SharedGroupChatCommandProcessor;
+
+use proto::group_chat_command_service_server::{GroupChatCommandService, GroupChatCommandServiceServer};
+use proto::{
+  message_body, AcceptInvitationRequest, AddMemberRequest, CreateGroupChatRequest, DeclineInvitationRequest,
+  DeleteGroupChatRequest, DeleteMessageRequest, EditMessageRequest, EraseUserAccountRequest, GroupChatReply,
+  InviteMemberRequest, LeaveGroupChatRequest, MessageBody, MessageReply, ModerateMessageRequest, OpenDirectChatRequest,
+  PostMessageRequest, RemoveMemberRequest, RenameGroupChatRequest, RestoreMessageRequest, RevokeInvitationRequest,
+  UpdateSettingsRequest, UserAccountErasureReply,
+};
+
+/// `proto/group_chat/command/v1/group_chat_command.proto`から生成したコード
+pub mod proto {
+  tonic::include_proto!("group_chat.command.v1");
+}
+
+/// GraphQLのMutationRootと同じ操作を提供するgRPCサービス。
+///
+/// NOTE: 入力値の検証・冪等キーの扱い・エラーハンドリングはGraphQLのリゾルバと共通にし、
+/// エラーは`code`拡張に対応するステータスコードで返す。
+pub struct GroupChatCommandGrpcService {
+  processor: SharedGroupChatCommandProcessor,
+  user_account_group_chat_dao: Arc<dyn UserAccountGroupChatDao>,
+  idempotency_store: Arc<dyn IdempotencyStore>,
+}
+
+impl GroupChatCommandGrpcService {
+  /// コンストラクタ
+  ///
+  /// # 引数
+  /// - `processor` - GraphQLのリゾルバと共有する[SharedGroupChatCommandProcessor]
+  /// - `user_account_group_chat_dao` - [UserAccountGroupChatDao]
+  /// - `idempotency_store` - GraphQLのリゾルバと共有する[IdempotencyStore]
+  pub fn new(
+    processor: SharedGroupChatCommandProcessor,
+    user_account_group_chat_dao: Arc<dyn UserAccountGroupChatDao>,
+    idempotency_store: Arc<dyn IdempotencyStore>,
+  ) -> Self {
+    Self {
+      processor,
+      user_account_group_chat_dao,
+      idempotency_store,
+    }
+  }
+
+  /// gRPCサーバに登録するサービスを生成する。
+  pub fn into_server(self) -> GroupChatCommandServiceServer<Self> {
+    GroupChatCommandServiceServer::new(self)
+  }
+}
+
+fn group_chat_reply(group_chat_id: GroupChatId) -> GroupChatReply {
+  GroupChatReply {
+    group_chat_id: group_chat_id.to_string(),
+  }
+}
+
+fn message_body_input(body: Option<MessageBody>) -> Option<MessageBodyInput> {
+  match body.and_then(|body| body.body)? {
+    message_body::Body::Text(text) => Some(MessageBodyInput::Text(text)),
+    message_body::Body::Attachment(attachment) => Some(MessageBodyInput::Attachment(AttachmentInput {
+      url: attachment.url,
+      mime: attachment.mime,
+      size: attachment.size,
+    })),
+    message_body::Body::LinkPreview(link_preview) => Some(MessageBodyInput::LinkPreview(LinkPreviewInput {
+      url: link_preview.url,
+      title: link_preview.title,
+      description: link_preview.description,
+    })),
+  }
+}
+
+#[tonic::async_trait]
+impl GroupChatCommandService for GroupChatCommandGrpcService {
+  async fn create_group_chat(
+    &self,
+    request: Request<CreateGroupChatRequest>,
+  ) -> Result<Response<GroupChatReply>, Status> {
+    let request = request.into_inner();
+    let group_chat_name = validate_group_chat_name(&request.name).map_err(status_from_graphql_error)?;
+    let executor_id = validate_user_account_id(&request.executor_id).map_err(status_from_graphql_error)?;
+    let idempotency_key = validate_idempotency_key(
+      "create_group_chat",
+      &executor_id,
+      request.idempotency_key.as_deref(),
+      &request,
+    )
+    .map_err(status_from_graphql_error)?;
+
+    let mut processor = self.processor.lock().await;
+    execute_idempotently(self.idempotency_store.as_ref(), idempotency_key, async {
+      processor
+        .handle(GroupChatCommand::CreateGroupChat {
+          group_chat_id: GroupChatId::new(),
+          name: group_chat_name,
+          executor_id,
+        })
+        .await
+        .map(group_chat_reply)
+        .map_err(error_handling)
+    })
+    .await
+    .map(Response::new)
+    .map_err(status_from_graphql_error)
+  }
+
+  async fn open_direct_chat(
+    &self,
+    request: Request<OpenDirectChatRequest>,
+  ) -> Result<Response<GroupChatReply>, Status> {
+    let request = request.into_inner();
+    let other_user_account_id =
+      validate_user_account_id(&request.other_user_account_id).map_err(status_from_graphql_error)?;
+    let executor_id = validate_user_account_id(&request.executor_id).map_err(status_from_graphql_error)?;
+    let idempotency_key = validate_idempotency_key(
+      "open_direct_chat",
+      &executor_id,
+      request.idempotency_key.as_deref(),
+      &request,
+    )
+    .map_err(status_from_graphql_error)?;
+
+    let mut processor = self.processor.lock().await;
+    execute_idempotently(self.idempotency_store.as_ref(), idempotency_key, async {
+      processor
+        .handle(GroupChatCommand::OpenDirectChat {
+          other_user_account_id,
+          executor_id,
+        })
+        .await
+        .map(group_chat_reply)
+        .map_err(error_handling)
+    })
+    .await
+    .map(Response::new)
+    .map_err(status_from_graphql_error)
+  }
+
+  async fn delete_group_chat(
+    &self,
+    request: Request<DeleteGroupChatRequest>,
+  ) -> Result<Response<GroupChatReply>, Status> {
+    let request = request.into_inner();
+    let group_chat_id = validate_group_chat_id(&request.group_chat_id).map_err(status_from_graphql_error)?;
+    let executor_id = validate_user_account_id(&request.executor_id).map_err(status_from_graphql_error)?;
+    let idempotency_key = validate_idempotency_key(
+      "delete_group_chat",
+      &executor_id,
+      request.idempotency_key.as_deref(),
+      &request,
+    )
+    .map_err(status_from_graphql_error)?;
+
+    let mut processor = self.processor.lock().await;
+    execute_idempotently(self.idempotency_store.as_ref(), idempotency_key, async {
+      processor
+        .handle(GroupChatCommand::DeleteGroupChat {
+          group_chat_id,
+          executor_id,
+        })
+        .await
+        .map(group_chat_reply)
+        .map_err(error_handling)
+    })
+    .await
+    .map(Response::new)
+    .map_err(status_from_graphql_error)
+  }
+
+  async fn rename_group_chat(
+    &self,
+    request: Request<RenameGroupChatRequest>,
+  ) -> Result<Response<GroupChatReply>, Status> {
+    let request = request.into_inner();
+    let group_chat_id = validate_group_chat_id(&request.group_chat_id).map_err(status_from_graphql_error)?;
+    let group_chat_name = validate_group_chat_name(&request.name).map_err(status_from_graphql_error)?;
+    let executor_id = validate_user_account_id(&request.executor_id).map_err(status_from_graphql_error)?;
+    let idempotency_key = validate_idempotency_key(
+      "rename_group_chat",
+      &executor_id,
+      request.idempotency_key.as_deref(),
+      &request,
+    )
+    .map_err(status_from_graphql_error)?;
+
+    let mut processor = self.processor.lock().await;
+    execute_idempotently(self.idempotency_store.as_ref(), idempotency_key, async {
+      processor
+        .handle(GroupChatCommand::RenameGroupChat {
+          group_chat_id,
+          name: group_chat_name,
+          executor_id,
+        })
+        .await
+        .map(group_chat_reply)
+        .map_err(error_handling)
+    })
+    .await
+    .map(Response::new)
+    .map_err(status_from_graphql_error)
+  }
+
+  async fn update_settings(&self, request: Request<UpdateSettingsRequest>) -> Result<Response<GroupChatReply>, Status> {
+    let request = request.into_inner();
+    let group_chat_id = validate_group_chat_id(&request.group_chat_id).map_err(status_from_graphql_error)?;
+    let settings = validate_settings(
+      request.max_members,
+      &request.post_permission,
+      &request.add_member_permission,
+      request.message_retention_days,
+    )
+    .map_err(status_from_graphql_error)?;
+    let executor_id = validate_user_account_id(&request.executor_id).map_err(status_from_graphql_error)?;
+    let idempotency_key = validate_idempotency_key(
+      "update_settings",
+      &executor_id,
+      request.idempotency_key.as_deref(),
+      &request,
+    )
+    .map_err(status_from_graphql_error)?;
+
+    let mut processor = self.processor.lock().await;
+    execute_idempotently(self.idempotency_store.as_ref(), idempotency_key, async {
+      processor
+        .handle(GroupChatCommand::UpdateSettings {
+          group_chat_id,
+          settings,
+          executor_id,
+        })
+        .await
+        .map(group_chat_reply)
+        .map_err(error_handling)
+    })
+    .await
+    .map(Response::new)
+    .map_err(status_from_graphql_error)
+  }
+
+  async fn add_member(&self, request: Request<AddMemberRequest>) -> Result<Response<GroupChatReply>, Status> {
+    let request = request.into_inner();
+    let group_chat_id = validate_group_chat_id(&request.group_chat_id).map_err(status_from_graphql_error)?;
+    let user_account_id = validate_user_account_id(&request.user_account_id).map_err(status_from_graphql_error)?;
+    let role = validate_member_role(&request.role).map_err(status_from_graphql_error)?;
+    let executor_id = validate_user_account_id(&request.executor_id).map_err(status_from_graphql_error)?;
+    let idempotency_key =
+      validate_idempotency_key("add_member", &executor_id, request.idempotency_key.as_deref(), &request)
+        .map_err(status_from_graphql_error)?;
+
+    let mut processor = self.processor.lock().await;
+    execute_idempotently(self.idempotency_store.as_ref(), idempotency_key, async {
+      processor
+        .handle(GroupChatCommand::AddMember {
+          group_chat_id,
+          user_account_id,
+          role,
+          executor_id,
+        })
+        .await
+        .map(group_chat_reply)
+        .map_err(error_handling)
+    })
+    .await
+    .map(Response::new)
+    .map_err(status_from_graphql_error)
+  }
+
+  async fn invite_member(&self, request: Request<InviteMemberRequest>) -> Result<Response<GroupChatReply>, Status> {
+    let request = request.into_inner();
+    let group_chat_id = validate_group_chat_id(&request.group_chat_id).map_err(status_from_graphql_error)?;
+    let user_account_id = validate_user_account_id(&request.user_account_id).map_err(status_from_graphql_error)?;
+    let role = validate_member_role(&request.role).map_err(status_from_graphql_error)?;
+    let executor_id = validate_user_account_id(&request.executor_id).map_err(status_from_graphql_error)?;
+    let idempotency_key = validate_idempotency_key(
+      "invite_member",
+      &executor_id,
+      request.idempotency_key.as_deref(),
+      &request,
+    )
+    .map_err(status_from_graphql_error)?;
+
+    let mut processor = self.processor.lock().await;
+    execute_idempotently(self.idempotency_store.as_ref(), idempotency_key, async {
+      processor
+        .handle(GroupChatCommand::InviteMember {
+          group_chat_id,
+          user_account_id,
+          role,
+          executor_id,
+        })
+        .await
+        .map(group_chat_reply)
+        .map_err(error_handling)
+    })
+    .await
+    .map(Response::new)
+    .map_err(status_from_graphql_error)
+  }
+
+  async fn accept_invitation(
+    &self,
+    request: Request<AcceptInvitationRequest>,
+  ) -> Result<Response<GroupChatReply>, Status> {
+    let request = request.into_inner();
+    let group_chat_id = validate_group_chat_id(&request.group_chat_id).map_err(status_from_graphql_error)?;
+    let executor_id = validate_user_account_id(&request.executor_id).map_err(status_from_graphql_error)?;
+    let idempotency_key = validate_idempotency_key(
+      "accept_invitation",
+      &executor_id,
+      request.idempotency_key.as_deref(),
+      &request,
+    )
+    .map_err(status_from_graphql_error)?;
+
+    let mut processor = self.processor.lock().await;
+    execute_idempotently(self.idempotency_store.as_ref(), idempotency_key, async {
+      processor
+        .handle(GroupChatCommand::AcceptInvitation {
+          group_chat_id,
+          executor_id,
+        })
+        .await
+        .map(group_chat_reply)
+        .map_err(error_handling)
+    })
+    .await
+    .map(Response::new)
+    .map_err(status_from_graphql_error)
+  }
+
+  async fn decline_invitation(
+    &self,
+    request: Request<DeclineInvitationRequest>,
+  ) -> Result<Response<GroupChatReply>, Status> {
+    let request = request.into_inner();
+    let group_chat_id = validate_group_chat_id(&request.group_chat_id).map_err(status_from_graphql_error)?;
+    let executor_id = validate_user_account_id(&request.executor_id).map_err(status_from_graphql_error)?;
+    let idempotency_key = validate_idempotency_key(
+      "decline_invitation",
+      &executor_id,
+      request.idempotency_key.as_deref(),
+      &request,
+    )
+    .map_err(status_from_graphql_error)?;
+
+    let mut processor = self.processor.lock().await;
+    execute_idempotently(self.idempotency_store.as_ref(), idempotency_key, async {
+      processor
+        .handle(GroupChatCommand::DeclineInvitation {
+          group_chat_id,
+          executor_id,
+        })
+        .await
+        .map(group_chat_reply)
+        .map_err(error_handling)
+    })
+    .await
+    .map(Response::new)
+    .map_err(status_from_graphql_error)
+  }
+
+  async fn revoke_invitation(
+    &self,
+    request: Request<RevokeInvitationRequest>,
+  ) -> Result<Response<GroupChatReply>, Status> {
+    let request = request.into_inner();
+    let group_chat_id = validate_group_chat_id(&request.group_chat_id).map_err(status_from_graphql_error)?;
+    let user_account_id = validate_user_account_id(&request.user_account_id).map_err(status_from_graphql_error)?;
+    let executor_id = validate_user_account_id(&request.executor_id).map_err(status_from_graphql_error)?;
+    let idempotency_key = validate_idempotency_key(
+      "revoke_invitation",
+      &executor_id,
+      request.idempotency_key.as_deref(),
+      &request,
+    )
+    .map_err(status_from_graphql_error)?;
+
+    let mut processor = self.processor.lock().await;
+    execute_idempotently(self.idempotency_store.as_ref(), idempotency_key, async {
+      processor
+        .handle(GroupChatCommand::RevokeInvitation {
+          group_chat_id,
+          user_account_id,
+          executor_id,
+        })
+        .await
+        .map(group_chat_reply)
+        .map_err(error_handling)
+    })
+    .await
+    .map(Response::new)
+    .map_err(status_from_graphql_error)
+  }
+
+  async fn remove_member(&self, request: Request<RemoveMemberRequest>) -> Result<Response<GroupChatReply>, Status> {
+    let request = request.into_inner();
+    let group_chat_id = validate_group_chat_id(&request.group_chat_id).map_err(status_from_graphql_error)?;
+    let user_account_id = validate_user_account_id(&request.user_account_id).map_err(status_from_graphql_error)?;
+    let executor_id = validate_user_account_id(&request.executor_id).map_err(status_from_graphql_error)?;
+    let idempotency_key = validate_idempotency_key(
+      "remove_member",
+      &executor_id,
+      request.idempotency_key.as_deref(),
+      &request,
+    )
+    .map_err(status_from_graphql_error)?;
+
+    let mut processor = self.processor.lock().await;
+    execute_idempotently(self.idempotency_store.as_ref(), idempotency_key, async {
+      processor
+        .handle(GroupChatCommand::RemoveMember {
+          group_chat_id,
+          user_account_id,
+          executor_id,
+        })
+        .await
+        .map(group_chat_reply)
+        .map_err(error_handling)
+    })
+    .await
+    .map(Response::new)
+    .map_err(status_from_graphql_error)
+  }
+
+  async fn leave_group_chat(
+    &self,
+    request: Request<LeaveGroupChatRequest>,
+  ) -> Result<Response<GroupChatReply>, Status> {
+    let request = request.into_inner();
+    let group_chat_id = validate_group_chat_id(&request.group_chat_id).map_err(status_from_graphql_error)?;
+    let executor_id = validate_user_account_id(&request.executor_id).map_err(status_from_graphql_error)?;
+    let idempotency_key = validate_idempotency_key(
+      "leave_group_chat",
+      &executor_id,
+      request.idempotency_key.as_deref(),
+      &request,
+    )
+    .map_err(status_from_graphql_error)?;
+
+    let mut processor = self.processor.lock().await;
+    execute_idempotently(self.idempotency_store.as_ref(), idempotency_key, async {
+      processor
+        .handle(GroupChatCommand::LeaveGroupChat {
+          group_chat_id,
+          executor_id,
+        })
+        .await
+        .map(group_chat_reply)
+        .map_err(error_handling)
+    })
+    .await
+    .map(Response::new)
+    .map_err(status_from_graphql_error)
+  }
+
+  async fn post_message(&self, request: Request<PostMessageRequest>) -> Result<Response<MessageReply>, Status> {
+    let request = request.into_inner();
+    let group_chat_id = validate_group_chat_id(&request.group_chat_id).map_err(status_from_graphql_error)?;
+    let executor_id = validate_user_account_id(&request.executor_id).map_err(status_from_graphql_error)?;
+    let idempotency_key = validate_idempotency_key(
+      "post_message",
+      &executor_id,
+      request.idempotency_key.as_deref(),
+      &request,
+    )
+    .map_err(status_from_graphql_error)?;
+    let message_id = match request.message_id.as_deref() {
+      Some(message_id) => validate_message_id(message_id).map_err(status_from_graphql_error)?,
+      None => MessageId::new(),
+    };
+    let message = validate_message(
+      None,
+      message_body_input(request.body),
+      message_id.clone(),
+      executor_id.clone(),
+    )
+    .map_err(status_from_graphql_error)?;
+
+    let mut processor = self.processor.lock().await;
+    execute_idempotently(self.idempotency_store.as_ref(), idempotency_key, async {
+      processor
+        .handle(GroupChatCommand::PostMessage {
+          group_chat_id,
+          message,
+          executor_id,
+        })
+        .await
+        .map(|group_chat_id| MessageReply {
+          group_chat_id: group_chat_id.to_string(),
+          message_id: message_id.to_string(),
+        })
+        .map_err(error_handling)
+    })
+    .await
+    .map(Response::new)
+    .map_err(status_from_graphql_error)
+  }
+
+  async fn edit_message(&self, request: Request<EditMessageRequest>) -> Result<Response<GroupChatReply>, Status> {
+    let request = request.into_inner();
+    let group_chat_id = validate_group_chat_id(&request.group_chat_id).map_err(status_from_graphql_error)?;
+    let executor_id = validate_user_account_id(&request.executor_id).map_err(status_from_graphql_error)?;
+    let idempotency_key = validate_idempotency_key(
+      "edit_message",
+      &executor_id,
+      request.idempotency_key.as_deref(),
+      &request,
+    )
+    .map_err(status_from_graphql_error)?;
+    let message_id = validate_message_id(&request.message_id).map_err(status_from_graphql_error)?;
+    let message = validate_message(None, message_body_input(request.body), message_id, executor_id.clone())
+      .map_err(status_from_graphql_error)?;
+
+    let mut processor = self.processor.lock().await;
+    execute_idempotently(self.idempotency_store.as_ref(), idempotency_key, async {
+      processor
+        .handle(GroupChatCommand::EditMessage {
+          group_chat_id,
+          message,
+          executor_id,
+        })
+        .await
+        .map(group_chat_reply)
+        .map_err(error_handling)
+    })
+    .await
+    .map(Response::new)
+    .map_err(status_from_graphql_error)
+  }
+
+  async fn delete_message(&self, request: Request<DeleteMessageRequest>) -> Result<Response<GroupChatReply>, Status> {
+    let request = request.into_inner();
+    let group_chat_id = validate_group_chat_id(&request.group_chat_id).map_err(status_from_graphql_error)?;
+    let message_id = validate_message_id(&request.message_id).map_err(status_from_graphql_error)?;
+    let executor_id = validate_user_account_id(&request.executor_id).map_err(status_from_graphql_error)?;
+    let idempotency_key = validate_idempotency_key(
+      "delete_message",
+      &executor_id,
+      request.idempotency_key.as_deref(),
+      &request,
+    )
+    .map_err(status_from_graphql_error)?;
+
+    let mut processor = self.processor.lock().await;
+    execute_idempotently(self.idempotency_store.as_ref(), idempotency_key, async {
+      processor
+        .handle(GroupChatCommand::DeleteMessage {
+          group_chat_id,
+          message_id,
+          executor_id,
+        })
+        .await
+        .map(group_chat_reply)
+        .map_err(error_handling)
+    })
+    .await
+    .map(Response::new)
+    .map_err(status_from_graphql_error)
+  }
+
+  async fn moderate_message(
+    &self,
+    request: Request<ModerateMessageRequest>,
+  ) -> Result<Response<GroupChatReply>, Status> {
+    let request = request.into_inner();
+    let group_chat_id = validate_group_chat_id(&request.group_chat_id).map_err(status_from_graphql_error)?;
+    let message_id = validate_message_id(&request.message_id).map_err(status_from_graphql_error)?;
+    let reason = validate_moderation_reason(&request.reason).map_err(status_from_graphql_error)?;
+    let executor_id = validate_user_account_id(&request.executor_id).map_err(status_from_graphql_error)?;
+    let idempotency_key = validate_idempotency_key(
+      "moderate_message",
+      &executor_id,
+      request.idempotency_key.as_deref(),
+      &request,
+    )
+    .map_err(status_from_graphql_error)?;
+
+    let mut processor = self.processor.lock().await;
+    execute_idempotently(self.idempotency_store.as_ref(), idempotency_key, async {
+      processor
+        .handle(GroupChatCommand::ModerateMessage {
+          group_chat_id,
+          message_id,
+          reason,
+          executor_id,
+        })
+        .await
+        .map(group_chat_reply)
+        .map_err(error_handling)
+    })
+    .await
+    .map(Response::new)
+    .map_err(status_from_graphql_error)
+  }
+
+  async fn restore_message(&self, request: Request<RestoreMessageRequest>) -> Result<Response<GroupChatReply>, Status> {
+    let request = request.into_inner();
+    let group_chat_id = validate_group_chat_id(&request.group_chat_id).map_err(status_from_graphql_error)?;
+    let message_id = validate_message_id(&request.message_id).map_err(status_from_graphql_error)?;
+    let executor_id = validate_user_account_id(&request.executor_id).map_err(status_from_graphql_error)?;
+    let idempotency_key = validate_idempotency_key(
+      "restore_message",
+      &executor_id,
+      request.idempotency_key.as_deref(),
+      &request,
+    )
+    .map_err(status_from_graphql_error)?;
+
+    let mut processor = self.processor.lock().await;
+    execute_idempotently(self.idempotency_store.as_ref(), idempotency_key, async {
+      processor
+        .handle(GroupChatCommand::RestoreMessage {
+          group_chat_id,
+          message_id,
+          executor_id,
+        })
+        .await
+        .map(group_chat_reply)
+        .map_err(error_handling)
+    })
+    .await
+    .map(Response::new)
+    .map_err(status_from_graphql_error)
+  }
+
+  async fn erase_user_account(
+    &self,
+    request: Request<EraseUserAccountRequest>,
+  ) -> Result<Response<UserAccountErasureReply>, Status> {
+    let request = request.into_inner();
+    let user_account_id = validate_user_account_id(&request.user_account_id).map_err(status_from_graphql_error)?;
+    let executor_id = validate_user_account_id(&request.executor_id).map_err(status_from_graphql_error)?;
+    let idempotency_key = validate_idempotency_key(
+      "erase_user_account",
+      &executor_id,
+      request.idempotency_key.as_deref(),
+      &request,
+    )
+    .map_err(status_from_graphql_error)?;
+
+    let group_chat_ids = self
+      .user_account_group_chat_dao
+      .get_group_chat_ids(&user_account_id)
+      .await
+      .map_err(|error| Status::internal(error.to_string()))?;
+
+    let mut processor = self.processor.lock().await;
+    execute_idempotently(self.idempotency_store.as_ref(), idempotency_key, async {
+      processor
+        .erase_user_account(user_account_id.clone(), group_chat_ids, executor_id)
+        .await
+        .map(|group_chat_ids| UserAccountErasureReply {
+          user_account_id: user_account_id.to_string(),
+          group_chat_ids: group_chat_ids.iter().map(|id| id.to_string()).collect(),
+        })
+        .map_err(error_handling)
+    })
+    .await
+    .map(Response::new)
+    .map_err(status_from_graphql_error)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use command_domain::group_chat_error::GroupChatError;
+  use command_domain::user_account::UserAccountId;
+  use command_processor::group_chat_command_processor::CommandProcessError;
+  use tonic::Code;
+
+  use super::*;
+
+  fn command_process_error_to_status(error: CommandProcessError) -> Status {
+    status_from_graphql_error(error_handling(error))
+  }
+
+  #[test]
+  fn test_command_process_error_to_status() {
+    let status = command_process_error_to_status(CommandProcessError::NotFoundError);
+    assert_eq!(status.code(), Code::NotFound);
+
+    let status = command_process_error_to_status(CommandProcessError::DomainLogicError(
+      GroupChatError::NotMemberError("executor_id".to_string(), UserAccountId::new()),
+    ));
+    assert_eq!(status.code(), Code::FailedPrecondition);
+    assert_eq!(status.metadata().get("error-code").unwrap(), "NOT_MEMBER");
+
+    let status = status_from_graphql_error(validate_group_chat_id("invalid").unwrap_err());
+    assert_eq!(status.code(), Code::InvalidArgument);
+  }
+
+  #[test]
+  fn test_validate_idempotency_key_of_request() {
+    let executor_id = UserAccountId::new();
+    let request = PostMessageRequest {
+      group_chat_id: GroupChatId::new().to_string(),
+      body: Some(MessageBody {
+        body: Some(message_body::Body::Text("Hello".to_string())),
+      }),
+      executor_id: executor_id.to_string(),
+      message_id: None,
+      idempotency_key: Some("key".to_string()),
+    };
+    let key = validate_idempotency_key(
+      "post_message",
+      &executor_id,
+      request.idempotency_key.as_deref(),
+      &request,
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(key.key, format!("post_message:{}:key", executor_id));
+
+    // 本文が異なる場合は、同じキーでもフィンガープリントが異なる
+    let other_request = PostMessageRequest {
+      body: Some(MessageBody {
+        body: Some(message_body::Body::Text("Bye".to_string())),
+      }),
+      ..request.clone()
+    };
+    let other_key = validate_idempotency_key(
+      "post_message",
+      &executor_id,
+      other_request.idempotency_key.as_deref(),
+      &other_request,
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(other_key.key, key.key);
+    assert_ne!(other_key.fingerprint, key.fingerprint);
+  }
+
+  #[test]
+  fn test_message_body_input() {
+    let body = MessageBody {
+      body: Some(message_body::Body::Text("Hello".to_string())),
+    };
+    assert!(matches!(message_body_input(Some(body)), Some(MessageBodyInput::Text(text)) if text == "Hello"));
+    assert!(message_body_input(Some(MessageBody { body: None })).is_none());
+    assert!(message_body_input(None).is_none());
+  }
+}
@@ -1,3 +1,5 @@
 pub mod controllers;
 pub mod gateways;
 pub mod graphql;
+pub mod grpc;
+pub mod rest;
@@ -0,0 +1,661 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use infrastructure::error::{error_code_of, ApiError, ErrorCode};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+use command_domain::group_chat::{GroupChatId, MessageId};
+use command_domain::user_account::UserAccountId;
+use command_interface_adaptor_if::IdempotencyStore;
+use command_processor::group_chat_command::GroupChatCommand;
+use command_processor::group_chat_command_processor::{CommandProcessError, GroupChatCommandProcessor};
+
+use crate::gateways::group_chat_repository::GroupChatRepositoryImpl;
+use crate::graphql::errors::{command_process_error, invalid_argument};
+use crate::graphql::inputs::{AttachmentInput, LinkPreviewInput, MessageBodyInput};
+use crate::graphql::resolvers::{
+  validate_group_chat_id, validate_group_chat_name, validate_idempotency_key, validate_member_role, validate_message,
+  validate_message_id, validate_user_account_id,
+};
+use crate::graphql::{execute_idempotently, IdempotencyKey, ES};
+
+/// GraphQLのリゾルバと共有する[GroupChatCommandProcessor]
+pub type SharedGroupChatCommandProcessor = Arc<Mutex<GroupChatCommandProcessor<GroupChatRepositoryImpl<ES>>>>;
+
+/// 冪等キーを指定するリクエストヘッダ
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateGroupChatRequest {
+  /// グループチャット名
+  pub name: String,
+  /// 実行者のアカウントID
+  pub executor_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RenameGroupChatRequest {
+  /// 新しいグループチャット名
+  pub name: String,
+  /// 実行者のアカウントID
+  pub executor_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AddMemberRequest {
+  /// 追加するアカウントID
+  pub user_account_id: String,
+  /// ロール(admin, member)
+  pub role: String,
+  /// 実行者のアカウントID
+  pub executor_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PostMessageRequest {
+  /// メッセージID。省略した場合は生成する。再送時に同じIDを指定すると、重複した投稿は422になる
+  pub message_id: Option<String>,
+  pub body: MessageBodyRequest,
+  /// 実行者(送信者)のアカウントID
+  pub executor_id: String,
+}
+
+/// ユーザが投稿できるメッセージ本文。`type`で種類を指定する。
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MessageBodyRequest {
+  Text {
+    text: String,
+  },
+  Attachment {
+    url: String,
+    mime: String,
+    /// サイズ(バイト)
+    size: u64,
+  },
+  LinkPreview {
+    url: String,
+    title: Option<String>,
+    description: Option<String>,
+  },
+}
+
+impl From<MessageBodyRequest> for MessageBodyInput {
+  fn from(body: MessageBodyRequest) -> Self {
+    match body {
+      MessageBodyRequest::Text { text } => MessageBodyInput::Text(text),
+      MessageBodyRequest::Attachment { url, mime, size } => {
+        MessageBodyInput::Attachment(AttachmentInput { url, mime, size })
+      }
+      MessageBodyRequest::LinkPreview {
+        url,
+        title,
+        description,
+      } => MessageBodyInput::LinkPreview(LinkPreviewInput {
+        url,
+        title,
+        description,
+      }),
+    }
+  }
+}
+
+/// 本文を持たない操作(削除など)の実行者
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExecutorQuery {
+  /// 実行者のアカウントID
+  pub executor_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GroupChatResponse {
+  pub group_chat_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MessageResponse {
+  pub group_chat_id: String,
+  pub message_id: String,
+}
+
+/// エラーレスポンス
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ErrorResponse {
+  /// エラーメッセージ
+  pub message: String,
+  /// エラーコード(GraphQLのエラーの`code`拡張と同じ値。例: `NOT_MEMBER`)
+  pub code: String,
+}
+
+/// REST APIのエラー。HTTPステータスコードとエラーレスポンスに変換される。
+#[derive(Debug)]
+pub struct RestError {
+  code: ErrorCode,
+  message: String,
+}
+
+impl RestError {
+  pub fn status(&self) -> StatusCode {
+    StatusCode::from_u16(self.code.status()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+  }
+
+  pub fn code(&self) -> ErrorCode {
+    self.code
+  }
+}
+
+impl From<ApiError> for RestError {
+  fn from(error: ApiError) -> Self {
+    Self {
+      code: error.code(),
+      message: error.message().to_string(),
+    }
+  }
+}
+
+/// 入力値の検証エラー(`validate_*`のエラー)
+impl From<async_graphql::Error> for RestError {
+  fn from(error: async_graphql::Error) -> Self {
+    Self {
+      code: error_code_of(&error).unwrap_or(ErrorCode::InvalidArgument),
+      message: error.message,
+    }
+  }
+}
+
+impl From<CommandProcessError> for RestError {
+  fn from(error: CommandProcessError) -> Self {
+    command_process_error(&error).into()
+  }
+}
+
+impl IntoResponse for RestError {
+  fn into_response(self) -> Response {
+    let status = self.status();
+    let body = ErrorResponse {
+      message: self.message,
+      code: self.code.as_str().to_string(),
+    };
+    (status, Json(body)).into_response()
+  }
+}
+
+/// `Idempotency-Key`ヘッダの冪等キーを検証し、操作名と実行者で修飾する。
+///
+/// NOTE: GraphQLのミューテーションと同じ規則でフィンガープリントを計算する。
+/// パスパラメータも入力に含めるので、同じキーを別のリソースに対する操作に再利用した場合は422になる。
+///
+/// # 引数
+/// - `operation` - 操作名(GraphQLのミューテーションと同じ名前)
+/// - `executor_id` - 実行者のユーザアカウントID
+/// - `headers` - リクエストヘッダ
+/// - `input` - パスパラメータとリクエストボディ
+///
+/// # 戻り値
+/// - 冪等キーを指定していない場合は`None`を返します。
+fn idempotency_key<I: Serialize>(
+  operation: &str,
+  executor_id: &UserAccountId,
+  headers: &HeaderMap,
+  input: &I,
+) -> Result<Option<IdempotencyKey>, RestError> {
+  let value = headers
+    .get(IDEMPOTENCY_KEY_HEADER)
+    .map(|value| {
+      value
+        .to_str()
+        .map_err(|_| invalid_argument("Idempotency-Key", "the idempotency key is not a visible ASCII string"))
+    })
+    .transpose()?;
+  Ok(validate_idempotency_key(operation, executor_id, value, input)?)
+}
+
+/// グループチャットを作成する。
+#[utoipa::path(
+  post,
+  path = "/group-chats",
+  tag = "group-chats",
+  params(("Idempotency-Key" = Option<String>, Header, description = "冪等キー。同じキーで再送すると、最初の結果を返す")),
+  request_body = CreateGroupChatRequest,
+  responses(
+    (status = 201, description = "作成したグループチャット", body = GroupChatResponse),
+    (status = 400, description = "入力値が不正", body = ErrorResponse),
+    (status = 409, description = "同じ冪等キーのリクエストを処理中", body = ErrorResponse),
+    (status = 422, description = "ドメインのルールに違反、または冪等キーを異なるリクエストに再利用", body = ErrorResponse),
+  )
+)]
+pub async fn create_group_chat(
+  Extension(processor): Extension<SharedGroupChatCommandProcessor>,
+  Extension(idempotency_store): Extension<Arc<dyn IdempotencyStore>>,
+  headers: HeaderMap,
+  Json(request): Json<CreateGroupChatRequest>,
+) -> Result<(StatusCode, Json<GroupChatResponse>), RestError> {
+  let group_chat_name = validate_group_chat_name(&request.name)?;
+  let executor_id = validate_user_account_id(&request.executor_id)?;
+  let idempotency_key = idempotency_key("create_group_chat", &executor_id, &headers, &request)?;
+
+  let mut processor = processor.lock().await;
+  let response = execute_idempotently(idempotency_store.as_ref(), idempotency_key, async {
+    let group_chat_id = processor
+      .handle(GroupChatCommand::CreateGroupChat {
+        group_chat_id: GroupChatId::new(),
+        name: group_chat_name,
+        executor_id,
+      })
+      .await?;
+    Ok::<_, RestError>(GroupChatResponse {
+      group_chat_id: group_chat_id.to_string(),
+    })
+  })
+  .await?;
+  Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// グループチャット名を変更する。
+#[utoipa::path(
+  patch,
+  path = "/group-chats/{group_chat_id}",
+  tag = "group-chats",
+  params(
+    ("group_chat_id" = String, Path, description = "グループチャットID"),
+    ("Idempotency-Key" = Option<String>, Header, description = "冪等キー。同じキーで再送すると、最初の結果を返す"),
+  ),
+  request_body = RenameGroupChatRequest,
+  responses(
+    (status = 200, description = "変更したグループチャット", body = GroupChatResponse),
+    (status = 400, description = "入力値が不正", body = ErrorResponse),
+    (status = 404, description = "グループチャットが存在しない", body = ErrorResponse),
+    (status = 409, description = "同時に更新された、または同じ冪等キーのリクエストを処理中", body = ErrorResponse),
+    (status = 422, description = "ドメインのルールに違反、または冪等キーを異なるリクエストに再利用", body = ErrorResponse),
+  )
+)]
+pub async fn rename_group_chat(
+  Extension(processor): Extension<SharedGroupChatCommandProcessor>,
+  Extension(idempotency_store): Extension<Arc<dyn IdempotencyStore>>,
+  headers: HeaderMap,
+  Path(path_group_chat_id): Path<String>,
+  Json(request): Json<RenameGroupChatRequest>,
+) -> Result<Json<GroupChatResponse>, RestError> {
+  let group_chat_id = validate_group_chat_id(&path_group_chat_id)?;
+  let group_chat_name = validate_group_chat_name(&request.name)?;
+  let executor_id = validate_user_account_id(&request.executor_id)?;
+  let idempotency_key = idempotency_key(
+    "rename_group_chat",
+    &executor_id,
+    &headers,
+    &(&path_group_chat_id, &request),
+  )?;
+
+  let mut processor = processor.lock().await;
+  let response = execute_idempotently(idempotency_store.as_ref(), idempotency_key, async {
+    let group_chat_id = processor
+      .handle(GroupChatCommand::RenameGroupChat {
+        group_chat_id,
+        name: group_chat_name,
+        executor_id,
+      })
+      .await?;
+    Ok::<_, RestError>(GroupChatResponse {
+      group_chat_id: group_chat_id.to_string(),
+    })
+  })
+  .await?;
+  Ok(Json(response))
+}
+
+/// グループチャットを削除する。
+#[utoipa::path(
+  delete,
+  path = "/group-chats/{group_chat_id}",
+  tag = "group-chats",
+  params(
+    ("group_chat_id" = String, Path, description = "グループチャットID"),
+    ExecutorQuery,
+    ("Idempotency-Key" = Option<String>, Header, description = "冪等キー。同じキーで再送すると、最初の結果を返す"),
+  ),
+  responses(
+    (status = 200, description = "削除したグループチャット", body = GroupChatResponse),
+    (status = 400, description = "入力値が不正", body = ErrorResponse),
+    (status = 404, description = "グループチャットが存在しない", body = ErrorResponse),
+    (status = 409, description = "同時に更新された、または同じ冪等キーのリクエストを処理中", body = ErrorResponse),
+    (status = 422, description = "ドメインのルールに違反、または冪等キーを異なるリクエストに再利用", body = ErrorResponse),
+  )
+)]
+pub async fn delete_group_chat(
+  Extension(processor): Extension<SharedGroupChatCommandProcessor>,
+  Extension(idempotency_store): Extension<Arc<dyn IdempotencyStore>>,
+  headers: HeaderMap,
+  Path(path_group_chat_id): Path<String>,
+  Query(query): Query<ExecutorQuery>,
+) -> Result<Json<GroupChatResponse>, RestError> {
+  let group_chat_id = validate_group_chat_id(&path_group_chat_id)?;
+  let executor_id = validate_user_account_id(&query.executor_id)?;
+  let idempotency_key = idempotency_key(
+    "delete_group_chat",
+    &executor_id,
+    &headers,
+    &(&path_group_chat_id, &query),
+  )?;
+
+  let mut processor = processor.lock().await;
+  let response = execute_idempotently(idempotency_store.as_ref(), idempotency_key, async {
+    let group_chat_id = processor
+      .handle(GroupChatCommand::DeleteGroupChat {
+        group_chat_id,
+        executor_id,
+      })
+      .await?;
+    Ok::<_, RestError>(GroupChatResponse {
+      group_chat_id: group_chat_id.to_string(),
+    })
+  })
+  .await?;
+  Ok(Json(response))
+}
+
+/// グループチャットにメンバーを追加する。
+#[utoipa::path(
+  post,
+  path = "/group-chats/{group_chat_id}/members",
+  tag = "group-chats",
+  params(
+    ("group_chat_id" = String, Path, description = "グループチャットID"),
+    ("Idempotency-Key" = Option<String>, Header, description = "冪等キー。同じキーで再送すると、最初の結果を返す"),
+  ),
+  request_body = AddMemberRequest,
+  responses(
+    (status = 200, description = "メンバーを追加したグループチャット", body = GroupChatResponse),
+    (status = 400, description = "入力値が不正", body = ErrorResponse),
+    (status = 404, description = "グループチャットが存在しない", body = ErrorResponse),
+    (status = 409, description = "同時に更新された、または同じ冪等キーのリクエストを処理中", body = ErrorResponse),
+    (status = 422, description = "ドメインのルールに違反、または冪等キーを異なるリクエストに再利用", body = ErrorResponse),
+  )
+)]
+pub async fn add_member(
+  Extension(processor): Extension<SharedGroupChatCommandProcessor>,
+  Extension(idempotency_store): Extension<Arc<dyn IdempotencyStore>>,
+  headers: HeaderMap,
+  Path(path_group_chat_id): Path<String>,
+  Json(request): Json<AddMemberRequest>,
+) -> Result<Json<GroupChatResponse>, RestError> {
+  let group_chat_id = validate_group_chat_id(&path_group_chat_id)?;
+  let user_account_id = validate_user_account_id(&request.user_account_id)?;
+  let role = validate_member_role(&request.role)?;
+  let executor_id = validate_user_account_id(&request.executor_id)?;
+  let idempotency_key = idempotency_key("add_member", &executor_id, &headers, &(&path_group_chat_id, &request))?;
+
+  let mut processor = processor.lock().await;
+  let response = execute_idempotently(idempotency_store.as_ref(), idempotency_key, async {
+    let group_chat_id = processor
+      .handle(GroupChatCommand::AddMember {
+        group_chat_id,
+        user_account_id,
+        role,
+        executor_id,
+      })
+      .await?;
+    Ok::<_, RestError>(GroupChatResponse {
+      group_chat_id: group_chat_id.to_string(),
+    })
+  })
+  .await?;
+  Ok(Json(response))
+}
+
+/// グループチャットからメンバーを削除する。
+#[utoipa::path(
+  delete,
+  path = "/group-chats/{group_chat_id}/members/{user_account_id}",
+  tag = "group-chats",
+  params(
+    ("group_chat_id" = String, Path, description = "グループチャットID"),
+    ("user_account_id" = String, Path, description = "削除するアカウントID"),
+    ExecutorQuery,
+    ("Idempotency-Key" = Option<String>, Header, description = "冪等キー。同じキーで再送すると、最初の結果を返す"),
+  ),
+  responses(
+    (status = 200, description = "メンバーを削除したグループチャット", body = GroupChatResponse),
+    (status = 400, description = "入力値が不正", body = ErrorResponse),
+    (status = 404, description = "グループチャットが存在しない", body = ErrorResponse),
+    (status = 409, description = "同時に更新された、または同じ冪等キーのリクエストを処理中", body = ErrorResponse),
+    (status = 422, description = "ドメインのルールに違反、または冪等キーを異なるリクエストに再利用", body = ErrorResponse),
+  )
+)]
+pub async fn remove_member(
+  Extension(processor): Extension<SharedGroupChatCommandProcessor>,
+  Extension(idempotency_store): Extension<Arc<dyn IdempotencyStore>>,
+  headers: HeaderMap,
+  Path((path_group_chat_id, path_user_account_id)): Path<(String, String)>,
+  Query(query): Query<ExecutorQuery>,
+) -> Result<Json<GroupChatResponse>, RestError> {
+  let group_chat_id = validate_group_chat_id(&path_group_chat_id)?;
+  let user_account_id = validate_user_account_id(&path_user_account_id)?;
+  let executor_id = validate_user_account_id(&query.executor_id)?;
+  let idempotency_key = idempotency_key(
+    "remove_member",
+    &executor_id,
+    &headers,
+    &(&path_group_chat_id, &path_user_account_id, &query),
+  )?;
+
+  let mut processor = processor.lock().await;
+  let response = execute_idempotently(idempotency_store.as_ref(), idempotency_key, async {
+    let group_chat_id = processor
+      .handle(GroupChatCommand::RemoveMember {
+        group_chat_id,
+        user_account_id,
+        executor_id,
+      })
+      .await?;
+    Ok::<_, RestError>(GroupChatResponse {
+      group_chat_id: group_chat_id.to_string(),
+    })
+  })
+  .await?;
+  Ok(Json(response))
+}
+
+/// グループチャットにメッセージを投稿する。
+#[utoipa::path(
+  post,
+  path = "/group-chats/{group_chat_id}/messages",
+  tag = "group-chats",
+  params(
+    ("group_chat_id" = String, Path, description = "グループチャットID"),
+    ("Idempotency-Key" = Option<String>, Header, description = "冪等キー。同じキーで再送すると、最初の結果を返す"),
+  ),
+  request_body = PostMessageRequest,
+  responses(
+    (status = 201, description = "投稿したメッセージ", body = MessageResponse),
+    (status = 400, description = "入力値が不正", body = ErrorResponse),
+    (status = 404, description = "グループチャットが存在しない", body = ErrorResponse),
+    (status = 409, description = "同時に更新された、または同じ冪等キーのリクエストを処理中", body = ErrorResponse),
+    (status = 422, description = "ドメインのルールに違反、または冪等キーを異なるリクエストに再利用", body = ErrorResponse),
+  )
+)]
+pub async fn post_message(
+  Extension(processor): Extension<SharedGroupChatCommandProcessor>,
+  Extension(idempotency_store): Extension<Arc<dyn IdempotencyStore>>,
+  headers: HeaderMap,
+  Path(path_group_chat_id): Path<String>,
+  Json(request): Json<PostMessageRequest>,
+) -> Result<(StatusCode, Json<MessageResponse>), RestError> {
+  let group_chat_id = validate_group_chat_id(&path_group_chat_id)?;
+  let executor_id = validate_user_account_id(&request.executor_id)?;
+  let idempotency_key = idempotency_key("post_message", &executor_id, &headers, &(&path_group_chat_id, &request))?;
+  let message_id = match request.message_id.as_deref() {
+    Some(message_id) => validate_message_id(message_id)?,
+    None => MessageId::new(),
+  };
+  let message = validate_message(None, Some(request.body.into()), message_id.clone(), executor_id.clone())?;
+
+  let mut processor = processor.lock().await;
+  let response = execute_idempotently(idempotency_store.as_ref(), idempotency_key, async {
+    let group_chat_id = processor
+      .handle(GroupChatCommand::PostMessage {
+        group_chat_id,
+        message,
+        executor_id,
+      })
+      .await?;
+    Ok::<_, RestError>(MessageResponse {
+      group_chat_id: group_chat_id.to_string(),
+      message_id: message_id.to_string(),
+    })
+  })
+  .await?;
+  Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// グループチャットのメッセージを削除する。
+#[utoipa::path(
+  delete,
+  path = "/group-chats/{group_chat_id}/messages/{message_id}",
+  tag = "group-chats",
+  params(
+    ("group_chat_id" = String, Path, description = "グループチャットID"),
+    ("message_id" = String, Path, description = "メッセージID"),
+    ExecutorQuery,
+    ("Idempotency-Key" = Option<String>, Header, description = "冪等キー。同じキーで再送すると、最初の結果を返す"),
+  ),
+  responses(
+    (status = 200, description = "メッセージを削除したグループチャット", body = GroupChatResponse),
+    (status = 400, description = "入力値が不正", body = ErrorResponse),
+    (status = 404, description = "グループチャットが存在しない", body = ErrorResponse),
+    (status = 409, description = "同時に更新された、または同じ冪等キーのリクエストを処理中", body = ErrorResponse),
+    (status = 422, description = "ドメインのルールに違反、または冪等キーを異なるリクエストに再利用", body = ErrorResponse),
+  )
+)]
+pub async fn delete_message(
+  Extension(processor): Extension<SharedGroupChatCommandProcessor>,
+  Extension(idempotency_store): Extension<Arc<dyn IdempotencyStore>>,
+  headers: HeaderMap,
+  Path((path_group_chat_id, path_message_id)): Path<(String, String)>,
+  Query(query): Query<ExecutorQuery>,
+) -> Result<Json<GroupChatResponse>, RestError> {
+  let group_chat_id = validate_group_chat_id(&path_group_chat_id)?;
+  let message_id = validate_message_id(&path_message_id)?;
+  let executor_id = validate_user_account_id(&query.executor_id)?;
+  let idempotency_key = idempotency_key(
+    "delete_message",
+    &executor_id,
+    &headers,
+    &(&path_group_chat_id, &path_message_id, &query),
+  )?;
+
+  let mut processor = processor.lock().await;
+  let response = execute_idempotently(idempotency_store.as_ref(), idempotency_key, async {
+    let group_chat_id = processor
+      .handle(GroupChatCommand::DeleteMessage {
+        group_chat_id,
+        message_id,
+        executor_id,
+      })
+      .await?;
+    Ok::<_, RestError>(GroupChatResponse {
+      group_chat_id: group_chat_id.to_string(),
+    })
+  })
+  .await?;
+  Ok(Json(response))
+}
+
+/// REST APIのOpenAPI定義
+#[derive(OpenApi)]
+#[openapi(
+  info(title = "Write API", description = "グループチャットのコマンドを実行するREST API"),
+  paths(
+    create_group_chat,
+    rename_group_chat,
+    delete_group_chat,
+    add_member,
+    remove_member,
+    post_message,
+    delete_message
+  ),
+  components(schemas(
+    CreateGroupChatRequest,
+    RenameGroupChatRequest,
+    AddMemberRequest,
+    PostMessageRequest,
+    MessageBodyRequest,
+    GroupChatResponse,
+    MessageResponse,
+    ErrorResponse
+  )),
+  tags((name = "group-chats", description = "グループチャット"))
+)]
+pub struct ApiDoc;
+
+/// OpenAPI定義を返すエンドポイント。
+pub async fn openapi() -> Json<utoipa::openapi::OpenApi> {
+  Json(ApiDoc::openapi())
+}
+
+#[cfg(test)]
+mod tests {
+  use command_domain::group_chat_error::GroupChatError;
+
+  use super::*;
+
+  #[test]
+  fn test_openapi_contains_paths() {
+    let openapi = ApiDoc::openapi();
+    let paths = &openapi.paths.paths;
+    assert!(paths.contains_key("/group-chats"));
+    assert!(paths.contains_key("/group-chats/{group_chat_id}/members"));
+    assert!(paths.contains_key("/group-chats/{group_chat_id}/messages/{message_id}"));
+    assert!(openapi.to_yaml().is_ok());
+  }
+
+  #[test]
+  fn test_command_process_error_to_status() {
+    let error = RestError::from(CommandProcessError::NotFoundError);
+    assert_eq!(error.status(), StatusCode::NOT_FOUND);
+
+    let error = RestError::from(CommandProcessError::DomainLogicError(GroupChatError::NotMemberError(
+      "executor_id".to_string(),
+      UserAccountId::new(),
+    )));
+    assert_eq!(error.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(error.code(), ErrorCode::NotMember);
+
+    let error = RestError::from(validate_group_chat_id("invalid").unwrap_err());
+    assert_eq!(error.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(error.code(), ErrorCode::InvalidId);
+  }
+
+  #[test]
+  fn test_idempotency_key() {
+    let executor_id = UserAccountId::new();
+    let request = ExecutorQuery {
+      executor_id: executor_id.to_string(),
+    };
+    let mut headers = HeaderMap::new();
+    assert!(idempotency_key("delete_message", &executor_id, &headers, &request)
+      .unwrap()
+      .is_none());
+
+    headers.insert(IDEMPOTENCY_KEY_HEADER, "key".parse().unwrap());
+    let key = idempotency_key("delete_message", &executor_id, &headers, &("a", &request))
+      .unwrap()
+      .unwrap();
+    assert_eq!(key.key, format!("delete_message:{}:key", executor_id));
+    // パスパラメータが異なる場合は、同じキーでもフィンガープリントが異なる
+    let other_key = idempotency_key("delete_message", &executor_id, &headers, &("b", &request))
+      .unwrap()
+      .unwrap();
+    assert_eq!(other_key.key, key.key);
+    assert_ne!(other_key.fingerprint, key.fingerprint);
+
+    headers.insert(IDEMPOTENCY_KEY_HEADER, " ".parse().unwrap());
+    let error = idempotency_key("delete_message", &executor_id, &headers, &request).unwrap_err();
+    assert_eq!(error.status(), StatusCode::BAD_REQUEST);
+  }
+}
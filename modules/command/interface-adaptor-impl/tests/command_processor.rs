@@ -1,9 +1,10 @@
 use serial_test::serial;
 
-use command_domain::group_chat::{GroupChatName, MemberRole, Message};
+use command_domain::group_chat::{ChatKind, GroupChatId, GroupChatName, MemberRole, Message};
 use command_domain::group_chat::{Members, MessageId};
 use command_domain::user_account::UserAccountId;
 use command_interface_adaptor_if::*;
+use command_processor::group_chat_command::GroupChatCommand;
 use command_processor::group_chat_command_processor::GroupChatCommandProcessor;
 use common::*;
 
@@ -12,23 +13,214 @@ mod common;
 #[tokio::test]
 #[serial]
 async fn test_group_chat_create() {
-  let (repository, container, client) = get_repository().await;
+  let (repository, _container, _client) = get_repository().await;
   // Given
   let name = GroupChatName::new("ABC").unwrap();
   let admin_id = UserAccountId::new();
   let _members = Members::new(admin_id.clone());
   let mut command_processor = GroupChatCommandProcessor::new(repository);
   // When
-  let result = command_processor.create_group_chat(name, admin_id).await;
+  let result = command_processor
+    .handle(GroupChatCommand::CreateGroupChat {
+      group_chat_id: GroupChatId::new(),
+      name,
+      executor_id: admin_id,
+    })
+    .await;
 
   // Then
   assert!(result.is_ok());
 }
 
+#[tokio::test]
+#[serial]
+async fn test_open_direct_chat() {
+  let (repository, _container, _client) = get_repository().await;
+  // Given
+  let user_account_id1 = UserAccountId::new();
+  let user_account_id2 = UserAccountId::new();
+  let mut command_processor = GroupChatCommandProcessor::new(repository.clone());
+
+  // When
+  let id1 = command_processor
+    .handle(GroupChatCommand::OpenDirectChat {
+      other_user_account_id: user_account_id2.clone(),
+      executor_id: user_account_id1.clone(),
+    })
+    .await
+    .unwrap();
+  let id2 = command_processor
+    .handle(GroupChatCommand::OpenDirectChat {
+      other_user_account_id: user_account_id1.clone(),
+      executor_id: user_account_id2.clone(),
+    })
+    .await
+    .unwrap();
+
+  // Then
+  assert_eq!(id1, id2);
+  let group_chat = repository.find_by_id(&id1).await.unwrap().unwrap();
+  assert_eq!(*group_chat.kind(), ChatKind::Direct);
+  assert!(group_chat.members().is_member(&user_account_id1));
+  assert!(group_chat.members().is_member(&user_account_id2));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_group_chat_invite_and_accept() {
+  let (repository, _container, _client) = get_repository().await;
+  // Given
+  let name = GroupChatName::new("ABC").unwrap();
+  let admin_id = UserAccountId::new();
+  let user_account_id = UserAccountId::new();
+  let mut command_processor = GroupChatCommandProcessor::new(repository.clone());
+
+  let id = command_processor
+    .handle(GroupChatCommand::CreateGroupChat {
+      group_chat_id: GroupChatId::new(),
+      name: name.clone(),
+      executor_id: admin_id.clone(),
+    })
+    .await
+    .unwrap();
+
+  // When
+  command_processor
+    .handle(GroupChatCommand::InviteMember {
+      group_chat_id: id.clone(),
+      user_account_id: user_account_id.clone(),
+      role: MemberRole::Member,
+      executor_id: admin_id.clone(),
+    })
+    .await
+    .unwrap();
+  command_processor
+    .handle(GroupChatCommand::AcceptInvitation {
+      group_chat_id: id.clone(),
+      executor_id: user_account_id.clone(),
+    })
+    .await
+    .unwrap();
+
+  // Then
+  let group_chat = repository.find_by_id(&id).await.unwrap().unwrap();
+  assert!(group_chat.members().is_member(&user_account_id));
+  assert!(group_chat
+    .invitations()
+    .find_pending(&user_account_id, &chrono::Utc::now())
+    .is_none());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_group_chat_leave() {
+  let (repository, _container, _client) = get_repository().await;
+  // Given
+  let name = GroupChatName::new("ABC").unwrap();
+  let admin_id = UserAccountId::new();
+  let user_account_id = UserAccountId::new();
+  let mut command_processor = GroupChatCommandProcessor::new(repository.clone());
+
+  let id = command_processor
+    .handle(GroupChatCommand::CreateGroupChat {
+      group_chat_id: GroupChatId::new(),
+      name: name.clone(),
+      executor_id: admin_id.clone(),
+    })
+    .await
+    .unwrap();
+  command_processor
+    .handle(GroupChatCommand::AddMember {
+      group_chat_id: id.clone(),
+      user_account_id: user_account_id.clone(),
+      role: MemberRole::Member,
+      executor_id: admin_id.clone(),
+    })
+    .await
+    .unwrap();
+
+  // When
+  command_processor
+    .handle(GroupChatCommand::LeaveGroupChat {
+      group_chat_id: id.clone(),
+      executor_id: user_account_id.clone(),
+    })
+    .await
+    .unwrap();
+  let result = command_processor
+    .handle(GroupChatCommand::LeaveGroupChat {
+      group_chat_id: id.clone(),
+      executor_id: admin_id.clone(),
+    })
+    .await;
+
+  // Then
+  assert!(result.is_err());
+  let group_chat = repository.find_by_id(&id).await.unwrap().unwrap();
+  assert!(!group_chat.members().is_member(&user_account_id));
+  assert!(group_chat.members().is_administrator(&admin_id));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_group_chat_erase_user_account() {
+  let (repository, _container, _client) = get_repository().await;
+  // Given
+  let name = GroupChatName::new("ABC").unwrap();
+  let admin_id = UserAccountId::new();
+  let user_account_id = UserAccountId::new();
+  let mut command_processor = GroupChatCommandProcessor::new(repository.clone());
+  let id = command_processor
+    .handle(GroupChatCommand::CreateGroupChat {
+      group_chat_id: GroupChatId::new(),
+      name: name.clone(),
+      executor_id: admin_id.clone(),
+    })
+    .await
+    .unwrap();
+  command_processor
+    .handle(GroupChatCommand::AddMember {
+      group_chat_id: id.clone(),
+      user_account_id: user_account_id.clone(),
+      role: MemberRole::Member,
+      executor_id: admin_id.clone(),
+    })
+    .await
+    .unwrap();
+  let message = Message::new(MessageId::new(), "ABC".to_string(), user_account_id.clone());
+  command_processor
+    .handle(GroupChatCommand::PostMessage {
+      group_chat_id: id.clone(),
+      message,
+      executor_id: user_account_id.clone(),
+    })
+    .await
+    .unwrap();
+
+  // When
+  let erased = command_processor
+    .erase_user_account(user_account_id.clone(), vec![id.clone()], admin_id.clone())
+    .await
+    .unwrap();
+  let erased_again = command_processor
+    .erase_user_account(user_account_id.clone(), vec![id.clone()], admin_id.clone())
+    .await
+    .unwrap();
+
+  // Then
+  assert_eq!(erased, vec![id.clone()]);
+  assert!(erased_again.is_empty());
+  let group_chat = repository.find_by_id(&id).await.unwrap().unwrap();
+  assert!(!group_chat.members().is_member(&user_account_id));
+  let message = group_chat.messages().get_at(0).unwrap();
+  assert!(message.is_redacted());
+  assert_eq!(message.breach_encapsulation_of_text(), "");
+}
+
 #[tokio::test]
 #[serial]
 async fn test_group_chat_rename() {
-  let (repository, container, client) = get_repository().await;
+  let (repository, _container, _client) = get_repository().await;
   // Given
   let name = GroupChatName::new("ABC").unwrap();
   let admin_id = UserAccountId::new();
@@ -36,14 +228,22 @@ async fn test_group_chat_rename() {
   let mut command_processor = GroupChatCommandProcessor::new(repository.clone());
 
   let id = command_processor
-    .create_group_chat(name.clone(), admin_id.clone())
+    .handle(GroupChatCommand::CreateGroupChat {
+      group_chat_id: GroupChatId::new(),
+      name: name.clone(),
+      executor_id: admin_id.clone(),
+    })
     .await
     .unwrap();
 
   // When
   let name = GroupChatName::new("DEF").unwrap();
   let result = command_processor
-    .rename_group_chat(id.clone(), name.clone(), admin_id)
+    .handle(GroupChatCommand::RenameGroupChat {
+      group_chat_id: id.clone(),
+      name: name.clone(),
+      executor_id: admin_id,
+    })
     .await;
 
   // Then
@@ -55,7 +255,7 @@ async fn test_group_chat_rename() {
 #[tokio::test]
 #[serial]
 async fn test_group_chat_add_member() {
-  let (repository, container, client) = get_repository().await;
+  let (repository, _container, _client) = get_repository().await;
   // with_repository(|mut repository| async move {
   // Given
   let name = GroupChatName::new("ABC").unwrap();
@@ -63,7 +263,11 @@ async fn test_group_chat_add_member() {
   let _members = Members::new(admin_id.clone());
   let mut command_processor = GroupChatCommandProcessor::new(repository.clone());
   let id = command_processor
-    .create_group_chat(name.clone(), admin_id.clone())
+    .handle(GroupChatCommand::CreateGroupChat {
+      group_chat_id: GroupChatId::new(),
+      name: name.clone(),
+      executor_id: admin_id.clone(),
+    })
     .await
     .unwrap();
   let user_account_id = UserAccountId::new();
@@ -71,7 +275,12 @@ async fn test_group_chat_add_member() {
 
   // When
   let result = command_processor
-    .add_member(id.clone(), user_account_id.clone(), role, admin_id.clone())
+    .handle(GroupChatCommand::AddMember {
+      group_chat_id: id.clone(),
+      user_account_id: user_account_id.clone(),
+      role,
+      executor_id: admin_id.clone(),
+    })
     .await;
 
   // Then
@@ -84,7 +293,7 @@ async fn test_group_chat_add_member() {
 #[tokio::test]
 #[serial]
 async fn test_group_chat_remove_member() {
-  let (repository, container, client) = get_repository().await;
+  let (repository, _container, _client) = get_repository().await;
   let user_account_id = UserAccountId::new();
   let admin_id = UserAccountId::new();
 
@@ -93,22 +302,30 @@ async fn test_group_chat_remove_member() {
   let _members = Members::new(admin_id.clone());
   let mut command_processor = GroupChatCommandProcessor::new(repository.clone());
   let id = command_processor
-    .create_group_chat(name.clone(), admin_id.clone())
+    .handle(GroupChatCommand::CreateGroupChat {
+      group_chat_id: GroupChatId::new(),
+      name: name.clone(),
+      executor_id: admin_id.clone(),
+    })
     .await
     .unwrap();
   command_processor
-    .add_member(
-      id.clone(),
-      user_account_id.clone(),
-      MemberRole::Member,
-      admin_id.clone(),
-    )
+    .handle(GroupChatCommand::AddMember {
+      group_chat_id: id.clone(),
+      user_account_id: user_account_id.clone(),
+      role: MemberRole::Member,
+      executor_id: admin_id.clone(),
+    })
     .await
     .unwrap();
 
   // When
   let result = command_processor
-    .remove_member(id.clone(), user_account_id.clone(), admin_id.clone())
+    .handle(GroupChatCommand::RemoveMember {
+      group_chat_id: id.clone(),
+      user_account_id: user_account_id.clone(),
+      executor_id: admin_id.clone(),
+    })
     .await;
 
   // Then
@@ -121,20 +338,29 @@ async fn test_group_chat_remove_member() {
 #[tokio::test]
 #[serial]
 async fn test_group_chat_post_message() {
-  let (repository, container, client) = get_repository().await;
+  let (repository, _container, _client) = get_repository().await;
   // Given
   let name = GroupChatName::new("ABC").unwrap();
   let admin_id = UserAccountId::new();
   let _members = Members::new(admin_id.clone());
   let mut command_processor = GroupChatCommandProcessor::new(repository.clone());
   let id = command_processor
-    .create_group_chat(name.clone(), admin_id.clone())
+    .handle(GroupChatCommand::CreateGroupChat {
+      group_chat_id: GroupChatId::new(),
+      name: name.clone(),
+      executor_id: admin_id.clone(),
+    })
     .await
     .unwrap();
   let user_account_id = UserAccountId::new();
   let role = MemberRole::Member;
   command_processor
-    .add_member(id.clone(), user_account_id.clone(), role, admin_id.clone())
+    .handle(GroupChatCommand::AddMember {
+      group_chat_id: id.clone(),
+      user_account_id: user_account_id.clone(),
+      role,
+      executor_id: admin_id.clone(),
+    })
     .await
     .unwrap();
   let message_id = MessageId::new();
@@ -143,7 +369,11 @@ async fn test_group_chat_post_message() {
 
   // When
   let result = command_processor
-    .post_message(id.clone(), message, user_account_id.clone())
+    .handle(GroupChatCommand::PostMessage {
+      group_chat_id: id.clone(),
+      message,
+      executor_id: user_account_id.clone(),
+    })
     .await;
 
   // Then
@@ -159,20 +389,29 @@ async fn test_group_chat_post_message() {
 #[tokio::test]
 #[serial]
 async fn test_group_chat_edit_message() {
-  let (repository, container, client) = get_repository().await;
+  let (repository, _container, _client) = get_repository().await;
   // Given
   let name = GroupChatName::new("ABC").unwrap();
   let admin_id = UserAccountId::new();
   let _members = Members::new(admin_id.clone());
   let mut command_processor = GroupChatCommandProcessor::new(repository.clone());
   let id = command_processor
-    .create_group_chat(name.clone(), admin_id.clone())
+    .handle(GroupChatCommand::CreateGroupChat {
+      group_chat_id: GroupChatId::new(),
+      name: name.clone(),
+      executor_id: admin_id.clone(),
+    })
     .await
     .unwrap();
   let user_account_id = UserAccountId::new();
   let role = MemberRole::Member;
   command_processor
-    .add_member(id.clone(), user_account_id.clone(), role, admin_id.clone())
+    .handle(GroupChatCommand::AddMember {
+      group_chat_id: id.clone(),
+      user_account_id: user_account_id.clone(),
+      role,
+      executor_id: admin_id.clone(),
+    })
     .await
     .unwrap();
   let message_id = MessageId::new();
@@ -180,13 +419,21 @@ async fn test_group_chat_edit_message() {
   let message = Message::new(message_id, text.clone(), user_account_id.clone());
 
   let _ = command_processor
-    .post_message(id.clone(), message.clone(), user_account_id.clone())
+    .handle(GroupChatCommand::PostMessage {
+      group_chat_id: id.clone(),
+      message: message.clone(),
+      executor_id: user_account_id.clone(),
+    })
     .await;
 
   let text2 = "DEF".to_string();
   let message = message.with_text(text2.clone());
   let result = command_processor
-    .edit_message(id.clone(), message.clone(), user_account_id.clone())
+    .handle(GroupChatCommand::EditMessage {
+      group_chat_id: id.clone(),
+      message: message.clone(),
+      executor_id: user_account_id.clone(),
+    })
     .await;
 
   // Then
@@ -202,37 +449,50 @@ async fn test_group_chat_edit_message() {
 #[tokio::test]
 #[serial]
 async fn test_group_chat_delete_message() {
-  let (repository, container, client) = get_repository().await;
+  let (repository, _container, _client) = get_repository().await;
   // Given
   let name = GroupChatName::new("ABC").unwrap();
   let admin_id = UserAccountId::new();
   let _members = Members::new(admin_id.clone());
   let mut command_processor = GroupChatCommandProcessor::new(repository.clone());
   let id = command_processor
-    .create_group_chat(name.clone(), admin_id.clone())
+    .handle(GroupChatCommand::CreateGroupChat {
+      group_chat_id: GroupChatId::new(),
+      name: name.clone(),
+      executor_id: admin_id.clone(),
+    })
     .await
     .unwrap();
   let user_account_id = UserAccountId::new();
   let role = MemberRole::Member;
   command_processor
-    .add_member(id.clone(), user_account_id.clone(), role, admin_id.clone())
+    .handle(GroupChatCommand::AddMember {
+      group_chat_id: id.clone(),
+      user_account_id: user_account_id.clone(),
+      role,
+      executor_id: admin_id.clone(),
+    })
     .await
     .unwrap();
   let message_id = MessageId::new();
   let text = "ABC".to_string();
   let message = Message::new(message_id, text.clone(), user_account_id.clone());
   command_processor
-    .post_message(id.clone(), message.clone(), user_account_id.clone())
+    .handle(GroupChatCommand::PostMessage {
+      group_chat_id: id.clone(),
+      message: message.clone(),
+      executor_id: user_account_id.clone(),
+    })
     .await
     .unwrap();
 
   // When
   let result = command_processor
-    .delete_message(
-      id.clone(),
-      message.breach_encapsulation_of_id().clone(),
-      user_account_id.clone(),
-    )
+    .handle(GroupChatCommand::DeleteMessage {
+      group_chat_id: id.clone(),
+      message_id: message.breach_encapsulation_of_id().clone(),
+      executor_id: user_account_id.clone(),
+    })
     .await;
 
   // Then
@@ -241,24 +501,149 @@ async fn test_group_chat_delete_message() {
   assert_eq!(group_chat.messages().len(), 0);
 }
 
+#[tokio::test]
+#[serial]
+async fn test_group_chat_restore_message() {
+  let (repository, _container, _client) = get_repository().await;
+  // Given
+  let name = GroupChatName::new("ABC").unwrap();
+  let admin_id = UserAccountId::new();
+  let mut command_processor = GroupChatCommandProcessor::new(repository.clone());
+  let id = command_processor
+    .handle(GroupChatCommand::CreateGroupChat {
+      group_chat_id: GroupChatId::new(),
+      name: name.clone(),
+      executor_id: admin_id.clone(),
+    })
+    .await
+    .unwrap();
+  let message_id = MessageId::new();
+  let message = Message::new(message_id.clone(), "ABC".to_string(), admin_id.clone());
+  command_processor
+    .handle(GroupChatCommand::PostMessage {
+      group_chat_id: id.clone(),
+      message,
+      executor_id: admin_id.clone(),
+    })
+    .await
+    .unwrap();
+  command_processor
+    .handle(GroupChatCommand::DeleteMessage {
+      group_chat_id: id.clone(),
+      message_id: message_id.clone(),
+      executor_id: admin_id.clone(),
+    })
+    .await
+    .unwrap();
+
+  // When
+  let result = command_processor
+    .handle(GroupChatCommand::RestoreMessage {
+      group_chat_id: id.clone(),
+      message_id: message_id.clone(),
+      executor_id: admin_id.clone(),
+    })
+    .await;
+
+  // Then
+  assert!(result.is_ok());
+  let group_chat = repository.find_by_id(&id).await.unwrap().unwrap();
+  assert_eq!(group_chat.messages().len(), 1);
+  assert!(group_chat.messages().contains(&message_id));
+}
+
 #[tokio::test]
 #[serial]
 async fn test_group_chat_destroy() {
   init_logger();
 
-  let (repository, container, client) = get_repository().await;
+  let (repository, _container, _client) = get_repository().await;
   // Given
   let name = GroupChatName::new("ABC").unwrap();
   let admin_id = UserAccountId::new();
   let _members = Members::new(admin_id.clone());
   let mut command_processor = GroupChatCommandProcessor::new(repository.clone());
   let id = command_processor
-    .create_group_chat(name, admin_id.clone())
+    .handle(GroupChatCommand::CreateGroupChat {
+      group_chat_id: GroupChatId::new(),
+      name,
+      executor_id: admin_id.clone(),
+    })
     .await
     .unwrap();
   // When
-  let result = command_processor.delete_group_chat(id, admin_id).await;
+  let result = command_processor
+    .handle(GroupChatCommand::DeleteGroupChat {
+      group_chat_id: id,
+      executor_id: admin_id,
+    })
+    .await;
 
   // Then
   assert!(result.is_ok());
 }
+
+#[tokio::test]
+#[serial]
+async fn test_group_chat_execute_batch() {
+  init_logger();
+
+  let (repository, _container, _client) = get_repository().await;
+  // Given
+  let name = GroupChatName::new("ABC").unwrap();
+  let admin_id = UserAccountId::new();
+  let user_account_id = UserAccountId::new();
+  let message_id = MessageId::new();
+  let mut command_processor = GroupChatCommandProcessor::new(repository.clone());
+  let id = GroupChatId::new();
+
+  // When
+  command_processor
+    .execute_batch(
+      id.clone(),
+      vec![
+        GroupChatCommand::CreateGroupChat {
+          group_chat_id: id.clone(),
+          name,
+          executor_id: admin_id.clone(),
+        },
+        GroupChatCommand::AddMember {
+          group_chat_id: id.clone(),
+          user_account_id: user_account_id.clone(),
+          role: MemberRole::Member,
+          executor_id: admin_id.clone(),
+        },
+        GroupChatCommand::PostMessage {
+          group_chat_id: id.clone(),
+          message: Message::new(message_id.clone(), "ABC".to_string(), admin_id.clone()),
+          executor_id: admin_id.clone(),
+        },
+      ],
+    )
+    .await
+    .unwrap();
+  let result = command_processor
+    .execute_batch(
+      id.clone(),
+      vec![
+        GroupChatCommand::DeleteMessage {
+          group_chat_id: id.clone(),
+          message_id: message_id.clone(),
+          executor_id: admin_id.clone(),
+        },
+        GroupChatCommand::AddMember {
+          group_chat_id: id.clone(),
+          user_account_id: user_account_id.clone(),
+          role: MemberRole::Member,
+          executor_id: admin_id.clone(),
+        },
+      ],
+    )
+    .await;
+
+  // Then
+  assert!(result.is_err());
+  let group_chat = repository.find_by_id(&id).await.unwrap().unwrap();
+  assert!(group_chat.members().is_member(&user_account_id));
+  assert!(group_chat.messages().contains(&message_id));
+}
@@ -11,12 +11,12 @@ use aws_sdk_dynamodb::types::{
 };
 use aws_sdk_dynamodb::Client;
 use command_domain::group_chat::{GroupChat, GroupChatEvent, GroupChatId};
-use event_store_adapter_rs::EventStoreForDynamoDB;
 use testcontainers::core::WaitFor;
 use testcontainers::runners::AsyncRunner;
 use testcontainers::{ContainerAsync, GenericImage, ImageExt};
 
 use command_interface_adaptor_impl::gateways::group_chat_repository::GroupChatRepositoryImpl;
+use command_interface_adaptor_impl::gateways::multi_event_store::MultiEventStoreForDynamoDB;
 
 pub fn init_logger() {
   env::set_var("RUST_LOG", "debug");
@@ -190,8 +190,18 @@ async fn wait_table(client: &Client, target_table_name: &str) -> bool {
   }
 }
 
-pub async fn get_repository<'a>() -> (
-  GroupChatRepositoryImpl<EventStoreForDynamoDB<GroupChatId, GroupChat, GroupChatEvent>>,
+pub async fn get_repository() -> (
+  GroupChatRepositoryImpl<MultiEventStoreForDynamoDB<GroupChatId, GroupChat, GroupChatEvent>>,
+  ContainerAsync<GenericImage>,
+  Client,
+) {
+  let (epg, dynamodb_node, client) = get_event_store().await;
+  let repository = GroupChatRepositoryImpl::new(epg, 10);
+  (repository, dynamodb_node, client)
+}
+
+pub async fn get_event_store() -> (
+  MultiEventStoreForDynamoDB<GroupChatId, GroupChat, GroupChatEvent>,
   ContainerAsync<GenericImage>,
   Client,
 ) {
@@ -235,7 +245,7 @@ pub async fn get_repository<'a>() -> (
     sleep(Duration::from_millis(1000 * test_time_factor));
   }
 
-  let epg = EventStoreForDynamoDB::new(
+  let epg = MultiEventStoreForDynamoDB::new(
     client.clone(),
     journal_table_name.to_string(),
     journal_aid_index_name.to_string(),
@@ -243,6 +253,5 @@ pub async fn get_repository<'a>() -> (
     snapshot_aid_index_name.to_string(),
     64,
   );
-  let repository = GroupChatRepositoryImpl::new(epg, 10);
-  (repository, dynamodb_node, client)
+  (epg, dynamodb_node, client)
 }
@@ -3,4 +3,5 @@ mod common;
 pub mod gateways {
   pub mod group_chat_read_model_dao_impl;
   pub mod group_chat_repository;
+  pub mod multi_event_store;
 }
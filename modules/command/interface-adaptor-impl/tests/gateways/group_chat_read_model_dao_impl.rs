@@ -8,7 +8,9 @@ use testcontainers::runners::AsyncRunner;
 use testcontainers::{ContainerRequest, GenericImage, ImageExt};
 
 use crate::common::init_logger;
-use command_domain::group_chat::{GroupChatId, GroupChatName, MemberRole, Message};
+use command_domain::group_chat::{
+  ChatKind, GroupChatId, GroupChatName, GroupChatSettings, MemberRole, Message, Permission,
+};
 use command_domain::group_chat::{MemberId, MessageId};
 use command_domain::user_account::UserAccountId;
 use command_interface_adaptor_if::GroupChatReadModelUpdateDao;
@@ -80,7 +82,7 @@ async fn test_insert_group_chat() {
   let admin_id = UserAccountId::new();
 
   dao
-    .insert_group_chat(aggregate_id, name, admin_id, Utc::now())
+    .insert_group_chat(aggregate_id, ChatKind::Group, name, admin_id, Utc::now())
     .await
     .unwrap();
 }
@@ -104,7 +106,7 @@ async fn test_delete_group_chat() {
   let admin_id = UserAccountId::new();
 
   dao
-    .insert_group_chat(aggregate_id.clone(), name, admin_id, Utc::now())
+    .insert_group_chat(aggregate_id.clone(), ChatKind::Group, name, admin_id, Utc::now())
     .await
     .unwrap();
   dao.delete_group_chat(aggregate_id, Utc::now()).await.unwrap();
@@ -129,7 +131,13 @@ async fn test_rename_group_chat() {
   let admin_id = UserAccountId::new();
 
   dao
-    .insert_group_chat(aggregate_id.clone(), name, admin_id.clone(), Utc::now())
+    .insert_group_chat(
+      aggregate_id.clone(),
+      ChatKind::Group,
+      name,
+      admin_id.clone(),
+      Utc::now(),
+    )
     .await
     .unwrap();
 
@@ -137,6 +145,46 @@ async fn test_rename_group_chat() {
   dao.rename_group_chat(aggregate_id, name, Utc::now()).await.unwrap();
 }
 
+#[tokio::test]
+#[serial]
+async fn test_upsert_group_chat_settings() {
+  init_logger();
+
+  let mysql_node = mysql_image().start().await.unwrap();
+  let mysql_port = mysql_node.get_host_port_ipv4(3306).await.unwrap();
+
+  refinery_migrate(mysql_port);
+
+  let url = make_database_url_for_application(mysql_port);
+  let pool = MySqlPool::connect(&url).await.unwrap();
+  let dao = GroupChatReadModelUpdateDaoImpl::new(pool);
+
+  let aggregate_id = GroupChatId::new();
+  let name = GroupChatName::new("test").unwrap();
+  let admin_id = UserAccountId::new();
+
+  dao
+    .insert_group_chat(
+      aggregate_id.clone(),
+      ChatKind::Group,
+      name,
+      admin_id.clone(),
+      Utc::now(),
+    )
+    .await
+    .unwrap();
+
+  dao
+    .upsert_group_chat_settings(aggregate_id.clone(), GroupChatSettings::default(), Utc::now())
+    .await
+    .unwrap();
+  let settings = GroupChatSettings::new(10, Permission::AdminsOnly, Permission::Everyone, Some(30)).unwrap();
+  dao
+    .upsert_group_chat_settings(aggregate_id, settings, Utc::now())
+    .await
+    .unwrap();
+}
+
 #[tokio::test]
 #[serial]
 async fn test_insert_member() {
@@ -156,7 +204,7 @@ async fn test_insert_member() {
   let admin_id = UserAccountId::new();
 
   dao
-    .insert_group_chat(aggregate_id.clone(), name, admin_id, Utc::now())
+    .insert_group_chat(aggregate_id.clone(), ChatKind::Group, name, admin_id, Utc::now())
     .await
     .unwrap();
 
@@ -190,7 +238,7 @@ async fn test_delete_member() {
   let admin_id = UserAccountId::new();
 
   dao
-    .insert_group_chat(aggregate_id.clone(), name, admin_id, Utc::now())
+    .insert_group_chat(aggregate_id.clone(), ChatKind::Group, name, admin_id, Utc::now())
     .await
     .unwrap();
 
@@ -232,7 +280,7 @@ async fn test_post_message() {
   let admin_id = UserAccountId::new();
 
   dao
-    .insert_group_chat(aggregate_id.clone(), name, admin_id, Utc::now())
+    .insert_group_chat(aggregate_id.clone(), ChatKind::Group, name, admin_id, Utc::now())
     .await
     .unwrap();
 
@@ -277,7 +325,7 @@ async fn test_delete_message() {
   let admin_id = UserAccountId::new();
 
   dao
-    .insert_group_chat(aggregate_id.clone(), name, admin_id, Utc::now())
+    .insert_group_chat(aggregate_id.clone(), ChatKind::Group, name, admin_id, Utc::now())
     .await
     .unwrap();
 
@@ -305,7 +353,80 @@ async fn test_delete_message() {
     .unwrap();
 
   dao
-    .delete_message(message.breach_encapsulation_of_id().clone(), Utc::now())
+    .delete_message(
+      message.breach_encapsulation_of_id().clone(),
+      user_account_id.clone(),
+      Utc::now(),
+    )
+    .await
+    .unwrap();
+
+  dao
+    .restore_message(message.breach_encapsulation_of_id().clone(), Utc::now())
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_moderate_message() {
+  init_logger();
+
+  let mysql_node = mysql_image().start().await.unwrap();
+  let mysql_port = mysql_node.get_host_port_ipv4(3306).await.unwrap();
+
+  refinery_migrate(mysql_port);
+
+  let url = make_database_url_for_application(mysql_port);
+  let pool = MySqlPool::connect(&url).await.unwrap();
+  let dao = GroupChatReadModelUpdateDaoImpl::new(pool);
+
+  let aggregate_id = GroupChatId::new();
+  let _seq_nr = 1;
+  let name = GroupChatName::new("test").unwrap();
+  let admin_id = UserAccountId::new();
+
+  dao
+    .insert_group_chat(
+      aggregate_id.clone(),
+      ChatKind::Group,
+      name,
+      admin_id.clone(),
+      Utc::now(),
+    )
+    .await
+    .unwrap();
+
+  let member_id = MemberId::new();
+  let user_account_id = UserAccountId::new();
+  let role = MemberRole::Member;
+
+  dao
+    .insert_member(
+      aggregate_id.clone(),
+      member_id,
+      user_account_id.clone(),
+      role,
+      Utc::now(),
+    )
+    .await
+    .unwrap();
+
+  let message_id = MessageId::new();
+  let message = Message::new(message_id, "test".to_string(), user_account_id.clone());
+
+  dao
+    .insert_message(aggregate_id.clone(), message.clone(), Utc::now())
+    .await
+    .unwrap();
+
+  dao
+    .moderate_message(
+      message.breach_encapsulation_of_id().clone(),
+      admin_id,
+      "spam".to_string(),
+      Utc::now(),
+    )
     .await
     .unwrap();
 }
@@ -1,11 +1,36 @@
-use crate::common::{get_repository, init_logger};
-use command_domain::group_chat::{GroupChat, GroupChatName, MemberRole};
+use std::sync::Arc;
+
+use crate::common::{get_event_store, get_repository, init_logger};
+use aws_sdk_dynamodb::types::AttributeValue;
+use aws_sdk_dynamodb::Client;
+use command_domain::group_chat::{GroupChat, GroupChatName, MemberRole, Message, MessageId};
 use command_domain::group_chat::{MemberId, Members};
 use command_domain::user_account::UserAccountId;
 use command_interface_adaptor_if::GroupChatRepository;
+use command_interface_adaptor_impl::gateways::group_chat_repository::GroupChatRepositoryImpl;
+use command_interface_adaptor_impl::gateways::payload_cipher::AesGcmPayloadCipher;
+use command_interface_adaptor_impl::gateways::payload_cipher_event_serializer::PayloadCipherEventSerializer;
+use command_interface_adaptor_impl::gateways::payload_cipher_snapshot_serializer::PayloadCipherSnapshotSerializer;
+use command_interface_adaptor_impl::gateways::user_account_key_store::MockUserAccountKeyStore;
 use event_store_adapter_rs::types::Aggregate;
+use serde_json::Value;
 use serial_test::serial;
 
+/// スナップショットのテーブルに保存されたペイロードを、復号せずにJSONとして取得する。
+async fn get_raw_snapshot(client: &Client, group_chat: &GroupChat) -> Value {
+  let output = client
+    .scan()
+    .table_name("snapshot")
+    .filter_expression("aid = :aid")
+    .expression_attribute_values(":aid", AttributeValue::S(group_chat.id().to_string()))
+    .send()
+    .await
+    .unwrap();
+  let item = output.items().first().unwrap();
+  let payload = item.get("payload").unwrap().as_b().unwrap();
+  serde_json::from_slice(payload.as_ref()).unwrap()
+}
+
 #[tokio::test]
 #[serial]
 async fn test_group_chat_create() {
@@ -78,6 +103,55 @@ async fn test_group_chat_add_member() {
   assert!(actual.members().is_member(&user_account_id));
 
   drop(client);
-  let _ = container.stop().await.unwrap();
+  container.stop().await.unwrap();
+  drop(container);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_group_chat_snapshot_is_encrypted() {
+  init_logger();
+  let (event_store, container, client) = get_event_store().await;
+  let payload_cipher = Arc::new(AesGcmPayloadCipher::new(Arc::new(MockUserAccountKeyStore::default())));
+  let event_store = event_store
+    .with_event_serializer(Arc::new(PayloadCipherEventSerializer::new(payload_cipher.clone())))
+    .with_snapshot_serializer(Arc::new(PayloadCipherSnapshotSerializer::new(payload_cipher)));
+  // NOTE: 1件ずつの保存でも常にスナップショットを保存するように、間隔を1にする
+  let mut repository = GroupChatRepositoryImpl::new(event_store, 1);
+  let admin_id = UserAccountId::new();
+  let (mut group_chat, create_event) =
+    GroupChat::new(GroupChatName::new("ABC").unwrap(), Members::new(admin_id.clone()));
+  repository.store(&create_event, &group_chat).await.unwrap();
+
+  // When: 1件ずつ保存する
+  let message = Message::new(MessageId::new(), "first secret".to_string(), admin_id.clone());
+  let event = group_chat.post_message(message, admin_id.clone()).unwrap();
+  repository.store(&event, &group_chat).await.unwrap();
+
+  // Then
+  let snapshot = get_raw_snapshot(&client, &group_chat).await;
+  assert_eq!(snapshot["messages"][0]["body"]["type"], "Encrypted");
+  assert!(!snapshot.to_string().contains("first secret"));
+
+  // When: まとめて保存する
+  let message = Message::new(MessageId::new(), "second secret".to_string(), admin_id.clone());
+  let event = group_chat.post_message(message, admin_id.clone()).unwrap();
+  repository.store_all(&[event], &group_chat).await.unwrap();
+
+  // Then
+  let snapshot = get_raw_snapshot(&client, &group_chat).await;
+  assert_eq!(snapshot["messages"][1]["body"]["type"], "Encrypted");
+  assert!(!snapshot.to_string().contains("second secret"));
+
+  let actual = repository.find_by_id(group_chat.id()).await.unwrap().unwrap();
+  let texts = actual
+    .messages()
+    .iter()
+    .map(|message| message.breach_encapsulation_of_text())
+    .collect::<Vec<_>>();
+  assert_eq!(texts, vec!["first secret", "second secret"]);
+
+  drop(client);
+  container.stop().await.unwrap();
   drop(container);
 }
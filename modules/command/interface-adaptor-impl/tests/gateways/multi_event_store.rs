@@ -0,0 +1,121 @@
+use crate::common::{get_event_store, init_logger};
+use command_domain::group_chat::{
+  GroupChat, GroupChatEvent, GroupChatId, GroupChatName, MemberId, MemberRole, Members, Message, MessageId,
+};
+use command_domain::user_account::UserAccountId;
+use command_interface_adaptor_impl::gateways::multi_event_store::MultiEventStore;
+use event_store_adapter_rs::types::{Aggregate, Event, EventStore, EventStoreWriteError};
+use event_store_adapter_rs::EventStoreForDynamoDB;
+use serial_test::serial;
+
+/// NOTE: MultiEventStoreForDynamoDBはevent-store-adapter-rsの項目の形式を独自に実装しているので、
+/// まとめて保存した項目をEventStoreForDynamoDBで読み書きできることを確認する。
+#[tokio::test]
+#[serial]
+async fn test_batch_written_items_are_compatible_with_event_store_for_dynamodb() {
+  init_logger();
+  let (mut multi_event_store, container, client) = get_event_store().await;
+  let mut event_store = EventStoreForDynamoDB::<GroupChatId, GroupChat, GroupChatEvent>::new(
+    client.clone(),
+    "journal".to_string(),
+    "journal-aid-index".to_string(),
+    "snapshot".to_string(),
+    "snapshot-aid-index".to_string(),
+    64,
+  );
+  let admin_id = UserAccountId::new();
+
+  // Given: 作成とメンバーの追加をまとめて保存する
+  let (mut group_chat, create_event) =
+    GroupChat::new(GroupChatName::new("ABC").unwrap(), Members::new(admin_id.clone()));
+  let add_member_event = group_chat
+    .add_member(
+      MemberId::new(),
+      UserAccountId::new(),
+      MemberRole::Member,
+      admin_id.clone(),
+    )
+    .unwrap();
+  multi_event_store
+    .persist_events_and_snapshot(&[create_event.clone(), add_member_event.clone()], &group_chat)
+    .await
+    .unwrap();
+
+  // Then: EventStoreForDynamoDBで読み込める
+  let snapshot = event_store
+    .get_latest_snapshot_by_id(group_chat.id())
+    .await
+    .unwrap()
+    .unwrap();
+  assert_eq!(snapshot, group_chat);
+  assert_eq!(snapshot.version(), 1);
+  let events = event_store
+    .get_events_by_id_since_seq_nr(group_chat.id(), 0)
+    .await
+    .unwrap();
+  assert_eq!(
+    events.iter().map(|event| event.seq_nr()).collect::<Vec<_>>(),
+    vec![create_event.seq_nr(), add_member_event.seq_nr()]
+  );
+
+  // When: EventStoreForDynamoDBで1件保存した後に、まとめて保存する
+  let mut group_chat = snapshot;
+  let rename_event = group_chat
+    .rename(GroupChatName::new("DEF").unwrap(), admin_id.clone())
+    .unwrap();
+  event_store
+    .persist_event_and_snapshot(&rename_event, &group_chat)
+    .await
+    .unwrap();
+  let mut group_chat = multi_event_store
+    .get_latest_snapshot_by_id(group_chat.id())
+    .await
+    .unwrap()
+    .unwrap();
+  assert_eq!(group_chat.version(), 2);
+  let stale_group_chat = group_chat.clone();
+  let message = Message::new(MessageId::new(), "Hello".to_string(), admin_id.clone());
+  let post_message_event = group_chat.post_message(message, admin_id.clone()).unwrap();
+  multi_event_store
+    .persist_events_and_snapshot(std::slice::from_ref(&post_message_event), &group_chat)
+    .await
+    .unwrap();
+
+  // Then: バージョンとイベントが連続している
+  let snapshot = event_store
+    .get_latest_snapshot_by_id(group_chat.id())
+    .await
+    .unwrap()
+    .unwrap();
+  assert_eq!(snapshot.version(), 3);
+  assert_eq!(snapshot.name(), &GroupChatName::new("DEF").unwrap());
+  assert_eq!(snapshot.messages().len(), 1);
+  let events = event_store
+    .get_events_by_id_since_seq_nr(group_chat.id(), rename_event.seq_nr())
+    .await
+    .unwrap();
+  assert_eq!(
+    events.iter().map(|event| event.seq_nr()).collect::<Vec<_>>(),
+    vec![rename_event.seq_nr(), post_message_event.seq_nr()]
+  );
+
+  // Then: 古いバージョンのスナップショットでは、どちらの方法でも保存できない
+  let mut stale_group_chat = stale_group_chat;
+  let stale_event = stale_group_chat
+    .rename(GroupChatName::new("GHI").unwrap(), admin_id.clone())
+    .unwrap();
+  let error = multi_event_store
+    .persist_events_and_snapshot(std::slice::from_ref(&stale_event), &stale_group_chat)
+    .await
+    .unwrap_err();
+  assert!(matches!(error, EventStoreWriteError::OptimisticLockError(_)));
+  let error = event_store
+    .persist_event_and_snapshot(&stale_event, &stale_group_chat)
+    .await
+    .unwrap_err();
+  assert!(matches!(error, EventStoreWriteError::OptimisticLockError(_)));
+
+  drop(client);
+  container.stop().await.unwrap();
+  drop(container);
+}
@@ -0,0 +1,191 @@
+use serde::{Deserialize, Serialize};
+
+use command_domain::group_chat::{GroupChatId, GroupChatName, GroupChatSettings, MemberRole, Message, MessageId};
+use command_domain::user_account::UserAccountId;
+
+/// グループチャットに対するコマンド。
+///
+/// [crate::group_chat_command_processor::GroupChatCommandProcessor::handle]で処理する。
+/// JSONにシリアライズできるので、ログに出力して再実行やデバッグに利用できる。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GroupChatCommand {
+  /// グループチャットを作成する。IDは呼び出し元で採番する
+  CreateGroupChat {
+    group_chat_id: GroupChatId,
+    name: GroupChatName,
+    executor_id: UserAccountId,
+  },
+  /// 1対1のチャットを開く。既に存在する場合は何もしない
+  OpenDirectChat {
+    other_user_account_id: UserAccountId,
+    executor_id: UserAccountId,
+  },
+  RenameGroupChat {
+    group_chat_id: GroupChatId,
+    name: GroupChatName,
+    executor_id: UserAccountId,
+  },
+  UpdateSettings {
+    group_chat_id: GroupChatId,
+    settings: GroupChatSettings,
+    executor_id: UserAccountId,
+  },
+  AddMember {
+    group_chat_id: GroupChatId,
+    user_account_id: UserAccountId,
+    role: MemberRole,
+    executor_id: UserAccountId,
+  },
+  InviteMember {
+    group_chat_id: GroupChatId,
+    user_account_id: UserAccountId,
+    role: MemberRole,
+    executor_id: UserAccountId,
+  },
+  AcceptInvitation {
+    group_chat_id: GroupChatId,
+    executor_id: UserAccountId,
+  },
+  DeclineInvitation {
+    group_chat_id: GroupChatId,
+    executor_id: UserAccountId,
+  },
+  RevokeInvitation {
+    group_chat_id: GroupChatId,
+    user_account_id: UserAccountId,
+    executor_id: UserAccountId,
+  },
+  RemoveMember {
+    group_chat_id: GroupChatId,
+    user_account_id: UserAccountId,
+    executor_id: UserAccountId,
+  },
+  LeaveGroupChat {
+    group_chat_id: GroupChatId,
+    executor_id: UserAccountId,
+  },
+  PostMessage {
+    group_chat_id: GroupChatId,
+    message: Message,
+    executor_id: UserAccountId,
+  },
+  EditMessage {
+    group_chat_id: GroupChatId,
+    message: Message,
+    executor_id: UserAccountId,
+  },
+  DeleteMessage {
+    group_chat_id: GroupChatId,
+    message_id: MessageId,
+    executor_id: UserAccountId,
+  },
+  ModerateMessage {
+    group_chat_id: GroupChatId,
+    message_id: MessageId,
+    reason: String,
+    executor_id: UserAccountId,
+  },
+  RestoreMessage {
+    group_chat_id: GroupChatId,
+    message_id: MessageId,
+    executor_id: UserAccountId,
+  },
+  /// 保持期間を過ぎたメッセージを完全に削除する。システムが実行するので実行者はいない
+  ExpireMessage {
+    group_chat_id: GroupChatId,
+    message_id: MessageId,
+  },
+  /// ユーザアカウントに関するデータをグループチャットから消去する
+  EraseUserAccount {
+    group_chat_id: GroupChatId,
+    user_account_id: UserAccountId,
+    executor_id: UserAccountId,
+  },
+  DeleteGroupChat {
+    group_chat_id: GroupChatId,
+    executor_id: UserAccountId,
+  },
+}
+
+impl GroupChatCommand {
+  /// コマンドの名前(ログやメトリクスのラベルに利用する)。
+  pub fn name(&self) -> &'static str {
+    match self {
+      GroupChatCommand::CreateGroupChat { .. } => "create_group_chat",
+      GroupChatCommand::OpenDirectChat { .. } => "open_direct_chat",
+      GroupChatCommand::RenameGroupChat { .. } => "rename_group_chat",
+      GroupChatCommand::UpdateSettings { .. } => "update_settings",
+      GroupChatCommand::AddMember { .. } => "add_member",
+      GroupChatCommand::InviteMember { .. } => "invite_member",
+      GroupChatCommand::AcceptInvitation { .. } => "accept_invitation",
+      GroupChatCommand::DeclineInvitation { .. } => "decline_invitation",
+      GroupChatCommand::RevokeInvitation { .. } => "revoke_invitation",
+      GroupChatCommand::RemoveMember { .. } => "remove_member",
+      GroupChatCommand::LeaveGroupChat { .. } => "leave_group_chat",
+      GroupChatCommand::PostMessage { .. } => "post_message",
+      GroupChatCommand::EditMessage { .. } => "edit_message",
+      GroupChatCommand::DeleteMessage { .. } => "delete_message",
+      GroupChatCommand::ModerateMessage { .. } => "moderate_message",
+      GroupChatCommand::RestoreMessage { .. } => "restore_message",
+      GroupChatCommand::ExpireMessage { .. } => "expire_message",
+      GroupChatCommand::EraseUserAccount { .. } => "erase_user_account",
+      GroupChatCommand::DeleteGroupChat { .. } => "delete_group_chat",
+    }
+  }
+
+  /// コマンドの対象となるグループチャットのID。
+  ///
+  /// [GroupChatCommand::OpenDirectChat]の場合は、2人のユーザアカウントIDから導出したIDを返す。
+  pub fn group_chat_id(&self) -> GroupChatId {
+    match self {
+      GroupChatCommand::OpenDirectChat {
+        other_user_account_id,
+        executor_id,
+      } => GroupChatId::direct(executor_id, other_user_account_id),
+      GroupChatCommand::CreateGroupChat { group_chat_id, .. }
+      | GroupChatCommand::RenameGroupChat { group_chat_id, .. }
+      | GroupChatCommand::UpdateSettings { group_chat_id, .. }
+      | GroupChatCommand::AddMember { group_chat_id, .. }
+      | GroupChatCommand::InviteMember { group_chat_id, .. }
+      | GroupChatCommand::AcceptInvitation { group_chat_id, .. }
+      | GroupChatCommand::DeclineInvitation { group_chat_id, .. }
+      | GroupChatCommand::RevokeInvitation { group_chat_id, .. }
+      | GroupChatCommand::RemoveMember { group_chat_id, .. }
+      | GroupChatCommand::LeaveGroupChat { group_chat_id, .. }
+      | GroupChatCommand::PostMessage { group_chat_id, .. }
+      | GroupChatCommand::EditMessage { group_chat_id, .. }
+      | GroupChatCommand::DeleteMessage { group_chat_id, .. }
+      | GroupChatCommand::ModerateMessage { group_chat_id, .. }
+      | GroupChatCommand::RestoreMessage { group_chat_id, .. }
+      | GroupChatCommand::ExpireMessage { group_chat_id, .. }
+      | GroupChatCommand::EraseUserAccount { group_chat_id, .. }
+      | GroupChatCommand::DeleteGroupChat { group_chat_id, .. } => group_chat_id.clone(),
+    }
+  }
+
+  /// コマンドの実行者。システムが実行するコマンドの場合は`None`を返す。
+  pub fn executor_id(&self) -> Option<&UserAccountId> {
+    match self {
+      GroupChatCommand::ExpireMessage { .. } => None,
+      GroupChatCommand::CreateGroupChat { executor_id, .. }
+      | GroupChatCommand::OpenDirectChat { executor_id, .. }
+      | GroupChatCommand::RenameGroupChat { executor_id, .. }
+      | GroupChatCommand::UpdateSettings { executor_id, .. }
+      | GroupChatCommand::AddMember { executor_id, .. }
+      | GroupChatCommand::InviteMember { executor_id, .. }
+      | GroupChatCommand::AcceptInvitation { executor_id, .. }
+      | GroupChatCommand::DeclineInvitation { executor_id, .. }
+      | GroupChatCommand::RevokeInvitation { executor_id, .. }
+      | GroupChatCommand::RemoveMember { executor_id, .. }
+      | GroupChatCommand::LeaveGroupChat { executor_id, .. }
+      | GroupChatCommand::PostMessage { executor_id, .. }
+      | GroupChatCommand::EditMessage { executor_id, .. }
+      | GroupChatCommand::DeleteMessage { executor_id, .. }
+      | GroupChatCommand::ModerateMessage { executor_id, .. }
+      | GroupChatCommand::RestoreMessage { executor_id, .. }
+      | GroupChatCommand::EraseUserAccount { executor_id, .. }
+      | GroupChatCommand::DeleteGroupChat { executor_id, .. } => Some(executor_id),
+    }
+  }
+}
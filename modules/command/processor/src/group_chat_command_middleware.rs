@@ -0,0 +1,294 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use command_domain::group_chat::GroupChatId;
+use command_domain::user_account::UserAccountId;
+use once_cell::sync::Lazy;
+use prometheus::{
+  register_histogram, register_histogram_vec, register_int_counter_vec, Histogram, HistogramVec, IntCounterVec,
+};
+
+use crate::group_chat_command::GroupChatCommand;
+use crate::group_chat_command_processor::CommandProcessError;
+
+/// [crate::group_chat_command_processor::GroupChatCommandProcessor]がコマンドを処理する前後に呼び出すフック。
+///
+/// ログ出力・認可・メトリクス・検証などの横断的な処理を、コマンドごとの処理から分離するために利用する。
+#[async_trait::async_trait]
+pub trait GroupChatCommandMiddleware: Send + Sync + 'static {
+  /// コマンドを処理する前に呼び出す。
+  ///
+  /// # 戻り値
+  /// - Errを返した場合は、コマンドを処理せずにそのErrを呼び出し元に返す(認可や検証に利用する)。
+  async fn before(&self, _command: &GroupChatCommand) -> Result<(), CommandProcessError> {
+    Ok(())
+  }
+
+  /// コマンドを処理した後に呼び出す。[GroupChatCommandMiddleware::before]でErrを返した場合は呼び出さない。
+  ///
+  /// # 引数
+  /// - `command` - 処理したコマンド
+  /// - `result` - 処理の結果
+  /// - `elapsed` - 処理にかかった時間
+  async fn after(
+    &self,
+    _command: &GroupChatCommand,
+    _result: &Result<GroupChatId, CommandProcessError>,
+    _elapsed: Duration,
+  ) {
+  }
+
+  /// [crate::group_chat_command_processor::GroupChatCommandProcessor::execute_batch]でコマンドをまとめて処理した後に呼び出す。
+  /// いずれかのコマンドに対して[GroupChatCommandMiddleware::before]がErrを返した場合は呼び出さない。
+  ///
+  /// NOTE: バッチのコマンドは1つのトランザクションで保存するので、コマンドごとの処理時間は計測できない。
+  /// そのためバッチの場合は[GroupChatCommandMiddleware::after]ではなく、こちらを一度だけ呼び出す。
+  ///
+  /// # 引数
+  /// - `commands` - 処理したコマンド(適用順)
+  /// - `result` - バッチ全体の処理の結果
+  /// - `elapsed` - バッチ全体の処理にかかった時間
+  async fn after_batch(
+    &self,
+    _commands: &[GroupChatCommand],
+    _result: &Result<GroupChatId, CommandProcessError>,
+    _elapsed: Duration,
+  ) {
+  }
+}
+
+/// コマンドの内容を検証する[GroupChatCommandMiddleware]。
+///
+/// API経由のコマンドは必ず実行者を持つので、実行者のないコマンド([GroupChatCommand::ExpireMessage])を拒否する。
+///
+/// NOTE: API経由のコマンドを処理する[crate::group_chat_command_processor::GroupChatCommandProcessor]に登録する。
+/// メッセージの保持期間を管理するジョブなど、システムがコマンドを実行するプロセッサには登録しないこと。
+#[derive(Debug, Clone, Default)]
+pub struct ValidationMiddleware;
+
+#[async_trait::async_trait]
+impl GroupChatCommandMiddleware for ValidationMiddleware {
+  async fn before(&self, command: &GroupChatCommand) -> Result<(), CommandProcessError> {
+    if command.executor_id().is_none() {
+      return Err(CommandProcessError::InvalidCommandError(format!(
+        "{} requires an executor",
+        command.name()
+      )));
+    }
+    Ok(())
+  }
+}
+
+/// 特権が必要なコマンドを、特権のない実行者から拒否する[GroupChatCommandMiddleware]。
+///
+/// - [GroupChatCommand::ExpireMessage]はシステムのみが実行できるので、常に拒否する。
+/// - [GroupChatCommand::EraseUserAccount]は、消去するユーザアカウント自身か、特権を持つユーザアカウントのみが実行できる。
+///
+/// NOTE: [ValidationMiddleware]と同じく、API経由のコマンドを処理するプロセッサにのみ登録すること。
+#[derive(Debug, Clone, Default)]
+pub struct AuthorizationMiddleware {
+  privileged_user_account_ids: HashSet<UserAccountId>,
+}
+
+impl AuthorizationMiddleware {
+  /// コンストラクタ。
+  ///
+  /// # 引数
+  /// - `privileged_user_account_ids` - 特権を持つユーザアカウントのID(運用者など)
+  pub fn new(privileged_user_account_ids: impl IntoIterator<Item = UserAccountId>) -> Self {
+    Self {
+      privileged_user_account_ids: privileged_user_account_ids.into_iter().collect(),
+    }
+  }
+
+  fn is_privileged(&self, executor_id: &UserAccountId) -> bool {
+    self.privileged_user_account_ids.contains(executor_id)
+  }
+}
+
+#[async_trait::async_trait]
+impl GroupChatCommandMiddleware for AuthorizationMiddleware {
+  async fn before(&self, command: &GroupChatCommand) -> Result<(), CommandProcessError> {
+    match command {
+      GroupChatCommand::ExpireMessage { .. } => Err(CommandProcessError::ForbiddenError(format!(
+        "{} can only be executed by the system",
+        command.name()
+      ))),
+      GroupChatCommand::EraseUserAccount {
+        user_account_id,
+        executor_id,
+        ..
+      } if executor_id != user_account_id && !self.is_privileged(executor_id) => {
+        Err(CommandProcessError::ForbiddenError(format!(
+          "{} requires privilege: executor_id = {}",
+          command.name(),
+          executor_id
+        )))
+      }
+      _ => Ok(()),
+    }
+  }
+}
+
+/// コマンドと処理の結果をログに出力する[GroupChatCommandMiddleware]。
+///
+/// NOTE: コマンドのJSONはメッセージの本文を含むので、`debug`レベルでのみ出力する。
+/// `info`レベルではコマンドの名前・グループチャットID・実行者のみを出力する。
+#[derive(Debug, Clone, Default)]
+pub struct LoggingMiddleware;
+
+#[async_trait::async_trait]
+impl GroupChatCommandMiddleware for LoggingMiddleware {
+  async fn before(&self, command: &GroupChatCommand) -> Result<(), CommandProcessError> {
+    if log::log_enabled!(log::Level::Debug) {
+      match serde_json::to_string(command) {
+        Ok(json) => log::debug!("command = {}", json),
+        Err(error) => log::warn!("failed to serialize the command: {:?}", error),
+      }
+    }
+    Ok(())
+  }
+
+  async fn after(
+    &self,
+    command: &GroupChatCommand,
+    result: &Result<GroupChatId, CommandProcessError>,
+    elapsed: Duration,
+  ) {
+    let executor_id = command
+      .executor_id()
+      .map(|executor_id| executor_id.to_string())
+      .unwrap_or_else(|| "-".to_string());
+    match result {
+      Ok(_) => log::info!(
+        "{}: group_chat_id = {}, executor_id = {}, elapsed = {:?}",
+        command.name(),
+        command.group_chat_id(),
+        executor_id,
+        elapsed
+      ),
+      Err(error) => log::warn!(
+        "{}: group_chat_id = {}, executor_id = {}, elapsed = {:?}, error = {}",
+        command.name(),
+        command.group_chat_id(),
+        executor_id,
+        elapsed,
+        error
+      ),
+    }
+  }
+
+  async fn after_batch(
+    &self,
+    commands: &[GroupChatCommand],
+    result: &Result<GroupChatId, CommandProcessError>,
+    elapsed: Duration,
+  ) {
+    let names = commands
+      .iter()
+      .map(|command| command.name())
+      .collect::<Vec<_>>()
+      .join(",");
+    let group_chat_id = commands
+      .first()
+      .map(|command| command.group_chat_id().to_string())
+      .unwrap_or_else(|| "-".to_string());
+    match result {
+      Ok(_) => log::info!(
+        "batch: group_chat_id = {}, commands = [{}], elapsed = {:?}",
+        group_chat_id,
+        names,
+        elapsed
+      ),
+      Err(error) => log::warn!(
+        "batch: group_chat_id = {}, commands = [{}], elapsed = {:?}, error = {}",
+        group_chat_id,
+        names,
+        elapsed,
+        error
+      ),
+    }
+  }
+}
+
+/// 処理したコマンドの件数(`command`: コマンドの名前, `result`: `ok`またはエラーの種類)
+static COMMANDS_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+  register_int_counter_vec!(
+    "group_chat_commands_total",
+    "Number of processed group chat commands",
+    &["command", "result"]
+  )
+  .unwrap()
+});
+
+/// コマンドの処理時間(秒)
+static COMMAND_DURATION_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+  register_histogram_vec!(
+    "group_chat_command_duration_seconds",
+    "Time to process a group chat command",
+    &["command"]
+  )
+  .unwrap()
+});
+
+/// バッチの処理時間(秒)
+static COMMAND_BATCH_DURATION_SECONDS: Lazy<Histogram> = Lazy::new(|| {
+  register_histogram!(
+    "group_chat_command_batch_duration_seconds",
+    "Time to process a batch of group chat commands"
+  )
+  .unwrap()
+});
+
+/// コマンドの件数と処理時間をPrometheusのメトリクスとして記録する[GroupChatCommandMiddleware]。
+///
+/// NOTE: [crate::group_chat_command_processor::GroupChatCommandProcessor::execute_batch]の場合は、
+/// 件数はコマンドごとに記録し、処理時間はバッチ全体の処理時間として別のメトリクスに記録する。
+#[derive(Debug, Clone, Default)]
+pub struct MetricsMiddleware;
+
+impl MetricsMiddleware {
+  fn result_label(result: &Result<GroupChatId, CommandProcessError>) -> &'static str {
+    match result {
+      Ok(_) => "ok",
+      Err(CommandProcessError::NotFoundError) => "not_found",
+      Err(CommandProcessError::RepositoryError(_)) => "repository_error",
+      Err(CommandProcessError::DomainLogicError(_)) => "domain_logic_error",
+      Err(CommandProcessError::PayloadCipherError(_)) => "payload_cipher_error",
+      Err(CommandProcessError::InvalidBatchError(_)) => "invalid_batch_error",
+      Err(CommandProcessError::InvalidCommandError(_)) => "invalid_command_error",
+      Err(CommandProcessError::ForbiddenError(_)) => "forbidden_error",
+    }
+  }
+}
+
+#[async_trait::async_trait]
+impl GroupChatCommandMiddleware for MetricsMiddleware {
+  async fn after(
+    &self,
+    command: &GroupChatCommand,
+    result: &Result<GroupChatId, CommandProcessError>,
+    elapsed: Duration,
+  ) {
+    COMMANDS_TOTAL
+      .with_label_values(&[command.name(), Self::result_label(result)])
+      .inc();
+    COMMAND_DURATION_SECONDS
+      .with_label_values(&[command.name()])
+      .observe(elapsed.as_secs_f64());
+  }
+
+  async fn after_batch(
+    &self,
+    commands: &[GroupChatCommand],
+    result: &Result<GroupChatId, CommandProcessError>,
+    elapsed: Duration,
+  ) {
+    for command in commands {
+      COMMANDS_TOTAL
+        .with_label_values(&[command.name(), Self::result_label(result)])
+        .inc();
+    }
+    COMMAND_BATCH_DURATION_SECONDS.observe(elapsed.as_secs_f64());
+  }
+}
@@ -1,12 +1,17 @@
+use chrono::Duration;
 use event_store_adapter_rs::types::Event;
 use std::sync::Arc;
+use std::time::Instant;
 use thiserror::Error;
 use tokio::sync::Mutex;
 
 use command_domain::group_chat::*;
 use command_domain::group_chat_error::GroupChatError;
 use command_domain::user_account::UserAccountId;
-use command_interface_adaptor_if::{GroupChatRepository, GroupChatRepositoryError};
+use command_interface_adaptor_if::{GroupChatRepository, GroupChatRepositoryError, PayloadCipher, PayloadCipherError};
+
+use crate::group_chat_command::GroupChatCommand;
+use crate::group_chat_command_middleware::GroupChatCommandMiddleware;
 
 #[derive(Error, Debug)]
 pub enum CommandProcessError {
@@ -16,6 +21,46 @@ pub enum CommandProcessError {
   RepositoryError(#[from] GroupChatRepositoryError),
   #[error("GroupChatError: {0:?}")]
   DomainLogicError(#[from] GroupChatError),
+  #[error("PayloadCipherError: {0:?}")]
+  PayloadCipherError(#[from] PayloadCipherError),
+  #[error("Invalid batch: {0}")]
+  InvalidBatchError(String),
+  #[error("Invalid command: {0}")]
+  InvalidCommandError(String),
+  #[error("Forbidden: {0}")]
+  ForbiddenError(String),
+}
+
+/// [GroupChatCommandProcessor]の設定。
+#[derive(Debug, Clone)]
+pub struct GroupChatCommandProcessorConfig {
+  /// 削除されたメッセージを復元できる期間
+  pub message_restore_window: Duration,
+  /// 投稿したメッセージを編集できる期間
+  pub message_edit_window: Duration,
+  /// 招待の有効期間
+  pub invitation_ttl: Duration,
+  /// 最後の管理者が退出しようとした場合の扱い
+  pub last_administrator_policy: LastAdministratorPolicy,
+  /// グループチャットに保持日数が設定されていない場合のメッセージの保持期間(未設定の場合は無期限)
+  pub default_message_retention: Option<Duration>,
+  /// [GroupChatCommandProcessor::execute_batch]で一度に適用できるコマンドの数
+  ///
+  /// NOTE: イベントとスナップショットを1つのトランザクションで保存するため、DynamoDBのトランザクションの上限(100件)未満にすること
+  pub max_batch_size: usize,
+}
+
+impl Default for GroupChatCommandProcessorConfig {
+  fn default() -> Self {
+    Self {
+      message_restore_window: Duration::hours(24),
+      message_edit_window: Duration::hours(24),
+      invitation_ttl: Duration::days(7),
+      last_administrator_policy: LastAdministratorPolicy::default(),
+      default_message_retention: None,
+      max_batch_size: 50,
+    }
+  }
 }
 
 /// グループチャットへのコマンドを処理するユースケース実装。
@@ -23,6 +68,9 @@ pub enum CommandProcessError {
 /// NOTE: コマンドを処理するユースケースをコマンドプロセッサと呼びます(クエリを処理するユースケースはクエリプロセッサとなりますが、今回はGraphQLを採用しているためクエリプロッサは定義されていません)
 pub struct GroupChatCommandProcessor<TR: GroupChatRepository> {
   group_chat_repository: Arc<Mutex<TR>>,
+  config: GroupChatCommandProcessorConfig,
+  payload_cipher: Option<Arc<dyn PayloadCipher>>,
+  middlewares: Vec<Arc<dyn GroupChatCommandMiddleware>>,
 }
 
 impl<TR: GroupChatRepository> GroupChatCommandProcessor<TR> {
@@ -31,267 +79,323 @@ impl<TR: GroupChatRepository> GroupChatCommandProcessor<TR> {
   /// # 引数
   /// - `group_chat_repository` - グループチャットリポジトリ
   pub fn new(group_chat_repository: TR) -> Self {
+    Self::new_with_config(group_chat_repository, GroupChatCommandProcessorConfig::default())
+  }
+
+  /// コンストラクタ(設定指定)。
+  ///
+  /// # 引数
+  /// - `group_chat_repository` - グループチャットリポジトリ
+  /// - `config` - [GroupChatCommandProcessorConfig]
+  pub fn new_with_config(group_chat_repository: TR, config: GroupChatCommandProcessorConfig) -> Self {
     Self {
       group_chat_repository: Arc::new(Mutex::new(group_chat_repository)),
+      config,
+      payload_cipher: None,
+      middlewares: Vec::new(),
     }
   }
 
-  /// グループチャットを作成する。
+  /// ユーザアカウントの消去時に鍵を削除する[PayloadCipher]を設定する(クリプトシュレッディング)。
   ///
   /// # 引数
-  /// - `name` - グループチャット名
-  /// - `executor_id` - 実行者のユーザーアカウントID
-  ///
-  /// # 戻り値
-  /// - 成功した場合はOk([GroupChatId]), 失敗した場合はErrを返す。
-  pub async fn create_group_chat(
-    &mut self,
-    name: GroupChatName,
-    executor_id: UserAccountId,
-  ) -> Result<GroupChatId, CommandProcessError> {
-    let mut repository_mg = self.group_chat_repository.lock().await;
-
-    let members = Members::new(executor_id);
-    let (group_chat, group_chat_event) = GroupChat::new(name, members);
-
-    repository_mg
-      .store(&group_chat_event, &group_chat)
-      .await
-      .map(|_| group_chat_event.aggregate_id().clone())
-      .map_err(|e| CommandProcessError::RepositoryError(e))
+  /// - `payload_cipher` - メッセージの本文の暗号化に利用している[PayloadCipher]
+  pub fn with_payload_cipher(mut self, payload_cipher: Arc<dyn PayloadCipher>) -> Self {
+    self.payload_cipher = Some(payload_cipher);
+    self
   }
 
-  /// グループチャットの名前を変更する。
+  /// コマンドの処理の前後で呼び出す[GroupChatCommandMiddleware]を追加する。
   ///
-  /// # 引数
-  /// - `id` - グループチャットID
-  /// - `name` - グループチャット名
+  /// 追加した順に呼び出す。
   ///
-  /// # 戻り値
-  /// - 成功した場合はOk([GroupChatId]), 失敗した場合はErrを返す。
-  pub async fn rename_group_chat(
-    &mut self,
-    id: GroupChatId,
-    name: GroupChatName,
-    executor_id: UserAccountId,
-  ) -> Result<GroupChatId, CommandProcessError> {
-    let mut repository_mg = self.group_chat_repository.lock().await;
-
-    let mut group_chat = repository_mg
-      .find_by_id(&id)
-      .await
-      .map_err(|e| CommandProcessError::RepositoryError(e))?
-      .ok_or(CommandProcessError::NotFoundError)?;
-
-    let group_chat_event = group_chat
-      .rename(name, executor_id)
-      .map_err(|e| CommandProcessError::DomainLogicError(e))?;
-
-    repository_mg
-      .store(&group_chat_event, &group_chat)
-      .await
-      .map(|_| group_chat_event.aggregate_id().clone())
-      .map_err(|e| CommandProcessError::RepositoryError(e))
+  /// # 引数
+  /// - `middleware` - [GroupChatCommandMiddleware]
+  pub fn with_middleware(mut self, middleware: Arc<dyn GroupChatCommandMiddleware>) -> Self {
+    self.middlewares.push(middleware);
+    self
   }
 
-  /// グループチャットにメンバーを追加する。
+  /// コマンドを処理する。
+  ///
+  /// グループチャットを読み込んでコマンドを適用し、発生したイベントを保存する。
+  /// 処理の前後で[GroupChatCommandMiddleware]を呼び出し、いずれかの[GroupChatCommandMiddleware::before]が
+  /// Errを返した場合はコマンドを処理しない。
   ///
   /// # 引数
-  /// - `id` - グループチャットID
-  /// - `user_account_id` - ユーザーアカウントID
-  /// - `role` - メンバーの役割
-  /// - `executor_id` - 実行者のユーザーアカウントID
+  /// - `command` - [GroupChatCommand]
   ///
   /// # 戻り値
-  /// - 成功した場合はOk([GroupChatId]), 失敗した場合はErrを返す。
-  pub async fn add_member(
-    &mut self,
-    id: GroupChatId,
-    user_account_id: UserAccountId,
-    role: MemberRole,
-    executor_id: UserAccountId,
-  ) -> Result<GroupChatId, CommandProcessError> {
-    let mut repository_mg = self.group_chat_repository.lock().await;
+  /// - 成功した場合はOk(対象の[GroupChatId]), 失敗した場合はErrを返す。
+  #[tracing::instrument(skip_all, fields(command = command.name(), group_chat_id = %command.group_chat_id()))]
+  pub async fn handle(&mut self, command: GroupChatCommand) -> Result<GroupChatId, CommandProcessError> {
+    for middleware in &self.middlewares {
+      middleware.before(&command).await?;
+    }
+    let started_at = Instant::now();
+    let result = self.dispatch(command.clone()).await;
+    let elapsed = started_at.elapsed();
+    for middleware in &self.middlewares {
+      middleware.after(&command, &result, elapsed).await;
+    }
+    result
+  }
 
-    let mut group_chat = repository_mg
-      .find_by_id(&id)
-      .await
-      .map_err(|e| CommandProcessError::RepositoryError(e))?
-      .ok_or(CommandProcessError::NotFoundError)?;
+  async fn dispatch(&mut self, command: GroupChatCommand) -> Result<GroupChatId, CommandProcessError> {
+    let mut repository_mg = self.group_chat_repository.lock().await;
 
-    let member_id = MemberId::new();
-    let group_chat_event = group_chat
-      .add_member(member_id, user_account_id, role, executor_id)
-      .map_err(|e| CommandProcessError::DomainLogicError(e))?;
+    let (group_chat, group_chat_event) = match command {
+      GroupChatCommand::CreateGroupChat {
+        group_chat_id,
+        name,
+        executor_id,
+      } => GroupChat::from(group_chat_id, false, name, Members::new(executor_id), 0, 1),
+      GroupChatCommand::OpenDirectChat {
+        other_user_account_id,
+        executor_id,
+      } => {
+        // NOTE: 同じ2人の組み合わせのチャットが既に存在する場合は、新たに作成せずにそのIDを返す
+        let id = GroupChatId::direct(&executor_id, &other_user_account_id);
+        if repository_mg
+          .find_by_id(&id)
+          .await
+          .map_err(CommandProcessError::RepositoryError)?
+          .is_some()
+        {
+          return Ok(id);
+        }
+        GroupChat::new_direct(executor_id, other_user_account_id).map_err(CommandProcessError::DomainLogicError)?
+      }
+      command => {
+        let mut group_chat = repository_mg
+          .find_by_id(&command.group_chat_id())
+          .await
+          .map_err(CommandProcessError::RepositoryError)?
+          .ok_or(CommandProcessError::NotFoundError)?;
+        let group_chat_event = self
+          .apply_command(&mut group_chat, command)
+          .map_err(CommandProcessError::DomainLogicError)?;
+        (group_chat, group_chat_event)
+      }
+    };
 
     repository_mg
       .store(&group_chat_event, &group_chat)
       .await
       .map(|_| group_chat_event.aggregate_id().clone())
-      .map_err(|e| CommandProcessError::RepositoryError(e))
+      .map_err(CommandProcessError::RepositoryError)
   }
 
-  /// グループチャットからメンバーを削除する。
+  /// ユーザアカウントに関するデータを、指定したすべてのグループチャットから消去する。
+  ///
+  /// グループチャットごとに[GroupChatCommand::EraseUserAccount]を処理し、
+  /// [PayloadCipher]が設定されている場合は、最後にユーザアカウントの鍵を削除する。
   ///
   /// # 引数
-  /// - `id` - グループチャットID
-  /// - `user_account_id` - ユーザーアカウントID
+  /// - `user_account_id` - 消去するユーザーアカウントID
+  /// - `group_chat_ids` - 消去の対象となるグループチャットID
   /// - `executor_id` - 実行者のユーザーアカウントID
   ///
   /// # 戻り値
-  /// - 成功した場合はOk([GroupChatId]), 失敗した場合はErrを返す。
-  pub async fn remove_member(
+  /// - 成功した場合はOk(消去したグループチャットの[GroupChatId]), 失敗した場合はErrを返す。
+  ///
+  /// NOTE: 存在しないグループチャットと消去するデータがないグループチャットは無視するので、途中で失敗した場合は再実行できる。
+  #[tracing::instrument(skip_all, fields(user_account_id = %user_account_id))]
+  pub async fn erase_user_account(
     &mut self,
-    id: GroupChatId,
     user_account_id: UserAccountId,
+    group_chat_ids: Vec<GroupChatId>,
     executor_id: UserAccountId,
-  ) -> Result<GroupChatId, CommandProcessError> {
-    let mut repository_mg = self.group_chat_repository.lock().await;
-
-    let mut group_chat = repository_mg
-      .find_by_id(&id)
-      .await
-      .map_err(|e| CommandProcessError::RepositoryError(e))?
-      .ok_or(CommandProcessError::NotFoundError)?;
-
-    let group_chat_event = group_chat
-      .remove_member(user_account_id, executor_id)
-      .map_err(|e| CommandProcessError::DomainLogicError(e))?;
+  ) -> Result<Vec<GroupChatId>, CommandProcessError> {
+    let mut erased_group_chat_ids = Vec::new();
+    for group_chat_id in group_chat_ids {
+      let command = GroupChatCommand::EraseUserAccount {
+        group_chat_id,
+        user_account_id: user_account_id.clone(),
+        executor_id: executor_id.clone(),
+      };
+      match self.handle(command).await {
+        Ok(id) => erased_group_chat_ids.push(id),
+        Err(CommandProcessError::NotFoundError)
+        | Err(CommandProcessError::DomainLogicError(GroupChatError::NoUserAccountDataError(_, _))) => continue,
+        Err(error) => return Err(error),
+      }
+    }
 
-    repository_mg
-      .store(&group_chat_event, &group_chat)
-      .await
-      .map(|_| group_chat_event.aggregate_id().clone())
-      .map_err(|e| CommandProcessError::RepositoryError(e))
+    if let Some(payload_cipher) = &self.payload_cipher {
+      payload_cipher.shred(&user_account_id)?;
+    }
+    Ok(erased_group_chat_ids)
   }
 
-  /// グループチャットを削除する。
+  /// 1つのグループチャットに複数のコマンドをまとめて適用する。
+  ///
+  /// グループチャットを一度だけ読み込んですべてのコマンドを適用し、発生したイベントを1つのトランザクションで保存する。
+  /// いずれかのコマンドがドメインのルールに違反した場合は、どのイベントも保存しない。
   ///
   /// # 引数
-  /// - `id` - グループチャットID
-  /// - `executor_id` - 実行者のユーザーアカウントID
+  /// - `id` - グループチャットID。先頭のコマンドが[GroupChatCommand::CreateGroupChat]の場合は、作成するグループチャットのID
+  /// - `commands` - 適用するコマンド(適用順)。すべて`id`のグループチャットを対象とすること
   ///
   /// # 戻り値
   /// - 成功した場合はOk([GroupChatId]), 失敗した場合はErrを返す。
-  pub async fn delete_group_chat(
+  #[tracing::instrument(skip_all, fields(group_chat_id = %id, commands = commands.len()))]
+  pub async fn execute_batch(
     &mut self,
     id: GroupChatId,
-    executor_id: UserAccountId,
+    commands: Vec<GroupChatCommand>,
   ) -> Result<GroupChatId, CommandProcessError> {
-    let mut repository_mg = self.group_chat_repository.lock().await;
-
-    let mut group_chat = repository_mg
-      .find_by_id(&id)
-      .await
-      .map_err(|e| CommandProcessError::RepositoryError(e))?
-      .ok_or(CommandProcessError::NotFoundError)?;
-
-    let group_chat_event = group_chat
-      .delete(executor_id)
-      .map_err(|e| CommandProcessError::DomainLogicError(e))?;
-
-    repository_mg
-      .store(&group_chat_event, &group_chat)
-      .await
-      .map(|_| group_chat_event.aggregate_id().clone())
-      .map_err(|e| CommandProcessError::RepositoryError(e))
-  }
-
-  /// グループチャットにメッセージを投稿する。
-  ///
-  /// # 引数
-  /// - `id` - グループチャットID
-  /// - `message` - メッセージ
-  /// - `executor_id` - 実行者のユーザーアカウントID
-  ///
-  /// # 戻り値
-  /// - 成功した場合はOk([GroupChatId, MessageId]), 失敗した場合はErrを返す。
-  pub async fn post_message(
-    &mut self,
-    id: GroupChatId,
-    message: Message,
-    executor_id: UserAccountId,
-  ) -> Result<(GroupChatId, MessageId), CommandProcessError> {
-    let mut repository_mg = self.group_chat_repository.lock().await;
-
-    let mut group_chat = repository_mg
-      .find_by_id(&id)
-      .await
-      .map_err(|e| CommandProcessError::RepositoryError(e))?
-      .ok_or(CommandProcessError::NotFoundError)?;
-
-    let group_chat_event = group_chat
-      .post_message(message.clone(), executor_id)
-      .map_err(|e| CommandProcessError::DomainLogicError(e))?;
+    if commands.is_empty() {
+      return Err(CommandProcessError::InvalidBatchError("the batch is empty".to_string()));
+    }
+    if commands.len() > self.config.max_batch_size {
+      return Err(CommandProcessError::InvalidBatchError(format!(
+        "the batch has too many commands: {} (max: {})",
+        commands.len(),
+        self.config.max_batch_size
+      )));
+    }
+    for command in &commands {
+      if matches!(command, GroupChatCommand::OpenDirectChat { .. }) || command.group_chat_id() != id {
+        return Err(CommandProcessError::InvalidBatchError(format!(
+          "{} does not target the group chat: {}",
+          command.name(),
+          id
+        )));
+      }
+      for middleware in &self.middlewares {
+        middleware.before(command).await?;
+      }
+    }
 
-    repository_mg
-      .store(&group_chat_event, &group_chat)
-      .await
-      .map(|_| {
-        (
-          group_chat_event.aggregate_id().clone(),
-          message.breach_encapsulation_of_id().clone(),
-        )
-      })
-      .map_err(|e| CommandProcessError::RepositoryError(e))
+    let started_at = Instant::now();
+    let result = self.dispatch_batch(id, commands.clone()).await;
+    let elapsed = started_at.elapsed();
+    for middleware in &self.middlewares {
+      middleware.after_batch(&commands, &result, elapsed).await;
+    }
+    result
   }
 
-  pub async fn edit_message(
+  async fn dispatch_batch(
     &mut self,
     id: GroupChatId,
-    message: Message,
-    executor_id: UserAccountId,
+    commands: Vec<GroupChatCommand>,
   ) -> Result<GroupChatId, CommandProcessError> {
     let mut repository_mg = self.group_chat_repository.lock().await;
 
-    let mut group_chat = repository_mg
+    let existing_group_chat = repository_mg
       .find_by_id(&id)
       .await
-      .map_err(|e| CommandProcessError::RepositoryError(e))?
-      .ok_or(CommandProcessError::NotFoundError)?;
-
-    let group_chat_event = group_chat
-      .edit_message(message.clone(), executor_id)
-      .map_err(|e| CommandProcessError::DomainLogicError(e))?;
+      .map_err(CommandProcessError::RepositoryError)?;
+    let mut commands = commands.into_iter();
+    let (mut group_chat, mut group_chat_events) = match existing_group_chat {
+      Some(group_chat) => (group_chat, Vec::new()),
+      None => match commands.next() {
+        Some(GroupChatCommand::CreateGroupChat { name, executor_id, .. }) => {
+          let (group_chat, group_chat_event) =
+            GroupChat::from(id.clone(), false, name, Members::new(executor_id), 0, 1);
+          (group_chat, vec![group_chat_event])
+        }
+        _ => return Err(CommandProcessError::NotFoundError),
+      },
+    };
+
+    for command in commands {
+      if matches!(command, GroupChatCommand::CreateGroupChat { .. }) {
+        return Err(CommandProcessError::InvalidBatchError(
+          "createGroupChat must be the first command for a group chat that does not exist".to_string(),
+        ));
+      }
+      let group_chat_event = self
+        .apply_command(&mut group_chat, command)
+        .map_err(CommandProcessError::DomainLogicError)?;
+      group_chat_events.push(group_chat_event);
+    }
 
     repository_mg
-      .store(&group_chat_event, &group_chat)
+      .store_all(&group_chat_events, &group_chat)
       .await
-      .map(|_| group_chat_event.aggregate_id().clone())
-      .map_err(|e| CommandProcessError::RepositoryError(e))
+      .map(|_| id)
+      .map_err(CommandProcessError::RepositoryError)
   }
 
-  /// グループチャットのメッセージを削除する。
-  ///
-  /// # 引数
-  /// - `id` - グループチャットID
-  /// - `message_id` - メッセージID
-  /// - `executor_id` - 実行者のユーザーアカウントID
+  /// 既存のグループチャットにコマンドを1つ適用する。
   ///
-  /// # 戻り値
-  /// - 成功した場合はOk([GroupChatId]), 失敗した場合はErrを返す。
-  pub async fn delete_message(
-    &mut self,
-    id: GroupChatId,
-    message_id: MessageId,
-    executor_id: UserAccountId,
-  ) -> Result<GroupChatId, CommandProcessError> {
-    let mut repository_mg = self.group_chat_repository.lock().await;
-
-    let mut group_chat = repository_mg
-      .find_by_id(&id)
-      .await
-      .map_err(|e| CommandProcessError::RepositoryError(e))?
-      .ok_or(CommandProcessError::NotFoundError)?;
-
-    let group_chat_event = group_chat
-      .delete_message(message_id, executor_id)
-      .map_err(|e| CommandProcessError::DomainLogicError(e))?;
-
-    repository_mg
-      .store(&group_chat_event, &group_chat)
-      .await
-      .map(|_| group_chat_event.aggregate_id().clone())
-      .map_err(|e| CommandProcessError::RepositoryError(e))
+  /// NOTE: [GroupChatCommand::CreateGroupChat]と[GroupChatCommand::OpenDirectChat]は呼び出し元で処理するので、ここには渡さないこと。
+  fn apply_command(
+    &self,
+    group_chat: &mut GroupChat,
+    command: GroupChatCommand,
+  ) -> Result<GroupChatEvent, GroupChatError> {
+    match command {
+      GroupChatCommand::CreateGroupChat { .. } | GroupChatCommand::OpenDirectChat { .. } => {
+        unreachable!("creating commands are handled by the caller")
+      }
+      GroupChatCommand::RenameGroupChat { name, executor_id, .. } => group_chat.rename(name, executor_id),
+      GroupChatCommand::UpdateSettings {
+        settings, executor_id, ..
+      } => group_chat.update_settings(settings, executor_id),
+      GroupChatCommand::AddMember {
+        user_account_id,
+        role,
+        executor_id,
+        ..
+      } => group_chat.add_member(MemberId::new(), user_account_id, role, executor_id),
+      GroupChatCommand::InviteMember {
+        user_account_id,
+        role,
+        executor_id,
+        ..
+      } => group_chat.invite_member(user_account_id, role, executor_id, self.config.invitation_ttl),
+      GroupChatCommand::AcceptInvitation { executor_id, .. } => {
+        group_chat.accept_invitation(MemberId::new(), executor_id)
+      }
+      GroupChatCommand::DeclineInvitation { executor_id, .. } => group_chat.decline_invitation(executor_id),
+      GroupChatCommand::RevokeInvitation {
+        user_account_id,
+        executor_id,
+        ..
+      } => group_chat.revoke_invitation(user_account_id, executor_id),
+      GroupChatCommand::RemoveMember {
+        user_account_id,
+        executor_id,
+        ..
+      } => group_chat.remove_member(user_account_id, executor_id),
+      GroupChatCommand::LeaveGroupChat { executor_id, .. } => {
+        group_chat.leave(executor_id, self.config.last_administrator_policy)
+      }
+      GroupChatCommand::PostMessage {
+        message, executor_id, ..
+      } => group_chat.post_message(message, executor_id),
+      GroupChatCommand::EditMessage {
+        message, executor_id, ..
+      } => group_chat.edit_message(message, executor_id, self.config.message_edit_window),
+      GroupChatCommand::DeleteMessage {
+        message_id,
+        executor_id,
+        ..
+      } => group_chat.delete_message(message_id, executor_id),
+      GroupChatCommand::ModerateMessage {
+        message_id,
+        reason,
+        executor_id,
+        ..
+      } => group_chat.moderate_message(message_id, reason, executor_id),
+      GroupChatCommand::RestoreMessage {
+        message_id,
+        executor_id,
+        ..
+      } => group_chat.restore_message(message_id, executor_id, self.config.message_restore_window),
+      GroupChatCommand::ExpireMessage { message_id, .. } => {
+        group_chat.expire_message(message_id, self.config.default_message_retention)
+      }
+      GroupChatCommand::EraseUserAccount {
+        user_account_id,
+        executor_id,
+        ..
+      } => group_chat.erase_user_account(user_account_id, executor_id),
+      GroupChatCommand::DeleteGroupChat { executor_id, .. } => group_chat.delete(executor_id),
+    }
   }
 }
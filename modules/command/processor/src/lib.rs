@@ -1 +1,3 @@
+pub mod group_chat_command;
+pub mod group_chat_command_middleware;
 pub mod group_chat_command_processor;
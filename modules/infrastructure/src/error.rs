@@ -0,0 +1,259 @@
+use async_graphql::resolver_utils::EnumType;
+use async_graphql::{Enum, ErrorExtensions, Value};
+
+/// APIのエラーコード。
+///
+/// GraphQLのエラーの`code`拡張として返す。クライアントはメッセージではなくこのコードで判別すること。
+/// 名前は互換性のために変更しない(追加のみ行う)。
+///
+/// NOTE: Write API ServerとRead API Serverで共通のコードを利用し、両方のスキーマに登録してSDLに出力する。
+#[derive(Enum, Debug, Clone, Copy, PartialEq, Eq)]
+#[graphql(rename_items = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+  /// 入力値が不正(`argument`に入力の名前)
+  InvalidArgument,
+  /// IDの形式が不正(`value`に入力値)
+  InvalidId,
+  /// 役割が不正(`value`に入力値)
+  InvalidRole,
+  /// 権限の設定値が不正(`value`に入力値)
+  InvalidPermission,
+  /// チャットの種類が不正(`value`に入力値)
+  InvalidChatKind,
+  /// グループチャット名が空
+  NameEmpty,
+  /// グループチャット名が長すぎる
+  NameTooLong,
+  /// メッセージが空
+  MessageEmpty,
+  /// メッセージが長すぎる
+  MessageTooLong,
+  /// URLが不正(`url`に入力値)
+  InvalidUrl,
+  /// MIMEタイプが不正(`mime`に入力値)
+  InvalidMime,
+  /// 添付ファイルのサイズが不正(`size`に入力値)
+  InvalidAttachmentSize,
+  /// メンバー数の上限が範囲外(`maxMembers`に入力値)
+  InvalidMaxMembers,
+  /// メッセージの保持日数が不正
+  InvalidMessageRetentionDays,
+  /// モデレーションの理由が空
+  ModerationReasonEmpty,
+  /// バッチの内容が不正
+  InvalidBatch,
+  /// 操作が許可されていない
+  Forbidden,
+  /// 許可リストにないクエリ
+  PersistedQueryNotAllowed,
+  /// グループチャットが存在しない
+  GroupChatNotFound,
+  /// メンバーが存在しない
+  MemberNotFound,
+  /// メッセージが存在しない(`messageId`)
+  MessageNotFound,
+  /// 招待が存在しない(`userAccountId`)
+  InvitationNotFound,
+  /// グループチャットが削除済み(`groupChatId`)
+  GroupChatDeleted,
+  /// 管理者ではない(`userAccountId`)
+  NotAdministrator,
+  /// メンバーではない(`userAccountId`)
+  NotMember,
+  /// 既にメンバー(`userAccountId`)
+  AlreadyMember,
+  /// 既に招待済み(`userAccountId`)
+  AlreadyInvited,
+  /// ユーザアカウントが一致しない
+  MismatchedUserAccount,
+  /// 同じIDのメッセージが既に存在する(`messageId`)
+  MessageAlreadyExists,
+  /// メッセージの送信者ではない(`userAccountId`)
+  NotSender,
+  /// メッセージが削除されていない(`messageId`)
+  MessageNotDeleted,
+  /// メッセージを復元できる期間を過ぎた(`messageId`)
+  RestoreWindowExpired,
+  /// メッセージを編集できる期間を過ぎた(`messageId`)
+  EditWindowExpired,
+  /// システムメッセージは投稿・編集できない
+  SystemMessageNotAllowed,
+  /// 1対1のチャットでは利用できない操作(`operation`, `groupChatId`)
+  NotSupportedInDirectChat,
+  /// 自分自身との1対1のチャット
+  DirectChatWithSelf,
+  /// 最後の管理者は退出できない(`userAccountId`)
+  LastAdministrator,
+  /// メンバー数の上限に達した(`groupChatId`, `maxMembers`)
+  MemberLimitExceeded,
+  /// 投稿が許可されていない(`userAccountId`)
+  PostNotAllowed,
+  /// メンバー数の上限が現在のメンバー数より小さい(`maxMembers`, `currentMembers`)
+  MaxMembersBelowCurrent,
+  /// メッセージの保持期間が設定されていない(`groupChatId`)
+  RetentionNotConfigured,
+  /// メッセージが保持期間を過ぎていない(`messageId`)
+  MessageNotExpired,
+  /// ユーザアカウントのデータがない(`groupChatId`, `userAccountId`)
+  NoUserAccountData,
+  /// 同じ名前のグループチャットが既に存在する(`name`)
+  NameAlreadyExists,
+  /// 同時に更新された。再実行すること
+  Conflict,
+  /// 同じ冪等キーのリクエストを実行中。しばらく待ってから再送すること
+  IdempotencyKeyInProgress,
+  /// 冪等キーが異なる内容のリクエストで既に利用されている
+  IdempotencyKeyReused,
+  /// サーバの内部エラー
+  Internal,
+}
+
+impl ErrorCode {
+  /// SDLに出力される名前(`NOT_MEMBER`など)を返す。
+  pub fn as_str(&self) -> &'static str {
+    Self::items()
+      .iter()
+      .find(|item| item.value == *self)
+      .map(|item| item.name)
+      .unwrap()
+  }
+
+  /// 名前から[ErrorCode]を返す。
+  pub fn from_name(name: &str) -> Option<Self> {
+    Self::items()
+      .iter()
+      .find(|item| item.name == name)
+      .map(|item| item.value)
+  }
+
+  /// エラーの分類を表すHTTPのステータスコードを返す。
+  pub fn status(&self) -> u16 {
+    match self {
+      ErrorCode::InvalidArgument
+      | ErrorCode::InvalidId
+      | ErrorCode::InvalidRole
+      | ErrorCode::InvalidPermission
+      | ErrorCode::InvalidChatKind
+      | ErrorCode::NameEmpty
+      | ErrorCode::NameTooLong
+      | ErrorCode::MessageEmpty
+      | ErrorCode::MessageTooLong
+      | ErrorCode::InvalidUrl
+      | ErrorCode::InvalidMime
+      | ErrorCode::InvalidAttachmentSize
+      | ErrorCode::InvalidMaxMembers
+      | ErrorCode::InvalidMessageRetentionDays
+      | ErrorCode::ModerationReasonEmpty
+      | ErrorCode::InvalidBatch => 400,
+      ErrorCode::Forbidden | ErrorCode::PersistedQueryNotAllowed => 403,
+      ErrorCode::GroupChatNotFound
+      | ErrorCode::MemberNotFound
+      | ErrorCode::MessageNotFound
+      | ErrorCode::InvitationNotFound => 404,
+      ErrorCode::Conflict | ErrorCode::IdempotencyKeyInProgress => 409,
+      ErrorCode::Internal => 500,
+      _ => 422,
+    }
+  }
+}
+
+/// APIのエラー。[ErrorCode]と、エラーに関する値(IDなど)を持つ。
+///
+/// [async_graphql::Error]に変換すると、拡張に`code`(エラーコード)、`status`(HTTPのステータスコード)、
+/// 及び各フィールドを設定する。
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiError {
+  code: ErrorCode,
+  message: String,
+  fields: Vec<(&'static str, String)>,
+}
+
+impl ApiError {
+  /// コンストラクタ。
+  ///
+  /// # 引数
+  /// - `code` - [ErrorCode]
+  /// - `message` - 人が読むためのメッセージ
+  pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+    Self {
+      code,
+      message: message.into(),
+      fields: Vec::new(),
+    }
+  }
+
+  /// エラーに関する値を追加する。
+  ///
+  /// # 引数
+  /// - `name` - 拡張の名前(camelCase)
+  /// - `value` - 値
+  pub fn with_field(mut self, name: &'static str, value: impl ToString) -> Self {
+    self.fields.push((name, value.to_string()));
+    self
+  }
+
+  pub fn code(&self) -> ErrorCode {
+    self.code
+  }
+
+  pub fn message(&self) -> &str {
+    &self.message
+  }
+
+  pub fn fields(&self) -> &[(&'static str, String)] {
+    &self.fields
+  }
+}
+
+impl From<ApiError> for async_graphql::Error {
+  fn from(error: ApiError) -> Self {
+    async_graphql::Error::new(error.message).extend_with(|_, e| {
+      e.set("code", error.code.as_str());
+      e.set("status", error.code.status());
+      for (name, value) in &error.fields {
+        e.set(*name, value.as_str());
+      }
+    })
+  }
+}
+
+/// GraphQLのエラーの`code`拡張から[ErrorCode]を返す。
+///
+/// # 戻り値
+/// - `code`拡張がない場合や、[ErrorCode]ではない場合は`None`
+pub fn error_code_of(error: &async_graphql::Error) -> Option<ErrorCode> {
+  match error.extensions.as_ref().and_then(|extensions| extensions.get("code")) {
+    Some(Value::String(code)) => ErrorCode::from_name(code),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_api_error_to_graphql_error() {
+    let error: async_graphql::Error = ApiError::new(ErrorCode::NotMember, "not a member")
+      .with_field("userAccountId", "01H7C6DWMK1BKS1JYH1XZE529M")
+      .into();
+    assert_eq!(error.message, "not a member");
+    let extensions = error.extensions.as_ref().unwrap();
+    assert_eq!(extensions.get("code"), Some(&Value::from("NOT_MEMBER")));
+    assert_eq!(extensions.get("status"), Some(&Value::from(422)));
+    assert_eq!(
+      extensions.get("userAccountId"),
+      Some(&Value::from("01H7C6DWMK1BKS1JYH1XZE529M"))
+    );
+    assert_eq!(error_code_of(&error), Some(ErrorCode::NotMember));
+    assert_eq!(error_code_of(&async_graphql::Error::new("no code")), None);
+  }
+
+  #[test]
+  fn test_error_code_name() {
+    assert_eq!(ErrorCode::NameTooLong.as_str(), "NAME_TOO_LONG");
+    assert_eq!(ErrorCode::from_name("NAME_TOO_LONG"), Some(ErrorCode::NameTooLong));
+    assert_eq!(ErrorCode::from_name("400"), None);
+    assert_eq!(ErrorCode::GroupChatNotFound.status(), 404);
+  }
+}
@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Arc;
+
+use async_graphql::async_trait::async_trait;
+use async_graphql::extensions::apollo_persisted_queries::{ApolloPersistedQueries, CacheStorage, LruCacheStorage};
+use async_graphql::extensions::{Extension, ExtensionContext, ExtensionFactory, NextPrepareRequest};
+use async_graphql::parser::types::ExecutableDocument;
+use async_graphql::{ErrorExtensionValues, Request, SchemaBuilder, ServerError, ServerResult};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::error::ErrorCode;
+
+#[derive(Debug, Error)]
+pub enum GraphQLSettingsError {
+  #[error("Failed to read the persisted queries: {0}")]
+  IOError(#[from] std::io::Error),
+  #[error("The persisted queries are not a valid JSON: {0}")]
+  JsonError(#[from] serde_json::Error),
+  #[error("The hash does not match the persisted query: {0}")]
+  HashMismatchError(String),
+  #[error("The persisted query is not a valid GraphQL document: {0}")]
+  ParseError(String),
+  #[error("strict mode requires persisted_queries_file")]
+  MissingPersistedQueriesError,
+}
+
+/// GraphQL APIの制限と永続化クエリの設定。
+///
+/// 各アプリケーションの`ApiSettings`の`graphql`に設定する。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct GraphQLSettings {
+  /// クエリの深さの上限
+  pub max_depth: usize,
+  /// クエリの複雑さ(各フィールドのコストの合計)の上限
+  pub max_complexity: usize,
+  /// 永続化クエリの許可リスト。`{"<SHA-256ハッシュ>": "<クエリ>"}`形式のJSONファイル
+  pub persisted_queries_file: Option<String>,
+  /// 許可リストにないクエリを拒否する(本番環境向け)
+  pub strict: bool,
+  /// APQで登録されたクエリを保持する件数(strictの場合は登録できないので使われない)
+  pub apq_cache_size: usize,
+}
+
+impl Default for GraphQLSettings {
+  fn default() -> Self {
+    Self {
+      max_depth: 10,
+      max_complexity: 1000,
+      persisted_queries_file: None,
+      strict: false,
+      apq_cache_size: 256,
+    }
+  }
+}
+
+impl GraphQLSettings {
+  /// スキーマに深さと複雑さの制限、永続化クエリ(APQ)を設定する。
+  ///
+  /// # 引数
+  /// - `builder` - [SchemaBuilder]
+  ///
+  /// # 戻り値
+  /// - 設定済みの[SchemaBuilder]。許可リストが読み込めない場合はエラー
+  pub fn apply<Q, M, S>(
+    &self,
+    builder: SchemaBuilder<Q, M, S>,
+  ) -> Result<SchemaBuilder<Q, M, S>, GraphQLSettingsError> {
+    let allow_list = match &self.persisted_queries_file {
+      Some(path) => load_persisted_queries(&fs::read_to_string(path)?)?,
+      None if self.strict => return Err(GraphQLSettingsError::MissingPersistedQueriesError),
+      None => HashMap::new(),
+    };
+    let allow_list = Arc::new(allow_list);
+    let builder = builder
+      .limit_depth(self.max_depth)
+      .limit_complexity(self.max_complexity);
+    // NOTE: 拡張は登録順に実行されるので、APQでクエリが解決される前に許可リストを確認する
+    let builder = if self.strict {
+      builder.extension(PersistedQueryAllowList {
+        allow_list: allow_list.clone(),
+      })
+    } else {
+      builder
+    };
+    let storage = PersistedQueryStorage {
+      allow_list,
+      cache: (!self.strict).then(|| LruCacheStorage::new(self.apq_cache_size)),
+    };
+    Ok(builder.extension(ApolloPersistedQueries::new(storage)))
+  }
+}
+
+fn sha256_hex(query: &str) -> String {
+  format!("{:x}", Sha256::digest(query.as_bytes()))
+}
+
+/// 許可リストを読み込み、ハッシュとクエリを検証する。
+fn load_persisted_queries(json: &str) -> Result<HashMap<String, ExecutableDocument>, GraphQLSettingsError> {
+  let queries: HashMap<String, String> = serde_json::from_str(json)?;
+  queries
+    .into_iter()
+    .map(|(hash, query)| {
+      let hash = hash.to_lowercase();
+      if sha256_hex(&query) != hash {
+        return Err(GraphQLSettingsError::HashMismatchError(hash));
+      }
+      let document =
+        async_graphql::parser::parse_query(&query).map_err(|e| GraphQLSettingsError::ParseError(e.to_string()))?;
+      Ok((hash, document))
+    })
+    .collect()
+}
+
+/// 許可リストと、APQで登録されたクエリのキャッシュから永続化クエリを探す[CacheStorage]。
+#[derive(Clone)]
+struct PersistedQueryStorage {
+  allow_list: Arc<HashMap<String, ExecutableDocument>>,
+  /// APQで登録されたクエリのキャッシュ。strictの場合は登録させないので`None`
+  cache: Option<LruCacheStorage>,
+}
+
+#[async_trait]
+impl CacheStorage for PersistedQueryStorage {
+  async fn get(&self, key: String) -> Option<ExecutableDocument> {
+    if let Some(document) = self.allow_list.get(&key) {
+      return Some(document.clone());
+    }
+    match &self.cache {
+      Some(cache) => cache.get(key).await,
+      None => None,
+    }
+  }
+
+  async fn set(&self, key: String, query: ExecutableDocument) {
+    if let Some(cache) = &self.cache {
+      cache.set(key, query).await;
+    }
+  }
+}
+
+/// 許可リストにないクエリを拒否する拡張(strictモード)。
+///
+/// ハッシュのみのリクエストは、APQが許可リストから解決できない場合に`PersistedQueryNotFound`となる。
+struct PersistedQueryAllowList {
+  allow_list: Arc<HashMap<String, ExecutableDocument>>,
+}
+
+impl ExtensionFactory for PersistedQueryAllowList {
+  fn create(&self) -> Arc<dyn Extension> {
+    Arc::new(PersistedQueryAllowListExtension {
+      allow_list: self.allow_list.clone(),
+    })
+  }
+}
+
+struct PersistedQueryAllowListExtension {
+  allow_list: Arc<HashMap<String, ExecutableDocument>>,
+}
+
+#[async_trait]
+impl Extension for PersistedQueryAllowListExtension {
+  async fn prepare_request(
+    &self,
+    ctx: &ExtensionContext<'_>,
+    request: Request,
+    next: NextPrepareRequest<'_>,
+  ) -> ServerResult<Request> {
+    if !request.query.is_empty() && !self.allow_list.contains_key(&sha256_hex(&request.query)) {
+      let mut extensions = ErrorExtensionValues::default();
+      extensions.set("code", ErrorCode::PersistedQueryNotAllowed.as_str());
+      extensions.set("status", ErrorCode::PersistedQueryNotAllowed.status());
+      let mut error = ServerError::new("PersistedQueryNotAllowed", None);
+      error.extensions = Some(extensions);
+      return Err(error);
+    }
+    next.run(ctx, request).await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use async_graphql::{value, EmptyMutation, EmptySubscription, Object, Schema};
+
+  use super::*;
+
+  struct Query;
+
+  #[Object]
+  impl Query {
+    async fn value(&self) -> i32 {
+      100
+    }
+
+    #[graphql(complexity = 10)]
+    async fn values(&self) -> Vec<i32> {
+      vec![1, 2, 3]
+    }
+  }
+
+  const QUERY: &str = "{ value }";
+
+  fn create_schema(settings: &GraphQLSettings) -> Schema<Query, EmptyMutation, EmptySubscription> {
+    settings
+      .apply(Schema::build(Query, EmptyMutation, EmptySubscription))
+      .unwrap()
+      .finish()
+  }
+
+  fn persisted_query_request(query: &str, hash: &str) -> Request {
+    let mut request = Request::new(query);
+    request.extensions.insert(
+      "persistedQuery".to_string(),
+      value!({ "version": 1, "sha256Hash": hash }),
+    );
+    request
+  }
+
+  fn write_persisted_queries() -> String {
+    let path = std::env::temp_dir().join(format!("persisted-queries-{}.json", std::process::id()));
+    let json = serde_json::json!({ sha256_hex(QUERY): QUERY });
+    fs::write(&path, json.to_string()).unwrap();
+    path.to_string_lossy().to_string()
+  }
+
+  #[tokio::test]
+  async fn test_complexity_limit() {
+    let schema = create_schema(&GraphQLSettings {
+      max_complexity: 5,
+      ..GraphQLSettings::default()
+    });
+    assert!(schema.execute("{ value }").await.is_ok());
+    assert!(schema.execute("{ values }").await.is_err());
+  }
+
+  #[tokio::test]
+  async fn test_register_and_execute_persisted_query() {
+    let schema = create_schema(&GraphQLSettings::default());
+    let hash = sha256_hex(QUERY);
+
+    let response = schema.execute(persisted_query_request("", &hash)).await;
+    assert_eq!(response.errors[0].message, "PersistedQueryNotFound");
+
+    let response = schema.execute(persisted_query_request(QUERY, &hash)).await;
+    assert_eq!(response.into_result().unwrap().data, value!({ "value": 100 }));
+
+    let response = schema.execute(persisted_query_request("", &hash)).await;
+    assert_eq!(response.into_result().unwrap().data, value!({ "value": 100 }));
+  }
+
+  #[tokio::test]
+  async fn test_strict_mode_rejects_ad_hoc_query() {
+    let path = write_persisted_queries();
+    let schema = create_schema(&GraphQLSettings {
+      persisted_queries_file: Some(path.clone()),
+      strict: true,
+      ..GraphQLSettings::default()
+    });
+
+    let response = schema.execute(persisted_query_request("", &sha256_hex(QUERY))).await;
+    assert_eq!(response.into_result().unwrap().data, value!({ "value": 100 }));
+
+    let response = schema.execute(QUERY).await;
+    assert_eq!(response.into_result().unwrap().data, value!({ "value": 100 }));
+
+    let response = schema.execute("{ values }").await;
+    assert_eq!(response.errors[0].message, "PersistedQueryNotAllowed");
+
+    let response = schema
+      .execute(persisted_query_request("{ values }", &sha256_hex("{ values }")))
+      .await;
+    assert_eq!(response.errors[0].message, "PersistedQueryNotAllowed");
+
+    fs::remove_file(path).unwrap();
+  }
+
+  #[test]
+  fn test_reject_mismatched_hash() {
+    let json = serde_json::json!({ sha256_hex("{ values }"): QUERY }).to_string();
+    assert!(matches!(
+      load_persisted_queries(&json),
+      Err(GraphQLSettingsError::HashMismatchError(_))
+    ));
+  }
+}
@@ -0,0 +1,77 @@
+use serde::Deserialize;
+use tonic::metadata::MetadataValue;
+use tonic::{Code, Status};
+
+use crate::error::{error_code_of, ErrorCode};
+
+/// gRPCサーバの設定。
+///
+/// 各アプリケーションの`AppSettings`の`grpc`に設定する。省略した場合はgRPCサーバを起動しない。
+#[derive(Debug, Clone, Deserialize)]
+pub struct GrpcSettings {
+  pub host: String,
+  pub port: u16,
+}
+
+/// [ErrorCode]の分類(HTTPのステータスコード)に対応するgRPCのステータスコードを返す。
+///
+/// # 引数
+/// - `code` - [ErrorCode]
+///
+/// # 戻り値
+/// - [Code]。対応するものがない場合は[Code::Unknown]
+pub fn code_from_error_code(code: ErrorCode) -> Code {
+  match code.status() {
+    400 => Code::InvalidArgument,
+    403 => Code::PermissionDenied,
+    404 => Code::NotFound,
+    409 => Code::Aborted,
+    422 => Code::FailedPrecondition,
+    500 => Code::Internal,
+    _ => Code::Unknown,
+  }
+}
+
+/// GraphQLのエラーを、`code`拡張に対応するgRPCの[Status]に変換する。
+///
+/// NOTE: GraphQLとgRPCで同じエラーが同じ種類のエラーとして見えるように、
+/// リゾルバのエラーハンドリングを通してから変換する。
+/// [ErrorCode]の名前はメタデータの`error-code`に設定する。
+///
+/// # 引数
+/// - `error` - [async_graphql::Error]
+///
+/// # 戻り値
+/// - [Status]
+pub fn status_from_graphql_error(error: async_graphql::Error) -> Status {
+  let Some(error_code) = error_code_of(&error) else {
+    return Status::new(Code::Unknown, error.message);
+  };
+  let mut status = Status::new(code_from_error_code(error_code), error.message);
+  status
+    .metadata_mut()
+    .insert("error-code", MetadataValue::from_static(error_code.as_str()));
+  status
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::error::ApiError;
+
+  use super::*;
+
+  #[test]
+  fn test_status_from_graphql_error() {
+    let error = ApiError::new(ErrorCode::GroupChatNotFound, "not found").into();
+    let status = status_from_graphql_error(error);
+    assert_eq!(status.code(), Code::NotFound);
+    assert_eq!(status.message(), "not found");
+    assert_eq!(status.metadata().get("error-code").unwrap(), "GROUP_CHAT_NOT_FOUND");
+
+    let error = ApiError::new(ErrorCode::Conflict, "conflict").into();
+    assert_eq!(status_from_graphql_error(error).code(), Code::Aborted);
+
+    let error = async_graphql::Error::new("no code");
+    assert_eq!(status_from_graphql_error(error).code(), Code::Unknown);
+  }
+}
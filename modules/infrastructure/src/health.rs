@@ -0,0 +1,275 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_graphql::async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sqlx::MySqlPool;
+use tokio::sync::Mutex;
+use tokio::task::JoinSet;
+
+/// レディネスチェックの設定。
+///
+/// 各アプリケーションの`ApiSettings`の`health`に設定する。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct HealthSettings {
+  /// 依存先ごとのチェックのタイムアウト(ミリ秒)
+  pub timeout_ms: u64,
+  /// チェック結果をキャッシュする時間(ミリ秒)
+  pub cache_ttl_ms: u64,
+}
+
+impl Default for HealthSettings {
+  fn default() -> Self {
+    Self {
+      timeout_ms: 1000,
+      cache_ttl_ms: 2000,
+    }
+  }
+}
+
+/// 依存先の状態。
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+  Up,
+  Down,
+}
+
+/// 依存先(DynamoDBのテーブルやMySQLなど)の疎通を確認するチェック。
+#[async_trait]
+pub trait HealthCheck: Send + Sync {
+  /// レスポンスに表示する依存先の名前
+  fn name(&self) -> &str;
+
+  /// 依存先の疎通を確認する。
+  ///
+  /// # 戻り値
+  /// - 疎通できない場合はエラーメッセージ
+  async fn check(&self) -> Result<(), String>;
+}
+
+/// MySQLに`SELECT 1`を発行する[HealthCheck]。
+#[derive(Debug, Clone)]
+pub struct MySqlHealthCheck {
+  pool: MySqlPool,
+}
+
+impl MySqlHealthCheck {
+  pub fn new(pool: MySqlPool) -> Self {
+    Self { pool }
+  }
+}
+
+#[async_trait]
+impl HealthCheck for MySqlHealthCheck {
+  fn name(&self) -> &str {
+    "mysql"
+  }
+
+  async fn check(&self) -> Result<(), String> {
+    sqlx::query("SELECT 1")
+      .execute(&self.pool)
+      .await
+      .map(|_| ())
+      .map_err(|error| error.to_string())
+  }
+}
+
+/// 依存先ごとのチェック結果。
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyStatus {
+  pub name: String,
+  pub status: HealthStatus,
+  /// チェックにかかった時間(ミリ秒)
+  pub latency_ms: u64,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub error: Option<String>,
+}
+
+/// `/health/ready`のレスポンス。
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadinessReport {
+  /// すべての依存先が[HealthStatus::Up]の場合のみ[HealthStatus::Up]
+  pub status: HealthStatus,
+  pub dependencies: Vec<DependencyStatus>,
+}
+
+impl ReadinessReport {
+  pub fn is_ready(&self) -> bool {
+    self.status == HealthStatus::Up
+  }
+}
+
+/// 登録された[HealthCheck]を並行に実行して、レディネスを判定する。
+///
+/// NOTE: Kubernetesのプローブやロードバランサのヘルスチェックが依存先に集中しないように、
+/// 結果を`cache_ttl_ms`の間キャッシュする。チェック中はロックを保持するので、同時のリクエストは同じ結果を待つ。
+pub struct ReadinessChecker {
+  checks: Vec<Arc<dyn HealthCheck>>,
+  timeout: Duration,
+  cache_ttl: Duration,
+  cache: Mutex<Option<(Instant, ReadinessReport)>>,
+}
+
+impl ReadinessChecker {
+  /// コンストラクタ。チェックを登録しない場合は常にレディとみなす。
+  ///
+  /// # 引数
+  /// - `settings` - [HealthSettings]
+  pub fn new(settings: &HealthSettings) -> Self {
+    Self {
+      checks: Vec::new(),
+      timeout: Duration::from_millis(settings.timeout_ms),
+      cache_ttl: Duration::from_millis(settings.cache_ttl_ms),
+      cache: Mutex::new(None),
+    }
+  }
+
+  /// [HealthCheck]を登録する。
+  pub fn with_check(mut self, check: Arc<dyn HealthCheck>) -> Self {
+    self.checks.push(check);
+    self
+  }
+
+  /// レディネスを判定する。
+  ///
+  /// # 戻り値
+  /// - キャッシュが有効な場合はキャッシュした[ReadinessReport]
+  pub async fn check(&self) -> ReadinessReport {
+    let mut cache = self.cache.lock().await;
+    if let Some((checked_at, report)) = cache.as_ref() {
+      if checked_at.elapsed() < self.cache_ttl {
+        return report.clone();
+      }
+    }
+
+    let mut join_set = JoinSet::new();
+    for (index, check) in self.checks.iter().enumerate() {
+      let check = check.clone();
+      let timeout = self.timeout;
+      join_set.spawn(async move { (index, check_dependency(check.as_ref(), timeout).await) });
+    }
+    let mut results = Vec::with_capacity(self.checks.len());
+    while let Some(result) = join_set.join_next().await {
+      match result {
+        Ok(result) => results.push(result),
+        Err(error) => tracing::error!("health check task failed: {}", error),
+      }
+    }
+    results.sort_by_key(|(index, _)| *index);
+    let dependencies = results.into_iter().map(|(_, status)| status).collect::<Vec<_>>();
+
+    let status = if dependencies.len() == self.checks.len()
+      && dependencies
+        .iter()
+        .all(|dependency| dependency.status == HealthStatus::Up)
+    {
+      HealthStatus::Up
+    } else {
+      HealthStatus::Down
+    };
+    let report = ReadinessReport { status, dependencies };
+    *cache = Some((Instant::now(), report.clone()));
+    report
+  }
+}
+
+async fn check_dependency(check: &dyn HealthCheck, timeout: Duration) -> DependencyStatus {
+  let started_at = Instant::now();
+  let result = match tokio::time::timeout(timeout, check.check()).await {
+    Ok(result) => result,
+    Err(_) => Err(format!("timed out after {}ms", timeout.as_millis())),
+  };
+  let latency_ms = started_at.elapsed().as_millis() as u64;
+  if let Err(error) = &result {
+    tracing::warn!("health check of {} failed: {}", check.name(), error);
+  }
+  DependencyStatus {
+    name: check.name().to_string(),
+    status: if result.is_ok() {
+      HealthStatus::Up
+    } else {
+      HealthStatus::Down
+    },
+    latency_ms,
+    error: result.err(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::atomic::{AtomicUsize, Ordering};
+
+  use super::*;
+
+  struct TestCheck {
+    name: &'static str,
+    delay: Duration,
+    result: Result<(), String>,
+    count: AtomicUsize,
+  }
+
+  #[async_trait]
+  impl HealthCheck for TestCheck {
+    fn name(&self) -> &str {
+      self.name
+    }
+
+    async fn check(&self) -> Result<(), String> {
+      self.count.fetch_add(1, Ordering::SeqCst);
+      tokio::time::sleep(self.delay).await;
+      self.result.clone()
+    }
+  }
+
+  fn test_check(name: &'static str, delay_ms: u64, result: Result<(), String>) -> Arc<TestCheck> {
+    Arc::new(TestCheck {
+      name,
+      delay: Duration::from_millis(delay_ms),
+      result,
+      count: AtomicUsize::new(0),
+    })
+  }
+
+  #[tokio::test]
+  async fn test_readiness_checker() {
+    let settings = HealthSettings {
+      timeout_ms: 100,
+      cache_ttl_ms: 60000,
+    };
+    let up = test_check("up", 0, Ok(()));
+    let checker = ReadinessChecker::new(&settings).with_check(up.clone());
+    let report = checker.check().await;
+    assert!(report.is_ready());
+    assert_eq!(report.dependencies[0].name, "up");
+
+    let failed = test_check("failed", 0, Err("connection refused".to_string()));
+    let slow = test_check("slow", 1000, Ok(()));
+    let checker = ReadinessChecker::new(&settings)
+      .with_check(up.clone())
+      .with_check(failed)
+      .with_check(slow);
+    let report = checker.check().await;
+    assert!(!report.is_ready());
+    let statuses = report
+      .dependencies
+      .iter()
+      .map(|dependency| (dependency.name.as_str(), dependency.status))
+      .collect::<Vec<_>>();
+    assert_eq!(
+      statuses,
+      vec![
+        ("up", HealthStatus::Up),
+        ("failed", HealthStatus::Down),
+        ("slow", HealthStatus::Down)
+      ]
+    );
+    assert_eq!(report.dependencies[1].error.as_deref(), Some("connection refused"));
+    assert_eq!(report.dependencies[2].error.as_deref(), Some("timed out after 100ms"));
+
+    // キャッシュが有効な間は依存先にアクセスしない
+    checker.check().await;
+    assert_eq!(up.count.load(Ordering::SeqCst), 2);
+  }
+}
@@ -1 +1,7 @@
-// 特に実装はありません。必要であれば適宜実装を配置してください。
+pub mod error;
+pub mod graphql;
+pub mod grpc;
+pub mod health;
+pub mod metrics;
+pub mod shutdown;
+pub mod telemetry;
@@ -0,0 +1,35 @@
+use prometheus::{Encoder, TextEncoder};
+
+/// `/metrics`のレスポンスの`Content-Type`
+pub const METRICS_CONTENT_TYPE: &str = prometheus::TEXT_FORMAT;
+
+/// [prometheus]のデフォルトのレジストリに登録されたメトリクスを、Prometheusのテキスト形式で返す。
+///
+/// NOTE: 各モジュールのメトリクスはデフォルトのレジストリに登録する。
+/// プロセスのメトリクス(CPU時間やメモリ使用量など)も含まれる。
+pub fn gather_metrics() -> String {
+  let mut buffer = Vec::new();
+  if let Err(error) = TextEncoder::new().encode(&prometheus::gather(), &mut buffer) {
+    tracing::warn!("failed to encode metrics: {}", error);
+  }
+  String::from_utf8(buffer).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+  use once_cell::sync::Lazy;
+  use prometheus::{register_int_counter, IntCounter};
+
+  use super::*;
+
+  static TEST_COUNTER: Lazy<IntCounter> =
+    Lazy::new(|| register_int_counter!("infrastructure_test_total", "counter for the test").unwrap());
+
+  #[test]
+  fn test_gather_metrics() {
+    TEST_COUNTER.inc();
+    let metrics = gather_metrics();
+    assert!(metrics.contains("# TYPE infrastructure_test_total counter"));
+    assert!(metrics.contains("infrastructure_test_total 1"));
+  }
+}
@@ -0,0 +1,118 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+use tokio::sync::watch;
+
+/// グレースフルシャットダウンの設定。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ShutdownSettings {
+  /// シャットダウンのシグナルを受けてから、処理中のリクエストの完了を待つ秒数
+  ///
+  /// NOTE: Kubernetesの`terminationGracePeriodSeconds`から`preStop`の待ち時間を引いた時間より短くすること。
+  pub grace_period_secs: u64,
+}
+
+impl Default for ShutdownSettings {
+  fn default() -> Self {
+    Self { grace_period_secs: 4 }
+  }
+}
+
+impl ShutdownSettings {
+  pub fn grace_period(&self) -> Duration {
+    Duration::from_secs(self.grace_period_secs)
+  }
+}
+
+/// SIGTERM・SIGINTを受けたことを通知するシグナル。
+///
+/// クローンして、HTTPサーバ・gRPCサーバ・ストリームの処理などで共有する。
+#[derive(Debug, Clone)]
+pub struct ShutdownSignal {
+  receiver: watch::Receiver<bool>,
+}
+
+impl ShutdownSignal {
+  /// SIGTERM・SIGINTを待ち受けるタスクを起動する。
+  ///
+  /// NOTE: Tokioのランタイムの中で呼び出すこと。
+  pub fn listen() -> Self {
+    let (sender, signal) = Self::channel();
+    tokio::spawn(async move {
+      wait_for_os_signal().await;
+      tracing::info!("shutdown signal received");
+      let _ = sender.send(true);
+    });
+    signal
+  }
+
+  /// OSのシグナル以外を契機に通知するシグナルを生成する。返した[watch::Sender]に`true`を送ると通知する。
+  ///
+  /// NOTE: テストなどで、シャットダウンを任意のタイミングで通知するために利用する。
+  pub fn channel() -> (watch::Sender<bool>, Self) {
+    let (sender, receiver) = watch::channel(false);
+    (sender, Self { receiver })
+  }
+
+  /// シグナルを受けている場合は`true`を返す。
+  pub fn is_triggered(&self) -> bool {
+    *self.receiver.borrow()
+  }
+
+  /// シグナルを受けるまで待つ。`with_graceful_shutdown`などに渡す。
+  pub async fn triggered(mut self) {
+    if self.receiver.wait_for(|triggered| *triggered).await.is_err() {
+      // シグナルを待ち受けるタスクが終了した場合は、シャットダウンしない
+      std::future::pending::<()>().await;
+    }
+  }
+
+  /// シグナルを受けてから`grace_period`が経過するまで待つ。
+  ///
+  /// 処理中のリクエストの完了を待つサーバと[tokio::select]で競わせて、完了を待つ時間を制限する。
+  pub async fn deadline(self, grace_period: Duration) {
+    self.triggered().await;
+    tokio::time::sleep(grace_period).await;
+    tracing::warn!(
+      "in-flight requests did not complete within {}s, shutting down",
+      grace_period.as_secs()
+    );
+  }
+}
+
+#[cfg(unix)]
+async fn wait_for_os_signal() {
+  use tokio::signal::unix::{signal, SignalKind};
+  let mut terminate = signal(SignalKind::terminate()).expect("failed to listen for SIGTERM");
+  tokio::select! {
+    _ = tokio::signal::ctrl_c() => {}
+    _ = terminate.recv() => {}
+  }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_os_signal() {
+  let _ = tokio::signal::ctrl_c().await;
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[tokio::test]
+  async fn test_shutdown_signal() {
+    let (sender, signal) = ShutdownSignal::channel();
+    assert!(!signal.is_triggered());
+
+    let deadline = tokio::spawn(signal.clone().deadline(Duration::from_millis(10)));
+    sender.send(true).unwrap();
+    assert!(signal.is_triggered());
+    signal.clone().triggered().await;
+    deadline.await.unwrap();
+
+    drop(sender);
+    assert!(signal.is_triggered());
+    signal.triggered().await;
+  }
+}
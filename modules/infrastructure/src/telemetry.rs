@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+
+use opentelemetry::global;
+use opentelemetry::trace::{TraceError, TracerProvider as _};
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::TracerProvider;
+use opentelemetry_sdk::{runtime, Resource};
+use serde::Deserialize;
+use thiserror::Error;
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// トレースの送信先。
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TelemetryExporter {
+  /// トレースを送信しない
+  #[default]
+  None,
+  /// 標準出力に出力する(コレクタがない環境での確認用)
+  Stdout,
+  /// OTLP(gRPC)でコレクタに送信する
+  Otlp,
+}
+
+/// OpenTelemetryのトレースの設定。
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct TelemetrySettings {
+  /// トレースの送信先
+  pub exporter: TelemetryExporter,
+  /// OTLPのエンドポイント(`exporter = "otlp"`の場合のみ利用する)
+  pub otlp_endpoint: String,
+}
+
+impl Default for TelemetrySettings {
+  fn default() -> Self {
+    Self {
+      exporter: TelemetryExporter::None,
+      otlp_endpoint: "http://localhost:4317".to_string(),
+    }
+  }
+}
+
+#[derive(Debug, Error)]
+pub enum TelemetryError {
+  #[error("Failed to create the span exporter: {0}")]
+  ExporterError(#[from] TraceError),
+}
+
+/// OpenTelemetryのトレーサプロバイダ。
+///
+/// ドロップ時にプロバイダを停止し、送信されていないスパンを送信する。
+/// `main`の最後まで保持すること。
+#[derive(Debug)]
+pub struct Telemetry {
+  provider: Option<TracerProvider>,
+}
+
+impl Telemetry {
+  /// トレーサプロバイダを生成し、W3C Trace Contextのプロパゲータと一緒にグローバルに登録する。
+  ///
+  /// NOTE: OTLPの送信はTokioのランタイム上で行うので、ランタイムの中で呼び出すこと。
+  ///
+  /// # 引数
+  /// - `service_name` - スパンの`service.name`
+  /// - `settings` - [TelemetrySettings]
+  ///
+  /// # 戻り値
+  /// - 送信先が[TelemetryExporter::None]の場合は、何もしない[Telemetry]を返す。
+  pub fn new(service_name: &str, settings: &TelemetrySettings) -> Result<Self, TelemetryError> {
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    let builder = TracerProvider::builder().with_resource(Resource::new(vec![KeyValue::new(
+      "service.name",
+      service_name.to_string(),
+    )]));
+    let provider = match settings.exporter {
+      TelemetryExporter::None => return Ok(Self { provider: None }),
+      TelemetryExporter::Stdout => builder
+        .with_simple_exporter(opentelemetry_stdout::SpanExporter::default())
+        .build(),
+      TelemetryExporter::Otlp => {
+        let exporter = opentelemetry_otlp::SpanExporter::builder()
+          .with_tonic()
+          .with_endpoint(settings.otlp_endpoint.clone())
+          .build()?;
+        builder.with_batch_exporter(exporter, runtime::Tokio).build()
+      }
+    };
+    global::set_tracer_provider(provider.clone());
+    Ok(Self {
+      provider: Some(provider),
+    })
+  }
+
+  /// `tracing`のスパンをOpenTelemetryのスパンとして送信するレイヤーを返す。
+  ///
+  /// NOTE: AWS SDKなどのDEBUGレベルのスパンは送信しないように、INFOレベル以上に絞り込む。
+  ///
+  /// # 戻り値
+  /// - 送信先が[TelemetryExporter::None]の場合は`None`
+  pub fn layer<S>(&self) -> Option<Box<dyn Layer<S> + Send + Sync>>
+  where
+    S: Subscriber + for<'span> LookupSpan<'span> + Send + Sync,
+  {
+    self.provider.as_ref().map(|provider| {
+      tracing_opentelemetry::layer()
+        .with_tracer(provider.tracer("cqrs-es-example-rs"))
+        .with_filter(LevelFilter::INFO)
+        .boxed()
+    })
+  }
+
+  /// 送信されていないスパンを送信する。
+  ///
+  /// NOTE: Lambdaは呼び出しの間に停止されることがあるので、呼び出しごとに送信すること。
+  pub fn force_flush(&self) {
+    if let Some(provider) = &self.provider {
+      for result in provider.force_flush() {
+        if let Err(error) = result {
+          tracing::warn!("failed to flush spans: {}", error);
+        }
+      }
+    }
+  }
+}
+
+impl Drop for Telemetry {
+  fn drop(&mut self) {
+    if let Some(provider) = self.provider.take() {
+      if let Err(error) = provider.shutdown() {
+        eprintln!("failed to shutdown the tracer provider: {}", error);
+      }
+    }
+  }
+}
+
+/// 現在のスパンのトレースコンテキストを、W3C Trace Context形式(`traceparent`など)で返す。
+///
+/// # 戻り値
+/// - トレースしていない場合は空の[HashMap]
+pub fn current_trace_context() -> HashMap<String, String> {
+  let context = tracing::Span::current().context();
+  let mut carrier = HashMap::new();
+  global::get_text_map_propagator(|propagator| propagator.inject_context(&context, &mut carrier));
+  carrier
+}
+
+/// [current_trace_context]で取得したトレースコンテキストを、スパンの親に設定する。
+///
+/// # 引数
+/// - `span` - 親を設定するスパン
+/// - `trace_context` - W3C Trace Context形式のトレースコンテキスト
+pub fn set_parent_trace_context(span: &tracing::Span, trace_context: &HashMap<String, String>) {
+  let context = global::get_text_map_propagator(|propagator| propagator.extract(trace_context));
+  span.set_parent(context);
+}
+
+#[cfg(test)]
+mod tests {
+  use tracing_subscriber::layer::SubscriberExt;
+
+  use super::*;
+
+  #[test]
+  fn test_trace_context_propagation() {
+    let provider = TracerProvider::builder().build();
+    let subscriber =
+      tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    tracing::subscriber::with_default(subscriber, || {
+      let parent = tracing::info_span!("mutation");
+      let trace_context = parent.in_scope(current_trace_context);
+      let traceparent = trace_context.get("traceparent").unwrap();
+      let trace_id = traceparent.split('-').nth(1).unwrap().to_string();
+
+      let child = tracing::info_span!("update_read_model");
+      set_parent_trace_context(&child, &trace_context);
+      let child_trace_context = child.in_scope(current_trace_context);
+      assert!(child_trace_context["traceparent"].contains(&trace_id));
+    });
+    assert!(current_trace_context().is_empty());
+  }
+}
@@ -0,0 +1,10 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+  // NOTE: protocをインストールしていない環境でもビルドできるように、同梱のprotocと標準のprotoファイルを使う
+  std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+  let include_path = protoc_bin_vendored::include_path()?;
+  tonic_build::configure().compile(
+    &["../../../proto/group_chat/query/v1/group_chat_query.proto"],
+    &[std::path::Path::new("../../../proto"), include_path.as_path()],
+  )?;
+  Ok(())
+}
@@ -1,6 +1,7 @@
 use async_graphql::async_trait::async_trait;
-use async_graphql::SimpleObject;
+use async_graphql::{SimpleObject, Union};
 use chrono::NaiveDateTime;
+use serde::Deserialize;
 use sqlx::MySqlPool;
 use thiserror::Error;
 
@@ -9,17 +10,20 @@ use thiserror::Error;
 /// NOTE: リードモデルはDTOとして利用されるものです。
 /// 特段振る舞いのようなものはありません。
 #[derive(SimpleObject)]
+#[graphql(complex)]
 pub struct GroupChat {
   /// グループチャットID
-  id: String,
+  pub(crate) id: String,
+  /// チャットの種類(group, direct)
+  pub(crate) kind: String,
   /// グループチャット名
-  name: String,
+  pub(crate) name: String,
   /// 管理者ID
-  owner_id: String,
+  pub(crate) owner_id: String,
   /// 作成日時
-  created_at: NaiveDateTime,
+  pub(crate) created_at: NaiveDateTime,
   /// 更新日時
-  updated_at: NaiveDateTime,
+  pub(crate) updated_at: NaiveDateTime,
 }
 
 #[derive(Debug, Error)]
@@ -51,12 +55,60 @@ impl GroupChat {
   pub fn new(id: String, name: String, owner_id: String, created_at: NaiveDateTime, updated_at: NaiveDateTime) -> Self {
     Self {
       id,
+      kind: "group".to_string(),
       name,
       owner_id,
       created_at,
       updated_at,
     }
   }
+
+  /// チャットの種類を設定したグループチャットを返す。
+  pub fn with_kind(mut self, kind: String) -> Self {
+    self.kind = kind;
+    self
+  }
+
+  pub(crate) fn group_chat_id(&self) -> &str {
+    &self.id
+  }
+}
+
+/// グループチャット設定リードモデル
+#[derive(SimpleObject)]
+pub struct GroupChatSettings {
+  /// グループチャットID
+  pub(crate) group_chat_id: String,
+  /// メンバー数の上限
+  pub(crate) max_members: u32,
+  /// メッセージを投稿できるメンバー(everyone, admins_only)。admins_onlyの場合はアナウンスモード
+  pub(crate) post_permission: String,
+  /// メンバーを追加・招待できるメンバー(everyone, admins_only)
+  pub(crate) add_member_permission: String,
+  /// メッセージの保持日数(未設定の場合は無期限)
+  pub(crate) message_retention_days: Option<u32>,
+  /// 更新日時
+  pub(crate) updated_at: NaiveDateTime,
+}
+
+impl GroupChatSettings {
+  pub fn new(
+    group_chat_id: String,
+    max_members: u32,
+    post_permission: String,
+    add_member_permission: String,
+    message_retention_days: Option<u32>,
+    updated_at: NaiveDateTime,
+  ) -> Self {
+    Self {
+      group_chat_id,
+      max_members,
+      post_permission,
+      add_member_permission,
+      message_retention_days,
+      updated_at,
+    }
+  }
 }
 
 /// グループチャット用データアクセスオブジェクト。
@@ -70,6 +122,11 @@ pub trait GroupChatDao: Send + Sync {
     user_account_id: String,
   ) -> Result<GroupChat, GroupChatDaoError>;
   async fn get_group_chats(&self, user_account_id: String) -> Result<Vec<GroupChat>, GroupChatDaoError>;
+  async fn get_group_chat_settings(
+    &self,
+    group_chat_id: String,
+    user_account_id: String,
+  ) -> Result<GroupChatSettings, GroupChatDaoError>;
 }
 
 /// [GroupChatDao]の実装
@@ -92,7 +149,7 @@ impl GroupChatDao for GroupChatDaoImpl {
   ) -> Result<GroupChat, GroupChatDaoError> {
     sqlx::query_as!(
       GroupChat,
-      r#"SELECT gc.id, gc.name, gc.owner_id, gc.created_at, gc.updated_at
+      r#"SELECT gc.id, gc.kind, gc.name, gc.owner_id, gc.created_at, gc.updated_at
 		 FROM group_chats AS gc JOIN members AS m ON gc.id = m.group_chat_id
 		 WHERE gc.disabled = 'false' AND m.group_chat_id = ? AND m.user_account_id = ?"#,
       group_chat_id.clone(),
@@ -114,7 +171,7 @@ impl GroupChatDao for GroupChatDaoImpl {
   async fn get_group_chats(&self, user_account_id: String) -> Result<Vec<GroupChat>, GroupChatDaoError> {
     sqlx::query_as!(
       GroupChat,
-      r#"SELECT gc.id, gc.name, gc.owner_id, gc.created_at, gc.updated_at
+      r#"SELECT gc.id, gc.kind, gc.name, gc.owner_id, gc.created_at, gc.updated_at
 		 FROM group_chats AS gc JOIN members AS m ON gc.id = m.group_chat_id
          WHERE gc.disabled = 'false' AND m.user_account_id = ?"#,
       user_account_id.clone()
@@ -123,25 +180,53 @@ impl GroupChatDao for GroupChatDaoImpl {
     .await
     .map_err(GroupChatDaoError::OtherError)
   }
+
+  async fn get_group_chat_settings(
+    &self,
+    group_chat_id: String,
+    user_account_id: String,
+  ) -> Result<GroupChatSettings, GroupChatDaoError> {
+    sqlx::query_as!(
+      GroupChatSettings,
+      r#"SELECT s.group_chat_id, s.max_members, s.post_permission, s.add_member_permission,
+           s.message_retention_days, s.updated_at
+         FROM group_chats AS gc JOIN group_chat_settings AS s ON gc.id = s.group_chat_id
+         WHERE gc.disabled = 'false' AND s.group_chat_id = ?
+          AND EXISTS (SELECT 1 FROM members AS m WHERE m.group_chat_id = s.group_chat_id AND m.user_account_id = ?)"#,
+      group_chat_id.clone(),
+      user_account_id.clone()
+    )
+    .fetch_optional(&self.my_sql_pool)
+    .await
+    .map_err(GroupChatDaoError::OtherError)
+    .and_then(|opt| {
+      opt.ok_or_else(|| {
+        GroupChatDaoError::NotFoundError(format!(
+          "group_chat_id: {}, user_account_id: {}",
+          group_chat_id, user_account_id
+        ))
+      })
+    })
+  }
 }
 
 // ---
 
 /// メンバーリードモデル
-#[derive(SimpleObject)]
+#[derive(SimpleObject, Clone)]
 pub struct Member {
   /// メンバーID
-  id: String,
+  pub(crate) id: String,
   /// グループチャットID
-  group_chat_id: String,
+  pub(crate) group_chat_id: String,
   /// アカウントID
-  user_account_id: String,
+  pub(crate) user_account_id: String,
   /// ロール
-  role: String,
+  pub(crate) role: String,
   /// 作成日時
-  created_at: NaiveDateTime,
+  pub(crate) created_at: NaiveDateTime,
   /// 更新日時
-  updated_at: NaiveDateTime,
+  pub(crate) updated_at: NaiveDateTime,
 }
 
 impl Member {
@@ -162,6 +247,14 @@ impl Member {
       updated_at,
     }
   }
+
+  pub(crate) fn member_group_chat_id(&self) -> &str {
+    &self.group_chat_id
+  }
+
+  pub(crate) fn member_user_account_id(&self) -> &str {
+    &self.user_account_id
+  }
 }
 
 /// メンバー用データアクセスオブジェクト。
@@ -171,6 +264,14 @@ impl Member {
 pub trait MemberDao: Send + Sync {
   async fn get_member(&self, group_chat_id: String, user_account_id: String) -> Result<Member, MemberDaoError>;
   async fn get_members(&self, group_chat_id: String, user_account_id: String) -> Result<Vec<Member>, MemberDaoError>;
+  /// 指定した複数のグループチャットのメンバー一覧をまとめて取得する。
+  ///
+  /// NOTE: 閲覧アカウントの確認は行わないので、閲覧を許可されたグループチャットIDのみを指定すること。
+  /// (ネストしたフィールドのバッチ読み込み用)
+  ///
+  /// # 引数
+  /// - `group_chat_ids` - グループチャットIDの一覧
+  async fn get_members_by_group_chat_ids(&self, group_chat_ids: Vec<String>) -> Result<Vec<Member>, MemberDaoError>;
 }
 
 /// [MemberDao]の実装
@@ -222,25 +323,58 @@ impl MemberDao for MemberDaoImpl {
     .await
     .map_err(MemberDaoError::OtherError)
   }
+
+  async fn get_members_by_group_chat_ids(&self, group_chat_ids: Vec<String>) -> Result<Vec<Member>, MemberDaoError> {
+    // NOTE: IN句の要素数は可変なので、JSON配列で渡してJSON_TABLEで展開する
+    let group_chat_ids = serde_json::to_string(&group_chat_ids).unwrap();
+    sqlx::query_as!(
+      Member,
+      r#"SELECT m.id, m.group_chat_id, m.user_account_id, m.role, m.created_at, m.updated_at
+         FROM group_chats AS gc JOIN members AS m ON gc.id = m.group_chat_id
+         WHERE gc.disabled = 'false'
+          AND m.group_chat_id IN (SELECT ids.id FROM JSON_TABLE(?, '$[*]' COLUMNS (id VARCHAR(64) PATH '$')) AS ids)
+         ORDER BY m.group_chat_id, m.created_at, m.id"#,
+      group_chat_ids
+    )
+    .fetch_all(&self.my_sql_pool)
+    .await
+    .map_err(MemberDaoError::OtherError)
+  }
 }
 
 // ---
 
 /// メッセージリードモデル
 #[derive(SimpleObject, Clone)]
+#[graphql(complex)]
 pub struct Message {
   /// メッセージID
-  id: String,
+  pub(crate) id: String,
   /// グループチャットID
-  group_chat_id: String,
+  pub(crate) group_chat_id: String,
   /// アカウントID
-  user_account_id: String,
-  /// メッセージ本文
-  text: String,
+  pub(crate) user_account_id: String,
+  /// メッセージ本文(管理者によって削除された場合は"removed by moderator")
+  pub(crate) text: String,
+  /// 本文の種類(text, attachment, link_preview, system)
+  pub(crate) content_type: String,
+  /// 本文のJSON表現。`body`フィールドの解決に利用する
+  #[graphql(skip)]
+  pub(crate) content: Option<String>,
+  /// 編集日時
+  pub(crate) edited_at: Option<NaiveDateTime>,
+  /// 管理者によって削除された日時
+  pub(crate) moderated_at: Option<NaiveDateTime>,
+  /// 管理者による削除理由
+  pub(crate) moderation_reason: Option<String>,
+  /// 削除日時(管理者向けの削除済みメッセージ一覧でのみ設定される)
+  pub(crate) deleted_at: Option<NaiveDateTime>,
+  /// 削除したアカウントID(管理者向けの削除済みメッセージ一覧でのみ設定される)
+  pub(crate) deleted_by: Option<String>,
   /// 作成日時
-  created_at: NaiveDateTime,
+  pub(crate) created_at: NaiveDateTime,
   /// 更新日時
-  updated_at: NaiveDateTime,
+  pub(crate) updated_at: NaiveDateTime,
 }
 
 impl Message {
@@ -257,10 +391,274 @@ impl Message {
       group_chat_id,
       user_account_id,
       text,
+      content_type: "text".to_string(),
+      content: None,
+      edited_at: None,
+      moderated_at: None,
+      moderation_reason: None,
+      deleted_at: None,
+      deleted_by: None,
       created_at,
       updated_at,
     }
   }
+
+  pub(crate) fn message_id(&self) -> &str {
+    &self.id
+  }
+
+  pub(crate) fn message_group_chat_id(&self) -> &str {
+    &self.group_chat_id
+  }
+
+  pub(crate) fn sender_id(&self) -> &str {
+    &self.user_account_id
+  }
+
+  pub(crate) fn is_moderated(&self) -> bool {
+    self.moderated_at.is_some()
+  }
+
+  /// 本文の種類とJSON表現を設定したメッセージを返す。
+  pub fn with_content(mut self, content_type: String, content: String) -> Self {
+    self.content_type = content_type;
+    self.content = Some(content);
+    self
+  }
+
+  /// 本文を返す。
+  ///
+  /// NOTE: 本文のJSON表現を持たない場合(過去のメッセージや、管理者によって削除されたメッセージ)は`text`から組み立てる。
+  pub fn parse_body(&self) -> MessageContent {
+    let text = || self.text.clone();
+    let parsed = self
+      .content
+      .as_ref()
+      .and_then(|content| match serde_json::from_str::<MessageContent>(content) {
+        Ok(body) => Some(body),
+        Err(e) => {
+          log::warn!("Failed to parse message content: id = {}, {:?}", self.id, e);
+          None
+        }
+      });
+    match parsed {
+      Some(body) => body,
+      None if self.content_type == "system" => MessageContent::System(SystemContent { text: text() }),
+      None => MessageContent::Text(TextContent { text: text() }),
+    }
+  }
+
+  /// 編集日時を設定したメッセージを返す。
+  pub fn with_edited_at(mut self, edited_at: NaiveDateTime) -> Self {
+    self.edited_at = Some(edited_at);
+    self
+  }
+
+  /// 管理者による削除情報を設定したメッセージを返す。
+  pub fn with_moderated(mut self, moderated_at: NaiveDateTime, moderation_reason: String) -> Self {
+    self.moderated_at = Some(moderated_at);
+    self.moderation_reason = Some(moderation_reason);
+    self
+  }
+
+  /// 削除情報を設定したメッセージを返す。
+  pub fn with_deleted(mut self, deleted_at: NaiveDateTime, deleted_by: String) -> Self {
+    self.deleted_at = Some(deleted_at);
+    self.deleted_by = Some(deleted_by);
+    self
+  }
+}
+
+/// メッセージ本文
+#[derive(Union, Clone, Debug, PartialEq, Deserialize)]
+#[serde(tag = "type")]
+pub enum MessageContent {
+  Text(TextContent),
+  Attachment(AttachmentContent),
+  LinkPreview(LinkPreviewContent),
+  System(SystemContent),
+}
+
+/// テキストの本文
+#[derive(SimpleObject, Clone, Debug, PartialEq, Deserialize)]
+pub struct TextContent {
+  pub(crate) text: String,
+}
+
+/// 添付ファイルの本文
+#[derive(SimpleObject, Clone, Debug, PartialEq, Deserialize)]
+pub struct AttachmentContent {
+  pub(crate) url: String,
+  pub(crate) mime: String,
+  /// サイズ(バイト)
+  pub(crate) size: u64,
+}
+
+/// リンクプレビューの本文
+#[derive(SimpleObject, Clone, Debug, PartialEq, Deserialize)]
+pub struct LinkPreviewContent {
+  pub(crate) url: String,
+  pub(crate) title: Option<String>,
+  pub(crate) description: Option<String>,
+}
+
+/// システムメッセージ(参加・退出の通知など)の本文
+#[derive(SimpleObject, Clone, Debug, PartialEq, Deserialize)]
+pub struct SystemContent {
+  pub(crate) text: String,
+}
+
+/// メッセージの編集履歴リードモデル
+#[derive(SimpleObject, Clone)]
+pub struct MessageRevision {
+  /// メッセージID
+  message_id: String,
+  /// 編集前の本文
+  text: String,
+  /// 本文が書かれた日時
+  created_at: NaiveDateTime,
+}
+
+impl MessageRevision {
+  pub fn new(message_id: String, text: String, created_at: NaiveDateTime) -> Self {
+    Self {
+      message_id,
+      text,
+      created_at,
+    }
+  }
+}
+
+/// 抜粋の最大文字数
+const SNIPPET_LENGTH: usize = 100;
+/// 抜粋で最初の検索語より前に含める最大文字数
+const SNIPPET_LEADING_LENGTH: usize = 30;
+
+/// メッセージの検索結果
+#[derive(SimpleObject, Clone)]
+pub struct MessageSearchResult {
+  /// メッセージ
+  message: Message,
+  /// 関連度(大きいほど関連が高い)
+  score: f64,
+  /// 本文の抜粋。HTMLエスケープ済みで、検索語は`<mark>`で囲まれる
+  snippet: String,
+}
+
+impl MessageSearchResult {
+  pub fn new(message: Message, score: f64, query: &str) -> Self {
+    let snippet = make_snippet(&message.text, query);
+    Self {
+      message,
+      score,
+      snippet,
+    }
+  }
+}
+
+fn escape_html(c: char, out: &mut String) {
+  match c {
+    '&' => out.push_str("&amp;"),
+    '<' => out.push_str("&lt;"),
+    '>' => out.push_str("&gt;"),
+    '"' => out.push_str("&quot;"),
+    '\'' => out.push_str("&#39;"),
+    c => out.push(c),
+  }
+}
+
+/// 本文から検索語を含む抜粋を作る。
+///
+/// NOTE: ngramパーサは検索語を分割して一致させるため、検索語そのものが本文に含まれない場合がある。
+/// その場合は強調せずに本文の先頭を抜粋とする。
+pub(crate) fn make_snippet(text: &str, query: &str) -> String {
+  let chars = text.chars().collect::<Vec<_>>();
+  let lower = chars
+    .iter()
+    .map(|c| c.to_lowercase().next().unwrap_or(*c))
+    .collect::<Vec<_>>();
+  let terms = query
+    .split_whitespace()
+    .map(|term| {
+      term
+        .chars()
+        .map(|c| c.to_lowercase().next().unwrap_or(c))
+        .collect::<Vec<_>>()
+    })
+    .collect::<Vec<_>>();
+
+  // 一致した範囲を文字単位で記録する
+  let mut highlighted = vec![false; chars.len()];
+  let mut first_match: Option<usize> = None;
+  for term in terms.iter().filter(|term| !term.is_empty()) {
+    for start in 0..lower.len().saturating_sub(term.len() - 1) {
+      if lower[start..start + term.len()] == term[..] {
+        highlighted[start..start + term.len()].fill(true);
+        first_match = Some(first_match.map_or(start, |m| m.min(start)));
+      }
+    }
+  }
+
+  let start = first_match.map_or(0, |m| m.saturating_sub(SNIPPET_LEADING_LENGTH));
+  let end = (start + SNIPPET_LENGTH).min(chars.len());
+  let mut snippet = String::new();
+  if start > 0 {
+    snippet.push('…');
+  }
+  let mut in_mark = false;
+  for i in start..end {
+    if highlighted[i] != in_mark {
+      snippet.push_str(if highlighted[i] { "<mark>" } else { "</mark>" });
+      in_mark = highlighted[i];
+    }
+    escape_html(chars[i], &mut snippet);
+  }
+  if in_mark {
+    snippet.push_str("</mark>");
+  }
+  if end < chars.len() {
+    snippet.push('…');
+  }
+  snippet
+}
+
+/// 全文検索の結果行
+struct MessageSearchRow {
+  id: String,
+  group_chat_id: String,
+  user_account_id: String,
+  text: String,
+  content_type: String,
+  content: Option<String>,
+  edited_at: Option<NaiveDateTime>,
+  moderated_at: Option<NaiveDateTime>,
+  moderation_reason: Option<String>,
+  deleted_at: Option<NaiveDateTime>,
+  deleted_by: Option<String>,
+  created_at: NaiveDateTime,
+  updated_at: NaiveDateTime,
+  score: f64,
+}
+
+impl MessageSearchRow {
+  fn into_result(self, query: &str) -> MessageSearchResult {
+    let message = Message {
+      id: self.id,
+      group_chat_id: self.group_chat_id,
+      user_account_id: self.user_account_id,
+      text: self.text,
+      content_type: self.content_type,
+      content: self.content,
+      edited_at: self.edited_at,
+      moderated_at: self.moderated_at,
+      moderation_reason: self.moderation_reason,
+      deleted_at: self.deleted_at,
+      deleted_by: self.deleted_by,
+      created_at: self.created_at,
+      updated_at: self.updated_at,
+    };
+    MessageSearchResult::new(message, self.score, query)
+  }
 }
 
 /// メッセージ用データアクセスオブジェクト。
@@ -271,6 +669,70 @@ pub trait MessageDao: Send + Sync {
   async fn get_message(&self, message_id: String, user_account_id: String) -> Result<Message, MessageDaoError>;
   async fn get_messages(&self, group_chat_id: String, user_account_id: String)
     -> Result<Vec<Message>, MessageDaoError>;
+  /// 指定したアカウントIDがメンションされているメッセージ一覧を新しい順に取得する。
+  async fn get_mentions(&self, user_account_id: String) -> Result<Vec<Message>, MessageDaoError>;
+  /// 指定したメッセージの編集履歴を古い順に取得する。
+  async fn get_message_revisions(&self, message_id: String) -> Result<Vec<MessageRevision>, MessageDaoError>;
+  /// 削除済み(管理者による削除を含む)のメッセージ一覧を元の本文付きで取得する。
+  /// 閲覧アカウントがグループチャットの管理者でない場合は空を返す。
+  async fn get_deleted_messages(
+    &self,
+    group_chat_id: String,
+    user_account_id: String,
+  ) -> Result<Vec<Message>, MessageDaoError>;
+  /// 閲覧アカウントがメンバーであるグループチャットのメッセージを全文検索し、関連度の高い順に取得する。
+  ///
+  /// # 引数
+  /// - `user_account_id` - 閲覧アカウントID
+  /// - `query` - 検索語
+  /// - `group_chat_id` - 検索対象のグループチャットID(省略した場合はすべてのグループチャット)
+  /// - `limit` - 取得する最大件数
+  /// - `offset` - 読み飛ばす件数
+  async fn search_messages(
+    &self,
+    user_account_id: String,
+    query: String,
+    group_chat_id: Option<String>,
+    limit: u32,
+    offset: u32,
+  ) -> Result<Vec<MessageSearchResult>, MessageDaoError>;
+  /// 指定した複数のグループチャットのメッセージ一覧をまとめて古い順に取得する。
+  ///
+  /// NOTE: 閲覧アカウントの確認は行わないので、閲覧を許可されたグループチャットIDのみを指定すること。
+  /// (ネストしたフィールドのバッチ読み込み用)
+  ///
+  /// # 引数
+  /// - `group_chat_ids` - グループチャットIDの一覧
+  async fn get_messages_by_group_chat_ids(&self, group_chat_ids: Vec<String>) -> Result<Vec<Message>, MessageDaoError>;
+  /// 指定した複数のグループチャットの最新のメッセージをまとめて取得する。メッセージがないグループチャットは含まない。
+  ///
+  /// NOTE: 閲覧アカウントの確認は行わないので、閲覧を許可されたグループチャットIDのみを指定すること。
+  /// (ネストしたフィールドのバッチ読み込み用)
+  ///
+  /// # 引数
+  /// - `group_chat_ids` - グループチャットIDの一覧
+  async fn get_latest_messages_by_group_chat_ids(
+    &self,
+    group_chat_ids: Vec<String>,
+  ) -> Result<Vec<Message>, MessageDaoError>;
+  /// 指定したメッセージより後のメッセージ一覧を古い順に取得する。閲覧アカウントがメンバーでない場合は空を返す。
+  ///
+  /// NOTE: 新しいメッセージの配信で、前回取得したメッセージの続きを取得するために利用する。
+  ///
+  /// # 引数
+  /// - `group_chat_id` - グループチャットID
+  /// - `user_account_id` - 閲覧アカウントID
+  /// - `created_at` - 前回取得したメッセージの作成日時
+  /// - `message_id` - 前回取得したメッセージのID(作成日時が同じメッセージの順序付けに使う)
+  /// - `limit` - 取得する最大件数
+  async fn get_messages_after(
+    &self,
+    group_chat_id: String,
+    user_account_id: String,
+    created_at: NaiveDateTime,
+    message_id: String,
+    limit: u32,
+  ) -> Result<Vec<Message>, MessageDaoError>;
 }
 
 /// [MessageDao]の実装
@@ -289,7 +751,11 @@ impl MessageDao for MessageDaoImpl {
   async fn get_message(&self, message_id: String, user_account_id: String) -> Result<Message, MessageDaoError> {
     sqlx::query_as!(
       Message,
-      r#"SELECT m.id, m.group_chat_id, m.user_account_id, m.text, m.created_at, m.updated_at
+      r#"SELECT m.id, m.group_chat_id, m.user_account_id,
+           CASE WHEN m.moderated_at IS NULL THEN m.text ELSE 'removed by moderator' END AS text,
+           CASE WHEN m.moderated_at IS NULL THEN m.content_type ELSE 'text' END AS content_type,
+           CASE WHEN m.moderated_at IS NULL THEN m.content ELSE NULL END AS content,
+           m.edited_at, m.moderated_at, m.moderation_reason, m.deleted_at, m.deleted_by, m.created_at, m.updated_at
 		 FROM group_chats AS gc JOIN messages AS m ON gc.id = m.group_chat_id
          WHERE gc.disabled = 'false' AND m.disabled = 'false' AND m.id = ?
            AND EXISTS (SELECT 1 FROM members AS mem WHERE mem.group_chat_id = m.group_chat_id AND mem.user_account_id = ?)"#,
@@ -315,7 +781,11 @@ impl MessageDao for MessageDaoImpl {
   ) -> Result<Vec<Message>, MessageDaoError> {
     sqlx::query_as!(
       Message,
-      r#"SELECT m.id, m.group_chat_id, m.user_account_id, m.text, m.created_at, m.updated_at
+      r#"SELECT m.id, m.group_chat_id, m.user_account_id,
+           CASE WHEN m.moderated_at IS NULL THEN m.text ELSE 'removed by moderator' END AS text,
+           CASE WHEN m.moderated_at IS NULL THEN m.content_type ELSE 'text' END AS content_type,
+           CASE WHEN m.moderated_at IS NULL THEN m.content ELSE NULL END AS content,
+           m.edited_at, m.moderated_at, m.moderation_reason, m.deleted_at, m.deleted_by, m.created_at, m.updated_at
 		 FROM group_chats AS gc JOIN messages AS m ON gc.id = m.group_chat_id
          WHERE gc.disabled = 'false' AND m.disabled = 'false' AND m.group_chat_id = ?
           AND EXISTS (SELECT 1 FROM members AS mem WHERE mem.group_chat_id = m.group_chat_id AND mem.user_account_id = ?)"#,
@@ -326,19 +796,345 @@ impl MessageDao for MessageDaoImpl {
     .await
     .map_err(MessageDaoError::OtherError)
   }
+
+  async fn get_mentions(&self, user_account_id: String) -> Result<Vec<Message>, MessageDaoError> {
+    sqlx::query_as!(
+      Message,
+      r#"SELECT m.id, m.group_chat_id, m.user_account_id,
+           CASE WHEN m.moderated_at IS NULL THEN m.text ELSE 'removed by moderator' END AS text,
+           CASE WHEN m.moderated_at IS NULL THEN m.content_type ELSE 'text' END AS content_type,
+           CASE WHEN m.moderated_at IS NULL THEN m.content ELSE NULL END AS content,
+           m.edited_at, m.moderated_at, m.moderation_reason, m.deleted_at, m.deleted_by, m.created_at, m.updated_at
+		 FROM group_chats AS gc JOIN messages AS m ON gc.id = m.group_chat_id
+           JOIN message_mentions AS mm ON m.id = mm.message_id
+         WHERE gc.disabled = 'false' AND m.disabled = 'false' AND mm.user_account_id = ?
+          AND EXISTS (SELECT 1 FROM members AS mem WHERE mem.group_chat_id = m.group_chat_id AND mem.user_account_id = mm.user_account_id)
+         ORDER BY m.created_at DESC"#,
+      user_account_id
+    )
+    .fetch_all(&self.my_sql_pool)
+    .await
+    .map_err(MessageDaoError::OtherError)
+  }
+
+  async fn get_message_revisions(&self, message_id: String) -> Result<Vec<MessageRevision>, MessageDaoError> {
+    sqlx::query_as!(
+      MessageRevision,
+      r#"SELECT mr.message_id, mr.text, mr.created_at
+         FROM message_revisions AS mr
+         WHERE mr.message_id = ?
+         ORDER BY mr.id"#,
+      message_id
+    )
+    .fetch_all(&self.my_sql_pool)
+    .await
+    .map_err(MessageDaoError::OtherError)
+  }
+
+  async fn get_deleted_messages(
+    &self,
+    group_chat_id: String,
+    user_account_id: String,
+  ) -> Result<Vec<Message>, MessageDaoError> {
+    sqlx::query_as!(
+      Message,
+      r#"SELECT m.id, m.group_chat_id, m.user_account_id, m.text, m.content_type, m.content,
+           m.edited_at, m.moderated_at, m.moderation_reason,
+           m.deleted_at, m.deleted_by, m.created_at, m.updated_at
+		 FROM group_chats AS gc JOIN messages AS m ON gc.id = m.group_chat_id
+         WHERE gc.disabled = 'false' AND (m.disabled = 'true' OR m.moderated_at IS NOT NULL) AND m.group_chat_id = ?
+          AND EXISTS (SELECT 1 FROM members AS mem WHERE mem.group_chat_id = m.group_chat_id AND mem.user_account_id = ? AND mem.role = 'admin')
+         ORDER BY COALESCE(m.deleted_at, m.moderated_at) DESC"#,
+      group_chat_id.clone(),
+      user_account_id.clone()
+    )
+    .fetch_all(&self.my_sql_pool)
+    .await
+    .map_err(MessageDaoError::OtherError)
+  }
+
+  async fn search_messages(
+    &self,
+    user_account_id: String,
+    query: String,
+    group_chat_id: Option<String>,
+    limit: u32,
+    offset: u32,
+  ) -> Result<Vec<MessageSearchResult>, MessageDaoError> {
+    // NOTE: 管理者によって削除されたメッセージは元の本文で一致してしまうため、検索対象から除外する
+    let rows = sqlx::query_as!(
+      MessageSearchRow,
+      r#"SELECT m.id, m.group_chat_id, m.user_account_id, m.text, m.content_type, m.content,
+           m.edited_at, m.moderated_at, m.moderation_reason, m.deleted_at, m.deleted_by, m.created_at, m.updated_at,
+           MATCH (m.text) AGAINST (? IN NATURAL LANGUAGE MODE) AS score
+         FROM group_chats AS gc JOIN messages AS m ON gc.id = m.group_chat_id
+         WHERE gc.disabled = 'false' AND m.disabled = 'false' AND m.moderated_at IS NULL
+           AND MATCH (m.text) AGAINST (? IN NATURAL LANGUAGE MODE)
+           AND (? IS NULL OR m.group_chat_id = ?)
+           AND EXISTS (SELECT 1 FROM members AS mem WHERE mem.group_chat_id = m.group_chat_id AND mem.user_account_id = ?)
+         ORDER BY score DESC, m.created_at DESC, m.id
+         LIMIT ? OFFSET ?"#,
+      query.clone(),
+      query.clone(),
+      group_chat_id.clone(),
+      group_chat_id,
+      user_account_id,
+      limit,
+      offset
+    )
+    .fetch_all(&self.my_sql_pool)
+    .await
+    .map_err(MessageDaoError::OtherError)?;
+    Ok(rows.into_iter().map(|row| row.into_result(&query)).collect())
+  }
+
+  async fn get_messages_by_group_chat_ids(&self, group_chat_ids: Vec<String>) -> Result<Vec<Message>, MessageDaoError> {
+    // NOTE: IN句の要素数は可変なので、JSON配列で渡してJSON_TABLEで展開する
+    let group_chat_ids = serde_json::to_string(&group_chat_ids).unwrap();
+    sqlx::query_as!(
+      Message,
+      r#"SELECT m.id, m.group_chat_id, m.user_account_id,
+           CASE WHEN m.moderated_at IS NULL THEN m.text ELSE 'removed by moderator' END AS text,
+           CASE WHEN m.moderated_at IS NULL THEN m.content_type ELSE 'text' END AS content_type,
+           CASE WHEN m.moderated_at IS NULL THEN m.content ELSE NULL END AS content,
+           m.edited_at, m.moderated_at, m.moderation_reason, m.deleted_at, m.deleted_by, m.created_at, m.updated_at
+         FROM group_chats AS gc JOIN messages AS m ON gc.id = m.group_chat_id
+         WHERE gc.disabled = 'false' AND m.disabled = 'false'
+          AND m.group_chat_id IN (SELECT ids.id FROM JSON_TABLE(?, '$[*]' COLUMNS (id VARCHAR(64) PATH '$')) AS ids)
+         ORDER BY m.group_chat_id, m.created_at, m.id"#,
+      group_chat_ids
+    )
+    .fetch_all(&self.my_sql_pool)
+    .await
+    .map_err(MessageDaoError::OtherError)
+  }
+
+  async fn get_latest_messages_by_group_chat_ids(
+    &self,
+    group_chat_ids: Vec<String>,
+  ) -> Result<Vec<Message>, MessageDaoError> {
+    let group_chat_ids = serde_json::to_string(&group_chat_ids).unwrap();
+    sqlx::query_as!(
+      Message,
+      r#"SELECT m.id, m.group_chat_id, m.user_account_id,
+           CASE WHEN m.moderated_at IS NULL THEN m.text ELSE 'removed by moderator' END AS text,
+           CASE WHEN m.moderated_at IS NULL THEN m.content_type ELSE 'text' END AS content_type,
+           CASE WHEN m.moderated_at IS NULL THEN m.content ELSE NULL END AS content,
+           m.edited_at, m.moderated_at, m.moderation_reason, m.deleted_at, m.deleted_by, m.created_at, m.updated_at
+         FROM group_chats AS gc JOIN messages AS m ON gc.id = m.group_chat_id
+         WHERE gc.disabled = 'false' AND m.disabled = 'false'
+          AND m.group_chat_id IN (SELECT ids.id FROM JSON_TABLE(?, '$[*]' COLUMNS (id VARCHAR(64) PATH '$')) AS ids)
+          AND NOT EXISTS (
+            SELECT 1 FROM messages AS m2
+            WHERE m2.group_chat_id = m.group_chat_id AND m2.disabled = 'false'
+              AND (m2.created_at > m.created_at OR (m2.created_at = m.created_at AND m2.id > m.id))
+          )"#,
+      group_chat_ids
+    )
+    .fetch_all(&self.my_sql_pool)
+    .await
+    .map_err(MessageDaoError::OtherError)
+  }
+
+  async fn get_messages_after(
+    &self,
+    group_chat_id: String,
+    user_account_id: String,
+    created_at: NaiveDateTime,
+    message_id: String,
+    limit: u32,
+  ) -> Result<Vec<Message>, MessageDaoError> {
+    sqlx::query_as!(
+      Message,
+      r#"SELECT m.id, m.group_chat_id, m.user_account_id,
+           CASE WHEN m.moderated_at IS NULL THEN m.text ELSE 'removed by moderator' END AS text,
+           CASE WHEN m.moderated_at IS NULL THEN m.content_type ELSE 'text' END AS content_type,
+           CASE WHEN m.moderated_at IS NULL THEN m.content ELSE NULL END AS content,
+           m.edited_at, m.moderated_at, m.moderation_reason, m.deleted_at, m.deleted_by, m.created_at, m.updated_at
+         FROM group_chats AS gc JOIN messages AS m ON gc.id = m.group_chat_id
+         WHERE gc.disabled = 'false' AND m.disabled = 'false' AND m.group_chat_id = ?
+          AND (m.created_at > ? OR (m.created_at = ? AND m.id > ?))
+          AND EXISTS (SELECT 1 FROM members AS mem WHERE mem.group_chat_id = m.group_chat_id AND mem.user_account_id = ?)
+         ORDER BY m.created_at, m.id
+         LIMIT ?"#,
+      group_chat_id,
+      created_at,
+      created_at,
+      message_id,
+      user_account_id,
+      limit
+    )
+    .fetch_all(&self.my_sql_pool)
+    .await
+    .map_err(MessageDaoError::OtherError)
+  }
+}
+
+// ---
+
+/// 保持期間を過ぎたメッセージ
+///
+/// NOTE: GraphQLでは公開せず、保持期間ジョブが削除対象を探すためだけに利用する。
+#[derive(Debug, Clone)]
+pub struct ExpiredMessage {
+  /// グループチャットID
+  pub group_chat_id: String,
+  /// メッセージID
+  pub message_id: String,
+}
+
+/// 保持期間を過ぎたメッセージ用データアクセスオブジェクト。
+#[async_trait]
+pub trait ExpiredMessageDao: Send + Sync {
+  /// 保持期間を過ぎたメッセージを古い順に取得する。
+  ///
+  /// # 引数
+  /// - `default_retention_days` - グループチャットに保持日数が設定されていない場合の保持日数(未設定の場合は無期限)
+  /// - `limit` - 取得する最大件数
+  async fn get_expired_messages(
+    &self,
+    default_retention_days: Option<u32>,
+    limit: u32,
+  ) -> Result<Vec<ExpiredMessage>, MessageDaoError>;
+}
+
+/// [ExpiredMessageDao]の実装
+pub struct ExpiredMessageDaoImpl {
+  my_sql_pool: MySqlPool,
+}
+
+impl ExpiredMessageDaoImpl {
+  pub fn new(my_sql_pool: MySqlPool) -> Self {
+    Self { my_sql_pool }
+  }
+}
+
+#[async_trait]
+impl ExpiredMessageDao for ExpiredMessageDaoImpl {
+  async fn get_expired_messages(
+    &self,
+    default_retention_days: Option<u32>,
+    limit: u32,
+  ) -> Result<Vec<ExpiredMessage>, MessageDaoError> {
+    // NOTE: システムメッセージは集約に含まれないため対象外とする。
+    // 保持日数がグループチャットにも既定値にも設定されていない場合は、INTERVALがNULLとなり対象外となる。
+    sqlx::query_as!(
+      ExpiredMessage,
+      r#"SELECT m.group_chat_id, m.id AS message_id
+         FROM group_chats AS gc JOIN messages AS m ON gc.id = m.group_chat_id
+           LEFT JOIN group_chat_settings AS s ON gc.id = s.group_chat_id
+         WHERE gc.disabled = 'false' AND m.content_type <> 'system'
+          AND m.created_at <= UTC_TIMESTAMP() - INTERVAL COALESCE(s.message_retention_days, ?) DAY
+         ORDER BY m.created_at
+         LIMIT ?"#,
+      default_retention_days,
+      limit
+    )
+    .fetch_all(&self.my_sql_pool)
+    .await
+    .map_err(MessageDaoError::OtherError)
+  }
+}
+
+// ---
+
+#[derive(Debug, Error)]
+pub enum InvitationDaoError {
+  #[error("OtherError: {0}")]
+  OtherError(#[from] sqlx::Error),
+}
+
+/// 招待リードモデル
+#[derive(SimpleObject)]
+pub struct Invitation {
+  /// グループチャットID
+  group_chat_id: String,
+  /// グループチャット名
+  group_chat_name: String,
+  /// 招待されたアカウントID
+  user_account_id: String,
+  /// 参加時のロール
+  role: String,
+  /// 招待したアカウントID
+  invited_by: String,
+  /// 有効期限
+  expires_at: NaiveDateTime,
+  /// 作成日時
+  created_at: NaiveDateTime,
+}
+
+impl Invitation {
+  pub fn new(
+    group_chat_id: String,
+    group_chat_name: String,
+    user_account_id: String,
+    role: String,
+    invited_by: String,
+    expires_at: NaiveDateTime,
+    created_at: NaiveDateTime,
+  ) -> Self {
+    Self {
+      group_chat_id,
+      group_chat_name,
+      user_account_id,
+      role,
+      invited_by,
+      expires_at,
+      created_at,
+    }
+  }
+}
+
+/// 招待用データアクセスオブジェクト。
+///
+/// 招待を取得するためのインターフェース
+#[async_trait]
+pub trait InvitationDao: Send + Sync {
+  async fn get_pending_invitations(&self, user_account_id: String) -> Result<Vec<Invitation>, InvitationDaoError>;
+}
+
+/// [InvitationDao]の実装
+pub struct InvitationDaoImpl {
+  my_sql_pool: MySqlPool,
+}
+
+impl InvitationDaoImpl {
+  pub fn new(my_sql_pool: MySqlPool) -> Self {
+    Self { my_sql_pool }
+  }
+}
+
+#[async_trait]
+impl InvitationDao for InvitationDaoImpl {
+  async fn get_pending_invitations(&self, user_account_id: String) -> Result<Vec<Invitation>, InvitationDaoError> {
+    sqlx::query_as!(
+      Invitation,
+      r#"SELECT i.group_chat_id, gc.name AS group_chat_name, i.user_account_id, i.role, i.invited_by,
+           i.expires_at, i.created_at
+         FROM group_chats AS gc JOIN invitations AS i ON gc.id = i.group_chat_id
+         WHERE gc.disabled = 'false' AND i.user_account_id = ? AND i.expires_at > UTC_TIMESTAMP()
+         ORDER BY i.created_at DESC"#,
+      user_account_id.clone()
+    )
+    .fetch_all(&self.my_sql_pool)
+    .await
+    .map_err(InvitationDaoError::OtherError)
+  }
 }
 
 #[cfg(test)]
 mod tests {
-  use crate::gateways::{GroupChatDao, GroupChatDaoImpl, MemberDao, MemberDaoImpl, MessageDao, MessageDaoImpl};
+  use crate::gateways::{
+    make_snippet, GroupChatDao, GroupChatDaoImpl, MemberDao, MemberDaoImpl, MessageDao, MessageDaoImpl,
+  };
   use chrono::{DateTime, Utc};
-  use command_domain::group_chat::{GroupChatId, GroupChatName, MemberId, MemberRole, Message, MessageId};
+  use command_domain::group_chat::{ChatKind, GroupChatId, GroupChatName, MemberId, MemberRole, Message, MessageId};
   use command_domain::user_account::UserAccountId;
   use command_interface_adaptor_if::GroupChatReadModelUpdateDao;
   use command_interface_adaptor_impl::gateways::group_chat_read_model_dao_impl::GroupChatReadModelUpdateDaoImpl;
   use serial_test::serial;
   use sqlx::MySqlPool;
-  use std::sync::OnceLock;
+
   use testcontainers::core::{ContainerPort, WaitFor};
   use testcontainers::runners::AsyncRunner;
   use testcontainers::{ContainerRequest, GenericImage, ImageExt};
@@ -398,7 +1194,13 @@ mod tests {
   ) -> GroupChatId {
     let group_chat_id = GroupChatId::new();
     update_dao
-      .insert_group_chat(group_chat_id.clone(), group_chat_name, admin_id.clone(), created_at)
+      .insert_group_chat(
+        group_chat_id.clone(),
+        ChatKind::Group,
+        group_chat_name,
+        admin_id.clone(),
+        created_at,
+      )
       .await
       .unwrap();
     let member_id = MemberId::new();
@@ -530,6 +1332,53 @@ mod tests {
     assert!(members.iter().any(|e| e.user_account_id == user_account_id.to_string()));
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn test_get_members_by_group_chat_ids() {
+    init_logger();
+    let mysql_node = mysql_image().start().await.unwrap();
+    let mysql_port = mysql_node.get_host_port_ipv4(3306).await.unwrap();
+
+    refinery_migrate(mysql_port);
+
+    let url = make_database_url_for_application(mysql_port);
+    let pool = MySqlPool::connect(&url).await.unwrap();
+    let update_dao = GroupChatReadModelUpdateDaoImpl::new(pool.clone());
+
+    let admin_id = UserAccountId::new();
+    let user_account_id = UserAccountId::new();
+    let group_chat_name = GroupChatName::new("test").unwrap();
+    let created_at = Utc::now();
+
+    let group_chat_id1 =
+      insert_group_chat_and_member(&update_dao, group_chat_name.clone(), admin_id.clone(), created_at).await;
+    let group_chat_id2 =
+      insert_group_chat_and_member(&update_dao, group_chat_name.clone(), admin_id.clone(), created_at).await;
+    insert_member_read_model(update_dao, group_chat_id2.clone(), user_account_id.clone(), created_at).await;
+
+    let dao = MemberDaoImpl::new(pool);
+    let members = dao
+      .get_members_by_group_chat_ids(vec![group_chat_id1.to_string(), group_chat_id2.to_string()])
+      .await
+      .unwrap();
+
+    assert_eq!(members.len(), 3);
+    assert_eq!(
+      members
+        .iter()
+        .filter(|e| e.group_chat_id == group_chat_id1.to_string())
+        .count(),
+      1
+    );
+    assert_eq!(
+      members
+        .iter()
+        .filter(|e| e.group_chat_id == group_chat_id2.to_string())
+        .count(),
+      2
+    );
+  }
+
   #[tokio::test]
   async fn test_get_message() {
     init_logger();
@@ -630,4 +1479,199 @@ mod tests {
     assert_eq!(messages[0].user_account_id, admin_id.to_string());
     assert_eq!(messages[1].user_account_id, admin_id.to_string());
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_get_messages_after() {
+    init_logger();
+
+    let mysql_node = mysql_image().start().await.unwrap();
+    let mysql_port = mysql_node.get_host_port_ipv4(3306).await.unwrap();
+
+    refinery_migrate(mysql_port);
+
+    let url = make_database_url_for_application(mysql_port);
+    let pool = MySqlPool::connect(&url).await.unwrap();
+    let update_dao = GroupChatReadModelUpdateDaoImpl::new(pool.clone());
+
+    let admin_id = UserAccountId::new();
+    let group_chat_name = GroupChatName::new("test").unwrap();
+    let created_at = Utc::now();
+
+    let group_chat_id =
+      insert_group_chat_and_member(&update_dao, group_chat_name.clone(), admin_id.clone(), created_at).await;
+
+    for (i, text) in ["test1", "test2", "test3"].iter().enumerate() {
+      let message = Message::new(MessageId::new(), text.to_string(), admin_id.clone());
+      update_dao
+        .insert_message(
+          group_chat_id.clone(),
+          message,
+          created_at + chrono::Duration::seconds(i as i64),
+        )
+        .await
+        .unwrap();
+    }
+
+    let dao = MessageDaoImpl::new(pool.clone());
+    let messages = dao
+      .get_messages(group_chat_id.to_string(), admin_id.to_string())
+      .await
+      .unwrap();
+    let first = messages.iter().find(|e| e.text == "test1").unwrap();
+
+    let messages = dao
+      .get_messages_after(
+        group_chat_id.to_string(),
+        admin_id.to_string(),
+        first.created_at,
+        first.id.clone(),
+        10,
+      )
+      .await
+      .unwrap();
+    assert_eq!(
+      messages.iter().map(|e| e.text.as_str()).collect::<Vec<_>>(),
+      vec!["test2", "test3"]
+    );
+
+    let messages = dao
+      .get_messages_after(
+        group_chat_id.to_string(),
+        admin_id.to_string(),
+        first.created_at,
+        first.id.clone(),
+        1,
+      )
+      .await
+      .unwrap();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].text, "test2");
+
+    // メンバーでないアカウントには返さない
+    let messages = dao
+      .get_messages_after(
+        group_chat_id.to_string(),
+        UserAccountId::new().to_string(),
+        first.created_at,
+        first.id.clone(),
+        10,
+      )
+      .await
+      .unwrap();
+    assert!(messages.is_empty());
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_get_mentions() {
+    init_logger();
+
+    let mysql_node = mysql_image().start().await.unwrap();
+    let mysql_port = mysql_node.get_host_port_ipv4(3306).await.unwrap();
+
+    refinery_migrate(mysql_port);
+
+    let url = make_database_url_for_application(mysql_port);
+    let pool = MySqlPool::connect(&url).await.unwrap();
+    let update_dao = GroupChatReadModelUpdateDaoImpl::new(pool.clone());
+
+    let admin_id = UserAccountId::new();
+    let user_account_id = UserAccountId::new();
+    let group_chat_name = GroupChatName::new("test").unwrap();
+    let created_at = Utc::now();
+
+    let group_chat_id =
+      insert_group_chat_and_member(&update_dao, group_chat_name.clone(), admin_id.clone(), created_at).await;
+    let update_dao = GroupChatReadModelUpdateDaoImpl::new(pool.clone());
+    insert_member_read_model(update_dao, group_chat_id.clone(), user_account_id.clone(), created_at).await;
+
+    let update_dao = GroupChatReadModelUpdateDaoImpl::new(pool.clone());
+    let message = Message::new(MessageId::new(), format!("hi @{}", user_account_id), admin_id.clone());
+    update_dao
+      .insert_message(group_chat_id.clone(), message.clone(), created_at)
+      .await
+      .unwrap();
+
+    let dao = MessageDaoImpl::new(pool.clone());
+    let mentions = dao.get_mentions(user_account_id.to_string()).await.unwrap();
+    assert_eq!(mentions.len(), 1);
+    assert_eq!(mentions[0].id, message.breach_encapsulation_of_id().to_string());
+
+    let mentions = dao.get_mentions(admin_id.to_string()).await.unwrap();
+    assert!(mentions.is_empty());
+  }
+
+  #[test]
+  fn test_make_snippet() {
+    assert_eq!(
+      make_snippet("今日は<b>会議</b>です", "会議"),
+      "今日は&lt;b&gt;<mark>会議</mark>&lt;/b&gt;です"
+    );
+    assert_eq!(
+      make_snippet("Hello World", "world hello"),
+      "<mark>Hello</mark> <mark>World</mark>"
+    );
+    let text = format!("{}検索語{}", "あ".repeat(50), "い".repeat(100));
+    let snippet = make_snippet(&text, "検索語");
+    assert!(snippet.starts_with(&format!("…{}<mark>検索語</mark>", "あ".repeat(30))));
+    assert!(snippet.ends_with('…'));
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_search_messages() {
+    init_logger();
+
+    let mysql_node = mysql_image().start().await.unwrap();
+    let mysql_port = mysql_node.get_host_port_ipv4(3306).await.unwrap();
+
+    refinery_migrate(mysql_port);
+
+    let url = make_database_url_for_application(mysql_port);
+    let pool = MySqlPool::connect(&url).await.unwrap();
+    let update_dao = GroupChatReadModelUpdateDaoImpl::new(pool.clone());
+
+    let admin_id = UserAccountId::new();
+    let other_id = UserAccountId::new();
+    let created_at = Utc::now();
+
+    let group_chat_id = insert_group_chat_and_member(
+      &update_dao,
+      GroupChatName::new("test").unwrap(),
+      admin_id.clone(),
+      created_at,
+    )
+    .await;
+    let other_group_chat_id = insert_group_chat_and_member(
+      &update_dao,
+      GroupChatName::new("other").unwrap(),
+      other_id.clone(),
+      created_at,
+    )
+    .await;
+
+    for (id, text, sender) in [
+      (&group_chat_id, "明日の会議は午後からです", &admin_id),
+      (&group_chat_id, "ランチに行きましょう", &admin_id),
+      (&other_group_chat_id, "会議室を予約しました", &other_id),
+    ] {
+      let message = Message::new(MessageId::new(), text.to_string(), sender.clone());
+      update_dao
+        .insert_message(id.clone(), message, created_at)
+        .await
+        .unwrap();
+    }
+
+    let dao = MessageDaoImpl::new(pool.clone());
+    let results = dao
+      .search_messages(admin_id.to_string(), "会議".to_string(), None, 10, 0)
+      .await
+      .unwrap();
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].message.group_chat_id, group_chat_id.to_string());
+    assert!(results[0].snippet.contains("<mark>会議</mark>"));
+    assert!(results[0].score > 0.0);
+  }
 }
@@ -0,0 +1,461 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDateTime};
+use infrastructure::grpc::status_from_graphql_error;
+use infrastructure::shutdown::ShutdownSignal;
+use prost_types::Timestamp;
+use sqlx::MySqlPool;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+
+use crate::gateways::{GroupChat, GroupChatSettings, Member, Message, MessageContent};
+use crate::resolvers::{
+  create_service_context, group_chat_dao_error_handling, member_dao_error_handling, message_dao_error_handling,
+  ServiceContext,
+};
+
+use proto::group_chat_query_service_server::{GroupChatQueryService, GroupChatQueryServiceServer};
+use proto::{
+  message_body, GetGroupChatRequest, GetGroupChatSettingsRequest, GetGroupChatsReply, GetGroupChatsRequest,
+  GetMemberRequest, GetMembersReply, GetMembersRequest, GetMentionsRequest, GetMessageRequest, GetMessagesReply,
+  GetMessagesRequest, StreamMessagesRequest,
+};
+
+/// `proto/group_chat/query/v1/group_chat_query.proto`から生成したコード
+pub mod proto {
+  tonic::include_proto!("group_chat.query.v1");
+}
+
+/// 新しいメッセージを確認する既定の間隔
+const DEFAULT_MESSAGE_STREAM_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// 1回の確認で取得するメッセージの最大件数
+const MESSAGE_STREAM_BATCH_SIZE: u32 = 100;
+/// 配信待ちのメッセージを保持する件数
+const MESSAGE_STREAM_BUFFER_SIZE: usize = 128;
+
+/// GraphQLのQueryRootと同じリードモデルを返すgRPCサービス。
+///
+/// NOTE: エラーはGraphQLのリゾルバと同じエラーハンドリングを通し、`code`拡張に対応するステータスコードで返す。
+pub struct GroupChatQueryGrpcService {
+  ctx: Arc<ServiceContext>,
+  shutdown_signal: ShutdownSignal,
+  message_stream_poll_interval: Duration,
+}
+
+impl GroupChatQueryGrpcService {
+  /// コンストラクタ
+  ///
+  /// # 引数
+  /// - `ctx` - [ServiceContext]
+  /// - `shutdown_signal` - シグナルを受けたら、配信中のストリームを終了する[ShutdownSignal]
+  pub fn new(ctx: ServiceContext, shutdown_signal: ShutdownSignal) -> Self {
+    Self {
+      ctx: Arc::new(ctx),
+      shutdown_signal,
+      message_stream_poll_interval: DEFAULT_MESSAGE_STREAM_POLL_INTERVAL,
+    }
+  }
+
+  /// 新しいメッセージを確認する間隔を設定したサービスを返す。
+  pub fn with_message_stream_poll_interval(mut self, message_stream_poll_interval: Duration) -> Self {
+    self.message_stream_poll_interval = message_stream_poll_interval;
+    self
+  }
+
+  /// gRPCサーバに登録するサービスを生成する。
+  pub fn into_server(self) -> GroupChatQueryServiceServer<Self> {
+    GroupChatQueryServiceServer::new(self)
+  }
+}
+
+/// gRPCサーバに登録するサービスを生成する関数。
+///
+/// # 引数
+/// - `pool` - リードモデルのデータベースの接続プール
+/// - `shutdown_signal` - サーバと共有する[ShutdownSignal]
+pub fn create_grpc_service(
+  pool: MySqlPool,
+  shutdown_signal: ShutdownSignal,
+) -> GroupChatQueryServiceServer<GroupChatQueryGrpcService> {
+  GroupChatQueryGrpcService::new(create_service_context(pool), shutdown_signal).into_server()
+}
+
+fn timestamp(date_time: NaiveDateTime) -> Timestamp {
+  let date_time = date_time.and_utc();
+  Timestamp {
+    seconds: date_time.timestamp(),
+    nanos: date_time.timestamp_subsec_nanos() as i32,
+  }
+}
+
+impl From<GroupChat> for proto::GroupChat {
+  fn from(group_chat: GroupChat) -> Self {
+    Self {
+      id: group_chat.id,
+      kind: group_chat.kind,
+      name: group_chat.name,
+      owner_id: group_chat.owner_id,
+      created_at: Some(timestamp(group_chat.created_at)),
+      updated_at: Some(timestamp(group_chat.updated_at)),
+    }
+  }
+}
+
+impl From<GroupChatSettings> for proto::GroupChatSettings {
+  fn from(settings: GroupChatSettings) -> Self {
+    Self {
+      group_chat_id: settings.group_chat_id,
+      max_members: settings.max_members,
+      post_permission: settings.post_permission,
+      add_member_permission: settings.add_member_permission,
+      message_retention_days: settings.message_retention_days,
+      updated_at: Some(timestamp(settings.updated_at)),
+    }
+  }
+}
+
+impl From<Member> for proto::Member {
+  fn from(member: Member) -> Self {
+    Self {
+      id: member.id,
+      group_chat_id: member.group_chat_id,
+      user_account_id: member.user_account_id,
+      role: member.role,
+      created_at: Some(timestamp(member.created_at)),
+      updated_at: Some(timestamp(member.updated_at)),
+    }
+  }
+}
+
+impl From<MessageContent> for proto::MessageBody {
+  fn from(content: MessageContent) -> Self {
+    let body = match content {
+      MessageContent::Text(content) => message_body::Body::Text(content.text),
+      MessageContent::Attachment(content) => message_body::Body::Attachment(proto::Attachment {
+        url: content.url,
+        mime: content.mime,
+        size: content.size,
+      }),
+      MessageContent::LinkPreview(content) => message_body::Body::LinkPreview(proto::LinkPreview {
+        url: content.url,
+        title: content.title,
+        description: content.description,
+      }),
+      MessageContent::System(content) => message_body::Body::System(content.text),
+    };
+    Self { body: Some(body) }
+  }
+}
+
+impl From<Message> for proto::Message {
+  fn from(message: Message) -> Self {
+    let body = message.parse_body().into();
+    Self {
+      id: message.id,
+      group_chat_id: message.group_chat_id,
+      user_account_id: message.user_account_id,
+      text: message.text,
+      body: Some(body),
+      edited_at: message.edited_at.map(timestamp),
+      moderated_at: message.moderated_at.map(timestamp),
+      moderation_reason: message.moderation_reason,
+      created_at: Some(timestamp(message.created_at)),
+      updated_at: Some(timestamp(message.updated_at)),
+    }
+  }
+}
+
+#[tonic::async_trait]
+impl GroupChatQueryService for GroupChatQueryGrpcService {
+  async fn get_group_chat(&self, request: Request<GetGroupChatRequest>) -> Result<Response<proto::GroupChat>, Status> {
+    let request = request.into_inner();
+    self
+      .ctx
+      .get_group_chat_dao()
+      .get_group_chat(request.group_chat_id, request.user_account_id)
+      .await
+      .map(|group_chat| Response::new(group_chat.into()))
+      .map_err(|error| status_from_graphql_error(group_chat_dao_error_handling(error)))
+  }
+
+  async fn get_group_chats(
+    &self,
+    request: Request<GetGroupChatsRequest>,
+  ) -> Result<Response<GetGroupChatsReply>, Status> {
+    let request = request.into_inner();
+    self
+      .ctx
+      .get_group_chat_dao()
+      .get_group_chats(request.user_account_id)
+      .await
+      .map(|group_chats| {
+        Response::new(GetGroupChatsReply {
+          group_chats: group_chats.into_iter().map(Into::into).collect(),
+        })
+      })
+      .map_err(|error| status_from_graphql_error(group_chat_dao_error_handling(error)))
+  }
+
+  async fn get_group_chat_settings(
+    &self,
+    request: Request<GetGroupChatSettingsRequest>,
+  ) -> Result<Response<proto::GroupChatSettings>, Status> {
+    let request = request.into_inner();
+    self
+      .ctx
+      .get_group_chat_dao()
+      .get_group_chat_settings(request.group_chat_id, request.user_account_id)
+      .await
+      .map(|settings| Response::new(settings.into()))
+      .map_err(|error| status_from_graphql_error(group_chat_dao_error_handling(error)))
+  }
+
+  async fn get_member(&self, request: Request<GetMemberRequest>) -> Result<Response<proto::Member>, Status> {
+    let request = request.into_inner();
+    self
+      .ctx
+      .get_member_dao()
+      .get_member(request.group_chat_id, request.user_account_id)
+      .await
+      .map(|member| Response::new(member.into()))
+      .map_err(|error| status_from_graphql_error(member_dao_error_handling(error)))
+  }
+
+  async fn get_members(&self, request: Request<GetMembersRequest>) -> Result<Response<GetMembersReply>, Status> {
+    let request = request.into_inner();
+    self
+      .ctx
+      .get_member_dao()
+      .get_members(request.group_chat_id, request.user_account_id)
+      .await
+      .map(|members| {
+        Response::new(GetMembersReply {
+          members: members.into_iter().map(Into::into).collect(),
+        })
+      })
+      .map_err(|error| status_from_graphql_error(member_dao_error_handling(error)))
+  }
+
+  async fn get_message(&self, request: Request<GetMessageRequest>) -> Result<Response<proto::Message>, Status> {
+    let request = request.into_inner();
+    self
+      .ctx
+      .get_message_dao()
+      .get_message(request.message_id, request.user_account_id)
+      .await
+      .map(|message| Response::new(message.into()))
+      .map_err(|error| status_from_graphql_error(message_dao_error_handling(error)))
+  }
+
+  async fn get_messages(&self, request: Request<GetMessagesRequest>) -> Result<Response<GetMessagesReply>, Status> {
+    let request = request.into_inner();
+    self
+      .ctx
+      .get_message_dao()
+      .get_messages(request.group_chat_id, request.user_account_id)
+      .await
+      .map(|messages| {
+        Response::new(GetMessagesReply {
+          messages: messages.into_iter().map(Into::into).collect(),
+        })
+      })
+      .map_err(|error| status_from_graphql_error(message_dao_error_handling(error)))
+  }
+
+  async fn get_mentions(&self, request: Request<GetMentionsRequest>) -> Result<Response<GetMessagesReply>, Status> {
+    let request = request.into_inner();
+    self
+      .ctx
+      .get_message_dao()
+      .get_mentions(request.user_account_id)
+      .await
+      .map(|messages| {
+        Response::new(GetMessagesReply {
+          messages: messages.into_iter().map(Into::into).collect(),
+        })
+      })
+      .map_err(|error| status_from_graphql_error(message_dao_error_handling(error)))
+  }
+
+  type StreamMessagesStream = ReceiverStream<Result<proto::Message, Status>>;
+
+  /// 新しいメッセージを配信する。
+  ///
+  /// NOTE: リードモデルには変更の通知がないので、一定間隔でリードモデルを確認し、
+  /// 前回配信したメッセージより後のメッセージを配信する。
+  async fn stream_messages(
+    &self,
+    request: Request<StreamMessagesRequest>,
+  ) -> Result<Response<Self::StreamMessagesStream>, Status> {
+    let request = request.into_inner();
+    let group_chat_id = request.group_chat_id;
+    let user_account_id = request.user_account_id;
+    let group_chat_dao = self.ctx.get_group_chat_dao();
+    let message_dao = self.ctx.get_message_dao();
+
+    // 閲覧アカウントがメンバーであることを確認してから配信を始める
+    group_chat_dao
+      .get_group_chat(group_chat_id.clone(), user_account_id.clone())
+      .await
+      .map_err(|error| status_from_graphql_error(group_chat_dao_error_handling(error)))?;
+
+    let mut cursor = match request.after_message_id {
+      Some(message_id) => {
+        let message = message_dao
+          .get_message(message_id, user_account_id.clone())
+          .await
+          .map_err(|error| status_from_graphql_error(message_dao_error_handling(error)))?;
+        if message.group_chat_id != group_chat_id {
+          return Err(Status::invalid_argument(
+            "after_message_id does not belong to the group chat",
+          ));
+        }
+        (message.created_at, message.id)
+      }
+      None => message_dao
+        .get_latest_messages_by_group_chat_ids(vec![group_chat_id.clone()])
+        .await
+        .map_err(|error| status_from_graphql_error(message_dao_error_handling(error)))?
+        .into_iter()
+        .next()
+        .map(|message| (message.created_at, message.id))
+        .unwrap_or((DateTime::UNIX_EPOCH.naive_utc(), String::new())),
+    };
+
+    let (tx, rx) = mpsc::channel(MESSAGE_STREAM_BUFFER_SIZE);
+    let mut interval = tokio::time::interval(self.message_stream_poll_interval);
+    let shutdown_signal = self.shutdown_signal.clone();
+    tokio::spawn(async move {
+      loop {
+        // NOTE: シャットダウンのシグナルを受けたら送信側を閉じてストリームを正常に終了し、サーバが接続を閉じられるようにする
+        tokio::select! {
+          _ = interval.tick() => {}
+          _ = shutdown_signal.clone().triggered() => break,
+        }
+        if tx.is_closed() {
+          break;
+        }
+        if let Err(error) = group_chat_dao
+          .get_group_chat(group_chat_id.clone(), user_account_id.clone())
+          .await
+        {
+          let _ = tx
+            .send(Err(status_from_graphql_error(group_chat_dao_error_handling(error))))
+            .await;
+          break;
+        }
+        let messages = match message_dao
+          .get_messages_after(
+            group_chat_id.clone(),
+            user_account_id.clone(),
+            cursor.0,
+            cursor.1.clone(),
+            MESSAGE_STREAM_BATCH_SIZE,
+          )
+          .await
+        {
+          Ok(messages) => messages,
+          Err(error) => {
+            let _ = tx
+              .send(Err(status_from_graphql_error(message_dao_error_handling(error))))
+              .await;
+            break;
+          }
+        };
+        for message in messages {
+          cursor = (message.created_at, message.id.clone());
+          if tx.send(Ok(message.into())).await.is_err() {
+            return;
+          }
+        }
+      }
+    });
+
+    Ok(Response::new(ReceiverStream::new(rx)))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use tokio_stream::StreamExt;
+
+  use crate::resolvers::tests::create_service_context_on_test;
+
+  use super::*;
+
+  fn create_service_on_test() -> GroupChatQueryGrpcService {
+    let (_, shutdown_signal) = ShutdownSignal::channel();
+    GroupChatQueryGrpcService::new(create_service_context_on_test(), shutdown_signal)
+      .with_message_stream_poll_interval(Duration::from_millis(10))
+  }
+
+  #[tokio::test]
+  async fn test_get_message() {
+    let message = create_service_on_test()
+      .get_message(Request::new(GetMessageRequest {
+        message_id: "message_id".to_string(),
+        user_account_id: "user_account_id".to_string(),
+      }))
+      .await
+      .unwrap()
+      .into_inner();
+    assert_eq!(message.id, "message_id");
+    assert_eq!(
+      message.body.unwrap().body,
+      Some(message_body::Body::Attachment(proto::Attachment {
+        url: "https://example.com/a.png".to_string(),
+        mime: "image/png".to_string(),
+        size: 1024,
+      }))
+    );
+  }
+
+  #[tokio::test]
+  async fn test_stream_messages() {
+    let mut stream = create_service_on_test()
+      .stream_messages(Request::new(StreamMessagesRequest {
+        group_chat_id: "group_chat_id".to_string(),
+        user_account_id: "user_account_id".to_string(),
+        after_message_id: None,
+      }))
+      .await
+      .unwrap()
+      .into_inner();
+
+    // 購読を開始した時点の最新のメッセージ("1")は配信せず、その後のメッセージのみを配信する
+    let message = stream.next().await.unwrap().unwrap();
+    assert_eq!(message.id, "2");
+    assert_eq!(
+      message.body.unwrap().body,
+      Some(message_body::Body::Text("mock message 2".to_string()))
+    );
+    assert!(tokio::time::timeout(Duration::from_millis(50), stream.next())
+      .await
+      .is_err());
+  }
+
+  #[tokio::test]
+  async fn test_stream_messages_ends_on_shutdown() {
+    let (sender, shutdown_signal) = ShutdownSignal::channel();
+    let mut stream = GroupChatQueryGrpcService::new(create_service_context_on_test(), shutdown_signal)
+      .with_message_stream_poll_interval(Duration::from_millis(10))
+      .stream_messages(Request::new(StreamMessagesRequest {
+        group_chat_id: "group_chat_id".to_string(),
+        user_account_id: "user_account_id".to_string(),
+        after_message_id: None,
+      }))
+      .await
+      .unwrap()
+      .into_inner();
+    assert_eq!(stream.next().await.unwrap().unwrap().id, "2");
+
+    // シグナルを受けたら、エラーを返さずにストリームを終了する
+    sender.send(true).unwrap();
+    let next = tokio::time::timeout(Duration::from_secs(1), stream.next())
+      .await
+      .unwrap();
+    assert!(next.is_none());
+  }
+}
@@ -1,3 +1,5 @@
 pub mod controllers;
 pub mod gateways;
+pub mod grpc;
+pub mod loaders;
 pub mod resolvers;
@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_graphql::async_trait::async_trait;
+use async_graphql::dataloader::Loader;
+use async_graphql::Error;
+
+use crate::gateways::{Member, MemberDao, Message, MessageDao};
+use crate::resolvers::{member_dao_error_handling, message_dao_error_handling};
+
+/// グループチャットIDごとにメンバー一覧をまとめて読み込む[Loader]。
+///
+/// `GroupChat.members`と`Message.sender`で共有する。
+pub struct MembersLoader {
+  member_dao: Arc<dyn MemberDao>,
+}
+
+impl MembersLoader {
+  pub fn new(member_dao: Arc<dyn MemberDao>) -> Self {
+    Self { member_dao }
+  }
+}
+
+#[async_trait]
+impl Loader<String> for MembersLoader {
+  type Value = Vec<Member>;
+  type Error = Error;
+
+  async fn load(&self, keys: &[String]) -> Result<HashMap<String, Self::Value>, Self::Error> {
+    let members = self
+      .member_dao
+      .get_members_by_group_chat_ids(keys.to_vec())
+      .await
+      .map_err(member_dao_error_handling)?;
+    let mut result: HashMap<String, Vec<Member>> = HashMap::new();
+    for member in members {
+      result
+        .entry(member.member_group_chat_id().to_string())
+        .or_default()
+        .push(member);
+    }
+    Ok(result)
+  }
+}
+
+/// グループチャットIDごとにメッセージ一覧をまとめて読み込む[Loader]。
+pub struct MessagesLoader {
+  message_dao: Arc<dyn MessageDao>,
+}
+
+impl MessagesLoader {
+  pub fn new(message_dao: Arc<dyn MessageDao>) -> Self {
+    Self { message_dao }
+  }
+}
+
+#[async_trait]
+impl Loader<String> for MessagesLoader {
+  type Value = Vec<Message>;
+  type Error = Error;
+
+  async fn load(&self, keys: &[String]) -> Result<HashMap<String, Self::Value>, Self::Error> {
+    let messages = self
+      .message_dao
+      .get_messages_by_group_chat_ids(keys.to_vec())
+      .await
+      .map_err(message_dao_error_handling)?;
+    let mut result: HashMap<String, Vec<Message>> = HashMap::new();
+    for message in messages {
+      result
+        .entry(message.message_group_chat_id().to_string())
+        .or_default()
+        .push(message);
+    }
+    Ok(result)
+  }
+}
+
+/// グループチャットIDごとに最新のメッセージをまとめて読み込む[Loader]。
+pub struct LatestMessageLoader {
+  message_dao: Arc<dyn MessageDao>,
+}
+
+impl LatestMessageLoader {
+  pub fn new(message_dao: Arc<dyn MessageDao>) -> Self {
+    Self { message_dao }
+  }
+}
+
+#[async_trait]
+impl Loader<String> for LatestMessageLoader {
+  type Value = Message;
+  type Error = Error;
+
+  async fn load(&self, keys: &[String]) -> Result<HashMap<String, Self::Value>, Self::Error> {
+    let messages = self
+      .message_dao
+      .get_latest_messages_by_group_chat_ids(keys.to_vec())
+      .await
+      .map_err(message_dao_error_handling)?;
+    Ok(
+      messages
+        .into_iter()
+        .map(|message| (message.message_group_chat_id().to_string(), message))
+        .collect(),
+    )
+  }
+}
@@ -1,23 +1,46 @@
 use std::sync::Arc;
 
-use async_graphql::futures_util::Stream;
-use async_graphql::futures_util::StreamExt;
+use async_graphql::connection::{query, Connection, Edge};
+use async_graphql::dataloader::DataLoader;
+use async_graphql::extensions::Tracing;
 use async_graphql::{
-  Context, EmptyMutation, EmptySubscription, Error, ErrorExtensions, FieldResult, Object, Schema, SchemaBuilder,
-  Subscription,
+  ComplexObject, Context, EmptyMutation, EmptySubscription, Error, FieldResult, Object, Schema, SchemaBuilder,
 };
-use redis::{Client, PubSub};
+use infrastructure::error::{ApiError, ErrorCode};
+use infrastructure::graphql::{GraphQLSettings, GraphQLSettingsError};
 use sqlx::MySqlPool;
 
 use crate::gateways::{
-  GroupChat, GroupChatDao, GroupChatDaoError, GroupChatDaoImpl, Member, MemberDao, MemberDaoError, MemberDaoImpl,
-  Message, MessageDao, MessageDaoError, MessageDaoImpl,
+  GroupChat, GroupChatDao, GroupChatDaoError, GroupChatDaoImpl, GroupChatSettings, Invitation, InvitationDao,
+  InvitationDaoError, InvitationDaoImpl, Member, MemberDao, MemberDaoError, MemberDaoImpl, Message, MessageContent,
+  MessageDao, MessageDaoError, MessageDaoImpl, MessageRevision, MessageSearchResult,
 };
+use crate::loaders::{LatestMessageLoader, MembersLoader, MessagesLoader};
+
+/// `searchMessages`の1ページあたりの既定の件数
+const DEFAULT_SEARCH_PAGE_SIZE: usize = 20;
+/// `searchMessages`の1ページあたりの最大件数
+const MAX_SEARCH_PAGE_SIZE: usize = 100;
+/// 一覧を返すフィールドのコストの係数(想定する件数)
+const LIST_COST_FACTOR: usize = 10;
+/// メッセージ一覧を返すフィールドのコストの係数(想定する件数)
+///
+/// NOTE: メッセージ一覧はページングがなく件数が大きくなりやすいので、他の一覧より高くする
+const MESSAGES_COST_FACTOR: usize = 50;
+
+/// `searchMessages`の1ページあたりの件数
+fn search_page_size(first: Option<i32>) -> usize {
+  first
+    .map(|first| first.max(0) as usize)
+    .unwrap_or(DEFAULT_SEARCH_PAGE_SIZE)
+    .min(MAX_SEARCH_PAGE_SIZE)
+}
 
 pub struct ServiceContext {
   group_chat_dao: Arc<dyn GroupChatDao>,
   member_dao: Arc<dyn MemberDao>,
   message_dao: Arc<dyn MessageDao>,
+  invitation_dao: Arc<dyn InvitationDao>,
 }
 
 impl ServiceContext {
@@ -25,11 +48,13 @@ impl ServiceContext {
     group_chat_dao: Arc<dyn GroupChatDao>,
     member_dao: Arc<dyn MemberDao>,
     message_dao: Arc<dyn MessageDao>,
+    invitation_dao: Arc<dyn InvitationDao>,
   ) -> Self {
     Self {
       group_chat_dao,
       member_dao,
       message_dao,
+      invitation_dao,
     }
   }
 
@@ -44,6 +69,10 @@ impl ServiceContext {
   pub fn get_message_dao(&self) -> Arc<dyn MessageDao> {
     self.message_dao.clone()
   }
+
+  pub fn get_invitation_dao(&self) -> Arc<dyn InvitationDao> {
+    self.invitation_dao.clone()
+  }
 }
 
 /// クエリ
@@ -80,6 +109,7 @@ impl QueryRoot {
   ///
   /// # 戻り値
   /// - `Vec<GroupChat>` - グループチャット一覧
+  #[graphql(complexity = "LIST_COST_FACTOR * child_complexity")]
   async fn get_group_chats<'ctx>(&self, ctx: &Context<'ctx>, user_account_id: String) -> FieldResult<Vec<GroupChat>> {
     let ctx = ctx.data::<ServiceContext>().unwrap();
     ctx
@@ -89,6 +119,28 @@ impl QueryRoot {
       .map_err(group_chat_dao_error_handling)
   }
 
+  /// 指定されたグループチャットIDのグループチャットの設定を取得する。
+  ///
+  /// # 引数
+  /// - `group_chat_id` - グループチャットID
+  /// - `user_account_id` - 閲覧アカウントID
+  ///
+  /// # 戻り値
+  /// - `GroupChatSettings` - グループチャットの設定
+  async fn get_group_chat_settings<'ctx>(
+    &self,
+    ctx: &Context<'ctx>,
+    group_chat_id: String,
+    user_account_id: String,
+  ) -> FieldResult<GroupChatSettings> {
+    let ctx = ctx.data::<ServiceContext>().unwrap();
+    ctx
+      .group_chat_dao
+      .get_group_chat_settings(group_chat_id, user_account_id)
+      .await
+      .map_err(group_chat_dao_error_handling)
+  }
+
   /// 指定されたアカウントIDのメンバーを取得する
   ///
   /// # 引数
@@ -119,6 +171,7 @@ impl QueryRoot {
   ///
   /// # 戻り値
   /// - `Vec<Member>` - メンバー一覧
+  #[graphql(complexity = "LIST_COST_FACTOR * child_complexity")]
   async fn get_members<'ctx>(
     &self,
     ctx: &Context<'ctx>,
@@ -163,6 +216,7 @@ impl QueryRoot {
   ///
   /// # 戻り値
   /// - `Vec<Message>` - メッセージ一覧
+  #[graphql(complexity = "MESSAGES_COST_FACTOR * child_complexity")]
   async fn get_messages<'ctx>(
     &self,
     ctx: &Context<'ctx>,
@@ -176,57 +230,307 @@ impl QueryRoot {
       .await
       .map_err(message_dao_error_handling)
   }
+
+  /// 指定されたアカウントIDがメンションされているメッセージ一覧を取得する
+  ///
+  /// # 引数
+  /// - `user_account_id` - 閲覧アカウントID
+  ///
+  /// # 戻り値
+  /// - `Vec<Message>` - メッセージ一覧(新しい順)
+  #[graphql(complexity = "MESSAGES_COST_FACTOR * child_complexity")]
+  async fn get_mentions<'ctx>(&self, ctx: &Context<'ctx>, user_account_id: String) -> FieldResult<Vec<Message>> {
+    let ctx = ctx.data::<ServiceContext>().unwrap();
+    ctx
+      .message_dao
+      .get_mentions(user_account_id)
+      .await
+      .map_err(message_dao_error_handling)
+  }
+
+  /// 指定されたグループチャットIDの削除済みメッセージ一覧を取得する(管理者のみ)
+  ///
+  /// # 引数
+  /// - `group_chat_id` - グループチャットID
+  /// - `user_account_id` - 閲覧アカウントID(管理者)
+  ///
+  /// # 戻り値
+  /// - `Vec<Message>` - 削除日時と削除したアカウントIDを含むメッセージ一覧
+  #[graphql(complexity = "MESSAGES_COST_FACTOR * child_complexity")]
+  async fn get_deleted_messages<'ctx>(
+    &self,
+    ctx: &Context<'ctx>,
+    group_chat_id: String,
+    user_account_id: String,
+  ) -> FieldResult<Vec<Message>> {
+    let ctx = ctx.data::<ServiceContext>().unwrap();
+    ctx
+      .message_dao
+      .get_deleted_messages(group_chat_id, user_account_id)
+      .await
+      .map_err(message_dao_error_handling)
+  }
+
+  /// 閲覧アカウントがメンバーであるグループチャットのメッセージを全文検索する。
+  ///
+  /// # 引数
+  /// - `user_account_id` - 閲覧アカウントID
+  /// - `query` - 検索語
+  /// - `group_chat_id` - 検索対象のグループチャットID(省略した場合はすべてのグループチャット)
+  /// - `first` - 取得する件数(既定値は20、最大100)
+  /// - `after` - このカーソルより後の結果を取得する
+  ///
+  /// # 戻り値
+  /// - `MessageSearchResultConnection` - 関連度の高い順の検索結果
+  #[graphql(complexity = "search_page_size(first) * child_complexity")]
+  async fn search_messages<'ctx>(
+    &self,
+    ctx: &Context<'ctx>,
+    user_account_id: String,
+    query: String,
+    group_chat_id: Option<String>,
+    first: Option<i32>,
+    after: Option<String>,
+  ) -> FieldResult<Connection<usize, MessageSearchResult>> {
+    let ctx = ctx.data::<ServiceContext>().unwrap();
+    let search_query = query.trim().to_string();
+    if search_query.is_empty() {
+      return Err(
+        ApiError::new(ErrorCode::InvalidArgument, "query must not be empty")
+          .with_field("argument", "query")
+          .into(),
+      );
+    }
+    self::query(
+      after,
+      None,
+      first,
+      None,
+      |after: Option<usize>, _before: Option<usize>, first: Option<usize>, _last: Option<usize>| async move {
+        let offset = after.map(|after| after + 1).unwrap_or(0);
+        let limit = first.unwrap_or(DEFAULT_SEARCH_PAGE_SIZE).min(MAX_SEARCH_PAGE_SIZE);
+        // NOTE: 次のページの有無を判定するために1件多く取得する
+        let mut results = ctx
+          .message_dao
+          .search_messages(
+            user_account_id,
+            search_query,
+            group_chat_id,
+            (limit + 1) as u32,
+            offset as u32,
+          )
+          .await
+          .map_err(message_dao_error_handling)?;
+        let has_next_page = results.len() > limit;
+        results.truncate(limit);
+        let mut connection = Connection::new(offset > 0, has_next_page);
+        connection.edges.extend(
+          results
+            .into_iter()
+            .enumerate()
+            .map(|(i, result)| Edge::new(offset + i, result)),
+        );
+        Ok::<_, Error>(connection)
+      },
+    )
+    .await
+  }
+
+  /// 指定されたアカウントID宛ての有効期限内の招待一覧を取得する。
+  ///
+  /// # 引数
+  /// - `user_account_id` - 招待されたアカウントID
+  ///
+  /// # 戻り値
+  /// - `Vec<Invitation>` - 招待一覧(新しい順)
+  #[graphql(complexity = "LIST_COST_FACTOR * child_complexity")]
+  async fn pending_invitations<'ctx>(
+    &self,
+    ctx: &Context<'ctx>,
+    user_account_id: String,
+  ) -> FieldResult<Vec<Invitation>> {
+    let ctx = ctx.data::<ServiceContext>().unwrap();
+    ctx
+      .invitation_dao
+      .get_pending_invitations(user_account_id)
+      .await
+      .map_err(invitation_dao_error_handling)
+  }
 }
 
-fn group_chat_dao_error_handling(error: GroupChatDaoError) -> Error {
-  match error {
-    GroupChatDaoError::NotFoundError(_) => Error::new(error.to_string()).extend_with(|_, e| e.set("code", "404")),
-    GroupChatDaoError::OtherError(_) => Error::new(error.to_string()).extend_with(|_, e| e.set("code", "500")),
+// NOTE: ネストしたフィールドは親のグループチャットやメッセージの閲覧が許可されていることを前提に、
+// DataLoaderでグループチャットIDごとにまとめて読み込む(N+1問題の回避)
+#[ComplexObject]
+impl GroupChat {
+  /// メンバー一覧
+  #[graphql(complexity = "LIST_COST_FACTOR * child_complexity")]
+  async fn members<'ctx>(&self, ctx: &Context<'ctx>) -> FieldResult<Vec<Member>> {
+    let loader = ctx.data::<DataLoader<MembersLoader>>().unwrap();
+    Ok(
+      loader
+        .load_one(self.group_chat_id().to_string())
+        .await?
+        .unwrap_or_default(),
+    )
+  }
+
+  /// メッセージ一覧(古い順)
+  #[graphql(complexity = "MESSAGES_COST_FACTOR * child_complexity")]
+  async fn messages<'ctx>(&self, ctx: &Context<'ctx>) -> FieldResult<Vec<Message>> {
+    let loader = ctx.data::<DataLoader<MessagesLoader>>().unwrap();
+    Ok(
+      loader
+        .load_one(self.group_chat_id().to_string())
+        .await?
+        .unwrap_or_default(),
+    )
   }
+
+  /// 最新のメッセージ。メッセージがない場合はnullを返す。
+  async fn latest_message<'ctx>(&self, ctx: &Context<'ctx>) -> FieldResult<Option<Message>> {
+    let loader = ctx.data::<DataLoader<LatestMessageLoader>>().unwrap();
+    loader.load_one(self.group_chat_id().to_string()).await
+  }
+}
+
+#[ComplexObject]
+impl Message {
+  /// 本文(テキスト・添付ファイル・リンクプレビュー・システムメッセージ)
+  async fn body(&self) -> MessageContent {
+    self.parse_body()
+  }
+
+  /// 送信者。送信者がグループチャットから退出している場合はnullを返す。
+  async fn sender<'ctx>(&self, ctx: &Context<'ctx>) -> FieldResult<Option<Member>> {
+    let loader = ctx.data::<DataLoader<MembersLoader>>().unwrap();
+    let members = loader.load_one(self.message_group_chat_id().to_string()).await?;
+    Ok(
+      members
+        .unwrap_or_default()
+        .into_iter()
+        .find(|member| member.member_user_account_id() == self.sender_id()),
+    )
+  }
+
+  /// 編集前の本文の履歴(古い順)。管理者によって削除されたメッセージの場合は空を返す。
+  #[graphql(complexity = "LIST_COST_FACTOR * child_complexity")]
+  async fn revisions<'ctx>(&self, ctx: &Context<'ctx>) -> FieldResult<Vec<MessageRevision>> {
+    if self.is_moderated() {
+      return Ok(vec![]);
+    }
+    let ctx = ctx.data::<ServiceContext>().unwrap();
+    ctx
+      .message_dao
+      .get_message_revisions(self.message_id().to_string())
+      .await
+      .map_err(message_dao_error_handling)
+  }
+}
+
+/// DAOのその他のエラーを[ErrorCode::Internal]のエラーに変換する。
+///
+/// NOTE: 内部の詳細をクライアントに返さないように、原因はログにのみ出力する。
+fn internal_error(cause: &dyn std::fmt::Display) -> Error {
+  log::error!("internal error: {}", cause);
+  ApiError::new(ErrorCode::Internal, "internal server error").into()
 }
 
-fn member_dao_error_handling(error: MemberDaoError) -> Error {
-  match error {
-    MemberDaoError::NotFoundError(_) => Error::new(error.to_string()).extend_with(|_, e| e.set("code", "404")),
-    MemberDaoError::OtherError(_) => Error::new(error.to_string()).extend_with(|_, e| e.set("code", "500")),
+pub(crate) fn group_chat_dao_error_handling(error: GroupChatDaoError) -> Error {
+  match &error {
+    GroupChatDaoError::NotFoundError(_) => ApiError::new(ErrorCode::GroupChatNotFound, error.to_string()).into(),
+    GroupChatDaoError::OtherError(_) => internal_error(&error),
   }
 }
 
-fn message_dao_error_handling(error: MessageDaoError) -> Error {
-  match error {
-    MessageDaoError::NotFoundError(_) => Error::new(error.to_string()).extend_with(|_, e| e.set("code", "404")),
-    MessageDaoError::OtherError(_) => Error::new(error.to_string()).extend_with(|_, e| e.set("code", "500")),
+pub(crate) fn member_dao_error_handling(error: MemberDaoError) -> Error {
+  match &error {
+    MemberDaoError::NotFoundError(_) => ApiError::new(ErrorCode::MemberNotFound, error.to_string()).into(),
+    MemberDaoError::OtherError(_) => internal_error(&error),
+  }
+}
+
+pub(crate) fn message_dao_error_handling(error: MessageDaoError) -> Error {
+  match &error {
+    MessageDaoError::NotFoundError(_) => ApiError::new(ErrorCode::MessageNotFound, error.to_string()).into(),
+    MessageDaoError::OtherError(_) => internal_error(&error),
+  }
+}
+
+fn invitation_dao_error_handling(error: InvitationDaoError) -> Error {
+  match &error {
+    InvitationDaoError::OtherError(_) => internal_error(&error),
   }
 }
 
 pub type ApiSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
 
+/// スキーマのビルダーを生成する。
+///
+/// NOTE: [ErrorCode]はフィールドの型ではないが、エラーの`code`拡張の値としてSDLに出力するために登録する。
+/// リクエストとリゾルバごとのスパンは[Tracing]拡張で生成する。
 pub fn create_schema_builder() -> SchemaBuilder<QueryRoot, EmptyMutation, EmptySubscription> {
   Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+    .register_output_type::<ErrorCode>()
+    .extension(Tracing)
 }
 
-pub fn create_schema(pool: MySqlPool) -> ApiSchema {
+/// [ServiceContext]と、ネストしたフィールド用のDataLoaderを登録したスキーマを生成する。
+///
+/// NOTE: DataLoaderはキャッシュを持たないので、リクエストをまたいで古い値を返すことはない。
+///
+/// # 引数
+/// - `ctx` - [ServiceContext]
+/// - `graphql_settings` - クエリの深さ・複雑さの制限と永続化クエリの設定
+pub fn create_schema_with_context(
+  ctx: ServiceContext,
+  graphql_settings: &GraphQLSettings,
+) -> Result<ApiSchema, GraphQLSettingsError> {
+  let members_loader = DataLoader::new(MembersLoader::new(ctx.get_member_dao()), tokio::spawn);
+  let messages_loader = DataLoader::new(MessagesLoader::new(ctx.get_message_dao()), tokio::spawn);
+  let latest_message_loader = DataLoader::new(LatestMessageLoader::new(ctx.get_message_dao()), tokio::spawn);
+  let builder = create_schema_builder()
+    .data(ctx)
+    .data(members_loader)
+    .data(messages_loader)
+    .data(latest_message_loader);
+  Ok(graphql_settings.apply(builder)?.finish())
+}
+
+/// リードモデルのデータベースを参照する[ServiceContext]を生成する。
+///
+/// # 引数
+/// - `pool` - リードモデルのデータベースの接続プール
+pub fn create_service_context(pool: MySqlPool) -> ServiceContext {
   let group_chat_dao = GroupChatDaoImpl::new(pool.clone());
   let member_dao = MemberDaoImpl::new(pool.clone());
-  let message_dao = MessageDaoImpl::new(pool);
-  let ctx = ServiceContext::new(Arc::new(group_chat_dao), Arc::new(member_dao), Arc::new(message_dao));
-  create_schema_builder().data(ctx).finish()
+  let message_dao = MessageDaoImpl::new(pool.clone());
+  let invitation_dao = InvitationDaoImpl::new(pool);
+  ServiceContext::new(
+    Arc::new(group_chat_dao),
+    Arc::new(member_dao),
+    Arc::new(message_dao),
+    Arc::new(invitation_dao),
+  )
+}
+
+pub fn create_schema(pool: MySqlPool, graphql_settings: &GraphQLSettings) -> Result<ApiSchema, GraphQLSettingsError> {
+  create_schema_with_context(create_service_context(pool), graphql_settings)
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
   use std::sync::Arc;
 
   use async_graphql::async_trait::async_trait;
 
-  use chrono::NaiveDateTime;
+  use chrono::{DateTime, NaiveDateTime};
 
   use crate::gateways::GroupChatDao;
-  use crate::resolvers::{create_schema_builder, ServiceContext};
+  use crate::resolvers::ServiceContext;
 
   use super::*;
 
-  struct MockGroupChatDaoImpl;
+  pub(crate) struct MockGroupChatDaoImpl;
 
   #[async_trait]
   impl GroupChatDao for MockGroupChatDaoImpl {
@@ -235,8 +539,8 @@ mod tests {
         group_chat_id,
         "mock group chat".to_string(),
         "mock owner".to_string(),
-        NaiveDateTime::from_timestamp_opt(0, 0).unwrap(),
-        NaiveDateTime::from_timestamp_opt(0, 0).unwrap(),
+        DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+        DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
       );
       Ok(t1)
     }
@@ -246,14 +550,29 @@ mod tests {
         "1".to_string(),
         "mock group chat".to_string(),
         user_account_id,
-        NaiveDateTime::from_timestamp_opt(0, 0).unwrap(),
-        NaiveDateTime::from_timestamp_opt(0, 0).unwrap(),
+        DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+        DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
       );
       Ok(vec![t1])
     }
+
+    async fn get_group_chat_settings(
+      &self,
+      group_chat_id: String,
+      _account_id: String,
+    ) -> Result<GroupChatSettings, GroupChatDaoError> {
+      Ok(GroupChatSettings::new(
+        group_chat_id,
+        1000,
+        "admins_only".to_string(),
+        "admins_only".to_string(),
+        Some(30),
+        DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+      ))
+    }
   }
 
-  struct MockMemberDaoImpl;
+  pub(crate) struct MockMemberDaoImpl;
 
   #[async_trait]
   impl MemberDao for MockMemberDaoImpl {
@@ -263,8 +582,8 @@ mod tests {
         group_chat_id,
         user_account_id,
         "mock member".to_string(),
-        NaiveDateTime::from_timestamp_opt(0, 0).unwrap(),
-        NaiveDateTime::from_timestamp_opt(0, 0).unwrap(),
+        DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+        DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
       );
       Ok(m1)
     }
@@ -279,14 +598,33 @@ mod tests {
         group_chat_id,
         "mock member".to_string(),
         "mock member".to_string(),
-        NaiveDateTime::from_timestamp_opt(0, 0).unwrap(),
-        NaiveDateTime::from_timestamp_opt(0, 0).unwrap(),
+        DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+        DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
       );
       Ok(vec![m1])
     }
+
+    async fn get_members_by_group_chat_ids(&self, group_chat_ids: Vec<String>) -> Result<Vec<Member>, MemberDaoError> {
+      // NOTE: まとめて読み込まれたことを確認できるように、ロールに件数を設定する
+      let role = format!("batch of {}", group_chat_ids.len());
+      let members = group_chat_ids
+        .into_iter()
+        .map(|group_chat_id| {
+          Member::new(
+            "1".to_string(),
+            group_chat_id,
+            "mock member".to_string(),
+            role.clone(),
+            DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+            DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+          )
+        })
+        .collect();
+      Ok(members)
+    }
   }
 
-  struct MockMessageDaoImpl;
+  pub(crate) struct MockMessageDaoImpl;
 
   #[async_trait]
   impl MessageDao for MockMessageDaoImpl {
@@ -296,8 +634,12 @@ mod tests {
         "mock group chat".to_string(),
         user_account_id,
         "mock message".to_string(),
-        NaiveDateTime::from_timestamp_opt(0, 0).unwrap(),
-        NaiveDateTime::from_timestamp_opt(0, 0).unwrap(),
+        DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+        DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+      )
+      .with_content(
+        "attachment".to_string(),
+        r#"{"type":"Attachment","url":"https://example.com/a.png","mime":"image/png","size":1024}"#.to_string(),
       );
       Ok(m1)
     }
@@ -312,27 +654,176 @@ mod tests {
         group_chat_id,
         user_account_id,
         "mock message".to_string(),
-        NaiveDateTime::from_timestamp_opt(0, 0).unwrap(),
-        NaiveDateTime::from_timestamp_opt(0, 0).unwrap(),
+        DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+        DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+      );
+      Ok(vec![m1])
+    }
+
+    async fn get_mentions(&self, user_account_id: String) -> Result<Vec<Message>, MessageDaoError> {
+      let m1 = Message::new(
+        "1".to_string(),
+        "mock group chat".to_string(),
+        "mock sender".to_string(),
+        format!("hello @{}", user_account_id),
+        DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+        DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+      );
+      Ok(vec![m1])
+    }
+
+    async fn get_message_revisions(&self, message_id: String) -> Result<Vec<MessageRevision>, MessageDaoError> {
+      let r1 = MessageRevision::new(
+        message_id,
+        "mock revision".to_string(),
+        DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
       );
+      Ok(vec![r1])
+    }
+
+    async fn get_deleted_messages(
+      &self,
+      group_chat_id: String,
+      user_account_id: String,
+    ) -> Result<Vec<Message>, MessageDaoError> {
+      let m1 = Message::new(
+        "1".to_string(),
+        group_chat_id,
+        user_account_id.clone(),
+        "mock message".to_string(),
+        DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+        DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+      )
+      .with_deleted(DateTime::from_timestamp(0, 0).unwrap().naive_utc(), user_account_id);
       Ok(vec![m1])
     }
+
+    async fn search_messages(
+      &self,
+      user_account_id: String,
+      query: String,
+      group_chat_id: Option<String>,
+      limit: u32,
+      offset: u32,
+    ) -> Result<Vec<MessageSearchResult>, MessageDaoError> {
+      let results = (0..3)
+        .map(|i| {
+          let message = Message::new(
+            i.to_string(),
+            group_chat_id.clone().unwrap_or("mock group chat".to_string()),
+            user_account_id.clone(),
+            format!("mock {} message", query),
+            DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+            DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+          );
+          MessageSearchResult::new(message, 3.0 - i as f64, &query)
+        })
+        .skip(offset as usize)
+        .take(limit as usize)
+        .collect();
+      Ok(results)
+    }
+
+    async fn get_messages_by_group_chat_ids(
+      &self,
+      group_chat_ids: Vec<String>,
+    ) -> Result<Vec<Message>, MessageDaoError> {
+      let messages = group_chat_ids
+        .into_iter()
+        .flat_map(|group_chat_id| {
+          (0..2).map(move |i| {
+            Message::new(
+              i.to_string(),
+              group_chat_id.clone(),
+              "mock member".to_string(),
+              format!("mock message {}", i),
+              DateTime::from_timestamp(i, 0).unwrap().naive_utc(),
+              DateTime::from_timestamp(i, 0).unwrap().naive_utc(),
+            )
+          })
+        })
+        .collect();
+      Ok(messages)
+    }
+
+    async fn get_latest_messages_by_group_chat_ids(
+      &self,
+      group_chat_ids: Vec<String>,
+    ) -> Result<Vec<Message>, MessageDaoError> {
+      // NOTE: メッセージがないグループチャットを表すため、"empty"から始まるIDは含めない
+      let messages = group_chat_ids
+        .into_iter()
+        .filter(|group_chat_id| !group_chat_id.starts_with("empty"))
+        .map(|group_chat_id| {
+          Message::new(
+            "1".to_string(),
+            group_chat_id,
+            "mock member".to_string(),
+            "mock message 1".to_string(),
+            DateTime::from_timestamp(1, 0).unwrap().naive_utc(),
+            DateTime::from_timestamp(1, 0).unwrap().naive_utc(),
+          )
+        })
+        .collect();
+      Ok(messages)
+    }
+
+    async fn get_messages_after(
+      &self,
+      group_chat_id: String,
+      user_account_id: String,
+      created_at: NaiveDateTime,
+      _message_id: String,
+      _limit: u32,
+    ) -> Result<Vec<Message>, MessageDaoError> {
+      // NOTE: 最新のメッセージ(get_latest_messages_by_group_chat_ids)の後に、新しいメッセージが1件だけ投稿されたものとする
+      let m2 = Message::new(
+        "2".to_string(),
+        group_chat_id,
+        user_account_id,
+        "mock message 2".to_string(),
+        DateTime::from_timestamp(2, 0).unwrap().naive_utc(),
+        DateTime::from_timestamp(2, 0).unwrap().naive_utc(),
+      );
+      Ok(vec![m2].into_iter().filter(|m| m.created_at > created_at).collect())
+    }
   }
 
-  fn create_schema_on_test() -> ApiSchema {
-    let ctx = ServiceContext::new(
+  pub(crate) struct MockInvitationDaoImpl;
+
+  #[async_trait]
+  impl InvitationDao for MockInvitationDaoImpl {
+    async fn get_pending_invitations(&self, user_account_id: String) -> Result<Vec<Invitation>, InvitationDaoError> {
+      let i1 = Invitation::new(
+        "group_chat_id".to_string(),
+        "mock group chat".to_string(),
+        user_account_id,
+        "member".to_string(),
+        "mock owner".to_string(),
+        DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+        DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+      );
+      Ok(vec![i1])
+    }
+  }
+
+  pub(crate) fn create_service_context_on_test() -> ServiceContext {
+    ServiceContext::new(
       Arc::new(MockGroupChatDaoImpl),
       Arc::new(MockMemberDaoImpl),
       Arc::new(MockMessageDaoImpl),
-    );
+      Arc::new(MockInvitationDaoImpl),
+    )
+  }
 
-    create_schema_builder().data(ctx).finish()
+  fn create_schema_on_test() -> ApiSchema {
+    create_schema_with_context(create_service_context_on_test(), &GraphQLSettings::default()).unwrap()
   }
 
   #[tokio::test]
   async fn test_get_group_chat() {
     let result = create_schema_on_test()
-      .execute(r#"{ getGroupChat(groupChatId: "group_chat_id", userAccountId: "user_account_id") { id name } }"#)
+      .execute(r#"{ getGroupChat(groupChatId: "group_chat_id", userAccountId: "user_account_id") { id kind name } }"#)
       .await
       .into_result()
       .unwrap()
@@ -342,12 +833,64 @@ mod tests {
       async_graphql::value!({
           "getGroupChat": {
               "id": "group_chat_id",
+              "kind": "group",
               "name": "mock group chat"
           }
       })
     );
   }
 
+  #[tokio::test]
+  async fn test_get_group_chat_nested_fields() {
+    let result = create_schema_on_test()
+      .execute(
+        r#"{
+          a: getGroupChat(groupChatId: "group_chat_id", userAccountId: "user_account_id") {
+            id
+            members { userAccountId role }
+            messages { text }
+            latestMessage { text sender { userAccountId role } }
+          }
+          b: getGroupChat(groupChatId: "empty_group_chat_id", userAccountId: "user_account_id") {
+            id
+            members { role }
+            latestMessage { text }
+          }
+        }"#,
+      )
+      .await
+      .into_result()
+      .unwrap()
+      .data;
+    assert_eq!(
+      result,
+      async_graphql::value!({
+          "a": {
+              "id": "group_chat_id",
+              "members": [{ "userAccountId": "mock member", "role": "batch of 2" }],
+              "messages": [{ "text": "mock message 0" }, { "text": "mock message 1" }],
+              "latestMessage": {
+                  "text": "mock message 1",
+                  "sender": { "userAccountId": "mock member", "role": "batch of 1" }
+              }
+          },
+          "b": {
+              "id": "empty_group_chat_id",
+              "members": [{ "role": "batch of 2" }],
+              "latestMessage": null
+          }
+      })
+    );
+  }
+
+  #[tokio::test]
+  async fn test_reject_too_complex_query() {
+    let response = create_schema_on_test()
+      .execute(r#"{ getGroupChats(userAccountId: "user_account_id") { id messages { id text sender { role } } } }"#)
+      .await;
+    assert!(response.errors[0].message.contains("too complex"));
+  }
+
   #[tokio::test]
   async fn test_get_group_chats() {
     let result = create_schema_on_test()
@@ -416,6 +959,40 @@ mod tests {
     );
   }
 
+  #[tokio::test]
+  async fn test_get_message_body() {
+    let result = create_schema_on_test()
+      .execute(
+        r#"{ getMessage(messageId: "message_id", userAccountId: "user_account_id") {
+          contentType
+          body {
+            __typename
+            ... on AttachmentContent { url mime size }
+            ... on TextContent { text }
+          }
+        } }"#,
+      )
+      .await
+      .into_result()
+      .unwrap()
+      .data;
+
+    assert_eq!(
+      result,
+      async_graphql::value!({
+          "getMessage": {
+              "contentType": "attachment",
+              "body": {
+                  "__typename": "AttachmentContent",
+                  "url": "https://example.com/a.png",
+                  "mime": "image/png",
+                  "size": 1024
+              }
+          }
+      })
+    );
+  }
+
   #[tokio::test]
   async fn test_get_message() {
     let result = create_schema_on_test()
@@ -461,4 +1038,143 @@ mod tests {
       })
     );
   }
+
+  #[tokio::test]
+  async fn test_search_messages() {
+    let result = create_schema_on_test()
+      .execute(
+        r#"{ searchMessages(userAccountId: "user_account_id", query: "search", first: 2, after: "0") {
+             pageInfo { hasPreviousPage, hasNextPage }
+             edges { cursor, node { score, snippet, message { id } } }
+           } }"#,
+      )
+      .await
+      .into_result()
+      .unwrap()
+      .data;
+
+    assert_eq!(
+      result,
+      async_graphql::value!({
+          "searchMessages": {
+              "pageInfo": { "hasPreviousPage": true, "hasNextPage": false },
+              "edges": [
+                  { "cursor": "1", "node": { "score": 2.0, "snippet": "mock <mark>search</mark> message", "message": { "id": "1" } } },
+                  { "cursor": "2", "node": { "score": 1.0, "snippet": "mock <mark>search</mark> message", "message": { "id": "2" } } }
+              ]
+          }
+      })
+    );
+  }
+
+  #[tokio::test]
+  async fn test_get_deleted_messages() {
+    let result = create_schema_on_test()
+        .execute(r#"{ getDeletedMessages(groupChatId: "group_chat_id", userAccountId: "user_account_id") { id, text, deletedBy } }"#)
+        .await
+        .into_result()
+        .unwrap()
+        .data;
+
+    assert_eq!(
+      result,
+      async_graphql::value!({
+          "getDeletedMessages": [{
+              "id": "1",
+              "text": "mock message",
+              "deletedBy": "user_account_id"
+          }]
+      })
+    );
+  }
+
+  #[tokio::test]
+  async fn test_get_message_revisions() {
+    let result = create_schema_on_test()
+      .execute(
+        r#"{ getMessage(messageId: "message_id", userAccountId: "user_account_id") { id, text, revisions { messageId, text } } }"#,
+      )
+      .await
+      .into_result()
+      .unwrap()
+      .data;
+
+    assert_eq!(
+      result,
+      async_graphql::value!({
+          "getMessage": {
+              "id": "message_id",
+              "text": "mock message",
+              "revisions": [{
+                  "messageId": "message_id",
+                  "text": "mock revision"
+              }]
+          }
+      })
+    );
+  }
+
+  #[tokio::test]
+  async fn test_get_mentions() {
+    let result = create_schema_on_test()
+      .execute(r#"{ getMentions(userAccountId: "user_account_id") { id, text } }"#)
+      .await
+      .into_result()
+      .unwrap()
+      .data;
+
+    assert_eq!(
+      result,
+      async_graphql::value!({
+          "getMentions": [{
+              "id": "1",
+              "text": "hello @user_account_id"
+          }]
+      })
+    );
+  }
+
+  #[tokio::test]
+  async fn test_pending_invitations() {
+    let result = create_schema_on_test()
+      .execute(r#"{ pendingInvitations(userAccountId: "user_account_id") { groupChatId, groupChatName, role } }"#)
+      .await
+      .into_result()
+      .unwrap()
+      .data;
+
+    assert_eq!(
+      result,
+      async_graphql::value!({
+          "pendingInvitations": [{
+              "groupChatId": "group_chat_id",
+              "groupChatName": "mock group chat",
+              "role": "member"
+          }]
+      })
+    );
+  }
+
+  #[tokio::test]
+  async fn test_get_group_chat_settings() {
+    let result = create_schema_on_test()
+      .execute(
+        r#"{ getGroupChatSettings(groupChatId: "group_chat_id", userAccountId: "user_account_id") { maxMembers, postPermission, messageRetentionDays } }"#,
+      )
+      .await
+      .into_result()
+      .unwrap()
+      .data;
+
+    assert_eq!(
+      result,
+      async_graphql::value!({
+          "getGroupChatSettings": {
+              "maxMembers": 1000,
+              "postPermission": "admins_only",
+              "messageRetentionDays": 30
+          }
+      })
+    );
+  }
 }
@@ -1,13 +1,20 @@
 use aws_lambda_events::dynamodb;
+use chrono::Utc;
 use command_interface_adaptor_if::{GroupChatReadModelUpdateDao, GroupChatReadModelUpdateDaoError};
+use event_store_adapter_rs::serializer::{EventSerializer, JsonEventSerializer};
+use event_store_adapter_rs::types::{Event, EventStoreReadError};
 use lambda_runtime::LambdaEvent;
+use once_cell::sync::Lazy;
+use prometheus::{register_histogram, register_int_counter_vec, Histogram, IntCounterVec};
 use serde_dynamo::AttributeValue;
 use serde_json::Value;
 use std::string::FromUtf8Error;
 use thiserror::Error;
 
-use command_domain::group_chat::GroupChatEvent;
-use command_domain::group_chat::MemberRole;
+use command_domain::group_chat::{GroupChatEvent, GroupChatSettings};
+use command_interface_adaptor_impl::gateways::trace_context_event_serializer::trace_context_of;
+use infrastructure::telemetry::set_parent_trace_context;
+use tracing::Instrument;
 
 #[derive(Debug, Error)]
 pub enum UpdateReadModelError {
@@ -17,15 +24,62 @@ pub enum UpdateReadModelError {
   PayloadParseError(FromUtf8Error),
   #[error("Unexpected type: {0:?}")]
   UnexpectedType(Option<AttributeValue>),
+  #[error("Payload deserialize error: {0:?}")]
+  PayloadDeserializeError(EventStoreReadError),
   #[error("GroupChatReadModelUpdateError: {0:?}")]
   GroupChatReadModelUpdateError(GroupChatReadModelUpdateDaoError),
 }
 
+/// イベントの発生からリードモデルに反映するまでの時間(秒)
+static READ_MODEL_UPDATE_LAG_SECONDS: Lazy<Histogram> = Lazy::new(|| {
+  register_histogram!(
+    "read_model_update_lag_seconds",
+    "Time from the occurrence of an event to the update of the read model",
+    vec![0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 300.0]
+  )
+  .unwrap()
+});
+
+/// リードモデルのDAOのエラーの件数(`method`: DAOのメソッド名)
+static READ_MODEL_DAO_ERRORS_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+  register_int_counter_vec!(
+    "read_model_dao_errors_total",
+    "Number of errors from the read model DAO",
+    &["method"]
+  )
+  .unwrap()
+});
+
+/// DAOのエラーを件数に記録して[UpdateReadModelError]に変換する関数を返す。
+///
+/// # 引数
+/// - `method` - DAOのメソッド名
+fn dao_error(method: &'static str) -> impl Fn(GroupChatReadModelUpdateDaoError) -> UpdateReadModelError {
+  move |error| {
+    READ_MODEL_DAO_ERRORS_TOTAL.with_label_values(&[method]).inc();
+    UpdateReadModelError::GroupChatReadModelUpdateError(error)
+  }
+}
+
 // NOTE: イベントのシーケンス番号とリードモデルのシーケンス番号がズレないことを前提にしているため
 // DynamoDBを初期化した際は、必ずAurora側のデータベースも初期化すること
 pub async fn update_read_model<D: GroupChatReadModelUpdateDao>(
   group_chat_read_model_dao: &D,
   event: LambdaEvent<dynamodb::Event>,
+) -> Result<(), UpdateReadModelError> {
+  update_read_model_with_serializer(group_chat_read_model_dao, &JsonEventSerializer::default(), event).await
+}
+
+/// 指定した[EventSerializer]でイベントのペイロードをデシリアライズして、リードモデルを更新する。
+///
+/// # 引数
+/// - `group_chat_read_model_dao` - リードモデルを更新するDAO
+/// - `event_serializer` - 書き込み側と同じ[EventSerializer](クリプトシュレッディングが有効な場合は復号できるもの)
+/// - `event` - DynamoDBのストリームイベント
+pub async fn update_read_model_with_serializer<D: GroupChatReadModelUpdateDao>(
+  group_chat_read_model_dao: &D,
+  event_serializer: &dyn EventSerializer<GroupChatEvent>,
+  event: LambdaEvent<dynamodb::Event>,
 ) -> Result<(), UpdateReadModelError> {
   tracing::info!("Rust function invoked: event = {:?}", event);
   for record in event.payload.records {
@@ -47,81 +101,19 @@ pub async fn update_read_model<D: GroupChatReadModelUpdateDao>(
     tracing::info!("type_value_str = {}", type_value_str);
     match type_value_str {
       s if s.starts_with("GroupChat") => {
-        let ev = serde_json::from_str::<GroupChatEvent>(&payload_str).unwrap();
-        tracing::info!("ev = {:?}", ev);
-        match &ev {
-          GroupChatEvent::GroupChatCreated(body) => {
-            group_chat_read_model_dao
-              .insert_group_chat(
-                body.aggregate_id.clone(),
-                body.name.clone(),
-                body
-                  .members
-                  .administrator_id()
-                  .breach_encapsulation_of_user_account_id()
-                  .clone(),
-                body.occurred_at,
-              )
-              .await
-              .map_err(UpdateReadModelError::GroupChatReadModelUpdateError)?;
-            group_chat_read_model_dao
-              .insert_member(
-                body.aggregate_id.clone(),
-                body.members.administrator_id().breach_encapsulation_of_id().clone(),
-                body
-                  .members
-                  .administrator_id()
-                  .breach_encapsulation_of_user_account_id()
-                  .clone(),
-                MemberRole::Admin,
-                body.occurred_at,
-              )
-              .await
-              .map_err(UpdateReadModelError::GroupChatReadModelUpdateError)?;
-          }
-          GroupChatEvent::GroupChatDeleted(body) => group_chat_read_model_dao
-            .delete_group_chat(body.aggregate_id.clone(), body.occurred_at.clone())
-            .await
-            .map_err(UpdateReadModelError::GroupChatReadModelUpdateError)?,
-          GroupChatEvent::GroupChatRenamed(body) => group_chat_read_model_dao
-            .rename_group_chat(body.aggregate_id.clone(), body.name.clone(), body.occurred_at.clone())
-            .await
-            .map_err(UpdateReadModelError::GroupChatReadModelUpdateError)?,
-          GroupChatEvent::GroupChatMemberAdded(body) => group_chat_read_model_dao
-            .insert_member(
-              body.aggregate_id.clone(),
-              body.member.breach_encapsulation_of_id().clone(),
-              body.member.breach_encapsulation_of_user_account_id().clone(),
-              body.member.breach_encapsulation_of_role().clone(),
-              body.occurred_at,
-            )
-            .await
-            .map_err(UpdateReadModelError::GroupChatReadModelUpdateError)?,
-          GroupChatEvent::GroupChatMemberRemoved(body) => group_chat_read_model_dao
-            .delete_member(body.aggregate_id.clone(), body.user_account_id.clone())
-            .await
-            .map_err(UpdateReadModelError::GroupChatReadModelUpdateError)?,
-          GroupChatEvent::GroupChatMessagePosted(body) => group_chat_read_model_dao
-            .insert_message(
-              body.aggregate_id.clone(),
-              body.message.clone(),
-              body.occurred_at.clone(),
-            )
-            .await
-            .map_err(UpdateReadModelError::GroupChatReadModelUpdateError)?,
-          GroupChatEvent::GroupChatMessageEdited(body) => group_chat_read_model_dao
-            .update_message(
-              body.aggregate_id.clone(),
-              body.message.clone(),
-              body.occurred_at.clone(),
-            )
-            .await
-            .map_err(UpdateReadModelError::GroupChatReadModelUpdateError)?,
-          GroupChatEvent::GroupChatMessageDeleted(body) => group_chat_read_model_dao
-            .delete_message(body.message_id.clone(), body.occurred_at.clone())
-            .await
-            .map_err(UpdateReadModelError::GroupChatReadModelUpdateError)?,
+        let ev = *event_serializer
+          .deserialize(payload_str.as_bytes())
+          .map_err(UpdateReadModelError::PayloadDeserializeError)?;
+        // NOTE: 復号後のイベントにはメッセージの本文が含まれるので、種類とIDだけを出力する
+        tracing::debug!("event_type = {}, event_id = {}", s, ev.id());
+        // NOTE: イベントを保存したミューテーションのスパンを親にして、リードモデルの更新をトレースで紐付ける
+        let span = tracing::info_span!("update_read_model", event_type = %s);
+        if let Some(trace_context) = trace_context_of(payload_str.as_bytes()) {
+          set_parent_trace_context(&span, &trace_context);
         }
+        apply_event(group_chat_read_model_dao, &ev).instrument(span).await?;
+        let lag = Utc::now() - ev.occurred_at();
+        READ_MODEL_UPDATE_LAG_SECONDS.observe(lag.num_milliseconds() as f64 / 1000.0);
       }
       _ => {}
     }
@@ -130,8 +122,178 @@ pub async fn update_read_model<D: GroupChatReadModelUpdateDao>(
   Ok(())
 }
 
+/// イベントをリードモデルに反映する。
+async fn apply_event<D: GroupChatReadModelUpdateDao>(
+  group_chat_read_model_dao: &D,
+  ev: &GroupChatEvent,
+) -> Result<(), UpdateReadModelError> {
+  match ev {
+    GroupChatEvent::GroupChatCreated(body) => {
+      // NOTE: 1対1のチャットには管理者が存在しないため、最初のメンバーをオーナーとする
+      let members = body.members.to_vec();
+      let owner = body.members.find_administrator().or(members.first().copied()).unwrap();
+      group_chat_read_model_dao
+        .insert_group_chat(
+          body.aggregate_id.clone(),
+          body.kind.clone(),
+          body.name.clone(),
+          owner.breach_encapsulation_of_user_account_id().clone(),
+          body.occurred_at,
+        )
+        .await
+        .map_err(dao_error("insert_group_chat"))?;
+      for member in members {
+        group_chat_read_model_dao
+          .insert_member(
+            body.aggregate_id.clone(),
+            member.breach_encapsulation_of_id().clone(),
+            member.breach_encapsulation_of_user_account_id().clone(),
+            member.breach_encapsulation_of_role().clone(),
+            body.occurred_at,
+          )
+          .await
+          .map_err(dao_error("insert_member"))?;
+      }
+      group_chat_read_model_dao
+        .upsert_group_chat_settings(
+          body.aggregate_id.clone(),
+          GroupChatSettings::default(),
+          body.occurred_at,
+        )
+        .await
+        .map_err(dao_error("upsert_group_chat_settings"))?;
+    }
+    GroupChatEvent::GroupChatSettingsUpdated(body) => group_chat_read_model_dao
+      .upsert_group_chat_settings(body.aggregate_id.clone(), body.settings.clone(), body.occurred_at)
+      .await
+      .map_err(dao_error("upsert_group_chat_settings"))?,
+    GroupChatEvent::GroupChatDeleted(body) => group_chat_read_model_dao
+      .delete_group_chat(body.aggregate_id.clone(), body.occurred_at)
+      .await
+      .map_err(dao_error("delete_group_chat"))?,
+    GroupChatEvent::GroupChatRenamed(body) => group_chat_read_model_dao
+      .rename_group_chat(body.aggregate_id.clone(), body.name.clone(), body.occurred_at)
+      .await
+      .map_err(dao_error("rename_group_chat"))?,
+    GroupChatEvent::GroupChatMemberAdded(body) => {
+      group_chat_read_model_dao
+        .insert_member(
+          body.aggregate_id.clone(),
+          body.member.breach_encapsulation_of_id().clone(),
+          body.member.breach_encapsulation_of_user_account_id().clone(),
+          body.member.breach_encapsulation_of_role().clone(),
+          body.occurred_at,
+        )
+        .await
+        .map_err(dao_error("insert_member"))?;
+      if let Some(notice) = &body.notice {
+        group_chat_read_model_dao
+          .insert_message(body.aggregate_id.clone(), notice.clone(), body.occurred_at)
+          .await
+          .map_err(dao_error("insert_message"))?;
+      }
+    }
+    GroupChatEvent::GroupChatMemberRemoved(body) => {
+      group_chat_read_model_dao
+        .delete_member(body.aggregate_id.clone(), body.user_account_id.clone())
+        .await
+        .map_err(dao_error("delete_member"))?;
+      if let Some(notice) = &body.notice {
+        group_chat_read_model_dao
+          .insert_message(body.aggregate_id.clone(), notice.clone(), body.occurred_at)
+          .await
+          .map_err(dao_error("insert_message"))?;
+      }
+    }
+    GroupChatEvent::GroupChatMemberLeft(body) => {
+      group_chat_read_model_dao
+        .delete_member(body.aggregate_id.clone(), body.user_account_id.clone())
+        .await
+        .map_err(dao_error("delete_member"))?;
+      if let Some(notice) = &body.notice {
+        group_chat_read_model_dao
+          .insert_message(body.aggregate_id.clone(), notice.clone(), body.occurred_at)
+          .await
+          .map_err(dao_error("insert_message"))?;
+      }
+    }
+    GroupChatEvent::GroupChatMemberInvited(body) => group_chat_read_model_dao
+      .insert_invitation(body.aggregate_id.clone(), body.invitation.clone(), body.occurred_at)
+      .await
+      .map_err(dao_error("insert_invitation"))?,
+    GroupChatEvent::GroupChatInvitationAccepted(body) => {
+      group_chat_read_model_dao
+        .insert_member(
+          body.aggregate_id.clone(),
+          body.member.breach_encapsulation_of_id().clone(),
+          body.member.breach_encapsulation_of_user_account_id().clone(),
+          body.member.breach_encapsulation_of_role().clone(),
+          body.occurred_at,
+        )
+        .await
+        .map_err(dao_error("insert_member"))?;
+      group_chat_read_model_dao
+        .delete_invitation(body.aggregate_id.clone(), body.executor_id.clone())
+        .await
+        .map_err(dao_error("delete_invitation"))?;
+      if let Some(notice) = &body.notice {
+        group_chat_read_model_dao
+          .insert_message(body.aggregate_id.clone(), notice.clone(), body.occurred_at)
+          .await
+          .map_err(dao_error("insert_message"))?;
+      }
+    }
+    GroupChatEvent::GroupChatInvitationDeclined(body) => group_chat_read_model_dao
+      .delete_invitation(body.aggregate_id.clone(), body.user_account_id.clone())
+      .await
+      .map_err(dao_error("delete_invitation"))?,
+    GroupChatEvent::GroupChatInvitationRevoked(body) => group_chat_read_model_dao
+      .delete_invitation(body.aggregate_id.clone(), body.user_account_id.clone())
+      .await
+      .map_err(dao_error("delete_invitation"))?,
+    GroupChatEvent::GroupChatMessagePosted(body) => group_chat_read_model_dao
+      .insert_message(body.aggregate_id.clone(), body.message.clone(), body.occurred_at)
+      .await
+      .map_err(dao_error("insert_message"))?,
+    GroupChatEvent::GroupChatMessageEdited(body) => group_chat_read_model_dao
+      .update_message(body.aggregate_id.clone(), body.message.clone(), body.occurred_at)
+      .await
+      .map_err(dao_error("update_message"))?,
+    GroupChatEvent::GroupChatMessageDeleted(body) => group_chat_read_model_dao
+      .delete_message(body.message_id.clone(), body.executor_id.clone(), body.occurred_at)
+      .await
+      .map_err(dao_error("delete_message"))?,
+    GroupChatEvent::GroupChatMessageModerated(body) => group_chat_read_model_dao
+      .moderate_message(
+        body.message_id.clone(),
+        body.executor_id.clone(),
+        body.reason.clone(),
+        body.occurred_at,
+      )
+      .await
+      .map_err(dao_error("moderate_message"))?,
+    GroupChatEvent::GroupChatMessageExpired(body) => group_chat_read_model_dao
+      .purge_message(body.message_id.clone())
+      .await
+      .map_err(dao_error("purge_message"))?,
+    GroupChatEvent::GroupChatMessageRestored(body) => group_chat_read_model_dao
+      .restore_message(body.message_id.clone(), body.occurred_at)
+      .await
+      .map_err(dao_error("restore_message"))?,
+    GroupChatEvent::GroupChatUserAccountErased(body) => group_chat_read_model_dao
+      .erase_user_account(
+        body.aggregate_id.clone(),
+        body.user_account_id.clone(),
+        body.occurred_at,
+      )
+      .await
+      .map_err(dao_error("erase_user_account"))?,
+  }
+  Ok(())
+}
+
 /// DynamoDBのストリームから取得したイベントのペイロードからイベントタイプを取得する
-fn get_type_string(payload_str: &String) -> String {
+fn get_type_string(payload_str: &str) -> String {
   let parsed: Value = serde_json::from_str(payload_str).unwrap();
   let type_value = &parsed["type"];
   let type_value_str = type_value.as_str().unwrap();
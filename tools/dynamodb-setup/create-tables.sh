@@ -28,12 +28,14 @@ JOURNAL_TABLE_NAME=${JOURNAL_TABLE_NAME:-"${PREFIX}-journal"}
 JOURNAL_GSI_NAME=${JOURNAL_GSI_NAME:-"${PREFIX}-aid-index"}
 SNAPSHOT_TABLE_NAME=${SNAPSHOT_TABLE_NAME:-"${PREFIX}-snapshot"}
 SNAPSHOT_GSI_NAME=${SNAPSHOT_GSI_NAME:-"${PREFIX}-aid-index"}
+IDEMPOTENCY_TABLE_NAME=${IDEMPOTENCY_TABLE_NAME:-"${PREFIX}-idempotency"}
 
 echo "DYNAMODB_ENDPOINT = ${DYNAMODB_ENDPOINT}"
 echo "JOURNAL_TABLE_NAME = ${JOURNAL_TABLE_NAME}"
 echo "JOURNAL_GSI_NAME = ${JOURNAL_GSI_NAME}"
 echo "SNAPSHOT_TABLE_NAME = ${SNAPSHOT_TABLE_NAME}"
 echo "SNAPSHOT_GSI_NAME = ${SNAPSHOT_GSI_NAME}"
+echo "IDEMPOTENCY_TABLE_NAME = ${IDEMPOTENCY_TABLE_NAME}"
 
 if [ "${ENV_NAME}" = "dev" ]; then
   # shellcheck disable=SC2034
@@ -99,3 +101,21 @@ aws dynamodb create-table \
       }
     }
   ]"
+
+# ミューテーションの冪等キーと結果。expires_atを過ぎた項目はTTLによって削除される
+# shellcheck disable=SC2086
+aws dynamodb create-table \
+  ${ENDPOINT_URL_OPTION} \
+  --table-name "${IDEMPOTENCY_TABLE_NAME}" \
+  --attribute-definitions \
+    AttributeName=key,AttributeType=S \
+  --key-schema \
+    AttributeName=key,KeyType=HASH \
+  --provisioned-throughput \
+    ReadCapacityUnits=10,WriteCapacityUnits=10
+
+# shellcheck disable=SC2086
+aws dynamodb update-time-to-live \
+  ${ENDPOINT_URL_OPTION} \
+  --table-name "${IDEMPOTENCY_TABLE_NAME}" \
+  --time-to-live-specification Enabled=true,AttributeName=expires_at
@@ -0,0 +1,3 @@
+-- 日本語を検索できるようにngramパーサを利用する(トークンのサイズはngram_token_sizeで変更できる)
+ALTER TABLE `messages`
+    ADD FULLTEXT INDEX `idx_messages_text` (`text`) WITH PARSER ngram;
@@ -0,0 +1,3 @@
+ALTER TABLE `messages`
+    ADD COLUMN `deleted_at` datetime    NULL AFTER `text`,
+    ADD COLUMN `deleted_by` varchar(64) NULL AFTER `deleted_at`;
@@ -0,0 +1,4 @@
+ALTER TABLE `messages`
+    ADD COLUMN `moderated_at`      datetime     NULL AFTER `deleted_by`,
+    ADD COLUMN `moderated_by`      varchar(64)  NULL AFTER `moderated_at`,
+    ADD COLUMN `moderation_reason` varchar(255) NULL AFTER `moderated_by`;
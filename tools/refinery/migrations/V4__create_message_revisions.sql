@@ -0,0 +1,14 @@
+ALTER TABLE `messages`
+    ADD COLUMN `edited_at` datetime NULL AFTER `text`;
+
+CREATE TABLE `message_revisions`
+(
+    `id`         bigint unsigned NOT NULL AUTO_INCREMENT,
+    `message_id` varchar(64)     NOT NULL,
+    `text`       TEXT            NOT NULL,
+    `created_at` datetime        NOT NULL,
+    PRIMARY KEY (`id`),
+    KEY `message_id` (`message_id`),
+    FOREIGN KEY (`message_id`) REFERENCES messages (`id`)
+) ENGINE = InnoDB
+  DEFAULT CHARSET = utf8mb4;
@@ -0,0 +1,12 @@
+CREATE TABLE `message_mentions`
+(
+    `message_id`      varchar(64) NOT NULL,
+    `group_chat_id`   varchar(64) NOT NULL,
+    `user_account_id` varchar(64) NOT NULL,
+    `created_at`      datetime    NOT NULL,
+    PRIMARY KEY (`message_id`, `user_account_id`),
+    KEY `user_account_id` (`user_account_id`),
+    FOREIGN KEY (`message_id`) REFERENCES messages (`id`),
+    FOREIGN KEY (`group_chat_id`) REFERENCES group_chats (`id`)
+) ENGINE = InnoDB
+  DEFAULT CHARSET = utf8mb4;
@@ -0,0 +1,3 @@
+ALTER TABLE `messages`
+    ADD COLUMN `content_type` varchar(32) NOT NULL DEFAULT 'text' AFTER `text`,
+    ADD COLUMN `content`      TEXT        NULL AFTER `content_type`;
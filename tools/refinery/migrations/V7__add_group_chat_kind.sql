@@ -0,0 +1,2 @@
+ALTER TABLE `group_chats`
+    ADD COLUMN `kind` varchar(16) NOT NULL DEFAULT 'group' AFTER `disabled`;
@@ -0,0 +1,13 @@
+CREATE TABLE `invitations`
+(
+    `group_chat_id`   varchar(64) NOT NULL,
+    `user_account_id` varchar(64) NOT NULL,
+    `role`            varchar(64) NOT NULL,
+    `invited_by`      varchar(64) NOT NULL,
+    `expires_at`      datetime    NOT NULL,
+    `created_at`      datetime    NOT NULL,
+    PRIMARY KEY (`group_chat_id`, `user_account_id`),
+    KEY `user_account_id` (`user_account_id`),
+    FOREIGN KEY (`group_chat_id`) REFERENCES group_chats (`id`)
+) ENGINE = InnoDB
+  DEFAULT CHARSET = utf8mb4;
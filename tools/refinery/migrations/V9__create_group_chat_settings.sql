@@ -0,0 +1,18 @@
+CREATE TABLE `group_chat_settings`
+(
+    `group_chat_id`          varchar(64)  NOT NULL,
+    `max_members`            int unsigned NOT NULL,
+    `post_permission`        varchar(16)  NOT NULL,
+    `add_member_permission`  varchar(16)  NOT NULL,
+    `message_retention_days` int unsigned NULL,
+    `created_at`             datetime     NOT NULL,
+    `updated_at`             datetime     NOT NULL,
+    PRIMARY KEY (`group_chat_id`),
+    FOREIGN KEY (`group_chat_id`) REFERENCES group_chats (`id`)
+) ENGINE = InnoDB
+  DEFAULT CHARSET = utf8mb4;
+
+INSERT INTO `group_chat_settings` (`group_chat_id`, `max_members`, `post_permission`, `add_member_permission`,
+                                   `message_retention_days`, `created_at`, `updated_at`)
+SELECT `id`, 1000, 'everyone', 'admins_only', NULL, `created_at`, `updated_at`
+FROM `group_chats`;
@@ -0,0 +1,10 @@
+#!/usr/bin/env bash
+
+curl -s -X POST -H "Content-Type: application/json" \
+	${WRITE_API_SERVER_BASE_URL}/group-chats \
+	-d @- <<EOS
+{
+  "name": "group-chat-example",
+  "executor_id": "01H42K4ABWQ5V2XQEP3A48VE0Z"
+}
+EOS